@@ -0,0 +1,214 @@
+//! Statistics about a book's text for the analysis screen: length,
+//! vocabulary, sentence length, readability and how long it takes to read.
+//! They are worked out once at import and kept in the library.
+
+use crate::language::count_syllables;
+use crate::library::{book_file, Book};
+use crate::text::{ends_clause, ends_sentence, Pauses};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::time::Duration;
+
+/// How many rare words the analysis keeps
+const RARE_WORDS: usize = 10;
+/// Shorter words are left out of the rare words, to skip names and typos
+const RARE_MIN_LETTERS: usize = 6;
+/// LIX counts words longer than this as long
+const LIX_LONG_LETTERS: usize = 6;
+/// LIX of ordinary prose, like a newspaper, read at the reader's own speed
+const LIX_TYPICAL: f64 = 40.0;
+/// Speed change per LIX point away from typical, and how far it can go
+const WPM_PER_LIX: f64 = 0.01;
+const SUGGESTED_WPM_RANGE: (f64, f64) = (0.75, 1.2);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BookAnalysis {
+    pub words: usize,
+    /// Distinct words, ignoring case and punctuation
+    pub unique_words: usize,
+    pub sentences: usize,
+    /// Words ending a clause, which get a clause pause
+    pub clauses: usize,
+    /// The longest words the book uses only once, a stand-in for its
+    /// unusual vocabulary
+    pub rare_words: Vec<String>,
+    /// Syllables of all words, by English spelling, for Flesch-Kincaid
+    pub syllables: usize,
+    /// Words longer than six letters, for LIX
+    pub long_words: usize,
+}
+
+impl BookAnalysis {
+    pub fn of(text: &str) -> Self {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let (mut words, mut sentences, mut clauses) = (0, 0, 0);
+        let (mut syllables, mut long_words) = (0, 0);
+        for word in text.split_whitespace() {
+            words += 1;
+            syllables += count_syllables(word);
+            if word.chars().filter(|c| c.is_alphabetic()).count() > LIX_LONG_LETTERS {
+                long_words += 1;
+            }
+            if ends_sentence(word) {
+                sentences += 1;
+            } else if ends_clause(word) {
+                clauses += 1;
+            }
+            let key = word
+                .trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase();
+            if !key.is_empty() {
+                *counts.entry(key).or_default() += 1;
+            }
+        }
+        // A book that doesn't end its last sentence still has one
+        if words > 0 && sentences == 0 {
+            sentences = 1;
+        }
+
+        let mut rare: Vec<&String> = counts
+            .iter()
+            .filter(|&(word, &count)| {
+                count == 1
+                    && word.chars().count() >= RARE_MIN_LETTERS
+                    && word.chars().all(char::is_alphabetic)
+            })
+            .map(|(word, _)| word)
+            .collect();
+        rare.sort_by(|a, b| b.chars().count().cmp(&a.chars().count()).then(a.cmp(b)));
+
+        Self {
+            words,
+            unique_words: counts.len(),
+            sentences,
+            clauses,
+            rare_words: rare.into_iter().take(RARE_WORDS).cloned().collect(),
+            syllables,
+            long_words,
+        }
+    }
+
+    /// False for analyses kept before readability was, which need working
+    /// out again
+    pub fn is_current(&self) -> bool {
+        self.words == 0 || self.syllables > 0
+    }
+
+    /// US school grade the text suits, going by sentence length and
+    /// syllables per word. Meant for English.
+    pub fn flesch_kincaid_grade(&self) -> f64 {
+        let words = self.words.max(1) as f64;
+        0.39 * self.average_sentence_words() + 11.8 * self.syllables as f64 / words - 15.59
+    }
+
+    /// Läsbarhetsindex: sentence length plus the percentage of long words.
+    /// Works across languages; around 30 is easy, 40 ordinary prose and 50
+    /// and up hard.
+    pub fn lix(&self) -> f64 {
+        self.average_sentence_words() + 100.0 * self.long_words as f64 / self.words.max(1) as f64
+    }
+
+    /// A short readability label for library lists: the Flesch-Kincaid
+    /// grade for English, or LIX for other languages
+    pub fn readability(&self, language: Option<&str>) -> String {
+        match language {
+            Some("en") | None => format!("grade {:.0}", self.flesch_kincaid_grade().max(1.0)),
+            Some(_) => format!("LIX {:.0}", self.lix()),
+        }
+    }
+
+    /// A speed for this book, starting from the reader's usual `wpm`:
+    /// slower for hard text and a little faster for easy text, to the
+    /// nearest ten
+    pub fn suggested_wpm(&self, wpm: u32) -> u32 {
+        let (slowest, fastest) = SUGGESTED_WPM_RANGE;
+        let factor = (1.0 - (self.lix() - LIX_TYPICAL) * WPM_PER_LIX).clamp(slowest, fastest);
+        (wpm as f64 * factor / 10.0).round() as u32 * 10
+    }
+
+    pub fn average_sentence_words(&self) -> f64 {
+        self.words as f64 / self.sentences.max(1) as f64
+    }
+
+    /// Time to read the whole book at `wpm`, with the pauses at sentence
+    /// and clause ends
+    pub fn reading_time(&self, wpm: u32, pauses: &Pauses) -> Duration {
+        let periods = self.words as f64
+            + self.sentences as f64 * (pauses.sentence - 1.0)
+            + self.clauses as f64 * (pauses.clause - 1.0);
+        Duration::from_secs_f64(periods.max(0.0) * 60.0 / wpm.max(1) as f64)
+    }
+
+    /// Labelled lines for the analysis screen. `read` is the fraction of
+    /// the book already read, for the time left.
+    pub fn summary(&self, wpm: u32, pauses: &Pauses, read: f64) -> Vec<(&'static str, String)> {
+        let total = self.reading_time(wpm, pauses);
+        let left = total.mul_f64((1.0 - read).clamp(0.0, 1.0));
+        let unique_percent = self.unique_words as f64 * 100.0 / self.words.max(1) as f64;
+        let rare = if self.rare_words.is_empty() {
+            "none".to_string()
+        } else {
+            self.rare_words.join(", ")
+        };
+        vec![
+            ("Words", self.words.to_string()),
+            (
+                "Unique words",
+                format!("{} ({:.0}%)", self.unique_words, unique_percent),
+            ),
+            (
+                "Sentences",
+                format!(
+                    "{}, {:.1} words on average",
+                    self.sentences,
+                    self.average_sentence_words()
+                ),
+            ),
+            (
+                "Reading time",
+                format!("{} at {} WPM", format_reading_time(total), wpm),
+            ),
+            ("Time left", format_reading_time(left)),
+            (
+                "Readability",
+                format!(
+                    "grade {:.1} (Flesch-Kincaid), LIX {:.0}",
+                    self.flesch_kincaid_grade(),
+                    self.lix()
+                ),
+            ),
+            ("Rare words", rare),
+        ]
+    }
+}
+
+/// Work out the analysis of a book imported before analyses were kept, or
+/// before they held everything they do now, from its file. True if it did,
+/// and the library wants saving.
+pub fn fill_analysis(book: &mut Book) -> bool {
+    if book.analysis.as_ref().is_some_and(BookAnalysis::is_current) {
+        return false;
+    }
+    match fs::read_to_string(book_file(&book.id)) {
+        Ok(text) => {
+            book.analysis = Some(BookAnalysis::of(&text));
+            true
+        }
+        Err(e) => {
+            tracing::warn!("Couldn't read book {} to analyze it: {}", book.id, e);
+            false
+        }
+    }
+}
+
+/// A reading time like "3 h 05 min" or "12 min"
+pub fn format_reading_time(time: Duration) -> String {
+    let minutes = (time.as_secs_f64() / 60.0).round() as u64;
+    match minutes {
+        0 => "under a minute".to_string(),
+        1..=59 => format!("{} min", minutes),
+        _ => format!("{} h {:02} min", minutes / 60, minutes % 60),
+    }
+}
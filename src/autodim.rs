@@ -0,0 +1,64 @@
+//! Auto-dim: after a few seconds of playback without a key press,
+//! everything but the word fades toward the background, and comes back on
+//! pausing or any input
+
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::time::Instant;
+
+/// The `[auto_dim]` table in `config.toml`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoDim {
+    pub enabled: bool,
+    /// Seconds of undisturbed playback before fading starts
+    pub after_seconds: f64,
+    /// Seconds the fade takes
+    pub fade_seconds: f64,
+    /// How bright everything but the word ends up, from 0 (gone) to 1
+    pub level: f64,
+}
+
+impl Default for AutoDim {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            after_seconds: 5.0,
+            fade_seconds: 2.0,
+            level: 0.2,
+        }
+    }
+}
+
+/// Times how long playback has gone on undisturbed. It is asked while
+/// drawing, so it keeps its clock in a `Cell`.
+#[derive(Debug, Default)]
+pub struct Dimmer {
+    since: Cell<Option<Instant>>,
+}
+
+impl Dimmer {
+    /// How bright to draw everything but the word, from 1 down to the
+    /// configured level. The clock starts with playback and stops when it
+    /// pauses.
+    pub fn brightness(&self, playing: bool, settings: &AutoDim) -> f32 {
+        if !settings.enabled || !playing {
+            self.since.set(None);
+            return 1.0;
+        }
+        let since = self.since.get().unwrap_or_else(|| {
+            let now = Instant::now();
+            self.since.set(Some(now));
+            now
+        });
+        let faded = (since.elapsed().as_secs_f64() - settings.after_seconds.max(0.0))
+            / settings.fade_seconds.max(0.01);
+        let level = settings.level.clamp(0.0, 1.0);
+        (1.0 - faded.clamp(0.0, 1.0) * (1.0 - level)) as f32
+    }
+
+    /// Bring everything back after input, counting again from the next draw
+    pub fn wake(&self) {
+        self.since.set(None);
+    }
+}
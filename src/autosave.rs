@@ -0,0 +1,96 @@
+//! How often reading progress is written to the library while playing.
+//! Saves come after a number of words or a stretch of time, never closer
+//! together than a minimum gap, so fast reading doesn't mean a disk write
+//! every second. Pausing, quitting and jumping always save.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// The `[autosave]` table in `config.toml`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutosaveSettings {
+    /// Save after this many words read; 0 goes by time alone
+    pub words: usize,
+    /// Save after this long reading; 0 goes by words alone
+    pub seconds: f64,
+    /// Shortest time between two saves while playing
+    pub min_gap_seconds: f64,
+}
+
+impl Default for AutosaveSettings {
+    fn default() -> Self {
+        Self {
+            words: 10,
+            seconds: 0.0,
+            min_gap_seconds: 2.0,
+        }
+    }
+}
+
+impl AutosaveSettings {
+    fn min_gap(&self) -> Duration {
+        Duration::try_from_secs_f64(self.min_gap_seconds).unwrap_or_default()
+    }
+
+    fn interval(&self) -> Option<Duration> {
+        Duration::try_from_secs_f64(self.seconds)
+            .ok()
+            .filter(|interval| !interval.is_zero())
+    }
+}
+
+/// Counts the reading done since progress was last saved
+#[derive(Debug)]
+pub struct Autosave {
+    words: usize,
+    last_save: Instant,
+}
+
+impl Default for Autosave {
+    fn default() -> Self {
+        Self {
+            words: 0,
+            last_save: Instant::now(),
+        }
+    }
+}
+
+impl Autosave {
+    /// Count `words` more read
+    pub fn read(&mut self, words: usize) {
+        self.words += words;
+    }
+
+    /// Whether anything has been read since the last save
+    pub fn pending(&self) -> bool {
+        self.words > 0
+    }
+
+    /// Whether progress should be saved now: reading has stopped with
+    /// words unsaved, or enough has been read since the last save and that
+    /// save wasn't too recent
+    pub fn due(&self, playing: bool, settings: &AutosaveSettings) -> bool {
+        if !self.pending() {
+            return false;
+        }
+        if !playing {
+            return true;
+        }
+        let since = self.last_save.elapsed();
+        if since < settings.min_gap() {
+            return false;
+        }
+        let by_words = settings.words > 0 && self.words >= settings.words;
+        let by_time = settings
+            .interval()
+            .is_some_and(|interval| since >= interval);
+        by_words || by_time
+    }
+
+    /// Progress has just been saved
+    pub fn saved(&mut self) {
+        self.words = 0;
+        self.last_save = Instant::now();
+    }
+}
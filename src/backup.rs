@@ -0,0 +1,157 @@
+//! Backups of the whole config directory, as a zstd-compressed tar archive:
+//! the library, book texts, config and everything else kept there. Restoring
+//! one checks that every book in the restored library still has its text.
+
+use crate::library::{
+    book_file, books_dir, config_dir, ensure_config_dirs, library_backup_file, library_file,
+    read_library,
+};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// zstd's default level: quick, and book texts shrink well at it
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// What a backup or restore covered
+#[derive(Debug, Default)]
+pub struct BackupReport {
+    pub files: usize,
+    pub books: usize,
+    /// Titles of restored books whose text is missing
+    pub missing: Vec<String>,
+    /// Book texts no book in the library refers to
+    pub orphans: usize,
+}
+
+impl BackupReport {
+    pub fn summary(&self) -> String {
+        let mut summary = format!("{} files, {} books", self.files, self.books);
+        if !self.missing.is_empty() {
+            summary += &format!("\nBooks missing their text: {}", self.missing.join(", "));
+        }
+        if self.orphans > 0 {
+            summary += &format!("\n{} book files belong to no book", self.orphans);
+        }
+        summary
+    }
+}
+
+/// Files worth keeping: everything but the app's socket, temporary files
+/// and the library backup, which would be stale by the time it's restored
+fn backed_up_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let file_type = fs::symlink_metadata(&path)?.file_type();
+        if file_type.is_dir() {
+            backed_up_files(&path, files)?;
+        } else if file_type.is_file()
+            && path.extension().is_none_or(|ext| ext != "tmp")
+            && path != library_backup_file()
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Pack the config directory into `archive`
+pub fn create_backup(archive: &Path) -> io::Result<BackupReport> {
+    ensure_config_dirs()?;
+    let root = config_dir();
+    let mut files = Vec::new();
+    backed_up_files(&root, &mut files)?;
+    // The archive itself, if it is being written inside the config directory
+    let archive = std::path::absolute(archive)?;
+    files.retain(|path| *path != archive);
+
+    let encoder = zstd::Encoder::new(File::create(&archive)?, COMPRESSION_LEVEL)?;
+    let mut tar = tar::Builder::new(encoder);
+    for path in &files {
+        let name = path.strip_prefix(&root).map_err(io::Error::other)?;
+        tar.append_path_with_name(path, name)?;
+    }
+    tar.into_inner()?.finish()?.sync_all()?;
+
+    let books = read_library(&library_file()).map_or(0, |library| library.books.len());
+    Ok(BackupReport {
+        files: files.len(),
+        books,
+        ..BackupReport::default()
+    })
+}
+
+/// Unpack `archive` over the config directory, keeping the library being
+/// replaced as `library.json.bak`. The archive is unpacked beside the
+/// config directory first and only moved into place if it holds a
+/// readable library.
+pub fn restore_backup(archive: &Path) -> io::Result<BackupReport> {
+    let root = config_dir();
+    let staging = root.with_file_name("rsvp-reader-restore");
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    let unpacked = File::open(archive)
+        .and_then(zstd::Decoder::new)
+        .and_then(|decoder| tar::Archive::new(decoder).unpack(&staging))
+        .and_then(|()| match read_library(&staging.join("library.json")) {
+            Some(_) => Ok(()),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the archive has no readable library.json",
+            )),
+        });
+    if let Err(e) = unpacked {
+        if let Err(e) = fs::remove_dir_all(&staging) {
+            tracing::warn!("Couldn't remove {}: {}", staging.display(), e);
+        }
+        return Err(e);
+    }
+
+    ensure_config_dirs()?;
+    if library_file().exists() {
+        fs::rename(library_file(), library_backup_file())?;
+    }
+    let mut files = Vec::new();
+    backed_up_files(&staging, &mut files)?;
+    for path in &files {
+        let name = path.strip_prefix(&staging).map_err(io::Error::other)?;
+        let target = root.join(name);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(path, target)?;
+    }
+    fs::remove_dir_all(&staging)?;
+
+    let mut report = verify_library()?;
+    report.files = files.len();
+    Ok(report)
+}
+
+/// Check the library against the book texts on disk
+fn verify_library() -> io::Result<BackupReport> {
+    let library = read_library(&library_file())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "library.json can't be read"))?;
+    let missing = library
+        .books
+        .iter()
+        .filter(|book| !book_file(&book.id).is_file())
+        .map(|book| book.title.clone())
+        .collect();
+    let orphans = fs::read_dir(books_dir())?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let path = entry.path();
+            let id = path.file_stem().and_then(|stem| stem.to_str());
+            path.extension().is_some_and(|ext| ext == "txt")
+                && id.is_some_and(|id| library.book(id).is_none())
+        })
+        .count();
+    Ok(BackupReport {
+        files: 0,
+        books: library.books.len(),
+        missing,
+        orphans,
+    })
+}
@@ -0,0 +1,119 @@
+//! RSVP Reader - native messaging host for the browser extension
+//!
+//! The browser starts this program and talks to it over stdin/stdout, each
+//! message a 32-bit length in native byte order followed by that much JSON.
+//! The extension sends the page's article as
+//!
+//!   {"title": "...", "url": "...", "text": "..."}
+//!
+//! which is imported into the library and opened in the running app, or in
+//! the next one started if none is. Each message gets a reply like
+//! `{"ok": true, "book": "<id>", "delivered": true}` or
+//! `{"ok": false, "error": "..."}`.
+
+use rsvp::config::load_config;
+use rsvp::import::import_text;
+use rsvp::inbox::{self, Request};
+use rsvp::library::{load_library, save_library};
+use rsvp::logging;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{self, Read, Write};
+
+/// Browsers refuse to send more than this to a host anyway
+const MAX_MESSAGE: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+struct Article {
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    text: String,
+}
+
+/// Read one message, or `None` once the browser closes the pipe
+fn read_message(input: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut len = [0u8; 4];
+    match input.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let len = u32::from_ne_bytes(len) as usize;
+    if len > MAX_MESSAGE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "message too long",
+        ));
+    }
+    let mut message = vec![0u8; len];
+    input.read_exact(&mut message)?;
+    Ok(Some(message))
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let bytes = serde_json::to_vec(message)?;
+    output.write_all(&(bytes.len() as u32).to_ne_bytes())?;
+    output.write_all(&bytes)?;
+    output.flush()
+}
+
+/// Import the article and hand it to the app
+fn handle(message: &[u8]) -> Result<Value, String> {
+    let article: Article =
+        serde_json::from_slice(message).map_err(|e| format!("Bad message: {}", e))?;
+    if article.text.trim().is_empty() {
+        return Err("No article text was sent".to_string());
+    }
+
+    let url = article.url.unwrap_or_default();
+    let title = article
+        .title
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| {
+            if url.is_empty() {
+                "Web page".to_string()
+            } else {
+                url.clone()
+            }
+        });
+
+    let config = load_config().unwrap_or_else(|e| {
+        tracing::warn!("{}", e);
+        Default::default()
+    });
+    let mut library = load_library();
+    let book = import_text(
+        &mut library,
+        title.trim(),
+        &url,
+        &article.text,
+        &config.pipeline,
+    )?;
+    // So the next app started opens it if none is running now
+    library.mark_opened(&book.id);
+    save_library(&library).map_err(|e| format!("Couldn't save the library: {}", e))?;
+
+    let delivered = inbox::send(&Request::Open {
+        book_id: book.id.clone(),
+    })
+    .inspect_err(|e| tracing::info!("No app took {}: {}", book.id, e))
+    .is_ok();
+    Ok(json!({ "ok": true, "book": book.id, "delivered": delivered }))
+}
+
+fn main() -> io::Result<()> {
+    let _log = logging::init("rsvp-host");
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+    while let Some(message) = read_message(&mut stdin)? {
+        let reply = handle(&message).unwrap_or_else(|e| {
+            tracing::warn!("Couldn't take the article: {}", e);
+            json!({ "ok": false, "error": e })
+        });
+        write_message(&mut stdout, &reply)?;
+    }
+    Ok(())
+}
@@ -4,19 +4,62 @@
 //!   Space       - Start/Pause reading
 //!   Up/k        - Increase WPM by 50
 //!   Down/j      - Decrease WPM by 50
+//!   1-9         - Speed presets (200 to 1000 WPM)
 //!   Left/h      - Go back 1 word
 //!   Right/l     - Go forward 1 word
 //!   [/b         - Go back 10 words
 //!   ]/w         - Go forward 10 words
+//!   s/Alt+Left  - Start of sentence
+//!   p/Alt+Up    - Start of paragraph
+//!   ( / )       - Previous/next sentence
+//!   { / }       - Previous/next paragraph
+//!   Backspace   - Back to the position before the last jump (Tab: forward)
 //!   r           - Reset to beginning
-//!   o           - Open library
-//!   i           - Import file
+//!   o           - Open library (/ to search)
+//!   Ctrl+r      - Recent books
+//!   P           - Playlists (a in the library adds a book to one)
+//!   L           - Loop the sentence, then the paragraph, then stop looping
+//!   x           - Mark loop point A, then B to loop between them
+//!   i           - Import file (globs like ~/articles/*.txt import several)
 //!   d           - Delete current book
 //!   ?           - Show help
+//!   t           - Cycle color scheme
+//!   C           - Color blindness palette: deuteranopia, protanopia, tritanopia
+//!   u           - Progress in percent, words, time or chapter
+//!   W           - Pace this book by words, characters or syllables
+//!   D           - This book's own color scheme, chunk size and pacing profile
+//!   n           - Narration on/off
+//!   S           - Skim function words on/off
+//!   e           - Keyword emphasis on/off
+//!   c           - Recall check on the last minute of reading
+//!   a           - Book analysis: length, vocabulary and reading time
+//!   g           - Reading statistics: time spent, speed and progress by day
+//!   B           - Speed test: read a set passage, then answer questions on it
+//!   v           - Save the current word to the vocabulary list
+//!   V           - Saved words (x to export them for Anki, X for every book)
+//!   N           - Show the footnote just passed
+//!   U           - Links in the book (Enter opens one in the browser)
+//!   z/F11       - Minimal display: just the word and its focal guide
+//!   F5          - Reload config.toml
+//!   F12         - Timing diagnostics on/off
 //!   q/Escape    - Quit
+//!
+//! Reading keys can be rebound in config.toml.
+//!
+//! Files named on the command line are imported and opened, by the reader
+//! that is already running if there is one. With an empty library, a short
+//! tutorial on the controls is offered.
+//!
+//! `rsvp-tui backup <file.tar.zst>` packs up the library, books and config,
+//! and `rsvp-tui restore <file.tar.zst>` puts them back.
+//!
+//! Problems are logged to `logs/` in the config dir, and `--verbose` logs
+//! what the reader is doing as well.
 
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers, MediaKeyCode,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -24,17 +67,54 @@ use ratatui::{
     backend::CrosstermBackend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    text::{Line, Span, Text},
-    widgets::{Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph, Wrap},
     Frame, Terminal,
 };
-use serde::{Deserialize, Serialize};
+use rsvp::analysis::fill_analysis;
+use rsvp::autodim::Dimmer;
+use rsvp::autosave::Autosave;
+use rsvp::backup::{create_backup, restore_backup};
+use rsvp::blocks::{BlockPause, BlockStop};
+use rsvp::breaks::{format_countdown, BreakTimer, ChapterBreak, MicroPauseCounter};
+use rsvp::config::{
+    load_config, Action, Config, DialogueStyle, FocalGuide, FocusStyle, KeyChord, KeyName, Panel,
+    SentenceEnd,
+};
+use rsvp::diagnostics::Diagnostics;
+use rsvp::footnotes::{footnote_near, Footnote};
+use rsvp::history::JumpHistory;
+use rsvp::import::{
+    import_book, import_books, import_directory, reload_book, BatchImport, ImportOptions,
+    SourceWatch,
+};
+use rsvp::inbox::{self, Inbox, Request};
+use rsvp::language::{detect_book_language, LanguageProfile, Pacing};
+use rsvp::library::{
+    book_file, load_library, setting_name, Book, BookDisplay, ColorScheme, Library, LibraryWatch,
+};
+use rsvp::links::{open_in_browser, BookLink};
+use rsvp::logging;
+use rsvp::night::NightWatch;
+use rsvp::ramp::SpeedRamp;
+use rsvp::recall::{RecallCheck, RecentWords};
+use rsvp::repeat::{Mark, Repeat};
+use rsvp::sessions::{book_stats, completion_summary, SessionTracker};
+use rsvp::speech::Narration;
+use rsvp::speedtest::SpeedTest;
+use rsvp::stats_line::{book_progress, book_summary, library_summary, reading_times, StatsFields};
+use rsvp::text::{
+    display_unit, display_width, emphasis_runs, ends_sentence, frame_weight, split_at_orp,
+    split_long_word, Emphasis, PacingProfile, Pauses, WordSource,
+};
+use rsvp::timing::{Advance, WordClock};
+use rsvp::tutorial::{Progress, Tutorial};
+use rsvp::vocabulary::{export_all_vocabulary, export_vocabulary, VocabEntry};
 use std::{
-    collections::hash_map::DefaultHasher,
     fs,
-    hash::{Hash, Hasher},
     io::{self, stdout},
-    path::PathBuf,
+    ops::Range,
+    path::{Path, PathBuf},
     time::{Duration, Instant},
 };
 
@@ -42,60 +122,88 @@ use std::{
 // Data Structures
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct Book {
-    id: String,
-    title: String,
-    original_path: String,
-    total_words: usize,
-    progress: usize,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct Settings {
-    wpm: u32,
-}
-
-impl Default for Settings {
-    fn default() -> Self {
-        Self { wpm: 300 }
-    }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-struct Library {
-    books: Vec<Book>,
-    last_book: Option<String>,
-    settings: Settings,
-}
-
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum AppMode {
     Reading,
     Library,
+    Recents,
+    Playlists,
+    /// Naming the playlist the selected library book goes into
+    PlaylistName,
+    /// Editing the open book's own display settings
+    BookDisplay,
+    Vocabulary,
+    /// The open book's links
+    Links,
+    Analysis,
+    BookStats,
+    /// Everything about the book picked in the library
+    BookDetails,
+    /// Totals across the whole library
+    LibraryStats,
     FileInput,
     Help,
     Confirm,
+    Break,
+    Recall,
+    SpeedTest,
+    /// Summary of a book just finished, with what to do next
+    Finished,
+    /// Books in progress to pick from, on launch
+    Start,
 }
 
 struct App {
     mode: AppMode,
     library: Library,
-    words: Vec<String>,
+    config: Config,
+    words: WordSource,
     word_index: usize,
     current_book_id: Option<String>,
     current_book_title: String,
+    /// Pacing for the open book's language
+    language: LanguageProfile,
+    /// Characters or syllables in the open book's average word, with that
+    /// pacing
+    units_per_word: f64,
+    /// Settings picked for the open book, and the pacing profile they name
+    display: BookDisplay,
+    profile: PacingProfile,
     is_playing: bool,
     wpm: u32,
-    last_advance: Instant,
+    /// Times the word on screen
+    clock: WordClock,
+    // Frame within an over-long word, paired with the word index it belongs to
+    frame: (usize, usize),
+    word_half_width: usize,
 
     // Library browser state
     library_state: ListState,
+    library_filter: String,
+    library_filter_active: bool,
+
+    // Recent books popup state
+    recents_state: ListState,
+    /// The library book the details screen is about
+    details_book: Option<String>,
+    start_state: ListState,
+    playlists_state: ListState,
+    playlist_input: String,
+    /// The playlist being played through, if the open book came from one
+    playlist: Option<String>,
+    display_input: String,
+
+    // Saved words popup state
+    vocabulary_state: ListState,
+
+    // Links popup state
+    links_state: ListState,
 
     // File input state
     file_input: String,
     file_input_cursor: usize,
     file_input_error: Option<String>,
+    import_options: ImportOptions,
 
     // Confirm dialog state
     confirm_message: String,
@@ -103,70 +211,95 @@ struct App {
 
     // Status message
     status_message: Option<(String, Instant)>,
-}
-
-#[derive(Debug, Clone)]
-enum ConfirmAction {
-    DeleteBook(String),
-}
 
-// ============================================================================
-// Configuration Paths
-// ============================================================================
+    // Break reminders, and micro-pauses with the blank they are showing
+    breaks: BreakTimer,
+    micro_pauses: MicroPauseCounter,
+    blank_until: Option<Instant>,
+    /// The screen between two chapters, while it is up
+    chapter_break: Option<ChapterBreak>,
+    /// A code block or table shown whole, while it is up
+    block_pause: Option<BlockPause>,
+    /// A footnote brought up with the footnote key, and whether reading
+    /// carries on once it is put away
+    footnote: Option<(Footnote, bool)>,
+    /// Whether night mode has the night scheme on
+    night: NightWatch,
+    /// Fades everything but the word during undisturbed playback
+    dimmer: Dimmer,
 
-fn config_dir() -> PathBuf {
-    dirs::config_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("rsvp-reader")
-}
+    // Speech following the display
+    narration: Narration,
 
-fn library_file() -> PathBuf {
-    config_dir().join("library.json")
-}
+    // Words from the last minute of playback, and a recall check on them
+    recent_words: RecentWords,
+    /// Positions before big jumps, for jumping back
+    history: JumpHistory,
+    /// A sentence, paragraph or marked stretch playing over and over
+    repeat: Repeat,
+    recall: Option<RecallCheck>,
+    /// A speed test under way, reading its passage or asking its questions
+    speed_test: Option<SpeedTest>,
+    /// The first-launch tutorial, while it is playing
+    tutorial: Option<Tutorial>,
+    /// Reading sessions, for the statistics screen
+    sessions: SessionTracker,
+    /// The session's speed stepping up, with the speed ramp on
+    ramp: SpeedRamp,
+    /// Reading not yet saved to the library
+    autosave: Autosave,
 
-fn books_dir() -> PathBuf {
-    config_dir().join("books")
-}
+    // Pacing and drawing times, and whether they are shown
+    diagnostics: Diagnostics,
+    show_diagnostics: bool,
+    /// Only the word is drawn, for small panes
+    minimal: bool,
+    /// Set by key presses and resizes, whose effects aren't worth tracking
+    /// one by one
+    needs_redraw: bool,
 
-fn ensure_config_dirs() -> io::Result<()> {
-    fs::create_dir_all(config_dir())?;
-    fs::create_dir_all(books_dir())?;
-    Ok(())
+    // Books sent over by the browser extension
+    inbox: Option<Inbox>,
+    // Saves to the library by the other frontend or a sync tool
+    library_watch: LibraryWatch,
+    // The open book's source file, with watch_sources on
+    source_watch: SourceWatch,
 }
 
-fn load_library() -> Library {
-    if let Ok(content) = fs::read_to_string(library_file()) {
-        serde_json::from_str(&content).unwrap_or_default()
-    } else {
-        Library::default()
-    }
-}
+/// How long a status message stays in the stats bar
+const STATUS_DURATION: Duration = Duration::from_secs(3);
 
-fn save_library(library: &Library) {
-    let _ = ensure_config_dirs();
-    if let Ok(content) = serde_json::to_string_pretty(library) {
-        let _ = fs::write(library_file(), content);
-    }
-}
+/// Longest the main loop waits for a key with nothing else to do
+const IDLE_POLL: Duration = Duration::from_millis(500);
 
-// ============================================================================
-// Text Processing
-// ============================================================================
+/// How often the main loop wakes for a break's countdown, or to stop
+/// narration soon after pausing
+const BUSY_POLL: Duration = Duration::from_millis(100);
 
-fn tokenize_text(text: &str) -> Vec<String> {
-    text.split_whitespace().map(|s| s.to_string()).collect()
+/// The parts of the screen that ticks change, compared before and after
+/// each tick
+#[derive(PartialEq)]
+struct Screen {
+    mode: AppMode,
+    word: (usize, usize),
+    words: usize,
+    playing: bool,
+    blank: bool,
+    status: Option<Instant>,
+    countdown: Option<String>,
+    hint: bool,
+    chapter_break: bool,
+    night: bool,
+    /// Brightness around the word, in twentieths
+    dim: u8,
 }
 
-/// Calculate the Optimal Recognition Point (ORP) for a word
-fn calculate_orp(word: &str) -> usize {
-    let len = word.chars().count();
-    match len {
-        0..=1 => 0,
-        2..=5 => 1,
-        6..=9 => 2,
-        10..=13 => 3,
-        _ => 4,
-    }
+#[derive(Debug, Clone)]
+enum ConfirmAction {
+    DeleteBook(String),
+    DeletePlaylist(String),
+    StartTutorial,
+    ReloadBook(String),
 }
 
 // ============================================================================
@@ -176,25 +309,79 @@ fn calculate_orp(word: &str) -> usize {
 impl App {
     fn new() -> Self {
         let library = load_library();
-        let wpm = library.settings.wpm;
+        let (config, config_error) = match load_config() {
+            Ok(config) => {
+                let conflict = config.keys.conflicts().into_iter().next();
+                let conflict = conflict.map(|conflict| (conflict, Instant::now()));
+                (config, conflict)
+            }
+            Err(e) => (Config::default(), Some((e, Instant::now()))),
+        };
+        let wpm = config.clamp_wpm(library.settings.wpm);
 
         Self {
             mode: AppMode::Reading,
             library,
-            words: Vec::new(),
+            config,
+            words: WordSource::default(),
             word_index: 0,
             current_book_id: None,
             current_book_title: String::new(),
+            language: LanguageProfile::default(),
+            units_per_word: 1.0,
+            display: BookDisplay::default(),
+            profile: PacingProfile::default(),
             is_playing: false,
             wpm,
-            last_advance: Instant::now(),
+            clock: WordClock::default(),
+            frame: (0, 0),
+            word_half_width: usize::MAX,
             library_state: ListState::default(),
+            library_filter: String::new(),
+            library_filter_active: false,
+            recents_state: ListState::default(),
+            details_book: None,
+            start_state: ListState::default(),
+            playlists_state: ListState::default(),
+            playlist_input: String::new(),
+            playlist: None,
+            display_input: String::new(),
+            vocabulary_state: ListState::default(),
+            links_state: ListState::default(),
             file_input: String::new(),
             file_input_cursor: 0,
             file_input_error: None,
+            import_options: ImportOptions::default(),
             confirm_message: String::new(),
             confirm_action: None,
-            status_message: None,
+            status_message: config_error,
+            breaks: BreakTimer::default(),
+            micro_pauses: MicroPauseCounter::default(),
+            blank_until: None,
+            chapter_break: None,
+            block_pause: None,
+            footnote: None,
+            night: NightWatch::default(),
+            dimmer: Dimmer::default(),
+            narration: Narration::default(),
+            recent_words: RecentWords::default(),
+            history: JumpHistory::default(),
+            repeat: Repeat::default(),
+            recall: None,
+            speed_test: None,
+            tutorial: None,
+            sessions: SessionTracker::default(),
+            ramp: SpeedRamp::default(),
+            autosave: Autosave::default(),
+            diagnostics: Diagnostics::default(),
+            show_diagnostics: false,
+            minimal: false,
+            needs_redraw: true,
+            inbox: Inbox::listen()
+                .inspect_err(|e| tracing::warn!("Not listening for books to open: {}", e))
+                .ok(),
+            library_watch: LibraryWatch::default(),
+            source_watch: SourceWatch::default(),
         }
     }
 
@@ -202,24 +389,99 @@ impl App {
         self.status_message = Some((msg.to_string(), Instant::now()));
     }
 
+    /// Save the library, saying so if it fails
+    fn save_library(&mut self) {
+        if let Err(e) = self.library_watch.save(&self.library) {
+            tracing::error!("Couldn't save the library: {}", e);
+            self.show_status(&format!("Couldn't save the library: {}", e));
+        }
+    }
+
+    /// Follow what the other frontend, or a sync tool, saved to the library:
+    /// its speed, and its place in this book unless reading is going on
+    /// here, whose own place is saved over it
+    fn sync_library(&mut self) {
+        let Some(on_disk) = self.library_watch.poll() else {
+            return;
+        };
+        let changes = self.library.sync(on_disk, self.current_book_id.as_deref());
+        if let Some(wpm) = changes.wpm {
+            self.wpm = self.config.clamp_wpm(wpm);
+        }
+        let Some((progress, offset)) = changes.progress.filter(|_| !self.is_playing) else {
+            return;
+        };
+        let index = match offset {
+            Some(offset) => self.words.index_at_offset(offset),
+            None => progress.min(self.words.len().saturating_sub(1)),
+        };
+        if index != self.word_index {
+            self.jump(index);
+            self.show_status("Caught up with reading saved elsewhere");
+        }
+    }
+
+    /// Offer to read the open book again if the file it was imported from
+    /// has changed
+    fn check_source(&mut self) {
+        if self.mode != AppMode::Reading || !self.source_watch.changed() {
+            return;
+        }
+        let Some(book_id) = self.current_book_id.clone() else {
+            return;
+        };
+        self.is_playing = false;
+        self.save_progress();
+        self.confirm_message = format!("'{}' changed on disk. Reload it?", self.current_book_title);
+        self.confirm_action = Some(ConfirmAction::ReloadBook(book_id));
+        self.mode = AppMode::Confirm;
+    }
+
+    /// Read a book again from the file it was imported from
+    fn reload_book(&mut self, book_id: &str) {
+        self.save_progress();
+        if let Err(e) = reload_book(&mut self.library, book_id, &self.config.pipeline) {
+            self.show_status(&format!("Couldn't reload: {}", e));
+            return;
+        }
+        self.save_library();
+        if self.load_book(book_id) {
+            self.show_status(&format!("Reloaded: {}", self.current_book_title));
+        }
+    }
+
+    /// Re-read `config.toml`, keeping the current settings if it has errors
+    fn reload_config(&mut self) {
+        match load_config() {
+            Ok(config) => {
+                self.config = config;
+                self.wpm = self.config.clamp_wpm(self.wpm);
+                self.update_language();
+                self.night.recheck();
+                self.narration.reset();
+                // A key bound twice matters more than the reload itself
+                let conflict = self.config.keys.conflicts().into_iter().next();
+                self.show_status(conflict.as_deref().unwrap_or("Config reloaded"));
+            }
+            Err(e) => self.show_status(&e),
+        }
+    }
+
     fn load_last_book(&mut self) {
-        if let Some(book_id) = self.library.last_book.clone() {
+        if let Some(book_id) = self.library.last_read().map(|book| book.id.clone()) {
             self.load_book(&book_id);
         }
     }
 
     fn load_book(&mut self, book_id: &str) -> bool {
-        let book_file = books_dir().join(format!("{}.txt", book_id));
-
-        let content = match fs::read_to_string(&book_file) {
-            Ok(c) => c,
+        self.words = match WordSource::open(book_file(book_id)) {
+            Ok(words) => words,
             Err(_) => {
                 self.show_status("Book file not found");
                 return false;
             }
         };
 
-        self.words = tokenize_text(&content);
         if self.words.is_empty() {
             self.show_status("Book is empty");
             return false;
@@ -228,405 +490,2678 @@ impl App {
         // Find book info
         if let Some(book) = self.library.books.iter().find(|b| b.id == book_id) {
             self.current_book_title = book.title.clone();
-            self.word_index = book.progress.min(self.words.len().saturating_sub(1));
+            let saved = match book.progress_offset {
+                Some(offset) => self.words.index_at_offset(offset),
+                None => book.progress.min(self.words.len().saturating_sub(1)),
+            };
+            self.word_index = self.config.resume_at.snap(&self.words, saved);
         } else {
             self.current_book_title = "Unknown".to_string();
             self.word_index = 0;
         }
 
+        // Books imported before languages and analyses were kept get them now
+        let mut suggested_wpm = None;
+        if let Some(book) = self.library.book_mut(book_id) {
+            if book.language.is_none() {
+                book.language = detect_book_language(&self.words).map(str::to_string);
+            }
+            fill_analysis(book);
+            if self.config.suggest_wpm && book.progress_at == 0 {
+                suggested_wpm = book.analysis.as_ref().map(|a| a.suggested_wpm(self.wpm));
+            }
+        }
+        if let Some(wpm) = suggested_wpm {
+            self.wpm = self.config.clamp_wpm(wpm);
+            self.library.settings.set_wpm(self.wpm);
+        }
+
+        // Opening a book, from anywhere, ends a speed test or the tutorial
+        self.speed_test = None;
+        self.tutorial = None;
+        self.chapter_break = None;
+        self.block_pause = None;
+        self.footnote = None;
+        self.playlist = None;
+        self.repeat.clear();
         self.current_book_id = Some(book_id.to_string());
-        self.library.last_book = Some(book_id.to_string());
-        save_library(&self.library);
+        match self.library.books.iter().find(|b| b.id == book_id) {
+            Some(book) if self.config.watch_sources => self.source_watch.watch(book),
+            _ => self.source_watch.stop(),
+        }
+        self.update_language();
+        self.recent_words.clear();
+        self.history.clear();
+        self.library.mark_opened(book_id);
+        self.save_library();
+        if suggested_wpm.is_some() {
+            self.show_status(&format!("{} WPM suits this book", self.wpm));
+        }
 
         true
     }
 
-    fn import_file(&mut self, path: &str) -> bool {
-        let path = PathBuf::from(shellexpand(path));
+    /// Loop the sentence around the current word, then its paragraph, then
+    /// stop looping
+    fn toggle_loop(&mut self) {
+        if self.words.is_empty() {
+            return;
+        }
+        match self.repeat.cycle(&self.words, self.word_index) {
+            Some(scope) => {
+                self.start_loop();
+                self.show_status(&format!("Looping the {}", scope));
+            }
+            None => self.show_status("Loop off"),
+        }
+    }
 
-        let content = match fs::read_to_string(&path) {
-            Ok(c) => c,
-            Err(e) => {
-                self.file_input_error = Some(format!("Error: {}", e));
-                return false;
+    /// Mark loop point A, then point B, then stop looping
+    fn mark_loop(&mut self) {
+        if self.words.is_empty() {
+            return;
+        }
+        match self.repeat.mark(self.word_index) {
+            Mark::Started => self.show_status("Loop from here: mark where it ends"),
+            Mark::Looping => {
+                self.start_loop();
+                self.show_status("Looping A-B");
             }
+            Mark::Cleared => self.show_status("Loop off"),
+        }
+    }
+
+    /// Go back to the start of the loop just set
+    fn start_loop(&mut self) {
+        if let Some(active) = self.repeat.active {
+            self.jump(active.start);
+            self.clock.restart();
+        }
+    }
+
+    /// Start the loop over, faster by the repeat settings' step
+    fn next_pass(&mut self, start: usize) {
+        self.word_index = start;
+        let step = self.config.repeat.wpm_step;
+        if step > 0 {
+            self.wpm = self.config.clamp_wpm(self.wpm + step);
+            self.library.settings.set_wpm(self.wpm);
+        }
+        let passes = self.repeat.active.map_or(0, |active| active.passes);
+        self.show_status(&format!("Pass {} at {} WPM", passes + 1, self.wpm));
+    }
+
+    /// Open a playlist at its first unfinished item
+    fn play_playlist(&mut self, name: &str) {
+        let Some(book_id) = self.library.playlist_start(name).map(|b| b.id.clone()) else {
+            self.show_status("Playlist is empty");
+            return;
         };
+        self.save_progress();
+        if self.load_book(&book_id) {
+            self.playlist = Some(name.to_string());
+            self.mode = AppMode::Reading;
+        }
+    }
 
-        let words = tokenize_text(&content);
-        if words.is_empty() {
-            self.file_input_error = Some("File is empty".to_string());
+    /// Move on to the playing playlist's next item, still playing. False
+    /// at the end of the playlist.
+    fn next_in_playlist(&mut self) -> bool {
+        let (Some(name), Some(book_id)) = (self.playlist.clone(), &self.current_book_id) else {
+            return false;
+        };
+        let Some(next) = self.library.playlist_next(&name, book_id) else {
+            return false;
+        };
+        let (next_id, title) = (next.id.clone(), next.title.clone());
+        self.end_session();
+        self.save_progress();
+        if !self.load_book(&next_id) {
             return false;
         }
+        self.playlist = Some(name.clone());
+        self.clock.restart();
+        self.show_status(&format!("Next in {}: {}", name, title));
+        true
+    }
 
-        // Generate unique ID
-        let mut hasher = DefaultHasher::new();
-        path.hash(&mut hasher);
-        std::time::SystemTime::now().hash(&mut hasher);
-        let book_id = format!("{:x}", hasher.finish())[..12].to_string();
+    /// Add the current word and its sentence to the book's vocabulary list
+    fn save_word(&mut self) {
+        let Some(book_id) = self.current_book_id.clone() else {
+            self.show_status("No book loaded");
+            return;
+        };
+        let Some(entry) = VocabEntry::capture(&self.words, self.word_index) else {
+            return;
+        };
+        let word = entry.word.clone();
+        let Some(book) = self.library.book_mut(&book_id) else {
+            return;
+        };
+        if book.save_word(entry) {
+            self.save_library();
+            self.show_status(&format!("Saved \"{}\"", word));
+        } else {
+            self.show_status(&format!("\"{}\" is already saved", word));
+        }
+    }
 
-        // Save to books directory
-        let _ = ensure_config_dirs();
-        let book_file = books_dir().join(format!("{}.txt", book_id));
-        if fs::write(&book_file, &content).is_err() {
-            self.file_input_error = Some("Failed to save book".to_string());
-            return false;
+    /// Pause on the latest footnote at or just before the current words and
+    /// show it
+    fn show_footnote(&mut self) {
+        let covered = self.current_unit().map_or(1, |(_, covered)| covered);
+        let last = self.word_index + covered - 1;
+        let Some(note) = footnote_near(self.footnotes(), last).cloned() else {
+            self.show_status("No footnote nearby");
+            return;
+        };
+        self.footnote = Some((note, self.is_playing));
+        self.is_playing = false;
+    }
+
+    /// Whether a footnote reference is among the words on screen
+    fn at_footnote(&self) -> bool {
+        let covered = self.current_unit().map_or(1, |(_, covered)| covered);
+        let words = self.word_index..self.word_index + covered;
+        self.footnotes()
+            .iter()
+            .any(|note| words.contains(&note.word))
+    }
+
+    /// Footnotes of the open book
+    fn footnotes(&self) -> &[Footnote] {
+        self.current_book_id
+            .as_ref()
+            .and_then(|id| self.library.book(id))
+            .map_or(&[], |book| &book.footnotes)
+    }
+
+    /// Links kept with the open book
+    fn links(&self) -> &[BookLink] {
+        self.current_book_id
+            .as_ref()
+            .and_then(|id| self.library.book(id))
+            .map_or(&[], |book| &book.links)
+    }
+
+    /// What to read after the open book
+    fn next_book(&self) -> Option<&Book> {
+        self.library.next_book(self.current_book_id.as_deref()?)
+    }
+
+    /// Saved words of the open book
+    fn vocabulary(&self) -> &[VocabEntry] {
+        self.current_book_id
+            .as_ref()
+            .and_then(|id| self.library.book(id))
+            .map_or(&[], |book| &book.vocabulary)
+    }
+
+    /// Follow playback in the session log, logging sessions as they end
+    fn track_session(&mut self) {
+        let before = self.sessions.in_session();
+        let ended = self.sessions.update(
+            self.current_book_id.as_deref(),
+            self.is_playing,
+            self.word_index,
+        );
+        let started = self.sessions.in_session() && (!before || ended.is_some());
+        if let Some(session) = ended {
+            self.library.stats.record_session(session);
+            self.save_library();
+            self.ramp.stop();
+        }
+
+        // With the speed ramp on, each session starts slow and speeds up
+        // minute by minute
+        let ramp = self.config.ramp;
+        if started && ramp.enabled {
+            let start = match ramp.start_wpm {
+                0 => self.library.settings.wpm,
+                wpm => wpm,
+            };
+            let target = ramp.target_wpm;
+            self.wpm = self.config.clamp_wpm(start);
+            self.ramp.start(self.wpm);
+            self.show_status(&format!("Speed ramp: {} WPM, up to {}", self.wpm, target));
+        }
+        if let Some(step) = self.ramp.update(self.is_playing, self.wpm, &ramp) {
+            self.sessions.sustained(step.sustained);
+            if let Some(next) = step.next {
+                self.wpm = self.config.clamp_wpm(next);
+                self.show_status(&format!("Speed ramp: {} WPM", self.wpm));
+            }
+        }
+    }
+
+    /// Log the session in progress now instead of when it would end, as on
+    /// quitting or before showing statistics. The library still needs
+    /// saving.
+    fn end_session(&mut self) {
+        if let Some(session) = self.sessions.finish() {
+            self.library.stats.record_session(session);
+        }
+    }
+
+    /// Pick the pacing profile for the open book's language, with the
+    /// pacing and display settings picked for the book if there are any
+    fn update_language(&mut self) {
+        let book = self
+            .current_book_id
+            .as_ref()
+            .and_then(|id| self.library.books.iter().find(|b| &b.id == id));
+        let code = book.and_then(|book| book.language.as_deref());
+        let pacing = book.and_then(|book| book.pacing);
+        self.display = book.map(|book| book.display.clone()).unwrap_or_default();
+        self.language = LanguageProfile::for_language(code, &self.config.languages);
+        if let Some(pacing) = pacing {
+            self.language.pacing = pacing;
+        }
+        self.profile = self
+            .display
+            .profile
+            .as_ref()
+            .and_then(|name| self.config.profiles.get(name))
+            .copied()
+            .unwrap_or_default();
+        if let Some(verse) = self.display.verse {
+            self.profile.line_breaks = verse;
         }
+        self.units_per_word = self.language.units_per_word(&self.words);
+    }
+
+    /// Pace the open book by words, characters or syllables, or as its
+    /// language says
+    fn cycle_pacing(&mut self) {
+        let Some(id) = self.current_book_id.clone() else {
+            return;
+        };
+        let Some(book) = self.library.book_mut(&id) else {
+            return;
+        };
+        let pacing = Pacing::next_for_book(book.pacing);
+        book.pacing = pacing;
+        self.save_library();
+        self.update_language();
+        let message = match pacing {
+            Some(pacing) => format!("Pacing by {} ({})", pacing, pacing.rate_label()),
+            None => format!("Pacing by {}, as the book's language", self.language.pacing),
+        };
+        self.show_status(&message);
+    }
 
-        // Get title from filename
-        let title = path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("Unknown")
-            .to_string();
-
-        // Add to library
-        let book = Book {
-            id: book_id.clone(),
-            title: title.clone(),
-            original_path: path.to_string_lossy().to_string(),
-            total_words: words.len(),
-            progress: 0,
+    /// Save the settings typed in the book display dialog to the open book
+    fn save_book_display(&mut self) {
+        let display = match BookDisplay::parse(&self.display_input, &self.config.profiles) {
+            Ok(display) => display,
+            Err(e) => {
+                self.show_status(&e);
+                return;
+            }
         };
-        self.library.books.push(book);
-        save_library(&self.library);
+        let Some(book) = self
+            .current_book_id
+            .clone()
+            .and_then(|id| self.library.book_mut(&id))
+        else {
+            return;
+        };
+        book.display = display;
+        self.save_library();
+        self.update_language();
+        self.mode = AppMode::Reading;
+        self.show_status("Saved for this book");
+    }
 
-        self.show_status(&format!("Imported: {} ({} words)", title, words.len()));
-        self.load_book(&book_id);
+    /// Sentence and clause pauses, from the book's pacing profile if it
+    /// sets them
+    fn pauses(&self) -> Pauses {
+        self.profile.pauses.unwrap_or(self.config.pauses)
+    }
 
-        true
+    /// Import the file, folder, or every file matching a glob pattern, named
+    /// in the import dialog
+    fn import_file(&mut self, input: &str) -> bool {
+        match expand_paths(input).and_then(|paths| self.import_paths(&paths)) {
+            Ok(()) => true,
+            Err(e) => {
+                self.file_input_error = Some(e);
+                false
+            }
+        }
+    }
+
+    /// Import a folder or files and open the first book. Errors are
+    /// user-facing messages.
+    fn import_paths(&mut self, paths: &[PathBuf]) -> Result<(), String> {
+        let filters = self.import_options.filters(&self.config.pipeline);
+
+        if let [dir] = paths {
+            if dir.is_dir() {
+                let batch =
+                    import_directory(&mut self.library, dir, &self.import_options, &filters);
+                return self.finish_batch(batch);
+            }
+        }
+
+        if let [path] = paths {
+            let book = import_book(&mut self.library, path, &filters)?;
+            self.show_status(&format!(
+                "Imported: {} ({} words)",
+                book.title, book.total_words
+            ));
+            self.load_book(&book.id);
+            return Ok(());
+        }
+
+        let batch = import_books(&mut self.library, paths, &filters);
+        self.finish_batch(batch)
+    }
+
+    /// Open the first book of a multi-file import and report how it went
+    fn finish_batch(&mut self, batch: BatchImport) -> Result<(), String> {
+        match batch.imported.first() {
+            Some(first) => {
+                let first_id = first.id.clone();
+                self.load_book(&first_id);
+                self.show_status(&batch.summary());
+                Ok(())
+            }
+            None => Err(batch.summary()),
+        }
     }
 
     fn save_progress(&mut self) {
+        self.autosave.saved();
         if let Some(ref book_id) = self.current_book_id {
             if let Some(book) = self.library.books.iter_mut().find(|b| b.id == *book_id) {
-                book.progress = self.word_index;
+                book.set_progress(self.word_index, self.words.offset_of(self.word_index));
             }
-            save_library(&self.library);
+            self.save_library();
         }
     }
 
     fn tick(&mut self) {
         // Clear old status messages
         if let Some((_, instant)) = &self.status_message {
-            if instant.elapsed() > Duration::from_secs(3) {
+            if instant.elapsed() >= STATUS_DURATION {
                 self.status_message = None;
             }
         }
 
+        self.check_inbox();
+        self.sync_library();
+        self.check_source();
+
+        let system_dark = || dark_light::detect() == dark_light::Mode::Dark;
+        self.night.update(&self.config.night_mode, system_dark);
+
+        let pause = self.chapter_break.as_ref();
+        if pause.is_some_and(ChapterBreak::is_over) {
+            self.chapter_break = None;
+        }
+
+        // Break reminders
+        if self.breaks.update(self.is_playing, &self.config.breaks) {
+            self.is_playing = false;
+            self.save_progress();
+            self.mode = AppMode::Break;
+        }
+        let break_over = self.breaks.remaining(&self.config.breaks) == Some(Duration::ZERO);
+        if break_over && self.config.breaks.auto_resume {
+            self.finish_break();
+        }
+
+        self.track_session();
+
+        // The tutorial waits at the end of each lesson for its task
+        if let Some(tutorial) = &mut self.tutorial {
+            let help_open = self.mode == AppMode::Help;
+            match tutorial.update(self.word_index, self.wpm, self.is_playing, help_open) {
+                Progress::Reading => {}
+                Progress::Waiting => self.is_playing = false,
+                Progress::Next => {
+                    self.is_playing = true;
+                    self.clock.restart();
+                }
+                Progress::Finished => {
+                    self.tutorial = None;
+                    self.show_status("That's the tutorial. Press 'i' to import a book.");
+                }
+            }
+        }
+
         // Advance word if playing
         if self.is_playing && !self.words.is_empty() {
-            let delay = Duration::from_secs_f64(60.0 / self.wpm as f64);
-            if self.last_advance.elapsed() >= delay {
-                self.last_advance = Instant::now();
-                if self.word_index < self.words.len() - 1 {
-                    self.word_index += 1;
-                    // Save progress every 10 words
-                    if self.word_index % 10 == 0 {
-                        self.save_progress();
+            let delay = self.current_delay();
+            let since = self.clock.since();
+            let advance = self.clock.advance(delay);
+            if let Advance::Stalled(late) = advance {
+                self.diagnostics.stalled(late);
+                if self.config.pause_on_stall {
+                    self.is_playing = false;
+                    self.show_status("Paused: reading stalled");
+                }
+            }
+            if self.is_playing && advance != Advance::Wait {
+                self.diagnostics.word_shown(delay, since);
+                // Long words are shown as several frames, one word period each
+                let frame = self.frame_index();
+                let (shown, covered) = self.current_unit().unwrap_or_default();
+                if frame + 1 < self.current_frames().len() {
+                    self.frame = (self.word_index, frame + 1);
+                } else if self.word_index + covered < self.words.len() {
+                    // Every few words or sentences, blank the screen for a moment
+                    let micro = &self.config.micro_pauses;
+                    if self.micro_pauses.word_shown(&shown, micro) {
+                        let until = Instant::now() + micro.length();
+                        self.blank_until = Some(until);
+                        self.clock.hold_until(until);
+                    }
+                    for word in shown.split(' ') {
+                        self.recent_words.seen(word);
+                    }
+                    let from = self.word_index;
+                    self.word_index += covered;
+                    self.sessions.word_shown(covered);
+                    self.autosave.read(covered);
+                    // A loop starts over once playback runs past its end
+                    if let Some(start) = self.repeat.wrap(from, self.word_index) {
+                        self.next_pass(start);
+                    }
+                    // Code blocks and tables are skipped or shown whole, as
+                    // the book is set to
+                    let mode = self.display.blocks.unwrap_or_default();
+                    match BlockStop::entering(&self.words, from, self.word_index, mode) {
+                        Some(BlockStop::Skip(to)) => self.word_index = to,
+                        Some(BlockStop::Show(pause)) => {
+                            self.is_playing = false;
+                            self.block_pause = Some(pause);
+                        }
+                        None => {}
+                    }
+                    // Stop between chapters, for a while or until a key
+                    let settings = &self.config.chapter_breaks;
+                    let crossed =
+                        ChapterBreak::crossing(&self.words, from, self.word_index, settings);
+                    if let Some(pause) = crossed {
+                        match pause.until {
+                            Some(until) => self.clock.hold_until(until),
+                            None => self.is_playing = false,
+                        }
+                        self.chapter_break = Some(pause);
                     }
+                } else if let Some(start) = self.repeat.wrap(self.word_index, self.words.len()) {
+                    self.next_pass(start);
+                } else if let Some(test) = &mut self.speed_test {
+                    // The passage is read; on to the questions
+                    self.is_playing = false;
+                    test.reading = false;
+                    self.mode = AppMode::SpeedTest;
+                } else if self.next_in_playlist() {
+                    // Carried on into the playlist's next item
+                } else if self.current_book_id.is_some() {
+                    self.is_playing = false;
+                    self.end_session();
+                    self.save_progress();
+                    self.mode = AppMode::Finished;
                 } else {
                     self.is_playing = false;
                     self.show_status("Finished reading!");
-                    self.save_progress();
                 }
             }
         }
-    }
 
-    fn current_word(&self) -> Option<&str> {
-        self.words.get(self.word_index).map(|s| s.as_str())
-    }
+        // Save what has been read every so often, and once it stops
+        if self.autosave.due(self.is_playing, &self.config.autosave) {
+            self.save_progress();
+        }
 
-    fn progress_percent(&self) -> f64 {
-        if self.words.is_empty() {
-            0.0
-        } else {
-            (self.word_index as f64 / self.words.len() as f64) * 100.0
+        // Narration follows the word on screen
+        let speech = &self.config.speech;
+        let narrated = self.narration.update(
+            self.is_playing,
+            &self.words,
+            self.word_index,
+            self.wpm,
+            speech,
+        );
+        if let Err(e) = narrated {
+            self.show_status(&e);
         }
     }
-}
 
-fn shellexpand(path: &str) -> String {
-    if path.starts_with('~') {
-        if let Some(home) = dirs::home_dir() {
-            return path.replacen('~', &home.to_string_lossy(), 1);
+    /// Open books the browser extension has imported, and files another
+    /// launch was asked to open, since the last tick
+    fn check_inbox(&mut self) {
+        let requests = self.inbox.as_ref().map(Inbox::poll).unwrap_or_default();
+        for request in requests {
+            match request {
+                Request::Open { book_id } => {
+                    self.save_progress();
+                    // The book was added to the library file by another process
+                    self.library.merge(load_library());
+                    if !self.load_book(&book_id) {
+                        continue;
+                    }
+                    self.show_status(&format!("Opened: {}", self.current_book_title));
+                    if self.mode != AppMode::Break {
+                        self.mode = AppMode::Reading;
+                        self.recall = None;
+                        self.is_playing = true;
+                        self.clock.restart();
+                    }
+                }
+                Request::Import { paths } => {
+                    self.save_progress();
+                    match self.import_paths(&paths) {
+                        Ok(()) if self.mode != AppMode::Break => {
+                            self.mode = AppMode::Reading;
+                            self.recall = None;
+                        }
+                        Ok(()) => {}
+                        Err(e) => self.show_status(&e),
+                    }
+                }
+            }
         }
     }
-    path.to_string()
-}
 
-// ============================================================================
-// UI Rendering
-// ============================================================================
+    /// End the current break, early or not, and go back to reading
+    fn finish_break(&mut self) {
+        if let Some(outcome) = self.breaks.end_break(&self.config.breaks) {
+            self.library.stats.record_break(outcome);
+            self.save_library();
+        }
+        self.mode = AppMode::Reading;
+        self.is_playing = !self.words.is_empty();
+        self.clock.restart();
+    }
 
-fn ui(f: &mut Frame, app: &App) {
-    let size = f.area();
+    /// Pause and quiz the reader on the last minute of reading. Returns
+    /// false if too little has been read for a check.
+    fn start_recall_check(&mut self) -> bool {
+        let Some(check) = RecallCheck::new(&self.recent_words, &self.words, self.word_index) else {
+            return false;
+        };
+        self.is_playing = false;
+        self.save_progress();
+        self.recall = Some(check);
+        self.mode = AppMode::Recall;
+        true
+    }
 
-    // Main layout
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3), // Title bar
-            Constraint::Min(10),   // Word display
-            Constraint::Length(1), // Progress bar
-            Constraint::Length(3), // Stats
-        ])
-        .split(size);
+    /// Put the open book aside and play the speed test passage at the
+    /// current speed
+    fn start_speed_test(&mut self) {
+        let passage = match SpeedTest::passage() {
+            Ok(passage) => passage,
+            Err(e) => {
+                self.show_status(&format!("Couldn't start the speed test: {}", e));
+                return;
+            }
+        };
+        self.save_progress();
+        self.end_session();
+        self.save_library();
+        self.tutorial = None;
+        self.source_watch.stop();
+        self.speed_test = Some(SpeedTest::new(self.wpm, self.current_book_id.take()));
+        self.words = passage;
+        self.word_index = 0;
+        self.frame = (0, 0);
+        self.current_book_title = "Speed test".to_string();
+        self.update_language();
+        self.recent_words.clear();
+        self.history.clear();
+        self.mode = AppMode::SpeedTest;
+        self.is_playing = true;
+        self.clock.restart();
+    }
 
-    // Title bar
-    let title_text = if app.current_book_title.is_empty() {
-        "Press 'i' to import a file or 'o' to open library"
-    } else {
-        &app.current_book_title
-    };
-    let title = Paragraph::new(title_text)
-        .style(
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        )
-        .alignment(Alignment::Center)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .style(Style::default().fg(Color::DarkGray)),
-        );
-    f.render_widget(title, chunks[0]);
+    /// List the books in progress to pick one to carry on with, if there
+    /// are any
+    fn show_start(&mut self) -> bool {
+        if self.library.in_progress().is_empty() {
+            return false;
+        }
+        self.start_state.select(Some(0));
+        self.mode = AppMode::Start;
+        true
+    }
 
-    // Word display
-    render_word_display(f, app, chunks[1]);
+    /// Ask whether to play the tutorial, as on first launch
+    fn offer_tutorial(&mut self) {
+        self.confirm_message = "New to RSVP? Take a one-minute tour of the controls?".to_string();
+        self.confirm_action = Some(ConfirmAction::StartTutorial);
+        self.mode = AppMode::Confirm;
+    }
 
-    // Progress bar
-    let progress = app.progress_percent();
-    let gauge = Gauge::default()
-        .gauge_style(
-            Style::default()
-                .fg(Color::Magenta)
-                .bg(Color::DarkGray),
+    fn start_tutorial(&mut self) {
+        let book = match Tutorial::book() {
+            Ok(book) => book,
+            Err(e) => {
+                self.show_status(&format!("Couldn't start the tutorial: {}", e));
+                return;
+            }
+        };
+        self.save_progress();
+        self.speed_test = None;
+        self.tutorial = Some(Tutorial::new(&book));
+        self.current_book_id = None;
+        self.source_watch.stop();
+        self.words = book;
+        self.word_index = 0;
+        self.frame = (0, 0);
+        self.current_book_title = "Tutorial".to_string();
+        self.update_language();
+        self.recent_words.clear();
+        self.history.clear();
+        self.mode = AppMode::Reading;
+        self.is_playing = true;
+        self.clock.restart();
+    }
+
+    /// Finish or cancel the speed test and go back to the book it
+    /// interrupted
+    fn end_speed_test(&mut self) {
+        let Some(test) = self.speed_test.take() else {
+            return;
+        };
+        self.is_playing = false;
+        self.mode = AppMode::Reading;
+        let resumed = test
+            .resume_book
+            .is_some_and(|book_id| self.load_book(&book_id));
+        if !resumed {
+            self.words = WordSource::default();
+            self.word_index = 0;
+            self.current_book_title.clear();
+        }
+    }
+
+    /// Whether a micro-pause is blanking the screen
+    fn blanking(&self) -> bool {
+        self.blank_until.is_some_and(|until| Instant::now() < until)
+    }
+
+    /// Move to another position, remembering this one for jumping back
+    fn jump(&mut self, index: usize) {
+        if index != self.word_index {
+            self.history.record(self.word_index);
+            self.word_index = index;
+            self.chapter_break = None;
+            self.block_pause = None;
+            self.footnote = None;
+            self.save_progress();
+        }
+    }
+
+    /// What the stats line can show
+    fn stats_fields<'a>(&'a self, status: &'a str) -> StatsFields<'a> {
+        let analysis = self
+            .current_book_id
+            .as_ref()
+            .and_then(|id| self.library.book(id))
+            .and_then(|book| book.analysis.as_ref());
+        let current_wpm = self.current_wpm();
+        let (elapsed_time, remaining_time) = reading_times(
+            analysis,
+            self.word_index,
+            self.words.len(),
+            current_wpm,
+            &self.pauses(),
+        );
+        StatsFields {
+            wpm: self.wpm,
+            current_wpm,
+            pacing: self.language.pacing,
+            units_per_word: self.units_per_word,
+            word: self.word_index + 1,
+            words: self.words.len().max(1),
+            percent: self.progress_percent(),
+            elapsed_time,
+            remaining_time,
+            chapter: self.words.chapter_at(self.word_index),
+            chapters: self.words.chapters(),
+            unit: self.library.settings.progress_unit,
+            status,
+            breaks: self
+                .config
+                .breaks
+                .enabled
+                .then(|| self.library.stats.break_summary()),
+            recall: self.library.stats.recent_recall(),
+            repeat: self.repeat.summary(),
+            title: &self.current_book_title,
+        }
+    }
+
+    /// The speed for the current word, after any adaptive adjustment
+    fn current_wpm(&self) -> u32 {
+        self.config
+            .adaptive
+            .wpm(self.wpm, &self.words, self.word_index)
+    }
+
+    /// The current word, joined by the rest of its chunk and any words
+    /// skimming shows with it, and how many words that is
+    fn current_unit(&self) -> Option<(String, usize)> {
+        let chunk = self.display.chunk.unwrap_or(1);
+        let lines = self.profile.line_breaks;
+        display_unit(
+            &self.words,
+            self.word_index,
+            &self.config.skim,
+            chunk,
+            lines,
         )
-        .percent(progress as u16)
-        .label("");
-    f.render_widget(gauge, chunks[2]);
+    }
+
+    fn frame_index(&self) -> usize {
+        if self.frame.0 == self.word_index {
+            self.frame.1
+        } else {
+            0
+        }
+    }
+
+    /// The current word split into frames that fit the word display
+    fn current_frames(&self) -> Vec<String> {
+        self.current_unit()
+            .map(|(text, _)| split_long_word(&text, self.word_half_width))
+            .unwrap_or_default()
+    }
+
+    /// How long the current frame stays on screen. Punctuation pauses apply
+    /// only once the last frame of a word is shown.
+    fn current_delay(&self) -> Duration {
+        let period = 60.0 / self.current_wpm() as f64 * self.profile.slowdown.max(0.1);
+        let frames = self.current_frames();
+        let multiplier = if self.frame_index() + 1 >= frames.len() {
+            let pauses = self.pauses();
+            let covered = self.current_unit().map_or(1, |(_, covered)| covered);
+            let last = self.word_index + covered - 1;
+            frames.last().map_or(1.0, |w| {
+                frame_weight(w, &pauses, &self.config.skim, &self.language)
+                    + self.profile.line_pause(&self.words, last, &pauses)
+            })
+        } else {
+            frames
+                .get(self.frame_index())
+                .map_or(1.0, |frame| self.language.length_weight(frame))
+        };
+        Duration::from_secs_f64(period * multiplier)
+    }
+
+    /// How long the main loop can wait for a key before something on
+    /// screen changes by itself: the word on screen coming due, a status
+    /// message, micro-pause or chapter break running out, or a break's
+    /// countdown. With nothing coming up it only wakes to check the inbox.
+    fn poll_timeout(&self) -> Duration {
+        let mut timeout = IDLE_POLL;
+        if self.is_playing && !self.words.is_empty() {
+            timeout = timeout.min(self.clock.until_due(self.current_delay()));
+        }
+        if let Some((_, shown)) = &self.status_message {
+            timeout = timeout.min(STATUS_DURATION.saturating_sub(shown.elapsed()));
+        }
+        if let Some(until) = self.blank_until {
+            timeout = timeout.min(until.saturating_duration_since(Instant::now()));
+        }
+        let pause = self.chapter_break.as_ref();
+        if let Some(remaining) = pause.and_then(ChapterBreak::remaining) {
+            timeout = timeout.min(remaining);
+        }
+        if self.mode == AppMode::Break || self.narration.speaking() {
+            timeout = timeout.min(BUSY_POLL);
+        }
+        timeout
+    }
+
+    /// What the screen shows that can change without a key press, to tell
+    /// whether a tick needs a redraw
+    fn screen(&self) -> Screen {
+        Screen {
+            mode: self.mode,
+            word: (self.word_index, self.frame_index()),
+            words: self.words.len(),
+            playing: self.is_playing,
+            blank: self.blanking(),
+            status: self.status_message.as_ref().map(|(_, shown)| *shown),
+            countdown: self
+                .breaks
+                .remaining(&self.config.breaks)
+                .map(format_countdown),
+            hint: self.tutorial.is_some(),
+            chapter_break: self.chapter_break.is_some(),
+            night: self.night.is_night(),
+            dim: (self.brightness() * 20.0).round() as u8,
+        }
+    }
+
+    /// The part of the current word being shown right now
+    fn current_frame(&self) -> Option<String> {
+        let frames = self.current_frames();
+        let i = self.frame_index().min(frames.len().saturating_sub(1));
+        frames.into_iter().nth(i)
+    }
+
+    /// How to draw the frame on screen, if its first word is inside quotes
+    fn dialogue(&self) -> Option<DialogueStyle> {
+        let style = self.config.theme.dialogue;
+        (style != DialogueStyle::Plain && self.words.in_dialogue(self.word_index)).then_some(style)
+    }
+
+    /// Whether the frame on screen ends a sentence, for the `sentence_end`
+    /// marker
+    fn at_sentence_end(&self) -> bool {
+        self.current_frame()
+            .is_some_and(|frame| frame.rsplit(' ').next().is_some_and(ends_sentence))
+    }
+
+    /// Whether the frame on screen ends a line of verse, for its mark
+    fn at_line_end(&self) -> bool {
+        if !self.profile.line_breaks || self.frame_index() + 1 < self.current_frames().len() {
+            return false;
+        }
+        let covered = self.current_unit().map_or(1, |(_, covered)| covered);
+        self.words.ends_line(self.word_index + covered - 1)
+    }
+
+    /// Colors of the night scheme during night mode, otherwise of the
+    /// selected scheme or the open book's own, then any color blindness
+    /// preset and overrides from config.toml
+    fn palette(&self) -> Palette {
+        let scheme = if self.night.is_night() {
+            ColorScheme::Night
+        } else {
+            let scheme = self.display.color_scheme;
+            scheme.unwrap_or(self.library.settings.color_scheme)
+        };
+        let mut palette = Palette::for_scheme(scheme);
+        if let Some(safe) = self.library.settings.color_vision.colors() {
+            let rgb = |(r, g, b)| Color::Rgb(r, g, b);
+            palette.focus = rgb(safe.focus);
+            palette.guide = rgb(safe.guide);
+            palette.progress = rgb(safe.progress);
+        }
+        let colors = &self.config.theme;
+        palette.word = theme_color(&colors.word, palette.word);
+        palette.dialogue = theme_color(&colors.dialogue_color, palette.dialogue);
+        palette.focus = if colors.focus_has(FocusStyle::Color) {
+            theme_color(&colors.focus, palette.focus)
+        } else {
+            palette.word
+        };
+        if colors.focus_has(FocusStyle::Bold) {
+            palette.emphasis |= Modifier::BOLD;
+        }
+        if colors.focus_has(FocusStyle::Underline) {
+            palette.emphasis |= Modifier::UNDERLINED;
+        }
+        palette.progress = theme_color(&colors.progress, palette.progress);
+        palette.background = theme_color(&colors.background, palette.background);
+        palette.dimmed(self.brightness())
+    }
+
+    /// How bright everything but the word is drawn, with auto-dim
+    fn brightness(&self) -> f32 {
+        self.dimmer
+            .brightness(self.is_playing, &self.config.auto_dim)
+    }
+
+    fn progress_percent(&self) -> f64 {
+        if self.words.is_empty() {
+            0.0
+        } else {
+            (self.word_index as f64 / self.words.len() as f64) * 100.0
+        }
+    }
+
+    /// Indices into `library.books` matching the current filter, best match first
+    fn filtered_books(&self) -> Vec<usize> {
+        if self.library_filter.trim().is_empty() {
+            return (0..self.library.books.len()).collect();
+        }
+
+        let mut matches: Vec<(usize, i32)> = self
+            .library
+            .books
+            .iter()
+            .enumerate()
+            .filter_map(|(i, book)| book_match_score(&self.library_filter, book).map(|s| (i, s)))
+            .collect();
+        matches.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        matches.into_iter().map(|(i, _)| i).collect()
+    }
+
+    fn selected_book(&self) -> Option<&Book> {
+        let i = self.library_state.selected()?;
+        let book_index = *self.filtered_books().get(i)?;
+        self.library.books.get(book_index)
+    }
+
+    fn reset_library_filter(&mut self) {
+        self.library_filter.clear();
+        self.library_filter_active = false;
+        self.reset_library_selection();
+    }
+
+    fn reset_library_selection(&mut self) {
+        if self.filtered_books().is_empty() {
+            self.library_state.select(None);
+        } else {
+            self.library_state.select(Some(0));
+        }
+    }
+}
+
+fn shellexpand(path: &str) -> String {
+    if path.starts_with('~') {
+        if let Some(home) = dirs::home_dir() {
+            return path.replacen('~', &home.to_string_lossy(), 1);
+        }
+    }
+    path.to_string()
+}
+
+/// Expand `~` and glob patterns like `~/articles/*.txt`. Input naming an
+/// existing file is taken literally, even if it contains `*` or `[`.
+fn expand_paths(input: &str) -> Result<Vec<PathBuf>, String> {
+    let expanded = shellexpand(input);
+    let path = PathBuf::from(&expanded);
+    if path.exists() || !expanded.contains(['*', '?', '[']) {
+        return Ok(vec![path]);
+    }
+
+    let pattern = glob::glob(&expanded).map_err(|e| format!("Bad pattern: {}", e.msg))?;
+    let paths: Vec<PathBuf> = pattern
+        .filter_map(Result::ok)
+        .filter(|path| path.is_file())
+        .collect();
+    if paths.is_empty() {
+        Err("No files match".to_string())
+    } else {
+        Ok(paths)
+    }
+}
+
+// ============================================================================
+// Library Search
+// ============================================================================
+
+/// Score a book against a search query. Every whitespace-separated term must
+/// fuzzy-match the title or one of the tags.
+fn book_match_score(query: &str, book: &Book) -> Option<i32> {
+    query.split_whitespace().try_fold(0, |total, term| {
+        let best = std::iter::once(&book.title)
+            .chain(book.tags.iter())
+            .filter_map(|candidate| fuzzy_score(term, candidate))
+            .max()?;
+        Some(total + best)
+    })
+}
+
+/// Case-insensitive subsequence match. Consecutive characters and matches at
+/// the start of a word score higher; returns None if `query` is not a
+/// subsequence of `candidate`.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let query: Vec<char> = query.to_lowercase().chars().collect();
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+
+    let mut score = 0;
+    let mut qi = 0;
+    let mut prev_match: Option<usize> = None;
+
+    for (ci, &c) in candidate.iter().enumerate() {
+        if qi == query.len() {
+            break;
+        }
+        if c != query[qi] {
+            continue;
+        }
+
+        score += 1;
+        if ci > 0 && prev_match == Some(ci - 1) {
+            score += 5;
+        }
+        if ci == 0 || !candidate[ci - 1].is_alphanumeric() {
+            score += 3;
+        }
+        prev_match = Some(ci);
+        qi += 1;
+    }
+
+    if qi == query.len() {
+        Some(score)
+    } else {
+        None
+    }
+}
+
+// ============================================================================
+// Color Schemes
+// ============================================================================
 
-    // Stats bar
-    render_stats(f, app, chunks[3]);
+/// Colors for every part of the interface, by role
+#[derive(Debug, Clone, Copy)]
+struct Palette {
+    background: Color,
+    text: Color,
+    /// Borders and hints
+    muted: Color,
+    /// Focal guide marks
+    guide: Color,
+    /// Titles and dialog borders
+    accent: Color,
+    word: Color,
+    /// Quoted dialogue, with `dialogue = "color"`
+    dialogue: Color,
+    focus: Color,
+    /// Extra emphasis on the focal letter
+    emphasis: Modifier,
+    progress: Color,
+    track: Color,
+    selection: Color,
+    info: Color,
+    good: Color,
+    warn: Color,
+    error: Color,
+}
+
+impl Palette {
+    fn for_scheme(scheme: ColorScheme) -> Self {
+        match scheme {
+            ColorScheme::Default => Self {
+                background: Color::Reset,
+                text: Color::White,
+                muted: Color::DarkGray,
+                guide: Color::DarkGray,
+                accent: Color::Cyan,
+                word: Color::White,
+                dialogue: Color::Cyan,
+                focus: Color::Red,
+                emphasis: Modifier::empty(),
+                progress: Color::Magenta,
+                track: Color::DarkGray,
+                selection: Color::DarkGray,
+                info: Color::Blue,
+                good: Color::Green,
+                warn: Color::Yellow,
+                error: Color::Red,
+            },
+            ColorScheme::Solarized => Self {
+                background: Color::Rgb(0x00, 0x2b, 0x36),
+                text: Color::Rgb(0x93, 0xa1, 0xa1),
+                muted: Color::Rgb(0x58, 0x6e, 0x75),
+                guide: Color::Rgb(0x58, 0x6e, 0x75),
+                accent: Color::Rgb(0x2a, 0xa1, 0x98),
+                word: Color::Rgb(0xee, 0xe8, 0xd5),
+                dialogue: Color::Rgb(0x2a, 0xa1, 0x98),
+                focus: Color::Rgb(0xdc, 0x32, 0x2f),
+                emphasis: Modifier::empty(),
+                progress: Color::Rgb(0xd3, 0x36, 0x82),
+                track: Color::Rgb(0x07, 0x36, 0x42),
+                selection: Color::Rgb(0x07, 0x36, 0x42),
+                info: Color::Rgb(0x26, 0x8b, 0xd2),
+                good: Color::Rgb(0x85, 0x99, 0x00),
+                warn: Color::Rgb(0xb5, 0x89, 0x00),
+                error: Color::Rgb(0xdc, 0x32, 0x2f),
+            },
+            ColorScheme::Gruvbox => Self {
+                background: Color::Rgb(0x28, 0x28, 0x28),
+                text: Color::Rgb(0xeb, 0xdb, 0xb2),
+                muted: Color::Rgb(0x92, 0x83, 0x74),
+                guide: Color::Rgb(0x92, 0x83, 0x74),
+                accent: Color::Rgb(0x8e, 0xc0, 0x7c),
+                word: Color::Rgb(0xfb, 0xf1, 0xc7),
+                dialogue: Color::Rgb(0x8e, 0xc0, 0x7c),
+                focus: Color::Rgb(0xfb, 0x49, 0x34),
+                emphasis: Modifier::empty(),
+                progress: Color::Rgb(0xd3, 0x86, 0x9b),
+                track: Color::Rgb(0x3c, 0x38, 0x36),
+                selection: Color::Rgb(0x50, 0x49, 0x45),
+                info: Color::Rgb(0x83, 0xa5, 0x98),
+                good: Color::Rgb(0xb8, 0xbb, 0x26),
+                warn: Color::Rgb(0xfa, 0xbd, 0x2f),
+                error: Color::Rgb(0xfb, 0x49, 0x34),
+            },
+            // No hues at all; the focal letter stands out by weight instead
+            ColorScheme::Monochrome => Self {
+                background: Color::Reset,
+                text: Color::Gray,
+                muted: Color::DarkGray,
+                guide: Color::DarkGray,
+                accent: Color::White,
+                word: Color::Gray,
+                dialogue: Color::White,
+                focus: Color::White,
+                emphasis: Modifier::BOLD | Modifier::UNDERLINED,
+                progress: Color::Gray,
+                track: Color::DarkGray,
+                selection: Color::DarkGray,
+                info: Color::Gray,
+                good: Color::White,
+                warn: Color::White,
+                error: Color::White,
+            },
+            // Everything well past WCAG AAA contrast against pure black
+            ColorScheme::HighContrast => Self {
+                background: Color::Black,
+                text: Color::White,
+                muted: Color::Rgb(0xc0, 0xc0, 0xc0),
+                guide: Color::Rgb(0xc0, 0xc0, 0xc0),
+                accent: Color::Rgb(0xff, 0xff, 0x00),
+                word: Color::White,
+                dialogue: Color::Rgb(0x00, 0xff, 0xff),
+                focus: Color::Rgb(0xff, 0xff, 0x00),
+                emphasis: Modifier::BOLD,
+                progress: Color::Rgb(0xff, 0xff, 0x00),
+                track: Color::Rgb(0x33, 0x33, 0x33),
+                selection: Color::Rgb(0x33, 0x33, 0x33),
+                info: Color::Rgb(0x80, 0xd0, 0xff),
+                good: Color::Rgb(0x80, 0xff, 0x80),
+                warn: Color::Rgb(0xff, 0xff, 0x00),
+                error: Color::Rgb(0xff, 0x90, 0x90),
+            },
+            // Unlit on OLED screens, with a light grey word
+            ColorScheme::TrueBlack => Self {
+                background: Color::Rgb(0x00, 0x00, 0x00),
+                text: Color::Rgb(0xc8, 0xc8, 0xc8),
+                muted: Color::Rgb(0x5a, 0x5a, 0x5a),
+                guide: Color::Rgb(0x5a, 0x5a, 0x5a),
+                accent: Color::Rgb(0x8a, 0xb4, 0xf8),
+                word: Color::Rgb(0xe0, 0xe0, 0xe0),
+                dialogue: Color::Rgb(0x8a, 0xb4, 0xf8),
+                focus: Color::Rgb(0xe6, 0x3a, 0x3a),
+                emphasis: Modifier::empty(),
+                progress: Color::Rgb(0x80, 0x80, 0x80),
+                track: Color::Rgb(0x1a, 0x1a, 0x1a),
+                selection: Color::Rgb(0x26, 0x26, 0x26),
+                info: Color::Rgb(0x8a, 0xb4, 0xf8),
+                good: Color::Rgb(0x81, 0xc9, 0x95),
+                warn: Color::Rgb(0xfd, 0xd6, 0x63),
+                error: Color::Rgb(0xf2, 0x8b, 0x82),
+            },
+            // Dim amber on black: little blue light and little glare
+            ColorScheme::Night => Self {
+                background: Color::Black,
+                text: Color::Rgb(0xb8, 0x94, 0x6b),
+                muted: Color::Rgb(0x6b, 0x54, 0x40),
+                guide: Color::Rgb(0x6b, 0x54, 0x40),
+                accent: Color::Rgb(0xc0, 0x7a, 0x3a),
+                word: Color::Rgb(0xc8, 0x9a, 0x66),
+                dialogue: Color::Rgb(0xc0, 0x7a, 0x3a),
+                focus: Color::Rgb(0xd8, 0x5a, 0x26),
+                emphasis: Modifier::empty(),
+                progress: Color::Rgb(0x8a, 0x5a, 0x2a),
+                track: Color::Rgb(0x24, 0x1a, 0x12),
+                selection: Color::Rgb(0x2a, 0x1f, 0x16),
+                info: Color::Rgb(0xb0, 0x8c, 0x40),
+                good: Color::Rgb(0x9a, 0x9a, 0x50),
+                warn: Color::Rgb(0xd8, 0x80, 0x30),
+                error: Color::Rgb(0xd0, 0x58, 0x40),
+            },
+        }
+    }
+}
+
+/// Blank a modal's area, keeping the scheme's background
+fn clear_area(f: &mut Frame, palette: &Palette, area: Rect) {
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Block::default().style(Style::default().bg(palette.background)),
+        area,
+    );
+}
+
+// ============================================================================
+// UI Rendering
+// ============================================================================
+
+fn ui(f: &mut Frame, app: &App) {
+    let size = f.area();
+
+    let palette = app.palette();
+    f.render_widget(
+        Block::default().style(Style::default().bg(palette.background)),
+        size,
+    );
+
+    // Minimal display gives the word the whole screen
+    let word_area = if app.minimal {
+        render_word_display(f, app, size);
+        size
+    } else {
+        render_reader(f, app, size)
+    };
+
+    if app.show_diagnostics {
+        render_diagnostics(f, app, word_area);
+    }
 
     // Modal overlays
     match app.mode {
         AppMode::Library => render_library(f, app, size),
+        AppMode::Recents => render_recents(f, app, size),
+        AppMode::Playlists => render_playlists(f, app, size),
+        AppMode::PlaylistName => render_playlist_name(f, app, size),
+        AppMode::BookDisplay => render_book_display(f, app, size),
+        AppMode::Vocabulary => render_vocabulary(f, app, size),
+        AppMode::Links => render_links(f, app, size),
+        AppMode::Analysis => render_analysis(f, app, size),
+        AppMode::BookStats => render_book_stats(f, app, size),
+        AppMode::BookDetails => render_book_details(f, app, size),
+        AppMode::LibraryStats => render_library_stats(f, app, size),
         AppMode::FileInput => render_file_input(f, app, size),
-        AppMode::Help => render_help(f, size),
+        AppMode::Help => render_help(f, app, size),
         AppMode::Confirm => render_confirm(f, app, size),
+        AppMode::Break => render_break(f, app, size),
+        AppMode::Recall => render_recall(f, app, size),
+        AppMode::SpeedTest => render_speed_test(f, app, size),
+        AppMode::Finished => render_finished(f, app, size),
+        AppMode::Start => render_start(f, app, size),
+        AppMode::Reading if app.footnote.is_some() => render_footnote(f, app, size),
+        AppMode::Reading if app.block_pause.is_some() => render_block_pause(f, app, size),
+        AppMode::Reading if app.chapter_break.is_some() => render_chapter_break(f, app, size),
         _ => {}
     }
 }
 
+/// The panels the layout config asks for, top to bottom. Returns where
+/// the word went.
+fn render_reader(f: &mut Frame, app: &App, size: Rect) -> Rect {
+    let palette = app.palette();
+    let panels = app.config.layout.panels();
+    let constraints: Vec<Constraint> = panels
+        .iter()
+        .map(|panel| match panel {
+            Panel::Title => Constraint::Length(3),
+            Panel::Word => Constraint::Min(10),
+            Panel::Progress => Constraint::Length(1),
+            Panel::Stats => Constraint::Length(3),
+        })
+        .collect();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(size);
+
+    let mut word_area = size;
+    let mut above_word = true;
+    for (&panel, &area) in panels.iter().zip(chunks.iter()) {
+        match panel {
+            Panel::Title => render_title(f, app, area),
+            Panel::Word => {
+                render_word_display(f, app, area);
+                word_area = area;
+                above_word = false;
+            }
+            Panel::Progress => {
+                let gauge = Gauge::default()
+                    .gauge_style(Style::default().fg(palette.progress).bg(palette.track))
+                    .percent(app.progress_percent() as u16)
+                    .label("");
+                f.render_widget(gauge, area);
+            }
+            // The rule goes on the side facing the word
+            Panel::Stats => {
+                let rule = if above_word {
+                    Borders::BOTTOM
+                } else {
+                    Borders::TOP
+                };
+                render_stats(f, app, area, rule);
+            }
+        }
+    }
+    word_area
+}
+
+/// Title bar, which gives the tutorial's instructions while it waits
+fn render_title(f: &mut Frame, app: &App, area: Rect) {
+    let palette = app.palette();
+    let hint = app
+        .tutorial
+        .as_ref()
+        .and_then(|tutorial| tutorial.hint(&app.config.keys));
+    let title_text = if let Some(hint) = &hint {
+        hint
+    } else if app.current_book_title.is_empty() {
+        "Press 'i' to import a file or 'o' to open library"
+    } else {
+        &app.current_book_title
+    };
+    let title = Paragraph::new(title_text)
+        .style(
+            Style::default()
+                .fg(palette.accent)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(palette.muted)),
+        );
+    f.render_widget(title, area);
+}
+
 fn render_word_display(f: &mut Frame, app: &App, area: Rect) {
+    let palette = app.palette();
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::DarkGray))
+        .border_style(Style::default().fg(palette.muted))
         .title(" RSVP ")
         .title_alignment(Alignment::Center);
 
-    let inner = block.inner(area);
-    f.render_widget(block, area);
+    // Minimal display has no room to spare for a frame
+    let inner = if app.minimal {
+        area
+    } else {
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        inner
+    };
 
     // Calculate the center column position for the focal point
     let center_x = inner.x + inner.width / 2;
     let center_y = inner.y + inner.height / 2;
 
-    // Draw fixed focal point markers (subtle vertical guides)
-    let marker_style = Style::default().fg(Color::DarkGray);
+    let sentence_end = app.config.theme.sentence_end;
+    let at_sentence_end = sentence_end != SentenceEnd::None && app.at_sentence_end();
 
-    // Top marker
-    if center_y > inner.y + 1 {
-        let top_marker = Paragraph::new("|").style(marker_style);
-        f.render_widget(top_marker, Rect::new(center_x, center_y - 2, 1, 1));
+    // Draw fixed focal point markers
+    if app.config.layout.guides {
+        let color = if at_sentence_end && sentence_end == SentenceEnd::Guides {
+            palette.focus
+        } else {
+            palette.guide
+        };
+        let marker_style = Style::default().fg(color);
+        render_focal_guide(f, app.config.theme.guide, inner, marker_style);
     }
 
-    // Bottom marker
-    if center_y + 2 < inner.y + inner.height {
-        let bottom_marker = Paragraph::new("|").style(marker_style);
-        f.render_widget(bottom_marker, Rect::new(center_x, center_y + 2, 1, 1));
+    if app.blanking() {
+        return;
     }
 
-    if let Some(word) = app.current_word() {
-        let orp = calculate_orp(word);
-        let chars: Vec<char> = word.chars().collect();
+    if let Some(word) = app.current_frame() {
+        // Split word into three parts around the ORP grapheme
+        let (before, orp_char, after) = split_at_orp(&word);
+        let case = app.config.theme.letter_case;
+        let orp_char = case.apply(orp_char);
+        let before_width = display_width(&case.apply(before)) as u16;
+        let orp_width = display_width(&orp_char).max(1) as u16;
+        let after_width = display_width(&case.apply(after)) as u16;
+
+        // ORP character is always at center_x
+        // Render each part as a separate widget to avoid styling issues
+        let orp_start = before.len();
+        let orp_end = word.len() - after.len();
+
+        // Before ORP (right-aligned to center)
+        if !before.is_empty() {
+            let before_x = center_x.saturating_sub(before_width);
+            let before_widget = Paragraph::new(word_line(app, &palette, &word, 0..orp_start));
+            f.render_widget(before_widget, Rect::new(before_x, center_y, before_width, 1));
+        }
+
+        // ORP character (at center, highlighted)
+        let orp_widget = Paragraph::new(orp_char).style(
+            Style::default()
+                .fg(palette.focus)
+                .add_modifier(palette.emphasis),
+        );
+        f.render_widget(orp_widget, Rect::new(center_x, center_y, orp_width, 1));
+
+        // After ORP (left-aligned from the end of the ORP character)
+        if !after.is_empty() {
+            let after_x = center_x + orp_width;
+            let after_widget = Paragraph::new(word_line(app, &palette, &word, orp_end..word.len()));
+            f.render_widget(after_widget, Rect::new(after_x, center_y, after_width, 1));
+        }
 
-        // Split word into three parts
-        let before: String = chars[..orp].iter().collect();
-        let orp_char: String = chars.get(orp).map(|c| c.to_string()).unwrap_or_default();
-        let after: String = if orp + 1 < chars.len() {
-            chars[orp + 1..].iter().collect()
+        // A dim mark a space after a footnote reference, the last word of a
+        // line of verse, or of a sentence
+        let mark = if app.at_footnote() {
+            Some("†")
+        } else if app.at_line_end() {
+            Some("↵")
         } else {
-            String::new()
+            (at_sentence_end && sentence_end == SentenceEnd::Mark).then_some("¶")
         };
+        let mark_x = center_x + orp_width + after_width + 1;
+        if let Some(mark) = mark.filter(|_| mark_x < inner.right()) {
+            let mark = Paragraph::new(mark).style(Style::default().fg(palette.muted));
+            f.render_widget(mark, Rect::new(mark_x, center_y, 1, 1));
+        }
+    } else {
+        let text = Paragraph::new("Ready")
+            .style(Style::default().fg(palette.muted))
+            .alignment(Alignment::Center);
 
-        // ORP character is always at center_x
-        // Render each part as a separate widget to avoid styling issues
+        let centered = Rect::new(inner.x, center_y, inner.width, 1);
+        f.render_widget(text, centered);
+    }
+}
+
+/// Timing figures in the top right corner of the word display
+fn render_diagnostics(f: &mut Frame, app: &App, area: Rect) {
+    let palette = app.palette();
+    let lines: Vec<Line> = app
+        .diagnostics
+        .summary()
+        .into_iter()
+        .map(|(label, value)| {
+            Line::from(vec![
+                Span::styled(format!("{:<11}", label), Style::default().fg(palette.muted)),
+                Span::styled(value, Style::default().fg(palette.text)),
+            ])
+        })
+        .collect();
+
+    let width = 50.min(area.width.saturating_sub(2));
+    let height = (lines.len() as u16 + 2).min(area.height.saturating_sub(2));
+    let corner = Rect::new(area.right().saturating_sub(width + 1), area.y + 1, width, height);
+    clear_area(f, &palette, corner);
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .title(" Timing ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(palette.muted)),
+    );
+    f.render_widget(paragraph, corner);
+}
+
+/// Part of the word around the focal letter, in the configured letter
+/// case, styled as dialogue inside quotes and with keyword emphasis if it
+/// is on
+fn word_line<'a>(app: &App, palette: &Palette, word: &'a str, range: Range<usize>) -> Line<'a> {
+    let plain = match app.dialogue() {
+        Some(DialogueStyle::Color) => Style::default().fg(palette.dialogue),
+        Some(DialogueStyle::Italic) => Style::default()
+            .fg(palette.word)
+            .add_modifier(Modifier::ITALIC),
+        _ => Style::default().fg(palette.word),
+    };
+    let case = app.config.theme.letter_case;
+    if !app.config.theme.keywords {
+        return Line::styled(case.apply(&word[range]), plain);
+    }
+    let spans: Vec<Span> = emphasis_runs(word, range)
+        .into_iter()
+        .map(|(text, emphasis)| {
+            let style = match emphasis {
+                Emphasis::Dim => Style::default().fg(palette.muted),
+                Emphasis::Normal => plain,
+                Emphasis::Strong => plain.add_modifier(Modifier::BOLD),
+            };
+            Span::styled(case.apply(text), style)
+        })
+        .collect();
+    Line::from(spans)
+}
+
+/// Draw the configured focal guide around the center of `inner`. The word
+/// is drawn afterwards, so it covers any mark it runs into.
+fn render_focal_guide(f: &mut Frame, guide: FocalGuide, inner: Rect, style: Style) {
+    let center_x = inner.x + inner.width / 2;
+    let center_y = inner.y + inner.height / 2;
+    let has_top = center_y > inner.y + 1;
+    let has_bottom = center_y + 2 < inner.y + inner.height;
+
+    match guide {
+        FocalGuide::None => {}
+        FocalGuide::Bars | FocalGuide::Crosshair => {
+            if has_top {
+                let top_marker = Paragraph::new("|").style(style);
+                f.render_widget(top_marker, Rect::new(center_x, center_y - 2, 1, 1));
+            }
+            if has_bottom {
+                let bottom_marker = Paragraph::new("|").style(style);
+                f.render_widget(bottom_marker, Rect::new(center_x, center_y + 2, 1, 1));
+            }
+            if guide == FocalGuide::Crosshair && inner.width > 6 {
+                let left_mark = Paragraph::new("──").style(style);
+                f.render_widget(left_mark, Rect::new(inner.x + 1, center_y, 2, 1));
+                let right_mark = Paragraph::new("──").style(style);
+                let right_x = inner.x + inner.width - 3;
+                f.render_widget(right_mark, Rect::new(right_x, center_y, 2, 1));
+            }
+        }
+        FocalGuide::Reticle => {
+            let line = |notch: char| {
+                let line: String = (inner.x..inner.x + inner.width)
+                    .map(|x| if x == center_x { notch } else { '─' })
+                    .collect();
+                Paragraph::new(line).style(style)
+            };
+            if has_top {
+                f.render_widget(line('┬'), Rect::new(inner.x, center_y - 2, inner.width, 1));
+            }
+            if has_bottom {
+                f.render_widget(line('┴'), Rect::new(inner.x, center_y + 2, inner.width, 1));
+            }
+        }
+    }
+}
+
+impl Palette {
+    /// Everything but the word and its focal letter faded toward the
+    /// background, to `brightness` of full
+    fn dimmed(self, brightness: f32) -> Self {
+        if brightness >= 1.0 {
+            return self;
+        }
+        let fade = |color| fade(color, self.background, brightness);
+        Self {
+            text: fade(self.text),
+            muted: fade(self.muted),
+            guide: fade(self.guide),
+            accent: fade(self.accent),
+            progress: fade(self.progress),
+            track: fade(self.track),
+            selection: fade(self.selection),
+            info: fade(self.info),
+            good: fade(self.good),
+            warn: fade(self.warn),
+            error: fade(self.error),
+            ..self
+        }
+    }
+}
+
+/// `color` mixed with `background`, `brightness` of the way from it. Named
+/// colors can't be mixed, so they drop to dark grey once half faded.
+fn fade(color: Color, background: Color, brightness: f32) -> Color {
+    let background = match background {
+        Color::Rgb(r, g, b) => (r, g, b),
+        _ => (0, 0, 0),
+    };
+    match color {
+        Color::Rgb(r, g, b) => {
+            let mix = |c: u8, bg: u8| (bg as f32 + (c as f32 - bg as f32) * brightness) as u8;
+            Color::Rgb(
+                mix(r, background.0),
+                mix(g, background.1),
+                mix(b, background.2),
+            )
+        }
+        _ if brightness < 0.5 => Color::DarkGray,
+        named => named,
+    }
+}
+
+/// A color from the theme config, or `default` if it is unset or invalid
+fn theme_color(value: &Option<String>, default: Color) -> Color {
+    value
+        .as_deref()
+        .and_then(|color| color.parse().ok())
+        .unwrap_or(default)
+}
+
+fn render_stats(f: &mut Frame, app: &App, area: Rect, rule: Borders) {
+    let palette = app.palette();
+    let status = if app.is_playing {
+        "Playing"
+    } else {
+        "Paused"
+    };
+    let status_color = if app.is_playing {
+        palette.good
+    } else {
+        palette.warn
+    };
+
+    // Status messages follow the line either way
+    let fields = app.stats_fields(status);
+    let mut spans = if let Some(format) = &app.config.stats_format {
+        vec![Span::styled(
+            fields.fill(format),
+            Style::default().fg(palette.text),
+        )]
+    } else {
+        vec![
+            Span::styled(
+                format!("{} ", fields.speed(app.config.adaptive.enabled)),
+                Style::default().fg(palette.accent),
+            ),
+            Span::raw("| "),
+            Span::styled(
+                format!("{} ", fields.progress()),
+                Style::default().fg(palette.progress),
+            ),
+            Span::raw("| "),
+            Span::styled(
+                status,
+                Style::default()
+                    .fg(status_color)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            if app.config.breaks.enabled {
+                Span::styled(
+                    format!(" | Breaks: {}", app.library.stats.break_summary()),
+                    Style::default().fg(palette.muted),
+                )
+            } else {
+                Span::raw("")
+            },
+            match app.library.stats.recent_recall() {
+                Some(percent) => Span::styled(
+                    format!(" | Recall: {}%", percent),
+                    Style::default().fg(palette.muted),
+                ),
+                None => Span::raw(""),
+            },
+            match app.repeat.summary() {
+                Some(repeat) => Span::styled(
+                    format!(" | Loop: {}", repeat),
+                    Style::default().fg(palette.accent),
+                ),
+                None => Span::raw(""),
+            },
+        ]
+    };
+    if let Some((msg, _)) = &app.status_message {
+        spans.push(Span::styled(
+            format!(" | {}", msg),
+            Style::default().fg(palette.warn),
+        ));
+    }
+    let stats_text = Line::from(spans);
+
+    let stats = Paragraph::new(stats_text).alignment(Alignment::Center).block(
+        Block::default()
+            .borders(rule)
+            .border_style(Style::default().fg(palette.muted)),
+    );
+
+    f.render_widget(stats, area);
+}
+
+fn render_library(f: &mut Frame, app: &App, size: Rect) {
+    let area = centered_rect(60, 70, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
+
+    let block = Block::default()
+        .title(" Library ")
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(palette.accent));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    // Search line at the top while a filter is being typed or applied
+    let show_filter = app.library_filter_active || !app.library_filter.is_empty();
+    let list_area = if show_filter {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(inner);
+
+        let filter_style = if app.library_filter_active {
+            Style::default().fg(palette.warn)
+        } else {
+            Style::default().fg(palette.muted)
+        };
+        let filter = Paragraph::new(format!("/{}", app.library_filter)).style(filter_style);
+        f.render_widget(filter, chunks[0]);
+
+        if app.library_filter_active {
+            let cursor_x = chunks[0].x + 1 + app.library_filter.chars().count() as u16;
+            f.set_cursor_position((cursor_x.min(chunks[0].x + chunks[0].width - 1), chunks[0].y));
+        }
+        chunks[1]
+    } else {
+        inner
+    };
+
+    let filtered = app.filtered_books();
+
+    if app.library.books.is_empty() {
+        let text = Paragraph::new("No books in library.\n\nPress 'i' to import a file.")
+            .style(Style::default().fg(palette.muted))
+            .alignment(Alignment::Center);
+        f.render_widget(text, list_area);
+    } else if filtered.is_empty() {
+        let text = Paragraph::new("No matching books.")
+            .style(Style::default().fg(palette.muted))
+            .alignment(Alignment::Center);
+        f.render_widget(text, list_area);
+    } else {
+        let items: Vec<ListItem> = filtered
+            .iter()
+            .map(|&i| &app.library.books[i])
+            .map(|book| {
+                let marker = if Some(&book.id) == app.current_book_id.as_ref() {
+                    "> "
+                } else {
+                    "  "
+                };
+                let pct = if book.total_words > 0 {
+                    (book.progress as f64 / book.total_words as f64) * 100.0
+                } else {
+                    0.0
+                };
+                let readability = book.analysis.as_ref().map_or(String::new(), |a| {
+                    format!(", {}", a.readability(book.language.as_deref()))
+                });
+                let read = book
+                    .read_ago()
+                    .map_or(String::new(), |ago| format!(", {}", ago));
+                let mut spans = vec![
+                    Span::styled(marker, Style::default().fg(palette.good)),
+                    Span::styled(
+                        &book.title,
+                        Style::default()
+                            .fg(palette.text)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        book.author
+                            .as_ref()
+                            .map_or(String::new(), |author| format!(" by {}", author)),
+                        Style::default().fg(palette.text),
+                    ),
+                    Span::styled(
+                        format!(
+                            " ({:.0}% - {} words{}{})",
+                            pct, book.total_words, readability, read
+                        ),
+                        Style::default().fg(palette.muted),
+                    ),
+                ];
+                if !book.tags.is_empty() {
+                    spans.push(Span::styled(
+                        format!(" [{}]", book.tags.join(", ")),
+                        Style::default().fg(palette.info),
+                    ));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .highlight_style(
+                Style::default()
+                    .bg(palette.selection)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("-> ");
+
+        // Need to render with state for highlighting
+        let mut state = app.library_state.clone();
+        f.render_stateful_widget(list, list_area, &mut state);
+    }
+
+    // Help text at bottom
+    let help_area = Rect::new(area.x + 1, area.y + area.height - 2, area.width - 2, 1);
+    let help_text = if app.library_filter_active {
+        "Type to filter | Enter: Open | Esc: Clear search"
+    } else {
+        "Enter: Open | /: Search | I: Details | S: Stats | a: Add to playlist | d: Delete | Esc: Close"
+    };
+    let help = Paragraph::new(help_text)
+        .style(Style::default().fg(palette.muted))
+        .alignment(Alignment::Center);
+    f.render_widget(help, help_area);
+}
+
+fn render_recents(f: &mut Frame, app: &App, size: Rect) {
+    let area = centered_rect(50, 50, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
+
+    let block = Block::default()
+        .title(" Recent Books ")
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(palette.accent));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let recent = app.library.recent_books();
+    if recent.is_empty() {
+        let text = Paragraph::new("No recently opened books.")
+            .style(Style::default().fg(palette.muted))
+            .alignment(Alignment::Center);
+        f.render_widget(text, inner);
+    } else {
+        let items: Vec<ListItem> = recent
+            .iter()
+            .enumerate()
+            .map(|(i, book)| {
+                // Only the first nine get a number key
+                let number = if i < 9 {
+                    format!("{} ", i + 1)
+                } else {
+                    "  ".to_string()
+                };
+                let pct = if book.total_words > 0 {
+                    (book.progress as f64 / book.total_words as f64) * 100.0
+                } else {
+                    0.0
+                };
+                let title_style = if Some(&book.id) == app.current_book_id.as_ref() {
+                    Style::default().fg(palette.good)
+                } else {
+                    Style::default().fg(palette.text)
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(number, Style::default().fg(palette.muted)),
+                    Span::styled(&book.title, title_style.add_modifier(Modifier::BOLD)),
+                    Span::styled(
+                        match book.read_ago() {
+                            Some(ago) => format!(" ({:.0}%, {})", pct, ago),
+                            None => format!(" ({:.0}%)", pct),
+                        },
+                        Style::default().fg(palette.muted),
+                    ),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .highlight_style(
+                Style::default()
+                    .bg(palette.selection)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("-> ");
+
+        let mut state = app.recents_state.clone();
+        f.render_stateful_widget(list, inner, &mut state);
+    }
+
+    let help_area = Rect::new(area.x + 1, area.y + area.height - 2, area.width - 2, 1);
+    let help = Paragraph::new("Enter/1-9: Open | Esc: Close")
+        .style(Style::default().fg(palette.muted))
+        .alignment(Alignment::Center);
+    f.render_widget(help, help_area);
+}
+
+/// Width of the progress bars on the start screen, in cells
+const START_BAR_WIDTH: usize = 20;
+
+fn render_start(f: &mut Frame, app: &App, size: Rect) {
+    let area = centered_rect(60, 70, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
+
+    let block = Block::default()
+        .title(" Continue Reading ")
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(palette.accent));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let items: Vec<ListItem> = app
+        .library
+        .in_progress()
+        .iter()
+        .enumerate()
+        .map(|(i, book)| {
+            // Only the first nine get a number key
+            let number = if i < 9 {
+                format!("{} ", i + 1)
+            } else {
+                "  ".to_string()
+            };
+            let (pct, left) = book_progress(book, app.wpm, &app.config.pauses);
+            let filled = (pct / 100.0 * START_BAR_WIDTH as f64).round() as usize;
+            let mut details = format!(" {:.0}% - {} left", pct, left);
+            if let Some(ago) = book.read_ago() {
+                details += &format!(", {}", ago);
+            }
+            ListItem::new(vec![
+                Line::from(vec![
+                    Span::styled(number, Style::default().fg(palette.muted)),
+                    Span::styled(
+                        &book.title,
+                        Style::default()
+                            .fg(palette.text)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                ]),
+                Line::from(vec![
+                    Span::raw("  "),
+                    Span::styled("█".repeat(filled), Style::default().fg(palette.progress)),
+                    Span::styled(
+                        "█".repeat(START_BAR_WIDTH - filled),
+                        Style::default().fg(palette.track),
+                    ),
+                    Span::styled(details, Style::default().fg(palette.muted)),
+                ]),
+            ])
+        })
+        .collect();
+
+    let list = List::new(items)
+        .highlight_style(
+            Style::default()
+                .bg(palette.selection)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("-> ");
+
+    let mut state = app.start_state.clone();
+    f.render_stateful_widget(list, inner, &mut state);
+
+    let help_area = Rect::new(area.x + 1, area.y + area.height - 2, area.width - 2, 1);
+    let help = Paragraph::new("Enter/1-9: Open | o: Library | i: Import | Esc: Close")
+        .style(Style::default().fg(palette.muted))
+        .alignment(Alignment::Center);
+    f.render_widget(help, help_area);
+}
+
+fn render_vocabulary(f: &mut Frame, app: &App, size: Rect) {
+    let area = centered_rect(70, 70, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
+
+    let block = Block::default()
+        .title(format!(" Vocabulary - {} ", app.current_book_title))
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(palette.accent));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let list_area = Rect {
+        height: inner.height.saturating_sub(1),
+        ..inner
+    };
+
+    let vocabulary = app.vocabulary();
+    if vocabulary.is_empty() {
+        let key = app
+            .config
+            .keys
+            .keys(Action::SaveWord)
+            .first()
+            .map_or(String::new(), |k| format!(" with {}", k));
+        let text = Paragraph::new(format!("No saved words yet. Save the current word{}.", key))
+            .style(Style::default().fg(palette.muted))
+            .alignment(Alignment::Center);
+        f.render_widget(text, list_area);
+    } else {
+        let items: Vec<ListItem> = vocabulary
+            .iter()
+            .map(|entry| {
+                ListItem::new(vec![
+                    Line::from(Span::styled(
+                        &entry.word,
+                        Style::default()
+                            .fg(palette.good)
+                            .add_modifier(Modifier::BOLD),
+                    )),
+                    Line::from(Span::styled(
+                        format!("  {}", entry.context),
+                        Style::default().fg(palette.muted),
+                    )),
+                ])
+            })
+            .collect();
+
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(palette.selection))
+            .highlight_symbol("-> ");
+
+        let mut state = app.vocabulary_state.clone();
+        f.render_stateful_widget(list, list_area, &mut state);
+    }
+
+    let help_area = Rect::new(area.x + 1, area.y + area.height - 2, area.width - 2, 1);
+    let help = Paragraph::new("x: Export for Anki | X: Export all books | Esc: Close")
+        .style(Style::default().fg(palette.muted))
+        .alignment(Alignment::Center);
+    f.render_widget(help, help_area);
+}
+
+fn render_links(f: &mut Frame, app: &App, size: Rect) {
+    let area = centered_rect(70, 70, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
+
+    let block = Block::default()
+        .title(format!(" Links - {} ", app.current_book_title))
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(palette.accent));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let list_area = Rect {
+        height: inner.height.saturating_sub(1),
+        ..inner
+    };
+
+    let links = app.links();
+    if links.is_empty() {
+        let text = Paragraph::new("No links were found in this book")
+            .style(Style::default().fg(palette.muted))
+            .alignment(Alignment::Center);
+        f.render_widget(text, list_area);
+    } else {
+        let items: Vec<ListItem> = links
+            .iter()
+            .map(|link| {
+                let mut lines = vec![Line::from(Span::styled(
+                    &link.label,
+                    Style::default().fg(palette.good),
+                ))];
+                if link.label != link.url {
+                    lines.push(Line::from(Span::styled(
+                        format!("  {}", link.url),
+                        Style::default().fg(palette.muted),
+                    )));
+                }
+                ListItem::new(lines)
+            })
+            .collect();
+
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(palette.selection))
+            .highlight_symbol("-> ");
+
+        let mut state = app.links_state.clone();
+        f.render_stateful_widget(list, list_area, &mut state);
+    }
+
+    let help_area = Rect::new(area.x + 1, area.y + area.height - 2, area.width - 2, 1);
+    let help = Paragraph::new("Enter: Open in browser | Esc: Close")
+        .style(Style::default().fg(palette.muted))
+        .alignment(Alignment::Center);
+    f.render_widget(help, help_area);
+}
+
+fn render_analysis(f: &mut Frame, app: &App, size: Rect) {
+    let area = centered_rect(60, 50, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
+
+    let book = app
+        .current_book_id
+        .as_ref()
+        .and_then(|id| app.library.book(id));
+    let mut rows = book.map(Book::details).unwrap_or_default();
+    if let Some(analysis) = book.and_then(|book| book.analysis.as_ref()) {
+        let read = app.word_index as f64 / app.words.len().max(1) as f64;
+        rows.extend(analysis.summary(app.wpm, &app.pauses(), read));
+    }
+    let mut text = vec![Line::from("")];
+    for (label, value) in rows {
+        text.push(Line::from(vec![
+            Span::styled(
+                format!("  {:<14}", label),
+                Style::default().fg(palette.good),
+            ),
+            Span::styled(value, Style::default().fg(palette.text)),
+        ]));
+    }
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        "  Press any key to close",
+        Style::default().fg(palette.muted),
+    )));
+
+    let paragraph = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title(format!(" Analysis - {} ", app.current_book_title))
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(palette.accent)),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(paragraph, area);
+}
+
+fn render_book_details(f: &mut Frame, app: &App, size: Rect) {
+    let area = centered_rect(60, 60, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
+
+    let book = app
+        .details_book
+        .as_ref()
+        .and_then(|id| app.library.book(id));
+    let mut text = vec![Line::from("")];
+    if let Some(book) = book {
+        let rows = book_summary(book, &app.library.stats, app.wpm, &app.config.pauses);
+        for (label, value) in rows {
+            text.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:<16}", label),
+                    Style::default().fg(palette.good),
+                ),
+                Span::styled(value, Style::default().fg(palette.text)),
+            ]));
+        }
+    }
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        "  Press any key to go back",
+        Style::default().fg(palette.muted),
+    )));
+
+    let title = book.map_or("", |book| book.title.as_str());
+    let paragraph = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title(format!(" Details - {} ", title))
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(palette.accent)),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(paragraph, area);
+}
+
+fn render_library_stats(f: &mut Frame, app: &App, size: Rect) {
+    let area = centered_rect(60, 50, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
+
+    let mut text = vec![Line::from("")];
+    for (label, value) in library_summary(&app.library) {
+        text.push(Line::from(vec![
+            Span::styled(
+                format!("  {:<20}", label),
+                Style::default().fg(palette.good),
+            ),
+            Span::styled(value, Style::default().fg(palette.text)),
+        ]));
+    }
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        "  Press any key to go back",
+        Style::default().fg(palette.muted),
+    )));
+
+    let paragraph = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title(" Library Statistics ")
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(palette.accent)),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(paragraph, area);
+}
+
+fn render_book_stats(f: &mut Frame, app: &App, size: Rect) {
+    let area = centered_rect(60, 40, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
+
+    let mut text = vec![Line::from("")];
+    if let Some(id) = &app.current_book_id {
+        for (label, value) in book_stats(&app.library.stats, id, app.words.len()) {
+            text.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:<14}", label),
+                    Style::default().fg(palette.good),
+                ),
+                Span::styled(value, Style::default().fg(palette.text)),
+            ]));
+        }
+    }
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        "  Press any key to close",
+        Style::default().fg(palette.muted),
+    )));
+
+    let paragraph = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title(format!(" Statistics - {} ", app.current_book_title))
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(palette.accent)),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(paragraph, area);
+}
+
+/// Playlists on the left, and the selected one's items with their
+/// progress on the right
+fn render_playlists(f: &mut Frame, app: &App, size: Rect) {
+    let area = centered_rect(70, 60, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
+
+    let block = Block::default()
+        .title(" Playlists ")
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(palette.accent));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if app.library.playlists.is_empty() {
+        let text =
+            Paragraph::new("No playlists yet. Press 'a' in the library to add a book to one.")
+                .style(Style::default().fg(palette.muted))
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: false });
+        f.render_widget(text, inner);
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .split(inner);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(chunks[0]);
+
+    let playlists: Vec<ListItem> = app
+        .library
+        .playlists
+        .iter()
+        .map(|playlist| {
+            let books = app.library.playlist_books(&playlist.name);
+            let read = books.iter().filter(|book| book.is_finished()).count();
+            let name_style = if app.playlist.as_ref() == Some(&playlist.name) {
+                Style::default().fg(palette.good)
+            } else {
+                Style::default().fg(palette.text)
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(&playlist.name, name_style.add_modifier(Modifier::BOLD)),
+                Span::styled(
+                    format!(" ({}/{} read)", read, books.len()),
+                    Style::default().fg(palette.muted),
+                ),
+            ]))
+        })
+        .collect();
+    let list = List::new(playlists)
+        .highlight_style(
+            Style::default()
+                .bg(palette.selection)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("-> ");
+    let mut state = app.playlists_state.clone();
+    f.render_stateful_widget(list, columns[0], &mut state);
+
+    let selected = app
+        .playlists_state
+        .selected()
+        .and_then(|i| app.library.playlists.get(i));
+    if let Some(playlist) = selected {
+        let items: Vec<ListItem> = app
+            .library
+            .playlist_books(&playlist.name)
+            .into_iter()
+            .enumerate()
+            .map(|(i, book)| {
+                let pct = if book.total_words > 0 {
+                    (book.progress as f64 / book.total_words as f64) * 100.0
+                } else {
+                    0.0
+                };
+                let title_style = if Some(&book.id) == app.current_book_id.as_ref() {
+                    Style::default().fg(palette.good)
+                } else if book.is_finished() {
+                    Style::default().fg(palette.muted)
+                } else {
+                    Style::default().fg(palette.text)
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{:>2}. ", i + 1),
+                        Style::default().fg(palette.muted),
+                    ),
+                    Span::styled(&book.title, title_style),
+                    Span::styled(
+                        format!(" ({:.0}%)", pct),
+                        Style::default().fg(palette.muted),
+                    ),
+                ]))
+            })
+            .collect();
+        let items = List::new(items).block(
+            Block::default()
+                .borders(Borders::LEFT)
+                .border_style(Style::default().fg(palette.muted)),
+        );
+        f.render_widget(items, columns[1]);
+    }
+
+    let help = Paragraph::new("Enter: Play from the first unread | d: Delete | Esc: Close")
+        .style(Style::default().fg(palette.muted))
+        .alignment(Alignment::Center);
+    f.render_widget(help, chunks[1]);
+}
+
+/// Settings for the open book, typed as `key=value` pairs
+fn render_book_display(f: &mut Frame, app: &App, size: Rect) {
+    let area = centered_rect(60, 40, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
+
+    let block = Block::default()
+        .title(" Book Display ")
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(palette.accent));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+
+    let label = Paragraph::new(format!("Settings for '{}':", app.current_book_title))
+        .style(Style::default().fg(palette.text));
+    f.render_widget(label, chunks[0]);
+
+    let input = Paragraph::new(app.display_input.as_str())
+        .style(Style::default().fg(palette.text))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(palette.info)),
+        );
+    f.render_widget(input, chunks[1]);
+    let cursor_x = chunks[1].x + 1 + app.display_input.chars().count() as u16;
+    f.set_cursor_position((
+        cursor_x.min(chunks[1].x + chunks[1].width - 2),
+        chunks[1].y + 1,
+    ));
+
+    let schemes: Vec<String> = ColorScheme::ALL.into_iter().map(setting_name).collect();
+    let mut keys = vec![
+        format!("colors: {}", schemes.join(", ")),
+        "chunk: words shown at once".to_string(),
+        "blocks: read, skip or show code blocks and tables".to_string(),
+        "verse: on keeps to the text's own lines".to_string(),
+    ];
+    if !app.config.profiles.is_empty() {
+        let names: Vec<&str> = app.config.profiles.keys().map(String::as_str).collect();
+        keys.push(format!("profile: {}", names.join(", ")));
+    }
+    let keys = Paragraph::new(keys.join("\n"))
+        .style(Style::default().fg(palette.muted))
+        .wrap(Wrap { trim: false });
+    f.render_widget(keys, chunks[2]);
+
+    let help = Paragraph::new("Like: colors=gruvbox chunk=2 | Enter: Save | Esc: Cancel")
+        .style(Style::default().fg(palette.muted))
+        .alignment(Alignment::Center);
+    f.render_widget(help, chunks[3]);
+}
+
+/// The name of the playlist the selected library book goes into
+fn render_playlist_name(f: &mut Frame, app: &App, size: Rect) {
+    let area = centered_rect(50, 30, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
+
+    let block = Block::default()
+        .title(" Add to Playlist ")
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(palette.accent));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+
+    let title = app.selected_book().map_or("", |b| b.title.as_str());
+    let label = Paragraph::new(format!("Playlist for '{}':", title))
+        .style(Style::default().fg(palette.text));
+    f.render_widget(label, chunks[0]);
+
+    let input = Paragraph::new(app.playlist_input.as_str())
+        .style(Style::default().fg(palette.text))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(palette.info)),
+        );
+    f.render_widget(input, chunks[1]);
+    let cursor_x = chunks[1].x + 1 + app.playlist_input.chars().count() as u16;
+    f.set_cursor_position((
+        cursor_x.min(chunks[1].x + chunks[1].width - 2),
+        chunks[1].y + 1,
+    ));
+
+    if !app.library.playlists.is_empty() {
+        let names: Vec<&str> = app
+            .library
+            .playlists
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        let existing = Paragraph::new(format!("Playlists: {}", names.join(", ")))
+            .style(Style::default().fg(palette.muted))
+            .wrap(Wrap { trim: false });
+        f.render_widget(existing, chunks[2]);
+    }
 
-        // Before ORP (right-aligned to center)
-        if !before.is_empty() {
-            let before_x = center_x.saturating_sub(before.len() as u16);
-            let before_widget = Paragraph::new(before.clone())
-                .style(Style::default().fg(Color::White));
-            f.render_widget(before_widget, Rect::new(before_x, center_y, before.len() as u16, 1));
-        }
+    let help = Paragraph::new("Enter: Add | Tab: Complete | Esc: Cancel")
+        .style(Style::default().fg(palette.muted))
+        .alignment(Alignment::Center);
+    f.render_widget(help, chunks[3]);
+}
 
-        // ORP character (at center, in red)
-        let orp_widget = Paragraph::new(orp_char.clone())
-            .style(Style::default().fg(Color::Red));
-        f.render_widget(orp_widget, Rect::new(center_x, center_y, 1, 1));
+/// The book just finished: time spent, speed, sessions and streak, and
+/// what to read next
+fn render_finished(f: &mut Frame, app: &App, size: Rect) {
+    let area = centered_rect(60, 50, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
 
-        // After ORP (left-aligned from center+1)
-        if !after.is_empty() {
-            let after_x = center_x + 1;
-            let after_widget = Paragraph::new(after.clone())
-                .style(Style::default().fg(Color::White));
-            f.render_widget(after_widget, Rect::new(after_x, center_y, after.len() as u16, 1));
+    let mut text = vec![Line::from("")];
+    if let Some(id) = &app.current_book_id {
+        for (label, value) in completion_summary(&app.library.stats, id) {
+            text.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:<14}", label),
+                    Style::default().fg(palette.good),
+                ),
+                Span::styled(value, Style::default().fg(palette.text)),
+            ]));
         }
-    } else {
-        let text = Paragraph::new("Ready")
-            .style(Style::default().fg(Color::DarkGray))
-            .alignment(Alignment::Center);
-
-        let centered = Rect::new(inner.x, center_y, inner.width, 1);
-        f.render_widget(text, centered);
     }
+    text.push(Line::from(""));
+    let action = |key: &str, label: String| {
+        Line::from(vec![
+            Span::styled(format!("  {:<6}", key), Style::default().fg(palette.accent)),
+            Span::styled(label, Style::default().fg(palette.text)),
+        ])
+    };
+    text.push(action("r", "Read it again".to_string()));
+    text.push(action("o", "Open the library".to_string()));
+    if let Some(next) = app.next_book() {
+        text.push(action("n", format!("Start the next book: {}", next.title)));
+    }
+    text.push(action("Esc", "Close".to_string()));
+
+    let paragraph = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title(format!(" Finished - {} ", app.current_book_title))
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(palette.accent)),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(paragraph, area);
 }
 
-fn render_stats(f: &mut Frame, app: &App, area: Rect) {
-    let status = if app.is_playing {
-        "Playing"
-    } else {
-        "Paused"
+/// Between chapters: the one just read and the one coming up
+fn render_chapter_break(f: &mut Frame, app: &App, size: Rect) {
+    let Some(pause) = &app.chapter_break else {
+        return;
     };
-    let status_color = if app.is_playing {
-        Color::Green
+    let area = centered_rect(60, 40, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
+
+    let mut text = vec![Line::from("")];
+    if let Some(finished) = &pause.finished {
+        text.push(Line::from(Span::styled(
+            "Finished",
+            Style::default().fg(palette.muted),
+        )));
+        text.push(Line::from(Span::styled(
+            finished.as_str(),
+            Style::default().fg(palette.text),
+        )));
+        text.push(Line::from(""));
+    }
+    text.push(Line::from(Span::styled(
+        "Up next",
+        Style::default().fg(palette.muted),
+    )));
+    text.push(Line::from(Span::styled(
+        pause.next.as_str(),
+        Style::default()
+            .fg(palette.accent)
+            .add_modifier(Modifier::BOLD),
+    )));
+    text.push(Line::from(""));
+    let hint = if pause.until.is_some() {
+        "Press any key to carry on now"
     } else {
-        Color::Yellow
+        "Press any key to carry on"
     };
+    text.push(Line::from(Span::styled(
+        hint,
+        Style::default().fg(palette.muted),
+    )));
 
-    let stats_text = Line::from(vec![
-        Span::styled(
-            format!("WPM: {} ", app.wpm),
-            Style::default().fg(Color::Cyan),
-        ),
-        Span::raw("| "),
-        Span::styled(
-            format!("Word: {}/{} ", app.word_index + 1, app.words.len().max(1)),
-            Style::default().fg(Color::Blue),
-        ),
-        Span::raw("| "),
-        Span::styled(
-            format!("Progress: {:.1}% ", app.progress_percent()),
-            Style::default().fg(Color::Magenta),
-        ),
-        Span::raw("| "),
-        Span::styled(
-            status,
-            Style::default()
-                .fg(status_color)
-                .add_modifier(Modifier::BOLD),
-        ),
-        if let Some((msg, _)) = &app.status_message {
-            Span::styled(format!(" | {}", msg), Style::default().fg(Color::Yellow))
-        } else {
-            Span::raw("")
-        },
-    ]);
-
-    let stats = Paragraph::new(stats_text).alignment(Alignment::Center).block(
-        Block::default()
-            .borders(Borders::TOP)
-            .border_style(Style::default().fg(Color::DarkGray)),
-    );
+    let paragraph = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .title(" Chapter Break ")
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(palette.accent)),
+        )
+        .wrap(Wrap { trim: false });
 
-    f.render_widget(stats, area);
+    f.render_widget(paragraph, area);
 }
 
-fn render_library(f: &mut Frame, app: &App, size: Rect) {
-    let area = centered_rect(60, 70, size);
-    f.render_widget(Clear, area);
+/// A code block or table, drawn whole as written
+fn render_block_pause(f: &mut Frame, app: &App, size: Rect) {
+    let Some(pause) = &app.block_pause else {
+        return;
+    };
+    let area = centered_rect(90, 80, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
 
     let block = Block::default()
-        .title(" Library ")
+        .title(format!(" {} ", pause.kind))
         .title_alignment(Alignment::Center)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
-
+        .border_style(Style::default().fg(palette.accent));
     let inner = block.inner(area);
     f.render_widget(block, area);
 
-    if app.library.books.is_empty() {
-        let text = Paragraph::new("No books in library.\n\nPress 'i' to import a file.")
-            .style(Style::default().fg(Color::DarkGray))
-            .alignment(Alignment::Center);
-        f.render_widget(text, inner);
-    } else {
-        let items: Vec<ListItem> = app
-            .library
-            .books
-            .iter()
-            .map(|book| {
-                let marker = if Some(&book.id) == app.current_book_id.as_ref() {
-                    "> "
-                } else {
-                    "  "
-                };
-                let pct = if book.total_words > 0 {
-                    (book.progress as f64 / book.total_words as f64) * 100.0
-                } else {
-                    0.0
-                };
-                let line = Line::from(vec![
-                    Span::styled(marker, Style::default().fg(Color::Green)),
-                    Span::styled(
-                        &book.title,
-                        Style::default()
-                            .fg(Color::White)
-                            .add_modifier(Modifier::BOLD),
-                    ),
-                    Span::styled(
-                        format!(" ({:.0}% - {} words)", pct, book.total_words),
-                        Style::default().fg(Color::DarkGray),
-                    ),
-                ]);
-                ListItem::new(line)
-            })
-            .collect();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
 
-        let list = List::new(items)
-            .highlight_style(
-                Style::default()
-                    .bg(Color::DarkGray)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .highlight_symbol("-> ");
+    // Long lines are cut rather than wrapped, to keep columns lined up
+    let text = Paragraph::new(pause.text.as_str()).style(Style::default().fg(palette.text));
+    f.render_widget(text, chunks[0]);
 
-        // Need to render with state for highlighting
-        let mut state = app.library_state.clone();
-        f.render_stateful_widget(list, inner, &mut state);
-    }
+    let hint = Paragraph::new("Press any key to read on")
+        .style(Style::default().fg(palette.muted))
+        .alignment(Alignment::Center);
+    f.render_widget(hint, chunks[1]);
+}
 
-    // Help text at bottom
-    let help_area = Rect::new(area.x + 1, area.y + area.height - 2, area.width - 2, 1);
-    let help = Paragraph::new("Enter: Open | d: Delete | Esc: Close")
-        .style(Style::default().fg(Color::DarkGray))
+/// A footnote over the word display
+fn render_footnote(f: &mut Frame, app: &App, size: Rect) {
+    let Some((note, _)) = &app.footnote else {
+        return;
+    };
+    let area = centered_rect(70, 50, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
+
+    let block = Block::default()
+        .title(format!(" Footnote {} ", note.label))
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(palette.accent));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    let text = Paragraph::new(note.text.as_str())
+        .style(Style::default().fg(palette.text))
+        .wrap(Wrap { trim: true });
+    f.render_widget(text, chunks[0]);
+
+    let hint = Paragraph::new("Press any key to go back")
+        .style(Style::default().fg(palette.muted))
         .alignment(Alignment::Center);
-    f.render_widget(help, help_area);
+    f.render_widget(hint, chunks[1]);
 }
 
 fn render_file_input(f: &mut Frame, app: &App, size: Rect) {
-    let area = centered_rect(70, 30, size);
-    f.render_widget(Clear, area);
+    let area = centered_rect(70, 35, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
 
     let block = Block::default()
         .title(" Import File ")
         .title_alignment(Alignment::Center)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(Style::default().fg(palette.accent));
 
     let inner = block.inner(area);
     f.render_widget(block, area);
@@ -638,24 +3173,28 @@ fn render_file_input(f: &mut Frame, app: &App, size: Rect) {
             Constraint::Length(1),
             Constraint::Length(3),
             Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
             Constraint::Min(0),
         ])
         .split(inner);
 
     // Label
-    let label = Paragraph::new("Enter file path:").style(Style::default().fg(Color::White));
+    let label =
+        Paragraph::new("Enter file or folder path:").style(Style::default().fg(palette.text));
     f.render_widget(label, chunks[0]);
 
     // Input field
     let input_style = if app.file_input_error.is_some() {
-        Style::default().fg(Color::Red)
+        Style::default().fg(palette.error)
     } else {
-        Style::default().fg(Color::White)
+        Style::default().fg(palette.text)
     };
     let input = Paragraph::new(app.file_input.as_str()).style(input_style).block(
         Block::default()
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Blue)),
+            .border_style(Style::default().fg(palette.info)),
     );
     f.render_widget(input, chunks[1]);
 
@@ -666,26 +3205,59 @@ fn render_file_input(f: &mut Frame, app: &App, size: Rect) {
 
     // Error message
     if let Some(ref error) = app.file_input_error {
-        let error_text = Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red));
+        let error_text = Paragraph::new(error.as_str()).style(Style::default().fg(palette.error));
         f.render_widget(error_text, chunks[2]);
     }
 
+    // Import options
+    let checkbox = |checked: bool| if checked { "[x]" } else { "[ ]" };
+    let citations = Paragraph::new(format!(
+        "{} Strip citation markers (Tab)",
+        checkbox(app.import_options.strip_citations)
+    ))
+    .style(Style::default().fg(palette.text));
+    f.render_widget(citations, chunks[3]);
+    let collection = Paragraph::new(format!(
+        "{} Tag folder imports with the folder name (Shift+Tab)",
+        checkbox(app.import_options.tag_collection)
+    ))
+    .style(Style::default().fg(palette.text));
+    f.render_widget(collection, chunks[4]);
+    let matter = Paragraph::new(format!(
+        "{} Skip license, contents, index and bibliography (F2)",
+        checkbox(app.import_options.skip_matter)
+    ))
+    .style(Style::default().fg(palette.text));
+    f.render_widget(matter, chunks[5]);
+
     // Help
     let help = Paragraph::new("Enter: Import | Esc: Cancel")
-        .style(Style::default().fg(Color::DarkGray))
+        .style(Style::default().fg(palette.muted))
         .alignment(Alignment::Center);
-    f.render_widget(help, chunks[3]);
+    f.render_widget(help, chunks[6]);
 }
 
-fn render_help(f: &mut Frame, size: Rect) {
+fn render_help(f: &mut Frame, app: &App, size: Rect) {
     let area = centered_rect(60, 80, size);
-    f.render_widget(Clear, area);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
+
+    let key = |action: Action, description: &str| help_line(app, action, description);
+    let step = app.config.wpm_step;
+    let shift_step = app.config.shift_step();
+    let presets: Vec<String> = app
+        .config
+        .speed_presets
+        .iter()
+        .take(9)
+        .map(|wpm| wpm.to_string())
+        .collect();
 
     let help_text = vec![
         Line::from(Span::styled(
             "RSVP Reader - Keyboard Shortcuts",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(palette.accent)
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
@@ -693,90 +3265,91 @@ fn render_help(f: &mut Frame, size: Rect) {
             "Playback Controls:",
             Style::default().add_modifier(Modifier::BOLD),
         )),
-        Line::from(vec![
-            Span::styled("  Space      ", Style::default().fg(Color::Green)),
-            Span::raw("Start/Pause reading"),
-        ]),
-        Line::from(vec![
-            Span::styled("  r          ", Style::default().fg(Color::Green)),
-            Span::raw("Reset to beginning"),
-        ]),
+        key(Action::PlayPause, "Start/Pause reading"),
+        key(Action::Reset, "Reset to beginning"),
         Line::from(""),
         Line::from(Span::styled(
             "Speed Control:",
             Style::default().add_modifier(Modifier::BOLD),
         )),
+        key(
+            Action::SpeedUp,
+            &format!("Increase WPM by {} (Shift: {})", step, shift_step),
+        ),
+        key(
+            Action::SpeedDown,
+            &format!("Decrease WPM by {} (Shift: {})", step, shift_step),
+        ),
         Line::from(vec![
-            Span::styled("  Up / k     ", Style::default().fg(Color::Green)),
-            Span::raw("Increase WPM by 50"),
-        ]),
-        Line::from(vec![
-            Span::styled("  Down / j   ", Style::default().fg(Color::Green)),
-            Span::raw("Decrease WPM by 50"),
-        ]),
-        Line::from(vec![
-            Span::styled("  Shift+Up   ", Style::default().fg(Color::Green)),
-            Span::raw("Increase WPM by 100"),
-        ]),
-        Line::from(vec![
-            Span::styled("  Shift+Down ", Style::default().fg(Color::Green)),
-            Span::raw("Decrease WPM by 100"),
+            Span::styled(
+                format!("  {:<11}", "1-9"),
+                Style::default().fg(palette.good),
+            ),
+            Span::raw(format!("Speed presets: {}", presets.join(", "))),
         ]),
         Line::from(""),
         Line::from(Span::styled(
             "Navigation:",
             Style::default().add_modifier(Modifier::BOLD),
         )),
-        Line::from(vec![
-            Span::styled("  Left / h   ", Style::default().fg(Color::Green)),
-            Span::raw("Go back 1 word"),
-        ]),
-        Line::from(vec![
-            Span::styled("  Right / l  ", Style::default().fg(Color::Green)),
-            Span::raw("Go forward 1 word"),
-        ]),
-        Line::from(vec![
-            Span::styled("  [ / b      ", Style::default().fg(Color::Green)),
-            Span::raw("Go back 10 words"),
-        ]),
-        Line::from(vec![
-            Span::styled("  ] / w      ", Style::default().fg(Color::Green)),
-            Span::raw("Go forward 10 words"),
-        ]),
+        key(Action::PrevWord, "Go back 1 word"),
+        key(Action::NextWord, "Go forward 1 word"),
+        key(Action::SkipBack, "Go back 10 words"),
+        key(Action::SkipForward, "Go forward 10 words"),
+        key(Action::SentenceStart, "Start of sentence"),
+        key(Action::ParagraphStart, "Start of paragraph"),
+        key(Action::PrevSentence, "Previous sentence"),
+        key(Action::NextSentence, "Next sentence"),
+        key(Action::PrevParagraph, "Previous paragraph"),
+        key(Action::NextParagraph, "Next paragraph"),
+        key(Action::JumpBack, "Back to the position before a jump"),
+        key(Action::JumpForward, "Forward again"),
+        key(Action::ToggleLoop, "Loop sentence, then paragraph"),
+        key(Action::MarkLoop, "Mark loop point A, then B"),
         Line::from(""),
         Line::from(Span::styled(
             "Library:",
             Style::default().add_modifier(Modifier::BOLD),
         )),
+        key(Action::Library, "Open library"),
+        key(Action::Recents, "Recent books"),
+        key(Action::Playlists, "Playlists"),
         Line::from(vec![
-            Span::styled("  o          ", Style::default().fg(Color::Green)),
-            Span::raw("Open library"),
-        ]),
-        Line::from(vec![
-            Span::styled("  i          ", Style::default().fg(Color::Green)),
-            Span::raw("Import new file"),
-        ]),
-        Line::from(vec![
-            Span::styled("  d          ", Style::default().fg(Color::Green)),
-            Span::raw("Delete current book"),
+            Span::styled("  /          ", Style::default().fg(palette.good)),
+            Span::raw("Search library (title or tag)"),
         ]),
+        key(Action::Import, "Import new file"),
+        key(Action::DeleteBook, "Delete current book"),
         Line::from(""),
         Line::from(Span::styled(
             "Other:",
             Style::default().add_modifier(Modifier::BOLD),
         )),
-        Line::from(vec![
-            Span::styled("  ?          ", Style::default().fg(Color::Green)),
-            Span::raw("Show this help"),
-        ]),
-        Line::from(vec![
-            Span::styled("  q / Esc    ", Style::default().fg(Color::Green)),
-            Span::raw("Quit"),
-        ]),
+        key(Action::Help, "Show this help"),
+        key(Action::CycleTheme, "Cycle color scheme"),
+        key(Action::CycleColorVision, "Color blindness palette"),
+        key(Action::CycleProgress, "Progress in percent, words, time or chapter"),
+        key(Action::CyclePacing, "Pace by words, characters, syllables"),
+        key(Action::BookDisplay, "This book's colors, chunks and pacing"),
+        key(Action::Narrate, "Narration on/off"),
+        key(Action::ToggleSkim, "Skim function words"),
+        key(Action::ToggleKeywords, "Keyword emphasis"),
+        key(Action::RecallCheck, "Recall check"),
+        key(Action::SaveWord, "Save word to vocabulary"),
+        key(Action::Vocabulary, "Saved words"),
+        key(Action::ShowFootnote, "Show the footnote just passed"),
+        key(Action::Links, "Links in the book"),
+        key(Action::Analysis, "Book analysis"),
+        key(Action::Stats, "Reading statistics"),
+        key(Action::SpeedTest, "Speed test"),
+        key(Action::ToggleZen, "Minimal display"),
+        key(Action::ReloadConfig, "Reload config.toml"),
+        key(Action::ToggleDebug, "Timing diagnostics"),
+        key(Action::Quit, "Quit"),
         Line::from(""),
         Line::from(Span::styled(
             "Press any key to close",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(palette.muted),
         )),
     ];
 
@@ -786,36 +3359,58 @@ fn render_help(f: &mut Frame, size: Rect) {
                 .title(" Help ")
                 .title_alignment(Alignment::Center)
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan)),
+                .border_style(Style::default().fg(palette.accent)),
         )
         .alignment(Alignment::Left);
 
     f.render_widget(paragraph, area);
 }
 
+/// A help entry listing the keys currently bound to `action`
+fn help_line(app: &App, action: Action, description: &str) -> Line<'static> {
+    let palette = app.palette();
+    let keys: Vec<String> = app
+        .config
+        .keys
+        .keys(action)
+        .iter()
+        // Media keys are labelled on the keyboard already
+        .filter(|key| !key.key.is_media())
+        .map(|key| key.to_string())
+        .collect();
+    Line::from(vec![
+        Span::styled(
+            format!("  {:<11}", keys.join(" / ")),
+            Style::default().fg(palette.good),
+        ),
+        Span::raw(description.to_string()),
+    ])
+}
+
 fn render_confirm(f: &mut Frame, app: &App, size: Rect) {
     let area = centered_rect(50, 20, size);
-    f.render_widget(Clear, area);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
 
     let text = vec![
         Line::from(""),
         Line::from(Span::styled(
             &app.confirm_message,
-            Style::default().fg(Color::White),
+            Style::default().fg(palette.text),
         )),
         Line::from(""),
         Line::from(vec![
             Span::styled(
                 "y",
                 Style::default()
-                    .fg(Color::Green)
+                    .fg(palette.good)
                     .add_modifier(Modifier::BOLD),
             ),
             Span::raw(": Yes  "),
             Span::styled(
                 "n",
                 Style::default()
-                    .fg(Color::Red)
+                    .fg(palette.error)
                     .add_modifier(Modifier::BOLD),
             ),
             Span::raw(": No"),
@@ -828,13 +3423,200 @@ fn render_confirm(f: &mut Frame, app: &App, size: Rect) {
                 .title(" Confirm ")
                 .title_alignment(Alignment::Center)
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Yellow)),
+                .border_style(Style::default().fg(palette.warn)),
+        )
+        .alignment(Alignment::Center);
+
+    f.render_widget(paragraph, area);
+}
+
+fn render_break(f: &mut Frame, app: &App, size: Rect) {
+    let area = centered_rect(50, 40, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
+
+    let remaining = app.breaks.remaining(&app.config.breaks).unwrap_or_default();
+    let (countdown, hint) = if remaining.is_zero() {
+        ("Break over".to_string(), "Press any key to keep reading")
+    } else {
+        (
+            format_countdown(remaining),
+            "Press any key to end the break early",
+        )
+    };
+
+    let text = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "Time for a break",
+            Style::default()
+                .fg(palette.accent)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Look away from the screen and let your eyes rest",
+            Style::default().fg(palette.text),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            countdown,
+            Style::default()
+                .fg(palette.good)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(hint, Style::default().fg(palette.muted))),
+    ];
+
+    let paragraph = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title(" Break ")
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(palette.accent)),
+        )
+        .alignment(Alignment::Center);
+
+    f.render_widget(paragraph, area);
+}
+
+fn render_recall(f: &mut Frame, app: &App, size: Rect) {
+    let Some(check) = &app.recall else {
+        return;
+    };
+    let area = centered_rect(50, 50, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
+
+    let mut text = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "Which of these came up in the last minute?",
+            Style::default().fg(palette.text),
+        )),
+        Line::from(""),
+    ];
+    for (i, choice) in check.choices.iter().enumerate() {
+        let mark = if choice.picked { "[x]" } else { "[ ]" };
+        // Once answered, color each answer by whether it was right
+        let color = match check.result {
+            Some(_) if choice.picked == choice.appeared => palette.good,
+            Some(_) => palette.error,
+            None => palette.word,
+        };
+        let mut line = vec![
+            Span::styled(format!("{} ", i + 1), Style::default().fg(palette.muted)),
+            Span::styled(
+                format!("{} {}", mark, choice.word),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ),
+        ];
+        if check.result.is_some() && choice.appeared {
+            line.push(Span::styled(" (shown)", Style::default().fg(palette.muted)));
+        }
+        text.push(Line::from(line));
+    }
+    text.push(Line::from(""));
+
+    match check.result {
+        Some(score) => {
+            let average = app.library.stats.recent_recall().unwrap_or(score.percent());
+            text.push(Line::from(Span::styled(
+                format!(
+                    "{}/{} right, {}% over recent checks",
+                    score.correct, score.total, average
+                ),
+                Style::default()
+                    .fg(palette.accent)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            text.push(Line::from(Span::styled(
+                "Press any key to go back to reading",
+                Style::default().fg(palette.muted),
+            )));
+        }
+        None => text.push(Line::from(Span::styled(
+            "1-5: pick words  Enter: check answers  Esc: skip",
+            Style::default().fg(palette.muted),
+        ))),
+    }
+
+    let paragraph = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title(" Recall Check ")
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(palette.accent)),
         )
         .alignment(Alignment::Center);
 
     f.render_widget(paragraph, area);
 }
 
+fn render_speed_test(f: &mut Frame, app: &App, size: Rect) {
+    // The passage plays in the reading view; the popup is for the questions
+    let Some(test) = app.speed_test.as_ref().filter(|test| !test.reading) else {
+        return;
+    };
+    let area = centered_rect(60, 50, size);
+    let palette = app.palette();
+    clear_area(f, &palette, area);
+
+    let mut text = vec![Line::from("")];
+    if let Some(result) = test.result {
+        let baseline = app.library.stats.speed_baseline();
+        text.push(Line::from(Span::styled(
+            result.summary(baseline),
+            Style::default()
+                .fg(palette.accent)
+                .add_modifier(Modifier::BOLD),
+        )));
+        text.push(Line::from(""));
+        text.push(Line::from(Span::styled(
+            "Press any key to go back to reading",
+            Style::default().fg(palette.muted),
+        )));
+    } else if let Some((i, question)) = test.question() {
+        text.push(Line::from(Span::styled(
+            format!("{}. {}", i + 1, question.prompt),
+            Style::default().fg(palette.text),
+        )));
+        text.push(Line::from(""));
+        for (n, choice) in question.choices.iter().enumerate() {
+            text.push(Line::from(vec![
+                Span::styled(format!("{} ", n + 1), Style::default().fg(palette.muted)),
+                Span::styled(
+                    *choice,
+                    Style::default()
+                        .fg(palette.word)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]));
+        }
+        text.push(Line::from(""));
+        text.push(Line::from(Span::styled(
+            "1-4: answer  Esc: cancel",
+            Style::default().fg(palette.muted),
+        )));
+    }
+
+    let paragraph = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title(" Speed Test ")
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(palette.accent)),
+        )
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(paragraph, area);
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -860,97 +3642,207 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
 // ============================================================================
 
 fn handle_events(app: &mut App) -> io::Result<bool> {
-    if event::poll(Duration::from_millis(50))? {
-        if let Event::Key(key) = event::read()? {
+    if event::poll(app.poll_timeout())? {
+        let event = event::read()?;
+        app.needs_redraw = true;
+        app.dimmer.wake();
+        if let Event::Key(key) = event {
             match app.mode {
                 AppMode::Reading => return handle_reading_keys(app, key.code, key.modifiers),
                 AppMode::Library => handle_library_keys(app, key.code),
+                AppMode::Recents => handle_recents_keys(app, key.code),
+                AppMode::Playlists => handle_playlists_keys(app, key.code),
+                AppMode::PlaylistName => handle_playlist_name_keys(app, key.code),
+                AppMode::BookDisplay => handle_book_display_keys(app, key.code),
+                AppMode::Vocabulary => handle_vocabulary_keys(app, key.code),
+                AppMode::Links => handle_links_keys(app, key.code),
                 AppMode::FileInput => handle_file_input_keys(app, key.code),
-                AppMode::Help => app.mode = AppMode::Reading,
+                AppMode::Help | AppMode::Analysis | AppMode::BookStats => {
+                    app.mode = AppMode::Reading
+                }
+                // Back to the library it was opened from
+                AppMode::BookDetails | AppMode::LibraryStats => app.mode = AppMode::Library,
                 AppMode::Confirm => handle_confirm_keys(app, key.code),
+                AppMode::Break => app.finish_break(),
+                AppMode::Recall => handle_recall_keys(app, key.code),
+                AppMode::SpeedTest => handle_speed_test_keys(app, key.code),
+                AppMode::Finished => handle_finished_keys(app, key.code),
+                AppMode::Start => handle_start_keys(app, key.code),
             }
         }
     }
     Ok(false)
 }
 
-fn handle_reading_keys(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> io::Result<bool> {
-    match code {
-        KeyCode::Char('q') | KeyCode::Esc => return Ok(true),
-        KeyCode::Char(' ') => {
+fn handle_reading_keys(app: &mut App, code: KeyCode, modifiers: KeyModifiers) -> io::Result<bool> {
+    // Any key puts a footnote away, carrying on if reading was playing
+    if let Some((_, resume)) = app.footnote.take() {
+        if resume {
+            app.is_playing = true;
+            app.clock.restart();
+        }
+        return Ok(false);
+    }
+    // Any key puts a shown block away and reads on past it
+    if let Some(pause) = app.block_pause.take() {
+        app.word_index = pause.resume_at;
+        app.is_playing = true;
+        app.clock.restart();
+        return Ok(false);
+    }
+    // Any key ends a chapter break and carries on reading
+    if app.chapter_break.take().is_some() {
+        app.is_playing = true;
+        app.clock.restart();
+        return Ok(false);
+    }
+    let action = key_chord(code, modifiers).and_then(|key| app.config.keys.action(&key));
+    let Some(action) = action else {
+        // Number keys not bound to anything else pick a speed preset
+        let preset = match code {
+            KeyCode::Char(c) => app.config.speed_preset(c),
+            _ => None,
+        };
+        if let Some(wpm) = preset {
+            app.wpm = wpm;
+            app.library.settings.set_wpm(wpm);
+            app.save_library();
+            app.show_status(&format!("Speed: {} WPM", wpm));
+        }
+        return Ok(false);
+    };
+
+    match action {
+        Action::Quit => return Ok(true),
+        Action::PlayPause => {
             if !app.words.is_empty() {
                 if app.word_index >= app.words.len() - 1 {
                     app.word_index = 0;
                 }
                 app.is_playing = !app.is_playing;
-                app.last_advance = Instant::now();
+                app.clock.restart();
+                app.blank_until = None;
+                if !app.is_playing && app.config.recall.after_pause {
+                    app.start_recall_check();
+                }
             } else {
                 app.show_status("No book loaded. Press 'i' to import.");
             }
         }
-        KeyCode::Up | KeyCode::Char('k') => {
+        Action::SpeedUp => {
             let increment = if modifiers.contains(KeyModifiers::SHIFT) {
-                100
+                app.config.shift_step()
             } else {
-                50
+                app.config.wpm_step
             };
-            app.wpm = (app.wpm + increment).min(2000);
-            app.library.settings.wpm = app.wpm;
-            save_library(&app.library);
+            app.wpm = app.config.clamp_wpm(app.wpm + increment);
+            app.library.settings.set_wpm(app.wpm);
+            app.save_library();
             app.show_status(&format!("Speed: {} WPM", app.wpm));
         }
-        KeyCode::Down | KeyCode::Char('j') => {
+        Action::SpeedDown => {
             let decrement = if modifiers.contains(KeyModifiers::SHIFT) {
-                100
+                app.config.shift_step()
             } else {
-                50
+                app.config.wpm_step
             };
-            app.wpm = app.wpm.saturating_sub(decrement).max(50);
-            app.library.settings.wpm = app.wpm;
-            save_library(&app.library);
+            app.wpm = app.config.clamp_wpm(app.wpm.saturating_sub(decrement));
+            app.library.settings.set_wpm(app.wpm);
+            app.save_library();
             app.show_status(&format!("Speed: {} WPM", app.wpm));
         }
-        KeyCode::Left | KeyCode::Char('h') => {
+        Action::PrevWord => {
             app.is_playing = false;
             app.word_index = app.word_index.saturating_sub(1);
         }
-        KeyCode::Right | KeyCode::Char('l') => {
+        Action::NextWord => {
             app.is_playing = false;
             if !app.words.is_empty() {
                 app.word_index = (app.word_index + 1).min(app.words.len() - 1);
             }
         }
-        KeyCode::Char('[') | KeyCode::Char('b') => {
+        Action::SkipBack => {
             app.is_playing = false;
-            app.word_index = app.word_index.saturating_sub(10);
+            app.jump(app.word_index.saturating_sub(10));
         }
-        KeyCode::Char(']') | KeyCode::Char('w') => {
+        Action::SkipForward => {
             app.is_playing = false;
             if !app.words.is_empty() {
-                app.word_index = (app.word_index + 10).min(app.words.len() - 1);
+                app.jump((app.word_index + 10).min(app.words.len() - 1));
             }
         }
-        KeyCode::Char('r') => {
+        Action::SentenceStart => {
+            app.is_playing = false;
+            app.jump(app.words.sentence_start(app.word_index));
+        }
+        Action::ParagraphStart => {
+            app.is_playing = false;
+            app.jump(app.words.paragraph_start(app.word_index));
+        }
+        Action::PrevSentence => {
+            app.is_playing = false;
+            app.jump(app.words.prev_sentence(app.word_index));
+        }
+        Action::NextSentence => {
+            app.is_playing = false;
+            app.jump(app.words.next_sentence(app.word_index));
+        }
+        Action::PrevParagraph => {
+            app.is_playing = false;
+            app.jump(app.words.prev_paragraph(app.word_index));
+        }
+        Action::NextParagraph => {
+            app.is_playing = false;
+            app.jump(app.words.next_paragraph(app.word_index));
+        }
+        Action::JumpBack => {
+            app.is_playing = false;
+            match app.history.back(app.word_index) {
+                Some(index) => app.word_index = index,
+                None => app.show_status("No earlier position"),
+            }
+        }
+        Action::JumpForward => {
             app.is_playing = false;
-            app.word_index = 0;
+            match app.history.forward(app.word_index) {
+                Some(index) => app.word_index = index,
+                None => app.show_status("No later position"),
+            }
+        }
+        Action::Reset => {
+            app.is_playing = false;
+            app.jump(0);
             app.save_progress();
             app.show_status("Reset to beginning");
         }
-        KeyCode::Char('o') => {
+        Action::Library => {
             app.is_playing = false;
             app.mode = AppMode::Library;
-            if !app.library.books.is_empty() {
-                app.library_state.select(Some(0));
-            }
+            app.reset_library_filter();
         }
-        KeyCode::Char('i') => {
+        Action::Recents => {
+            app.is_playing = false;
+            app.mode = AppMode::Recents;
+            // The current book leads the list, so start on the one before it
+            let current_first = app.library.recent.first() == app.current_book_id.as_ref();
+            let count = app.library.recent_books().len();
+            let start = if current_first && count > 1 { 1 } else { 0 };
+            app.recents_state.select((count > 0).then_some(start));
+        }
+        Action::Playlists => {
+            app.is_playing = false;
+            app.mode = AppMode::Playlists;
+            let count = app.library.playlists.len();
+            app.playlists_state.select((count > 0).then_some(0));
+        }
+        Action::Import => {
             app.is_playing = false;
             app.mode = AppMode::FileInput;
             app.file_input.clear();
             app.file_input_cursor = 0;
             app.file_input_error = None;
         }
-        KeyCode::Char('d') => {
+        Action::DeleteBook => {
             if app.current_book_id.is_some() {
                 app.is_playing = false;
                 app.confirm_message = format!("Delete '{}'?", app.current_book_title);
@@ -962,54 +3854,210 @@ fn handle_reading_keys(app: &mut App, code: KeyCode, modifiers: KeyModifiers) ->
                 app.show_status("No book loaded");
             }
         }
-        KeyCode::Char('?') => {
+        Action::Help => {
             app.is_playing = false;
             app.mode = AppMode::Help;
         }
-        _ => {}
+        Action::CycleTheme => {
+            let scheme = app.library.settings.color_scheme.next();
+            app.library.settings.color_scheme = scheme;
+            app.save_library();
+            if app.night.is_night() {
+                app.show_status(&format!("Theme: {} (after night mode)", scheme));
+            } else if app.display.color_scheme.is_some() {
+                app.show_status(&format!("Theme: {} (this book keeps its own)", scheme));
+            } else {
+                app.show_status(&format!("Theme: {}", scheme));
+            }
+        }
+        Action::CycleColorVision => {
+            let vision = app.library.settings.color_vision.next();
+            app.library.settings.color_vision = vision;
+            app.save_library();
+            app.show_status(&format!("Palette: {}", vision));
+        }
+        // The stats line shows the change
+        Action::CyclePacing => app.cycle_pacing(),
+        Action::BookDisplay => {
+            if app.current_book_id.is_some() {
+                app.is_playing = false;
+                app.display_input = app.display.to_string();
+                app.mode = AppMode::BookDisplay;
+            } else {
+                app.show_status("No book loaded");
+            }
+        }
+        Action::ToggleLoop => app.toggle_loop(),
+        Action::MarkLoop => app.mark_loop(),
+        Action::CycleProgress => {
+            let unit = app.library.settings.progress_unit.next();
+            app.library.settings.progress_unit = unit;
+            app.save_library();
+        }
+        Action::ToggleKeywords => {
+            let enabled = !app.config.theme.keywords;
+            app.config.theme.keywords = enabled;
+            let state = if enabled { "on" } else { "off" };
+            app.show_status(&format!("Keyword emphasis {}", state));
+        }
+        Action::ToggleSkim => {
+            let enabled = !app.config.skim.enabled;
+            app.config.skim.enabled = enabled;
+            let state = if enabled { "on" } else { "off" };
+            app.show_status(&format!("Skimming {}", state));
+        }
+        Action::SaveWord => app.save_word(),
+        Action::ShowFootnote => app.show_footnote(),
+        Action::Vocabulary => {
+            app.is_playing = false;
+            app.mode = AppMode::Vocabulary;
+            // Newest words are at the bottom
+            let count = app.vocabulary().len();
+            app.vocabulary_state.select(count.checked_sub(1));
+        }
+        Action::Links => {
+            app.is_playing = false;
+            app.mode = AppMode::Links;
+            let count = app.links().len();
+            app.links_state.select((count > 0).then_some(0));
+        }
+        Action::Analysis => {
+            let Some(book) = app
+                .current_book_id
+                .clone()
+                .and_then(|id| app.library.book_mut(&id))
+            else {
+                app.show_status("No book loaded");
+                return Ok(false);
+            };
+            if fill_analysis(book) {
+                app.save_library();
+            }
+            app.is_playing = false;
+            app.mode = AppMode::Analysis;
+        }
+        Action::Stats => {
+            if app.current_book_id.is_none() {
+                app.show_status("No book loaded");
+                return Ok(false);
+            }
+            app.is_playing = false;
+            app.end_session();
+            app.save_library();
+            app.mode = AppMode::BookStats;
+        }
+        Action::SpeedTest => app.start_speed_test(),
+        Action::RecallCheck => {
+            if !app.start_recall_check() {
+                app.show_status("Read for a little longer before a recall check");
+            }
+        }
+        Action::Narrate => {
+            let enabled = !app.config.speech.enabled;
+            app.config.speech.enabled = enabled;
+            app.narration.reset();
+            let state = if enabled { "on" } else { "off" };
+            app.show_status(&format!("Narration {}", state));
+        }
+        Action::ToggleZen => app.minimal = !app.minimal,
+        // The terminal has no window to shrink, and its text size is the
+        // terminal's own
+        Action::ToggleCompact | Action::ScaleUp | Action::ScaleDown => {}
+        Action::ToggleDebug => app.show_diagnostics = !app.show_diagnostics,
+        Action::ReloadConfig => app.reload_config(),
     }
     Ok(false)
 }
 
+/// Translate a crossterm key into the form keybindings are written in
+fn key_chord(code: KeyCode, modifiers: KeyModifiers) -> Option<KeyChord> {
+    let key = match code {
+        KeyCode::Char(' ') => KeyName::Space,
+        KeyCode::Char(c) => KeyName::Char(c),
+        KeyCode::Enter => KeyName::Enter,
+        KeyCode::Tab => KeyName::Tab,
+        KeyCode::Backspace => KeyName::Backspace,
+        KeyCode::Esc => KeyName::Esc,
+        KeyCode::Up => KeyName::Up,
+        KeyCode::Down => KeyName::Down,
+        KeyCode::Left => KeyName::Left,
+        KeyCode::Right => KeyName::Right,
+        KeyCode::Home => KeyName::Home,
+        KeyCode::End => KeyName::End,
+        KeyCode::PageUp => KeyName::PageUp,
+        KeyCode::PageDown => KeyName::PageDown,
+        KeyCode::F(n) => KeyName::F(n),
+        // Only reported by terminals with the kitty keyboard protocol
+        KeyCode::Media(MediaKeyCode::PlayPause) => KeyName::MediaPlayPause,
+        KeyCode::Media(MediaKeyCode::TrackNext) => KeyName::MediaNext,
+        KeyCode::Media(MediaKeyCode::TrackPrevious) => KeyName::MediaPrev,
+        _ => return None,
+    };
+    Some(KeyChord {
+        key,
+        ctrl: modifiers.contains(KeyModifiers::CONTROL),
+        alt: modifiers.contains(KeyModifiers::ALT),
+        shift: modifiers.contains(KeyModifiers::SHIFT),
+    })
+}
+
 fn handle_library_keys(app: &mut App, code: KeyCode) {
+    if app.library_filter_active && handle_library_filter_keys(app, code) {
+        return;
+    }
+
     match code {
         KeyCode::Esc | KeyCode::Char('q') => {
             app.mode = AppMode::Reading;
         }
         KeyCode::Up | KeyCode::Char('k') => {
-            if !app.library.books.is_empty() {
+            let count = app.filtered_books().len();
+            if count > 0 {
                 let i = app.library_state.selected().unwrap_or(0);
-                let new_i = if i == 0 {
-                    app.library.books.len() - 1
-                } else {
-                    i - 1
-                };
+                let new_i = if i == 0 { count - 1 } else { i - 1 };
                 app.library_state.select(Some(new_i));
             }
         }
         KeyCode::Down | KeyCode::Char('j') => {
-            if !app.library.books.is_empty() {
+            let count = app.filtered_books().len();
+            if count > 0 {
                 let i = app.library_state.selected().unwrap_or(0);
-                let new_i = (i + 1) % app.library.books.len();
+                let new_i = (i + 1) % count;
                 app.library_state.select(Some(new_i));
             }
         }
         KeyCode::Enter => {
-            if let Some(i) = app.library_state.selected() {
-                if let Some(book) = app.library.books.get(i) {
-                    let book_id = book.id.clone();
-                    app.load_book(&book_id);
-                    app.mode = AppMode::Reading;
-                }
+            if let Some(book) = app.selected_book() {
+                let book_id = book.id.clone();
+                app.load_book(&book_id);
+                app.mode = AppMode::Reading;
+            }
+        }
+        KeyCode::Char('/') => {
+            app.library_filter_active = true;
+        }
+        KeyCode::Char('I') => {
+            if let Some(book) = app.selected_book() {
+                app.details_book = Some(book.id.clone());
+                app.mode = AppMode::BookDetails;
             }
         }
+        KeyCode::Char('S') => {
+            // Count the session under way
+            app.is_playing = false;
+            app.end_session();
+            app.save_library();
+            app.mode = AppMode::LibraryStats;
+        }
+        KeyCode::Char('a') if app.selected_book().is_some() => {
+            app.playlist_input.clear();
+            app.mode = AppMode::PlaylistName;
+        }
         KeyCode::Char('d') => {
-            if let Some(i) = app.library_state.selected() {
-                if let Some(book) = app.library.books.get(i) {
-                    app.confirm_message = format!("Delete '{}'?", book.title);
-                    app.confirm_action = Some(ConfirmAction::DeleteBook(book.id.clone()));
-                    app.mode = AppMode::Confirm;
-                }
+            if let Some(book) = app.selected_book().cloned() {
+                app.confirm_message = format!("Delete '{}'?", book.title);
+                app.confirm_action = Some(ConfirmAction::DeleteBook(book.id));
+                app.mode = AppMode::Confirm;
             }
         }
         KeyCode::Char('i') => {
@@ -1022,36 +4070,357 @@ fn handle_library_keys(app: &mut App, code: KeyCode) {
     }
 }
 
-fn handle_file_input_keys(app: &mut App, code: KeyCode) {
+/// Keys while typing a library search. Returns false for keys that should
+/// fall through to normal library navigation (arrows, Enter).
+fn handle_library_filter_keys(app: &mut App, code: KeyCode) -> bool {
     match code {
         KeyCode::Esc => {
+            app.reset_library_filter();
+        }
+        KeyCode::Char(c) => {
+            app.library_filter.push(c);
+            app.reset_library_selection();
+        }
+        KeyCode::Backspace => {
+            if app.library_filter.pop().is_none() {
+                app.library_filter_active = false;
+            }
+            app.reset_library_selection();
+        }
+        _ => return false,
+    }
+    true
+}
+
+fn handle_recents_keys(app: &mut App, code: KeyCode) {
+    let count = app.library.recent_books().len();
+    let choice = match code {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.mode = AppMode::Reading;
+            return;
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            if count > 0 {
+                let i = app.recents_state.selected().unwrap_or(0);
+                let new_i = if i == 0 { count - 1 } else { i - 1 };
+                app.recents_state.select(Some(new_i));
+            }
+            return;
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            if count > 0 {
+                let i = app.recents_state.selected().unwrap_or(0);
+                app.recents_state.select(Some((i + 1) % count));
+            }
+            return;
+        }
+        KeyCode::Enter => app.recents_state.selected(),
+        KeyCode::Char(c @ '1'..='9') => Some(c as usize - '1' as usize),
+        _ => return,
+    };
+
+    let book_id = choice.and_then(|i| app.library.recent_books().get(i).map(|b| b.id.clone()));
+    if let Some(book_id) = book_id {
+        // Keep the book being left at the spot it was left at
+        app.save_progress();
+        app.load_book(&book_id);
+        app.mode = AppMode::Reading;
+    }
+}
+
+fn handle_start_keys(app: &mut App, code: KeyCode) {
+    let count = app.library.in_progress().len();
+    let choice = match code {
+        KeyCode::Esc | KeyCode::Char('q') => {
             app.mode = AppMode::Reading;
+            return;
+        }
+        KeyCode::Char('o') => {
+            app.mode = AppMode::Library;
+            app.reset_library_filter();
+            return;
+        }
+        KeyCode::Char('i') => {
+            app.mode = AppMode::FileInput;
+            app.file_input.clear();
+            app.file_input_cursor = 0;
+            app.file_input_error = None;
+            return;
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            if count > 0 {
+                let i = app.start_state.selected().unwrap_or(0);
+                let new_i = if i == 0 { count - 1 } else { i - 1 };
+                app.start_state.select(Some(new_i));
+            }
+            return;
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            if count > 0 {
+                let i = app.start_state.selected().unwrap_or(0);
+                app.start_state.select(Some((i + 1) % count));
+            }
+            return;
+        }
+        KeyCode::Enter => app.start_state.selected(),
+        KeyCode::Char(c @ '1'..='9') => Some(c as usize - '1' as usize),
+        _ => return,
+    };
+
+    let book_id = choice.and_then(|i| app.library.in_progress().get(i).map(|b| b.id.clone()));
+    if let Some(book_id) = book_id {
+        app.load_book(&book_id);
+        app.mode = AppMode::Reading;
+    }
+}
+
+fn handle_playlists_keys(app: &mut App, code: KeyCode) {
+    let count = app.library.playlists.len();
+    let selected = app
+        .playlists_state
+        .selected()
+        .and_then(|i| app.library.playlists.get(i))
+        .map(|p| p.name.clone());
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => app.mode = AppMode::Reading,
+        KeyCode::Up | KeyCode::Char('k') if count > 0 => {
+            let i = app.playlists_state.selected().unwrap_or(0);
+            let new_i = if i == 0 { count - 1 } else { i - 1 };
+            app.playlists_state.select(Some(new_i));
         }
+        KeyCode::Down | KeyCode::Char('j') if count > 0 => {
+            let i = app.playlists_state.selected().unwrap_or(0);
+            app.playlists_state.select(Some((i + 1) % count));
+        }
+        KeyCode::Enter => {
+            if let Some(name) = selected {
+                app.play_playlist(&name);
+            }
+        }
+        KeyCode::Char('d') => {
+            if let Some(name) = selected {
+                app.confirm_message = format!("Delete the playlist '{}'?", name);
+                app.confirm_action = Some(ConfirmAction::DeletePlaylist(name));
+                app.mode = AppMode::Confirm;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Typing the name of the playlist for the selected library book. Tab
+/// completes the name of an existing playlist.
+fn handle_playlist_name_keys(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Esc => app.mode = AppMode::Library,
+        KeyCode::Enter => {
+            let Some(book_id) = app.selected_book().map(|b| b.id.clone()) else {
+                app.mode = AppMode::Library;
+                return;
+            };
+            let name = app.playlist_input.trim().to_string();
+            match app.library.add_to_playlist(&name, &book_id) {
+                Ok(()) => {
+                    app.save_library();
+                    app.show_status(&format!("Added to {}", name));
+                    app.mode = AppMode::Library;
+                }
+                Err(e) => app.show_status(&e),
+            }
+        }
+        KeyCode::Tab => {
+            let typed = app.playlist_input.to_lowercase();
+            let existing = app
+                .library
+                .playlists
+                .iter()
+                .find(|p| p.name.to_lowercase().starts_with(&typed));
+            if let Some(playlist) = existing {
+                app.playlist_input = playlist.name.clone();
+            }
+        }
+        KeyCode::Backspace => {
+            app.playlist_input.pop();
+        }
+        KeyCode::Char(c) => app.playlist_input.push(c),
+        _ => {}
+    }
+}
+
+fn handle_book_display_keys(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Esc => app.mode = AppMode::Reading,
+        KeyCode::Enter => app.save_book_display(),
+        KeyCode::Backspace => {
+            app.display_input.pop();
+        }
+        KeyCode::Char(c) => app.display_input.push(c),
+        _ => {}
+    }
+}
+
+fn handle_vocabulary_keys(app: &mut App, code: KeyCode) {
+    let count = app.vocabulary().len();
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => app.mode = AppMode::Reading,
+        KeyCode::Up | KeyCode::Char('k') if count > 0 => {
+            let i = app.vocabulary_state.selected().unwrap_or(0);
+            app.vocabulary_state.select(Some(i.saturating_sub(1)));
+        }
+        KeyCode::Down | KeyCode::Char('j') if count > 0 => {
+            let i = app.vocabulary_state.selected().unwrap_or(0);
+            app.vocabulary_state.select(Some((i + 1).min(count - 1)));
+        }
+        KeyCode::Char('x') => {
+            let book = app
+                .current_book_id
+                .as_ref()
+                .and_then(|id| app.library.book(id));
+            let message = match book {
+                Some(book) if !book.vocabulary.is_empty() => match export_vocabulary(book) {
+                    Ok(path) => format!("Exported to {}", path.display()),
+                    Err(e) => format!("Export failed: {}", e),
+                },
+                _ => "No saved words to export".to_string(),
+            };
+            app.show_status(&message);
+        }
+        KeyCode::Char('X') => {
+            let message = match export_all_vocabulary(&app.library) {
+                Ok(path) => format!("Exported every book's words to {}", path.display()),
+                Err(e) => format!("Export failed: {}", e),
+            };
+            app.show_status(&message);
+        }
+        _ => {}
+    }
+}
+
+fn handle_links_keys(app: &mut App, code: KeyCode) {
+    let count = app.links().len();
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => app.mode = AppMode::Reading,
+        KeyCode::Up | KeyCode::Char('k') if count > 0 => {
+            let i = app.links_state.selected().unwrap_or(0);
+            app.links_state.select(Some(i.saturating_sub(1)));
+        }
+        KeyCode::Down | KeyCode::Char('j') if count > 0 => {
+            let i = app.links_state.selected().unwrap_or(0);
+            app.links_state.select(Some((i + 1).min(count - 1)));
+        }
+        KeyCode::Enter => {
+            let Some(link) = app.links_state.selected().and_then(|i| app.links().get(i)) else {
+                return;
+            };
+            let message = match open_in_browser(&link.url) {
+                Ok(()) => format!("Opened {}", link.url),
+                Err(e) => format!("Couldn't open {}: {}", link.url, e),
+            };
+            app.show_status(&message);
+        }
+        _ => {}
+    }
+}
+
+fn handle_recall_keys(app: &mut App, code: KeyCode) {
+    let Some(check) = &mut app.recall else {
+        app.mode = AppMode::Reading;
+        return;
+    };
+    if check.result.is_some() {
+        app.recall = None;
+        app.mode = AppMode::Reading;
+        return;
+    }
+
+    match code {
+        KeyCode::Char(c @ '1'..='9') => check.toggle(c as usize - '1' as usize),
         KeyCode::Enter => {
-            if !app.file_input.is_empty() {
-                let path = app.file_input.clone();
-                if app.import_file(&path) {
-                    app.mode = AppMode::Reading;
+            let score = check.submit(app.wpm);
+            app.library.stats.record_recall(score);
+            app.save_library();
+        }
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.recall = None;
+            app.mode = AppMode::Reading;
+        }
+        _ => {}
+    }
+}
+
+fn handle_finished_keys(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Char('r') => {
+            app.mode = AppMode::Reading;
+            app.jump(0);
+            app.save_progress();
+        }
+        KeyCode::Char('o') => {
+            app.mode = AppMode::Library;
+            app.reset_library_filter();
+        }
+        KeyCode::Char('n') => {
+            if let Some(next) = app.next_book().map(|book| book.id.clone()) {
+                app.mode = AppMode::Reading;
+                if app.load_book(&next) {
+                    app.show_status(&format!("Opened: {}", app.current_book_title));
                 }
             }
         }
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => app.mode = AppMode::Reading,
+        _ => {}
+    }
+}
+
+fn handle_speed_test_keys(app: &mut App, code: KeyCode) {
+    let Some(test) = &mut app.speed_test else {
+        app.mode = AppMode::Reading;
+        return;
+    };
+    if test.result.is_some() {
+        app.end_speed_test();
+        return;
+    }
+
+    match code {
+        KeyCode::Char(c @ '1'..='4') if !test.reading => {
+            if let Some(result) = test.answer(c as usize - '1' as usize) {
+                app.library.stats.record_speed_test(result);
+                app.save_library();
+            }
+        }
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.end_speed_test();
+            app.show_status("Speed test cancelled");
+        }
+        _ => {}
+    }
+}
+
+fn handle_file_input_keys(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Esc => {
+            app.mode = AppMode::Reading;
+        }
+        KeyCode::Enter if !app.file_input.is_empty() => {
+            let path = app.file_input.clone();
+            if app.import_file(&path) {
+                app.mode = AppMode::Reading;
+            }
+        }
         KeyCode::Char(c) => {
             app.file_input.insert(app.file_input_cursor, c);
             app.file_input_cursor += 1;
             app.file_input_error = None;
         }
-        KeyCode::Backspace => {
-            if app.file_input_cursor > 0 {
-                app.file_input_cursor -= 1;
-                app.file_input.remove(app.file_input_cursor);
-                app.file_input_error = None;
-            }
+        KeyCode::Backspace if app.file_input_cursor > 0 => {
+            app.file_input_cursor -= 1;
+            app.file_input.remove(app.file_input_cursor);
+            app.file_input_error = None;
         }
-        KeyCode::Delete => {
-            if app.file_input_cursor < app.file_input.len() {
-                app.file_input.remove(app.file_input_cursor);
-                app.file_input_error = None;
-            }
+        KeyCode::Delete if app.file_input_cursor < app.file_input.len() => {
+            app.file_input.remove(app.file_input_cursor);
+            app.file_input_error = None;
         }
         KeyCode::Left => {
             app.file_input_cursor = app.file_input_cursor.saturating_sub(1);
@@ -1059,6 +4428,15 @@ fn handle_file_input_keys(app: &mut App, code: KeyCode) {
         KeyCode::Right => {
             app.file_input_cursor = (app.file_input_cursor + 1).min(app.file_input.len());
         }
+        KeyCode::BackTab => {
+            app.import_options.tag_collection = !app.import_options.tag_collection;
+        }
+        KeyCode::Tab => {
+            app.import_options.strip_citations = !app.import_options.strip_citations;
+        }
+        KeyCode::F(2) => {
+            app.import_options.skip_matter = !app.import_options.skip_matter;
+        }
         KeyCode::Home => {
             app.file_input_cursor = 0;
         }
@@ -1088,27 +4466,38 @@ fn handle_confirm_keys(app: &mut App, code: KeyCode) {
                             .unwrap_or_default();
 
                         // Remove from library
-                        app.library.books.retain(|b| b.id != book_id);
-                        if app.library.last_book.as_ref() == Some(&book_id) {
-                            app.library.last_book = None;
-                        }
-                        save_library(&app.library);
+                        app.library.remove_book(&book_id);
+                        app.save_library();
 
                         // Remove file
-                        let book_file = books_dir().join(format!("{}.txt", book_id));
-                        let _ = fs::remove_file(book_file);
+                        let path = book_file(&book_id);
+                        if let Err(e) = fs::remove_file(&path) {
+                            tracing::warn!("Couldn't remove {}: {}", path.display(), e);
+                        }
 
                         // Reset state if we deleted the current book
                         if is_current {
-                            app.words.clear();
+                            app.words = WordSource::default();
                             app.current_book_id = None;
+                            app.source_watch.stop();
                             app.current_book_title.clear();
                             app.word_index = 0;
+                            app.history.clear();
                             app.is_playing = false;
                         }
 
                         app.show_status(&format!("Deleted: {}", title));
                     }
+                    ConfirmAction::DeletePlaylist(name) => {
+                        app.library.remove_playlist(&name);
+                        app.save_library();
+                        if app.playlist.as_ref() == Some(&name) {
+                            app.playlist = None;
+                        }
+                        app.show_status(&format!("Deleted playlist: {}", name));
+                    }
+                    ConfirmAction::StartTutorial => app.start_tutorial(),
+                    ConfirmAction::ReloadBook(book_id) => app.reload_book(&book_id),
                 }
             }
             app.mode = AppMode::Reading;
@@ -1125,7 +4514,52 @@ fn handle_confirm_keys(app: &mut App, code: KeyCode) {
 // Main
 // ============================================================================
 
+/// Run `backup` or `restore` on the archive named after it, and exit
+fn backup_command(command: &str, archive: Option<&String>) -> io::Result<()> {
+    let Some(archive) = archive else {
+        eprintln!("Usage: rsvp-tui {} <file.tar.zst>", command);
+        std::process::exit(2);
+    };
+    let archive = Path::new(archive);
+    let (report, done) = if command == "backup" {
+        (create_backup(archive), "Backed up")
+    } else {
+        (restore_backup(archive), "Restored")
+    };
+    match report {
+        Ok(report) => {
+            println!("{} {}", done, report.summary());
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("{} failed: {}", command, e);
+            std::process::exit(1);
+        }
+    }
+}
+
 fn main() -> io::Result<()> {
+    let _log = logging::init("rsvp-tui");
+
+    // Backing up and restoring run on their own, without the reader
+    let args: Vec<String> = std::env::args()
+        .skip(1)
+        .filter(|arg| arg != logging::VERBOSE_FLAG)
+        .collect();
+    if let Some(command) = args
+        .first()
+        .filter(|arg| ["backup", "restore"].contains(&arg.as_str()))
+    {
+        return backup_command(command, args.get(1));
+    }
+
+    // Files to open go to the reader that is already running, if any
+    let files = inbox::file_args();
+    if inbox::forward_files(&files) {
+        println!("Opened in the running reader");
+        return Ok(());
+    }
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = stdout();
@@ -1133,9 +4567,18 @@ fn main() -> io::Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // Create app and load last book
+    // Create app, and offer the books in progress or else load the last
     let mut app = App::new();
-    app.load_last_book();
+    if !files.is_empty() || !app.show_start() {
+        app.load_last_book();
+    }
+    if !files.is_empty() {
+        if let Err(e) = app.import_paths(&files) {
+            app.show_status(&e);
+        }
+    } else if app.library.books.is_empty() {
+        app.offer_tutorial();
+    }
 
     // Main loop
     let result = run_app(&mut terminal, &mut app);
@@ -1150,6 +4593,7 @@ fn main() -> io::Result<()> {
     terminal.show_cursor()?;
 
     // Save progress before exit
+    app.end_session();
     app.save_progress();
 
     result
@@ -1160,8 +4604,21 @@ fn run_app<B: ratatui::backend::Backend>(
     app: &mut App,
 ) -> io::Result<()> {
     loop {
-        terminal.draw(|f| ui(f, app))?;
+        // Room on each side of the focal point inside the bordered word display
+        let size = terminal.size()?;
+        app.word_half_width = (size.width.saturating_sub(2) / 2).saturating_sub(1) as usize;
+
+        // Tick first so a word that just came due is drawn straight away.
+        // Ticks that change nothing on screen, like most while paused, skip
+        // drawing.
+        app.diagnostics.tick();
+        let before = app.screen();
         app.tick();
+        if std::mem::take(&mut app.needs_redraw) || app.screen() != before {
+            let drawing = Instant::now();
+            terminal.draw(|f| ui(f, app))?;
+            app.diagnostics.rendered(drawing.elapsed());
+        }
 
         if handle_events(app)? {
             break;
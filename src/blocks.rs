@@ -0,0 +1,212 @@
+//! Code blocks and tables in technical documents, which make no sense a
+//! word at a time. Each book can read them like the rest, skip them, or
+//! stop and show each one whole until a key.
+
+use crate::text::WordSource;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// What playback does on reaching a code block or table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockMode {
+    /// Word by word, like the text around it
+    #[default]
+    Read,
+    Skip,
+    /// Paused, with the block drawn whole until a key
+    Show,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockKind {
+    /// Between ``` or ~~~ fences
+    Code,
+    /// Lines of a Markdown pipe table or a `+---+` grid table
+    Table,
+}
+
+impl fmt::Display for BlockKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            BlockKind::Code => "Code",
+            BlockKind::Table => "Table",
+        })
+    }
+}
+
+/// A code block or table found in a book file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextBlock {
+    pub kind: BlockKind,
+    /// Its first word, fence included, and one past its last
+    pub first_word: usize,
+    pub end_word: usize,
+    /// Byte range of what to show, inside any fences
+    pub(crate) offset: u64,
+    pub(crate) len: usize,
+}
+
+impl TextBlock {
+    pub fn contains(&self, index: usize) -> bool {
+        (self.first_word..self.end_word).contains(&index)
+    }
+}
+
+/// A block that has been opened but not yet closed
+#[derive(Debug, Clone, Copy)]
+struct OpenBlock {
+    kind: BlockKind,
+    first_word: usize,
+    offset: u64,
+}
+
+/// Looks for blocks line by line through the chunks of a book file, in
+/// order, carrying any block still open from one chunk to the next
+#[derive(Debug)]
+pub(crate) struct BlockScan {
+    open: Option<OpenBlock>,
+    /// Whether the next chunk starts a line, rather than going on with one
+    /// cut at a space
+    line_start: bool,
+    pub(crate) blocks: Vec<TextBlock>,
+}
+
+impl Default for BlockScan {
+    fn default() -> Self {
+        Self {
+            open: None,
+            line_start: true,
+            blocks: Vec::new(),
+        }
+    }
+}
+
+impl BlockScan {
+    /// Scan a chunk at byte `offset` in the file whose words, at `spans`,
+    /// are numbered from `first_word`
+    pub(crate) fn chunk(
+        &mut self,
+        text: &str,
+        spans: &[(usize, usize)],
+        first_word: usize,
+        offset: u64,
+    ) {
+        let word_at = |byte: usize| first_word + spans.partition_point(|&(start, _)| start < byte);
+        let mut at = 0;
+        for line in text.split_inclusive('\n') {
+            let line_start = std::mem::replace(&mut self.line_start, line.ends_with('\n'));
+            let (start, end) = (at, at + line.len());
+            at = end;
+            if !line_start {
+                continue;
+            }
+            let trimmed = line.trim();
+            let fence = trimmed.starts_with("```") || trimmed.starts_with("~~~");
+            match self.open {
+                Some(open) if open.kind == BlockKind::Code => {
+                    if fence {
+                        self.close(offset + start as u64, word_at(end));
+                    }
+                }
+                Some(_) if is_table_line(trimmed) => {}
+                open => {
+                    if open.is_some() {
+                        self.close(offset + start as u64, word_at(start));
+                    }
+                    if fence {
+                        self.open = Some(OpenBlock {
+                            kind: BlockKind::Code,
+                            first_word: word_at(start),
+                            offset: offset + end as u64,
+                        });
+                    } else if is_table_line(trimmed) {
+                        self.open = Some(OpenBlock {
+                            kind: BlockKind::Table,
+                            first_word: word_at(start),
+                            offset: offset + start as u64,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Close any block left open at the end of the file
+    pub(crate) fn finish(&mut self, len: u64, total: usize) {
+        if self.open.is_some() {
+            self.close(len, total);
+        }
+    }
+
+    /// Close the open block, its text running up to byte `end` and its
+    /// words up to `end_word`. Blocks without words are dropped.
+    fn close(&mut self, end: u64, end_word: usize) {
+        let Some(open) = self.open.take() else {
+            return;
+        };
+        if end_word > open.first_word {
+            self.blocks.push(TextBlock {
+                kind: open.kind,
+                first_word: open.first_word,
+                end_word,
+                offset: open.offset,
+                len: end.saturating_sub(open.offset) as usize,
+            });
+        }
+    }
+}
+
+/// A line of a pipe table ("| a | b |") or a grid table ("+---+---+")
+fn is_table_line(line: &str) -> bool {
+    (line.starts_with('|') && line.len() > 1 && line.ends_with('|'))
+        || (line.starts_with('+') && line.contains("-+"))
+}
+
+/// A block held on screen whole until a key
+#[derive(Debug, Clone)]
+pub struct BlockPause {
+    pub kind: BlockKind,
+    pub text: String,
+    /// Where reading carries on afterwards
+    pub resume_at: usize,
+}
+
+/// What playback does on moving from word `from` to `to`
+#[derive(Debug, Clone)]
+pub enum BlockStop {
+    /// Go on from this word instead, past the block
+    Skip(usize),
+    Show(BlockPause),
+}
+
+impl BlockStop {
+    /// The stop due on moving from word `from` to word `to`, if that went
+    /// into a block. Playback started inside one reads it as text.
+    pub fn entering(words: &WordSource, from: usize, to: usize, mode: BlockMode) -> Option<Self> {
+        if mode == BlockMode::Read {
+            return None;
+        }
+        let block = words.block_at(to)?;
+        if block.contains(from) {
+            return None;
+        }
+        // Blocks can follow one another, as a table after its code, and
+        // are skipped or shown together
+        let mut blocks = vec![block];
+        while let Some(next) = words.block_at(blocks[blocks.len() - 1].end_word) {
+            blocks.push(next);
+        }
+        let end = blocks[blocks.len() - 1].end_word;
+        let resume_at = end.min(words.len().saturating_sub(1));
+        if mode == BlockMode::Skip {
+            return Some(BlockStop::Skip(resume_at));
+        }
+        let texts: Option<Vec<String>> = blocks.iter().map(|b| words.block_text(b)).collect();
+        Some(BlockStop::Show(BlockPause {
+            kind: block.kind,
+            text: texts?.join("\n"),
+            resume_at,
+        }))
+    }
+}
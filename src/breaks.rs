@@ -0,0 +1,265 @@
+//! Rest for the eyes: pomodoro-style break reminders that pause reading
+//! after a stretch of continuous playback, micro-pauses that blank the
+//! screen for a moment every few words or sentences, and a pause between
+//! chapters naming the one just read and the one to come
+
+use crate::text::{ends_sentence, WordSource};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Longest a micro-pause can blank the screen
+const MAX_MICRO_PAUSE: Duration = Duration::from_secs(60);
+
+/// The `[breaks]` table in `config.toml`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BreakSettings {
+    pub enabled: bool,
+    /// Minutes of continuous reading before a break
+    pub every_minutes: u32,
+    /// Length of each break in minutes
+    pub break_minutes: u32,
+    /// Start reading again by itself when a break is over, instead of
+    /// waiting for a key
+    pub auto_resume: bool,
+}
+
+impl Default for BreakSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            every_minutes: 25,
+            break_minutes: 5,
+            auto_resume: true,
+        }
+    }
+}
+
+impl BreakSettings {
+    fn reading_length(&self) -> Duration {
+        Duration::from_secs(u64::from(self.every_minutes.max(1)) * 60)
+    }
+
+    fn break_length(&self) -> Duration {
+        Duration::from_secs(u64::from(self.break_minutes) * 60)
+    }
+}
+
+/// How a break ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakOutcome {
+    /// Rested for the full length
+    Taken,
+    /// Went back to reading early
+    Skipped,
+}
+
+/// Tracks reading time between breaks. Frontends call `update` on every
+/// tick and show a break screen while `on_break` is true.
+#[derive(Debug, Default)]
+pub struct BreakTimer {
+    /// Playback time since the last break
+    reading: Duration,
+    /// Time paused since playback last ran
+    paused: Duration,
+    was_playing: bool,
+    last_update: Option<Instant>,
+    break_started: Option<Instant>,
+}
+
+impl BreakTimer {
+    /// Account for the time since the last call. Returns true when a break
+    /// has just started, at which point the frontend should pause.
+    pub fn update(&mut self, playing: bool, settings: &BreakSettings) -> bool {
+        let now = Instant::now();
+        let elapsed = self.last_update.map_or(Duration::ZERO, |t| now - t);
+        self.last_update = Some(now);
+        let was_playing = std::mem::replace(&mut self.was_playing, playing);
+
+        if !settings.enabled || self.on_break() {
+            return false;
+        }
+
+        if was_playing {
+            self.reading += elapsed;
+        } else {
+            self.paused += elapsed;
+            // Pausing for as long as a break counts as one
+            if self.paused >= settings.break_length() {
+                self.reading = Duration::ZERO;
+            }
+        }
+        if playing {
+            self.paused = Duration::ZERO;
+        }
+
+        if playing && self.reading >= settings.reading_length() {
+            self.reading = Duration::ZERO;
+            self.break_started = Some(now);
+            return true;
+        }
+        false
+    }
+
+    pub fn on_break(&self) -> bool {
+        self.break_started.is_some()
+    }
+
+    /// Time left in the current break; zero once it is over
+    pub fn remaining(&self, settings: &BreakSettings) -> Option<Duration> {
+        let started = self.break_started?;
+        Some(settings.break_length().saturating_sub(started.elapsed()))
+    }
+
+    /// Leave the current break, reporting whether it ran its full length
+    pub fn end_break(&mut self, settings: &BreakSettings) -> Option<BreakOutcome> {
+        let started = self.break_started.take()?;
+        self.was_playing = false;
+        self.last_update = Some(Instant::now());
+        Some(if started.elapsed() >= settings.break_length() {
+            BreakOutcome::Taken
+        } else {
+            BreakOutcome::Skipped
+        })
+    }
+}
+
+/// Format a countdown as minutes and seconds
+pub fn format_countdown(remaining: Duration) -> String {
+    let secs = remaining.as_secs_f64().ceil() as u64;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+/// What micro-pauses are counted in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseUnit {
+    #[default]
+    Words,
+    Sentences,
+}
+
+/// The `[micro_pauses]` table in `config.toml`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MicroPauses {
+    /// Pause after this many words or sentences; 0 turns micro-pauses off
+    pub every: u32,
+    pub unit: PauseUnit,
+    /// How long the screen stays blank
+    pub seconds: f64,
+}
+
+impl Default for MicroPauses {
+    fn default() -> Self {
+        Self {
+            every: 0,
+            unit: PauseUnit::Words,
+            seconds: 1.5,
+        }
+    }
+}
+
+impl MicroPauses {
+    /// How long the screen stays blank, kept to `MAX_MICRO_PAUSE` so an
+    /// infinite or huge setting can't stop reading for good
+    pub fn length(&self) -> Duration {
+        let seconds = self.seconds.clamp(0.0, MAX_MICRO_PAUSE.as_secs_f64());
+        Duration::try_from_secs_f64(seconds).unwrap_or_default()
+    }
+}
+
+/// The `[chapter_breaks]` table in `config.toml`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChapterBreaks {
+    pub enabled: bool,
+    /// How long the screen between chapters stays up before reading
+    /// carries on; 0 waits for a key
+    pub seconds: f64,
+}
+
+impl Default for ChapterBreaks {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            seconds: 5.0,
+        }
+    }
+}
+
+/// The pause between two chapters
+#[derive(Debug, Clone)]
+pub struct ChapterBreak {
+    /// Title of the chapter just read, None for text before the first
+    /// heading
+    pub finished: Option<String>,
+    pub next: String,
+    /// When reading carries on by itself, None if it waits for a key
+    pub until: Option<Instant>,
+}
+
+impl ChapterBreak {
+    /// The pause due on moving from word `from` to word `to`, if that
+    /// crossed into another chapter
+    pub fn crossing(
+        words: &WordSource,
+        from: usize,
+        to: usize,
+        settings: &ChapterBreaks,
+    ) -> Option<Self> {
+        if !settings.enabled {
+            return None;
+        }
+        let next = words.chapter_at(to)?;
+        let finished = words.chapter_at(from);
+        if finished == Some(next) {
+            return None;
+        }
+        let chapters = words.chapters();
+        Some(Self {
+            finished: finished.map(|i| chapters[i].title.clone()),
+            next: chapters[next].title.clone(),
+            // A length too long to count down waits for a key
+            until: Duration::try_from_secs_f64(settings.seconds)
+                .ok()
+                .filter(|length| !length.is_zero())
+                .and_then(|length| Instant::now().checked_add(length)),
+        })
+    }
+
+    /// Time left before reading carries on by itself
+    pub fn remaining(&self) -> Option<Duration> {
+        self.until
+            .map(|until| until.saturating_duration_since(Instant::now()))
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.until.is_some_and(|until| Instant::now() >= until)
+    }
+}
+
+/// Counts words or sentences toward the next micro-pause
+#[derive(Debug, Default)]
+pub struct MicroPauseCounter {
+    count: u32,
+}
+
+impl MicroPauseCounter {
+    /// Count a word that has finished showing. Returns true when a blank
+    /// pause should follow it.
+    pub fn word_shown(&mut self, word: &str, settings: &MicroPauses) -> bool {
+        if settings.every == 0 {
+            return false;
+        }
+        if settings.unit == PauseUnit::Words || ends_sentence(word) {
+            self.count += 1;
+        }
+        if self.count >= settings.every {
+            self.count = 0;
+            return true;
+        }
+        false
+    }
+}
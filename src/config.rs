@@ -0,0 +1,817 @@
+//! User preferences read from `config.toml` in the config dir: speed step,
+//! punctuation pauses, colors, keybindings, and the import pipeline. Unlike
+//! `library.json` the app never rewrites this file once it exists, so hand
+//! edits survive, and both frontends can reload it while running.
+
+use crate::autodim::AutoDim;
+use crate::autosave::AutosaveSettings;
+use crate::breaks::{BreakSettings, ChapterBreaks, MicroPauses};
+use crate::filters::{default_pipeline, Filter};
+use crate::language::LanguageProfile;
+use crate::library::{config_dir, ensure_config_dirs};
+use crate::night::NightMode;
+use crate::ramp::RampSettings;
+use crate::recall::RecallSettings;
+use crate::repeat::RepeatSettings;
+use crate::speech::SpeechSettings;
+use crate::text::{AdaptiveSpeed, PacingProfile, Pauses, Skim, WordSource};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub fn config_file() -> PathBuf {
+    config_dir().join("config.toml")
+}
+
+// ============================================================================
+// Config
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// WPM change per speed step
+    pub wpm_step: u32,
+    /// WPM change per step with Shift held (terminal), twice `wpm_step`
+    /// if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wpm_shift_step: Option<u32>,
+    /// Slowest and fastest speeds the speed keys reach
+    pub min_wpm: u32,
+    pub max_wpm: u32,
+    /// Where reading picks up when a book is reopened
+    pub resume_at: ResumeAt,
+    /// Pause when playback stalls, instead of easing back in from the word
+    /// on screen
+    pub pause_on_stall: bool,
+    /// Speeds the number keys 1-9 jump to
+    pub speed_presets: Vec<u32>,
+    /// Start a book opened for the first time at a speed suited to how
+    /// hard it reads
+    pub suggest_wpm: bool,
+    /// Offer to read a book again when the file it was imported from
+    /// changes
+    pub watch_sources: bool,
+    /// Hide the desktop window to the tray instead of quitting when it is
+    /// closed (desktop app built with the `tray` feature)
+    pub close_to_tray: bool,
+    /// Play/pause from anywhere, even while the desktop window is in the
+    /// background
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub global_hotkey: Option<KeyChord>,
+    pub pauses: Pauses,
+    /// Less time on function words, for faster reading
+    pub skim: Skim,
+    /// Slower for hard passages and faster for easy ones
+    pub adaptive: AdaptiveSpeed,
+    /// Each session speeding up minute by minute to a target
+    pub ramp: RampSettings,
+    /// How often reading progress is saved while playing
+    pub autosave: AutosaveSettings,
+    /// Pacing for books in a language, replacing the built-in profile
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub languages: BTreeMap<String, LanguageProfile>,
+    /// Pacing profiles books can be set to, by name
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, PacingProfile>,
+    pub breaks: BreakSettings,
+    pub micro_pauses: MicroPauses,
+    /// Stop between chapters with their titles on screen
+    pub chapter_breaks: ChapterBreaks,
+    pub recall: RecallSettings,
+    /// Loops for speed drills
+    pub repeat: RepeatSettings,
+    /// Narration through a system speech program
+    pub speech: SpeechSettings,
+    pub theme: ThemeColors,
+    /// The night theme at set hours
+    pub night_mode: NightMode,
+    /// Everything but the word fading during playback
+    pub auto_dim: AutoDim,
+    /// Template for the stats line, like "{wpm} wpm · {remaining_time}
+    /// left · {percent}%"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats_format: Option<String>,
+    /// Panels of the terminal reader and their order
+    pub layout: TuiLayout,
+    /// Filters applied, in order, to the text of every imported file
+    pub pipeline: Vec<Filter>,
+    pub keys: KeyBindings,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            wpm_step: 50,
+            wpm_shift_step: None,
+            min_wpm: 50,
+            max_wpm: 2000,
+            resume_at: ResumeAt::default(),
+            pause_on_stall: false,
+            speed_presets: (2..=10).map(|n| n * 100).collect(),
+            suggest_wpm: false,
+            watch_sources: false,
+            close_to_tray: false,
+            global_hotkey: None,
+            pauses: Pauses::default(),
+            skim: Skim::default(),
+            adaptive: AdaptiveSpeed::default(),
+            ramp: RampSettings::default(),
+            autosave: AutosaveSettings::default(),
+            languages: BTreeMap::new(),
+            profiles: BTreeMap::new(),
+            breaks: BreakSettings::default(),
+            micro_pauses: MicroPauses::default(),
+            chapter_breaks: ChapterBreaks::default(),
+            recall: RecallSettings::default(),
+            repeat: RepeatSettings::default(),
+            speech: SpeechSettings::default(),
+            theme: ThemeColors::default(),
+            night_mode: NightMode::default(),
+            auto_dim: AutoDim::default(),
+            stats_format: None,
+            layout: TuiLayout::default(),
+            pipeline: default_pipeline(),
+            keys: KeyBindings::default(),
+        }
+    }
+}
+
+impl Config {
+    pub fn shift_step(&self) -> u32 {
+        self.wpm_shift_step.unwrap_or(self.wpm_step * 2)
+    }
+
+    /// Keep a speed within `min_wpm` and `max_wpm`, and above zero
+    pub fn clamp_wpm(&self, wpm: u32) -> u32 {
+        let max = self.max_wpm.max(1);
+        wpm.clamp(self.min_wpm.clamp(1, max), max)
+    }
+
+    /// The preset speed for a number key, kept within the speed limits
+    pub fn speed_preset(&self, key: char) -> Option<u32> {
+        let n = key.to_digit(10).filter(|&n| n > 0)?;
+        let wpm = self.speed_presets.get(n as usize - 1)?;
+        Some(self.clamp_wpm(*wpm))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResumeAt {
+    /// The exact word reading stopped on
+    Word,
+    /// The start of that word's sentence
+    #[default]
+    Sentence,
+    Paragraph,
+}
+
+impl ResumeAt {
+    /// Move a saved position back to where reading should resume
+    pub fn snap(self, words: &WordSource, index: usize) -> usize {
+        match self {
+            ResumeAt::Word => index,
+            ResumeAt::Sentence => words.sentence_start(index),
+            ResumeAt::Paragraph => words.paragraph_start(index),
+        }
+    }
+}
+
+/// Load `config.toml`, writing the defaults out first if it doesn't exist
+/// yet. Errors are user-facing messages; callers keep their current config.
+pub fn load_config() -> Result<Config, String> {
+    let path = config_file();
+    if !path.exists() {
+        let config = Config::default();
+        let written = toml::to_string(&config)
+            .map_err(io::Error::other)
+            .and_then(|content| {
+                ensure_config_dirs()?;
+                fs::write(&path, content)
+            });
+        match written {
+            Ok(()) => tracing::info!("Wrote the default config to {}", path.display()),
+            Err(e) => tracing::warn!("Couldn't write {}: {}", path.display(), e),
+        }
+        return Ok(config);
+    }
+
+    let content = fs::read_to_string(&path).map_err(|e| format!("Config error: {}", e))?;
+    let config: Config = toml::from_str(&content)
+        .map_err(|e| match e.span() {
+            Some(span) => {
+                let line = content[..span.start].matches('\n').count() + 1;
+                format!("Config error (line {}): {}", line, e.message())
+            }
+            None => format!("Config error: {}", e.message()),
+        })
+        .inspect_err(|e| tracing::warn!("{}: {}", path.display(), e))?;
+    for conflict in config.keys.conflicts() {
+        tracing::warn!("{}: {}", path.display(), conflict);
+    }
+    Ok(config)
+}
+
+// ============================================================================
+// Theme
+// ============================================================================
+
+/// Color overrides as names ("red", "darkgray") or hex ("#e63333"). Unset
+/// colors keep each frontend's built-in look.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeColors {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub word: Option<String>,
+    /// The highlighted focal letter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub focus: Option<String>,
+    /// How the focal letter stands out. Without `Color` it is drawn in the
+    /// word color, which helps readers who can't pick out the red.
+    pub focus_style: Vec<FocusStyle>,
+    /// Marks that keep the eye on the focal column
+    pub guide: FocalGuide,
+    /// Bold long words and numbers and dim function words
+    pub keywords: bool,
+    /// Case the word is drawn in, whatever the text's own
+    pub letter_case: LetterCase,
+    /// How a word that ends a sentence is marked, so the rhythm shows even
+    /// when the full stop flashes by
+    pub sentence_end: SentenceEnd,
+    /// How words inside quotation marks stand out
+    pub dialogue: DialogueStyle,
+    /// Color of dialogue, in place of the theme's
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dialogue_color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+}
+
+impl Default for ThemeColors {
+    fn default() -> Self {
+        Self {
+            word: None,
+            focus: None,
+            focus_style: vec![FocusStyle::Color],
+            guide: FocalGuide::default(),
+            keywords: false,
+            letter_case: LetterCase::default(),
+            sentence_end: SentenceEnd::default(),
+            dialogue: DialogueStyle::default(),
+            dialogue_color: None,
+            progress: None,
+            background: None,
+        }
+    }
+}
+
+impl ThemeColors {
+    pub fn focus_has(&self, style: FocusStyle) -> bool {
+        self.focus_style.contains(&style)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusStyle {
+    Color,
+    Bold,
+    Underline,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FocalGuide {
+    /// Short vertical bars above and below the focal letter
+    #[default]
+    Bars,
+    /// Vertical bars plus horizontal marks either side of the word
+    Crosshair,
+    /// Horizontal lines above and below the word, notched at the focal column
+    Reticle,
+    None,
+}
+
+/// How the word's letters are drawn. Only the display changes; pacing and
+/// keyword emphasis still go by the text as written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LetterCase {
+    #[default]
+    Preserve,
+    /// All lowercase, which some readers find calmer at speed
+    Lower,
+    /// Lowercase letters as small capitals. Letters without one, like "x"
+    /// and most outside the Latin alphabet, stay lowercase.
+    SmallCaps,
+}
+
+impl LetterCase {
+    pub fn apply(self, text: &str) -> Cow<'_, str> {
+        match self {
+            LetterCase::Preserve => Cow::Borrowed(text),
+            LetterCase::Lower => Cow::Owned(text.to_lowercase()),
+            LetterCase::SmallCaps => Cow::Owned(text.chars().map(small_capital).collect()),
+        }
+    }
+}
+
+/// The small capital form of a lowercase Latin letter, or the letter itself
+fn small_capital(c: char) -> char {
+    const SMALL_CAPITALS: [char; 26] = [
+        'ᴀ', 'ʙ', 'ᴄ', 'ᴅ', 'ᴇ', 'ꜰ', 'ɢ', 'ʜ', 'ɪ', 'ᴊ', 'ᴋ', 'ʟ', 'ᴍ', 'ɴ', 'ᴏ', 'ᴘ', 'ꞯ', 'ʀ',
+        'ꜱ', 'ᴛ', 'ᴜ', 'ᴠ', 'ᴡ', 'x', 'ʏ', 'ᴢ',
+    ];
+    if c.is_ascii_lowercase() {
+        SMALL_CAPITALS[(c as u8 - b'a') as usize]
+    } else {
+        c
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SentenceEnd {
+    #[default]
+    None,
+    /// A dim pilcrow after the word
+    Mark,
+    /// The focal guide drawn in the focal letter's color
+    Guides,
+}
+
+/// How quoted dialogue is drawn. Each frame goes by its first word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DialogueStyle {
+    #[default]
+    Plain,
+    /// In its own color
+    Color,
+    Italic,
+}
+
+/// Parse a color as RGB. Accepts `#rrggbb` and the basic terminal color names.
+pub fn parse_color(value: &str) -> Option<(u8, u8, u8)> {
+    let value = value.trim().to_lowercase();
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return Some((channel(0)?, channel(2)?, channel(4)?));
+    }
+
+    let rgb = match value.replace([' ', '_', '-'], "").as_str() {
+        "black" => (0, 0, 0),
+        "red" => (205, 49, 49),
+        "green" => (13, 188, 121),
+        "yellow" => (229, 229, 16),
+        "blue" => (36, 114, 200),
+        "magenta" => (188, 63, 188),
+        "cyan" => (17, 168, 205),
+        "gray" | "grey" => (170, 170, 170),
+        "darkgray" | "darkgrey" => (102, 102, 102),
+        "white" => (229, 229, 229),
+        _ => return None,
+    };
+    Some(rgb)
+}
+
+// ============================================================================
+// Terminal layout
+// ============================================================================
+
+/// What the terminal reader shows, top to bottom
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TuiLayout {
+    /// Panels in order. The word is always shown, and goes after the title
+    /// if it is left out.
+    pub panels: Vec<Panel>,
+    /// Draw the focal guide set by `theme.guide`
+    pub guides: bool,
+}
+
+impl Default for TuiLayout {
+    fn default() -> Self {
+        Self {
+            panels: vec![Panel::Title, Panel::Word, Panel::Progress, Panel::Stats],
+            guides: true,
+        }
+    }
+}
+
+impl TuiLayout {
+    /// The panels to draw, each once, with the word among them
+    pub fn panels(&self) -> Vec<Panel> {
+        let mut panels = Vec::new();
+        for &panel in &self.panels {
+            if !panels.contains(&panel) {
+                panels.push(panel);
+            }
+        }
+        if !panels.contains(&Panel::Word) {
+            let at = usize::from(panels.first() == Some(&Panel::Title));
+            panels.insert(at, Panel::Word);
+        }
+        panels
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Panel {
+    /// Book title, or the tutorial's instructions
+    Title,
+    Word,
+    /// Progress bar
+    Progress,
+    /// Speed, position and status line
+    Stats,
+}
+
+// ============================================================================
+// Keybindings
+// ============================================================================
+
+/// Something a key can be bound to while reading
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    PlayPause,
+    SpeedUp,
+    SpeedDown,
+    PrevWord,
+    NextWord,
+    SkipBack,
+    SkipForward,
+    /// Back to the first word of the current sentence
+    SentenceStart,
+    /// Back to the first word of the current paragraph
+    ParagraphStart,
+    PrevSentence,
+    NextSentence,
+    PrevParagraph,
+    NextParagraph,
+    /// Back to where the reader was before the last big jump
+    JumpBack,
+    JumpForward,
+    Reset,
+    Library,
+    /// Quick switch between recently opened books (TUI)
+    Recents,
+    /// Playlists of shorter items, played through one after another
+    Playlists,
+    /// Pace the open book by words, characters or syllables, or as its
+    /// language says
+    CyclePacing,
+    /// Edit the open book's own theme, font size, chunk size and pacing
+    /// profile
+    BookDisplay,
+    /// Loop the current sentence, then its paragraph, then stop looping
+    ToggleLoop,
+    /// Mark point A, then point B to loop between them, then stop looping
+    MarkLoop,
+    Import,
+    DeleteBook,
+    Help,
+    CycleTheme,
+    /// Switch between the color blindness palette presets
+    CycleColorVision,
+    /// Show progress as a percentage, words, time or chapter
+    CycleProgress,
+    /// Turn skimming over function words on or off
+    ToggleSkim,
+    /// Turn keyword emphasis on or off
+    ToggleKeywords,
+    /// Quiz on the words of the last minute
+    RecallCheck,
+    /// Turn narration on or off
+    Narrate,
+    /// Add the current word and its sentence to the book's vocabulary list
+    SaveWord,
+    /// Show the book's saved words
+    Vocabulary,
+    /// Pause on the footnote just passed and show its text
+    ShowFootnote,
+    /// The links found in the open book, to open in the browser
+    Links,
+    /// Statistics about the open book
+    Analysis,
+    /// Time spent, speed and progress on the open book, from the session log
+    Stats,
+    /// Read a bundled passage and answer questions on it, to measure speed
+    /// and comprehension against a baseline
+    SpeedTest,
+    /// Hide everything but the word
+    ToggleZen,
+    /// Shrink to a small always-on-top strip with the word and progress (GUI)
+    ToggleCompact,
+    /// Make everything in the window bigger or smaller (GUI)
+    ScaleUp,
+    ScaleDown,
+    /// Show how long words really stay on screen, and drawing times
+    ToggleDebug,
+    ReloadConfig,
+    Quit,
+}
+
+/// A key with modifiers, written like "space", "k", "ctrl+r" or "shift+up"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct KeyChord {
+    pub key: KeyName,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyName {
+    Char(char),
+    /// Function key F1-F12
+    F(u8),
+    Space,
+    Enter,
+    Tab,
+    Backspace,
+    Esc,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    /// Keyboard media keys
+    MediaPlayPause,
+    MediaNext,
+    MediaPrev,
+}
+
+const NAMED_KEYS: &[(&str, KeyName)] = &[
+    ("space", KeyName::Space),
+    ("enter", KeyName::Enter),
+    ("tab", KeyName::Tab),
+    ("backspace", KeyName::Backspace),
+    ("esc", KeyName::Esc),
+    ("up", KeyName::Up),
+    ("down", KeyName::Down),
+    ("left", KeyName::Left),
+    ("right", KeyName::Right),
+    ("home", KeyName::Home),
+    ("end", KeyName::End),
+    ("pageup", KeyName::PageUp),
+    ("pagedown", KeyName::PageDown),
+    ("mediaplaypause", KeyName::MediaPlayPause),
+    ("medianext", KeyName::MediaNext),
+    ("mediaprev", KeyName::MediaPrev),
+];
+
+impl KeyName {
+    pub fn is_media(&self) -> bool {
+        matches!(
+            self,
+            KeyName::MediaPlayPause | KeyName::MediaNext | KeyName::MediaPrev
+        )
+    }
+}
+
+impl KeyChord {
+    pub fn new(key: KeyName) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            alt: false,
+            shift: false,
+        }
+    }
+
+    /// Whether a pressed key triggers this binding. Shift is part of the
+    /// character for printable keys ("?" or "R"), and elsewhere only has to
+    /// match when the binding asks for it, so "up" also fires on Shift+Up.
+    pub fn matches(&self, pressed: &KeyChord) -> bool {
+        let shift_ok = match self.key {
+            KeyName::Char(_) => true,
+            _ => !self.shift || pressed.shift,
+        };
+        self.key == pressed.key && self.ctrl == pressed.ctrl && self.alt == pressed.alt && shift_ok
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        // A lone "+" is a key, not a separator
+        let (modifiers, key) = match s.rsplit_once('+') {
+            Some((mods, "")) => (mods.strip_suffix('+').unwrap_or(mods), "+"),
+            Some((mods, key)) => (mods, key),
+            None => ("", s),
+        };
+
+        let key_lower = key.to_lowercase();
+        let name = match NAMED_KEYS.iter().find(|(name, _)| *name == key_lower) {
+            Some(&(_, name)) => name,
+            None if key_lower == "escape" => KeyName::Esc,
+            None if key_lower == "return" => KeyName::Enter,
+            None => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next(), function_key(&key_lower)) {
+                    (_, _, Some(n)) => KeyName::F(n),
+                    (Some(c), None, _) => KeyName::Char(c),
+                    _ => return Err(format!("unknown key \"{}\"", s)),
+                }
+            }
+        };
+
+        let mut chord = KeyChord::new(name);
+        for modifier in modifiers.split('+').filter(|m| !m.is_empty()) {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => chord.ctrl = true,
+                "alt" => chord.alt = true,
+                "shift" => chord.shift = true,
+                other => return Err(format!("unknown modifier \"{}\" in \"{}\"", other, s)),
+            }
+        }
+        Ok(chord)
+    }
+}
+
+/// The number of a function key written as "f1" to "f12"
+fn function_key(key: &str) -> Option<u8> {
+    let n: u8 = key.strip_prefix('f')?.parse().ok()?;
+    (1..=12).contains(&n).then_some(n)
+}
+
+impl TryFrom<String> for KeyChord {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, String> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "alt+")?;
+        }
+        if self.shift {
+            write!(f, "shift+")?;
+        }
+        match self.key {
+            KeyName::Char(c) => write!(f, "{}", c),
+            KeyName::F(n) => write!(f, "f{}", n),
+            named => {
+                let (name, _) = NAMED_KEYS.iter().find(|(_, key)| *key == named).unwrap();
+                write!(f, "{}", name)
+            }
+        }
+    }
+}
+
+impl From<KeyChord> for String {
+    fn from(chord: KeyChord) -> String {
+        chord.to_string()
+    }
+}
+
+/// Keys for each action. Actions left out of the config file keep their
+/// default keys; an empty list unbinds one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "BTreeMap<Action, Vec<KeyChord>>")]
+pub struct KeyBindings(BTreeMap<Action, Vec<KeyChord>>);
+
+impl KeyBindings {
+    /// The action bound to a pressed key, if any
+    pub fn action(&self, pressed: &KeyChord) -> Option<Action> {
+        self.0
+            .iter()
+            .find(|(_, chords)| chords.iter().any(|chord| chord.matches(pressed)))
+            .map(|(&action, _)| action)
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyChord] {
+        self.0.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Keys bound to more than one action, described for the user. Only
+    /// one of those actions can be reached with the key.
+    pub fn conflicts(&self) -> Vec<String> {
+        let mut conflicts = Vec::new();
+        for (i, (action, chords)) in self.0.iter().enumerate() {
+            for chord in chords {
+                for (other, others) in self.0.iter().skip(i + 1) {
+                    if others.iter().any(|o| o.matches(chord) || chord.matches(o)) {
+                        conflicts.push(format!(
+                            "{} is bound to both {} and {}",
+                            chord,
+                            action_name(*action),
+                            action_name(*other)
+                        ));
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+/// An action as it is written in `config.toml`
+fn action_name(action: Action) -> String {
+    serde_json::to_string(&action)
+        .unwrap_or_default()
+        .trim_matches('"')
+        .to_string()
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let bind =
+            |keys: &[&str]| -> Vec<KeyChord> { keys.iter().map(|k| k.parse().unwrap()).collect() };
+        Self(BTreeMap::from([
+            (Action::PlayPause, bind(&["space", "mediaplaypause"])),
+            (Action::SpeedUp, bind(&["up", "k"])),
+            (Action::SpeedDown, bind(&["down", "j"])),
+            (Action::PrevWord, bind(&["left", "h"])),
+            (Action::NextWord, bind(&["right", "l"])),
+            (Action::SkipBack, bind(&["[", "b"])),
+            (Action::SkipForward, bind(&["]", "w"])),
+            (Action::SentenceStart, bind(&["s", "alt+left"])),
+            (Action::ParagraphStart, bind(&["p", "alt+up"])),
+            (Action::PrevSentence, bind(&["(", "mediaprev"])),
+            (Action::NextSentence, bind(&[")", "medianext"])),
+            (Action::PrevParagraph, bind(&["{"])),
+            (Action::NextParagraph, bind(&["}"])),
+            (Action::JumpBack, bind(&["backspace", "ctrl+o"])),
+            (Action::JumpForward, bind(&["tab", "ctrl+i"])),
+            (Action::Reset, bind(&["r"])),
+            (Action::Library, bind(&["o"])),
+            (Action::Recents, bind(&["ctrl+r"])),
+            (Action::Playlists, bind(&["P"])),
+            (Action::CyclePacing, bind(&["W"])),
+            (Action::BookDisplay, bind(&["D"])),
+            (Action::ToggleLoop, bind(&["L"])),
+            (Action::MarkLoop, bind(&["x"])),
+            (Action::Import, bind(&["i"])),
+            (Action::DeleteBook, bind(&["d"])),
+            (Action::Help, bind(&["?"])),
+            (Action::CycleTheme, bind(&["t"])),
+            (Action::CycleColorVision, bind(&["C"])),
+            (Action::CycleProgress, bind(&["u"])),
+            (Action::ToggleSkim, bind(&["S"])),
+            (Action::ToggleKeywords, bind(&["e"])),
+            (Action::RecallCheck, bind(&["c"])),
+            (Action::Narrate, bind(&["n"])),
+            (Action::SaveWord, bind(&["v"])),
+            (Action::Vocabulary, bind(&["V"])),
+            (Action::ShowFootnote, bind(&["N"])),
+            (Action::Links, bind(&["U"])),
+            (Action::Analysis, bind(&["a"])),
+            (Action::Stats, bind(&["g"])),
+            (Action::SpeedTest, bind(&["B"])),
+            (Action::ToggleZen, bind(&["f11", "z"])),
+            (Action::ToggleCompact, bind(&["m"])),
+            (Action::ScaleUp, bind(&["ctrl+=", "ctrl++"])),
+            (Action::ScaleDown, bind(&["ctrl+-"])),
+            (Action::ToggleDebug, bind(&["f12"])),
+            (Action::ReloadConfig, bind(&["f5"])),
+            (Action::Quit, bind(&["q", "esc"])),
+        ]))
+    }
+}
+
+impl From<BTreeMap<Action, Vec<KeyChord>>> for KeyBindings {
+    fn from(overrides: BTreeMap<Action, Vec<KeyChord>>) -> Self {
+        let mut bindings = KeyBindings::default();
+        // A key set in the file is taken from any action left at its
+        // defaults, like ctrl+r from recents in files written before it
+        // had a key
+        for (action, chords) in &mut bindings.0 {
+            if !overrides.contains_key(action) {
+                chords.retain(|chord| {
+                    !overrides
+                        .values()
+                        .flatten()
+                        .any(|set| set.matches(chord) || chord.matches(set))
+                });
+            }
+        }
+        bindings.0.extend(overrides);
+        bindings
+    }
+}
@@ -0,0 +1,155 @@
+//! Timing figures for the debug overlay: how long each word really stayed
+//! on screen against how long it was meant to, how evenly the frontend's
+//! loop ticks, how long drawing takes, and how often playback stalled.
+//! Pacing complaints can then be put in numbers.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Recent samples kept of each figure
+const SAMPLES: usize = 120;
+
+/// Ticks further apart than this are the loop idling while paused, not jitter
+const IDLE_GAP: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    /// When the word on screen replaced the one before it
+    word_since: Option<Instant>,
+    /// Meant and actual display time of recent words
+    words: VecDeque<(Duration, Duration)>,
+    last_tick: Option<Instant>,
+    /// Time between recent ticks
+    ticks: VecDeque<Duration>,
+    /// Time recent frames took to draw
+    renders: VecDeque<Duration>,
+    /// How many times playback has stalled, and for how long the last time
+    stalls: u32,
+    last_stall: Duration,
+    /// Whether the word on screen sat through a stall, and its time says
+    /// nothing about pacing
+    in_stall: bool,
+}
+
+fn push<T>(samples: &mut VecDeque<T>, sample: T) {
+    if samples.len() >= SAMPLES {
+        samples.pop_front();
+    }
+    samples.push_back(sample);
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Average and largest of some samples, in milliseconds
+fn spread(samples: impl Iterator<Item = f64>) -> Option<(f64, f64)> {
+    let (count, sum, max) = samples.fold((0, 0.0, f64::MIN), |(n, sum, max), x| {
+        (n + 1, sum + x, max.max(x))
+    });
+    (count > 0).then(|| (sum / count as f64, max))
+}
+
+impl Diagnostics {
+    /// Playback moved on from a word meant to stay up for `meant`. `since`
+    /// is when the frontend started timing it, which is later than the
+    /// last advance if reading was paused or blanked in between.
+    pub fn word_shown(&mut self, meant: Duration, since: Instant) {
+        let now = Instant::now();
+        let start = self.word_since.map_or(since, |last| last.max(since));
+        if !std::mem::take(&mut self.in_stall) {
+            push(
+                &mut self.words,
+                (meant, now.saturating_duration_since(start)),
+            );
+        }
+        self.word_since = Some(now);
+    }
+
+    /// The frontend's loop came round again
+    pub fn tick(&mut self) {
+        let now = Instant::now();
+        if let Some(last) = self.last_tick.replace(now) {
+            let gap = now - last;
+            if gap < IDLE_GAP {
+                push(&mut self.ticks, gap);
+            }
+        }
+    }
+
+    /// Playback fell `late` behind and started over from the word on
+    /// screen
+    pub fn stalled(&mut self, late: Duration) {
+        tracing::info!("Playback stalled for {:.0} ms", ms(late));
+        self.stalls += 1;
+        self.last_stall = late;
+        self.in_stall = true;
+    }
+
+    /// A frame was drawn, taking `took`
+    pub fn rendered(&mut self, took: Duration) {
+        push(&mut self.renders, took);
+    }
+
+    /// Lines for the overlay, as label and value
+    pub fn summary(&self) -> Vec<(&'static str, String)> {
+        let none = || "-".to_string();
+        let mut lines = Vec::new();
+
+        lines.push((
+            "Last word",
+            self.words.back().map_or_else(none, |&(meant, actual)| {
+                format!("{:.1} ms, meant {:.1} ms", ms(actual), ms(meant))
+            }),
+        ));
+        let errors = self
+            .words
+            .iter()
+            .map(|&(meant, actual)| ms(actual) - ms(meant));
+        lines.push((
+            "Word error",
+            spread(errors).map_or_else(none, |(avg, max)| {
+                format!("avg {:+.1} ms, worst {:+.1} ms", avg, max)
+            }),
+        ));
+        let meant: Duration = self.words.iter().map(|&(meant, _)| meant).sum();
+        let actual: Duration = self.words.iter().map(|&(_, actual)| actual).sum();
+        lines.push((
+            "Drift",
+            if self.words.is_empty() {
+                none()
+            } else {
+                format!(
+                    "{:+.1} ms over {} words",
+                    ms(actual) - ms(meant),
+                    self.words.len()
+                )
+            },
+        ));
+
+        // Jitter is how far tick gaps stray from their average
+        let ticks = spread(self.ticks.iter().map(|&gap| ms(gap)));
+        lines.push((
+            "Tick",
+            ticks.map_or_else(none, |(avg, _)| {
+                let jitter = spread(self.ticks.iter().map(|&gap| (ms(gap) - avg).abs()))
+                    .map_or(0.0, |(jitter, _)| jitter);
+                format!("every {:.1} ms, jitter {:.1} ms", avg, jitter)
+            }),
+        ));
+        lines.push((
+            "Render",
+            spread(self.renders.iter().map(|&took| ms(took))).map_or_else(none, |(avg, max)| {
+                format!("avg {:.2} ms, worst {:.2} ms", avg, max)
+            }),
+        ));
+        lines.push((
+            "Stalls",
+            match self.stalls {
+                0 => "none".to_string(),
+                n => format!("{}, last {:.0} ms", n, ms(self.last_stall)),
+            },
+        ));
+        lines
+    }
+}
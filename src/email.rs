@@ -0,0 +1,531 @@
+//! Email import: the readable text of `.eml` messages and `.mbox` folders,
+//! so newsletters can be read like articles. The plain-text part is used
+//! when there is one, otherwise the HTML part with its markup stripped.
+
+use crate::import::decode_text;
+use crate::language::language_code;
+use encoding_rs::Encoding;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub const EMAIL_EXTENSIONS: &[&str] = &["eml", "mbox"];
+
+/// Multipart messages nested deeper than this are not looked into
+const MAX_DEPTH: usize = 8;
+
+/// A message's subject, sender, date and readable body
+#[derive(Debug, Clone, Default)]
+pub struct Email {
+    pub subject: Option<String>,
+    /// The sender's name, or their address if none is given
+    pub from: Option<String>,
+    /// The day it was sent, like "3 Mar 2020"
+    pub date: Option<String>,
+    /// The language it says it is in, as an ISO 639-1 code
+    pub language: Option<String>,
+    pub body: String,
+}
+
+pub fn is_email_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| EMAIL_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// Read an `.eml` file, or every message in an `.mbox` one with each
+/// message's subject heading its text. The subject and the other headers
+/// are only kept when there is a single message.
+pub fn read_email_file(path: &Path) -> io::Result<Email> {
+    let bytes = fs::read(path)?;
+    let is_mbox = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("mbox"));
+    if !is_mbox {
+        return Ok(parse_email(&bytes));
+    }
+
+    let mut messages: Vec<Email> = split_mbox(&bytes)
+        .iter()
+        .map(|message| parse_email(message))
+        .filter(|email| !email.body.trim().is_empty())
+        .collect();
+    if messages.len() == 1 {
+        return Ok(messages.remove(0));
+    }
+    let body = messages
+        .into_iter()
+        .map(|email| match email.subject {
+            Some(subject) => format!("{}\n\n{}", subject, email.body),
+            None => email.body,
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Ok(Email {
+        body,
+        ..Email::default()
+    })
+}
+
+/// Messages in an mbox start with a "From " line. Lines in the body that
+/// would look like one are escaped as ">From ".
+fn split_mbox(bytes: &[u8]) -> Vec<Vec<u8>> {
+    let mut messages: Vec<Vec<u8>> = Vec::new();
+    for line in bytes.split_inclusive(|&b| b == b'\n') {
+        if line.starts_with(b"From ") {
+            messages.push(Vec::new());
+            continue;
+        }
+        let Some(message) = messages.last_mut() else {
+            continue;
+        };
+        match line.strip_prefix(b">") {
+            Some(rest) if rest.starts_with(b"From ") || rest.starts_with(b">From ") => {
+                message.extend_from_slice(rest)
+            }
+            _ => message.extend_from_slice(line),
+        }
+    }
+    messages
+}
+
+pub fn parse_email(bytes: &[u8]) -> Email {
+    let part = Part::parse(bytes);
+    let subject = part
+        .header("subject")
+        .map(|subject| decode_words(subject).trim().to_string())
+        .filter(|subject| !subject.is_empty());
+    let body = readable_text(&part, 0).map_or_else(String::new, |(text, _)| text);
+    Email {
+        subject,
+        from: part.header("from").and_then(sender_name),
+        date: part.header("date").and_then(sent_day),
+        language: part.header("content-language").and_then(language_code),
+        body: body.trim().to_string(),
+    }
+}
+
+/// The name in a From header like "Jane Doe <jane@example.com>", or the
+/// address when there is no name
+fn sender_name(value: &str) -> Option<String> {
+    let value = decode_words(value);
+    let name = match value.split_once('<') {
+        Some((name, address)) => match name.trim().trim_matches('"').trim() {
+            "" => address.trim_end().trim_end_matches('>'),
+            name => name,
+        },
+        None => value.trim(),
+    };
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// The day in a Date header like "Tue, 3 Mar 2020 10:00:00 +0000"
+fn sent_day(value: &str) -> Option<String> {
+    let value = value.split_once(',').map_or(value, |(_, rest)| rest);
+    let mut fields = value.split_whitespace();
+    let (day, month, year) = (fields.next()?, fields.next()?, fields.next()?);
+    let numeric = |field: &str| field.chars().all(|c| c.is_ascii_digit());
+    let valid = numeric(day) && numeric(year) && month.chars().all(char::is_alphabetic);
+    valid.then(|| format!("{} {} {}", day, month, year))
+}
+
+// ============================================================================
+// MIME
+// ============================================================================
+
+/// A message or one part of a multipart message
+struct Part<'a> {
+    headers: Vec<(String, String)>,
+    body: &'a [u8],
+}
+
+impl<'a> Part<'a> {
+    fn parse(bytes: &'a [u8]) -> Self {
+        let (head, body) = split_head(bytes);
+        let mut headers: Vec<(String, String)> = Vec::new();
+        for line in String::from_utf8_lossy(head).lines() {
+            // Folded headers continue on lines that start with whitespace
+            if line.starts_with([' ', '\t']) {
+                if let Some((_, value)) = headers.last_mut() {
+                    value.push(' ');
+                    value.push_str(line.trim());
+                }
+            } else if let Some((name, value)) = line.split_once(':') {
+                headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+            }
+        }
+        Self { headers, body }
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The lowercased media type and its parameters, text/plain by default
+    fn content_type(&self) -> (String, Vec<(String, String)>) {
+        let Some(value) = self.header("content-type") else {
+            return ("text/plain".to_string(), Vec::new());
+        };
+        let mut fields = value.split(';');
+        let media_type = fields.next().unwrap_or("").trim().to_lowercase();
+        let params = fields
+            .filter_map(|param| param.split_once('='))
+            .map(|(name, value)| {
+                (
+                    name.trim().to_lowercase(),
+                    value.trim().trim_matches('"').to_string(),
+                )
+            })
+            .collect();
+        (media_type, params)
+    }
+
+    fn is_attachment(&self) -> bool {
+        self.header("content-disposition")
+            .is_some_and(|value| value.to_lowercase().starts_with("attachment"))
+    }
+
+    /// The body with its transfer encoding undone, in UTF-8
+    fn text(&self, charset: Option<&str>) -> String {
+        let encoding = self
+            .header("content-transfer-encoding")
+            .unwrap_or("")
+            .trim()
+            .to_lowercase();
+        let bytes = match encoding.as_str() {
+            "base64" => decode_base64(self.body),
+            "quoted-printable" => decode_quoted_printable(self.body),
+            _ => self.body.to_vec(),
+        };
+        match charset.and_then(|label| Encoding::for_label(label.as_bytes())) {
+            Some(encoding) => encoding.decode(&bytes).0.into_owned(),
+            None => decode_text(&bytes),
+        }
+    }
+}
+
+/// Split headers from body at the first blank line
+fn split_head(bytes: &[u8]) -> (&[u8], &[u8]) {
+    let mut start = 0;
+    for line in bytes.split_inclusive(|&b| b == b'\n') {
+        let end = start + line.len();
+        if line.iter().all(|b| b.is_ascii_whitespace()) {
+            return (&bytes[..start], &bytes[end..]);
+        }
+        start = end;
+    }
+    (bytes, &[])
+}
+
+/// The text to read from a part, and whether it came from HTML. Plain text
+/// wins over HTML alternatives, and attachments are skipped.
+fn readable_text(part: &Part, depth: usize) -> Option<(String, bool)> {
+    let (media_type, params) = part.content_type();
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    };
+
+    if let Some(subtype) = media_type.strip_prefix("multipart/") {
+        if depth >= MAX_DEPTH {
+            return None;
+        }
+        let boundary = param("boundary")?;
+        let mut found: Vec<(String, bool)> = split_multipart(part.body, boundary)
+            .into_iter()
+            .map(Part::parse)
+            .filter(|part| !part.is_attachment())
+            .filter_map(|part| readable_text(&part, depth + 1))
+            .collect();
+        // Alternatives say the same thing in different forms; other
+        // multiparts are read one part after another
+        if subtype == "alternative" {
+            let plain = found.iter().position(|(_, html)| !html).unwrap_or(0);
+            return (!found.is_empty()).then(|| found.swap_remove(plain));
+        }
+        let html = found.iter().all(|(_, html)| *html);
+        let text: Vec<String> = found.into_iter().map(|(text, _)| text).collect();
+        return (!text.is_empty()).then(|| (text.join("\n\n"), html));
+    }
+
+    match media_type.as_str() {
+        "text/plain" => Some((part.text(param("charset")), false)),
+        "text/html" => Some((html_to_text(&part.text(param("charset"))), true)),
+        "message/rfc822" if depth < MAX_DEPTH => readable_text(&Part::parse(part.body), depth + 1),
+        _ => None,
+    }
+}
+
+/// The parts between "--boundary" lines, up to the closing "--boundary--"
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let mut parts = Vec::new();
+    let mut part_start: Option<usize> = None;
+    let mut start = 0;
+    for line in body.split_inclusive(|&b| b == b'\n') {
+        let end = start + line.len();
+        let trimmed = line.trim_ascii_end();
+        if let Some(rest) = trimmed.strip_prefix(delimiter.as_bytes()) {
+            if let Some(from) = part_start {
+                parts.push(&body[from..start]);
+            }
+            if rest.starts_with(b"--") {
+                return parts;
+            }
+            part_start = Some(end);
+        }
+        start = end;
+    }
+    // No closing delimiter: keep what came after the last one
+    if let Some(from) = part_start {
+        parts.push(&body[from..]);
+    }
+    parts
+}
+
+fn decode_base64(input: &[u8]) -> Vec<u8> {
+    let value = |c: u8| match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' | b'-' => Some(62),
+        b'/' | b'_' => Some(63),
+        _ => None,
+    };
+    let mut out = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for sextet in input.iter().filter_map(|&c| value(c)) {
+        buffer = (buffer << 6) | u32::from(sextet);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    out
+}
+
+fn decode_quoted_printable(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        if input[i] != b'=' {
+            out.push(input[i]);
+            i += 1;
+            continue;
+        }
+        let rest = &input[i + 1..];
+        // A soft line break joins the line to the next
+        if rest.starts_with(b"\r\n") {
+            i += 3;
+        } else if rest.starts_with(b"\n") {
+            i += 2;
+        } else if let Some(byte) = rest
+            .get(..2)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(b'=');
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Decode RFC 2047 encoded words like "=?utf-8?Q?Caf=C3=A9?=" in a header.
+/// Whitespace between two encoded words is dropped.
+fn decode_words(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    let mut after_word = false;
+    while let Some(start) = rest.find("=?") {
+        let (before, candidate) = rest.split_at(start);
+        // The closing "?=" comes after the charset and encoding, as the text
+        // itself can start with "=" in Q encoding
+        let decoded = word_end(&candidate[2..]).and_then(|end| {
+            let word = &candidate[2..2 + end];
+            decode_word(word).map(|text| (text, 2 + end + 2))
+        });
+        match decoded {
+            Some((text, len)) => {
+                if !(after_word && before.trim().is_empty()) {
+                    out.push_str(before);
+                }
+                out.push_str(&text);
+                rest = &candidate[len..];
+                after_word = true;
+            }
+            None => {
+                out.push_str(before);
+                out.push_str("=?");
+                rest = &candidate[2..];
+                after_word = false;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Where the "?=" closing an encoded word is, in the word after its "=?"
+fn word_end(word: &str) -> Option<usize> {
+    let charset = word.find('?')?;
+    let encoding = charset + 1 + word[charset + 1..].find('?')?;
+    let text = encoding + 1;
+    word[text..].find("?=").map(|end| text + end)
+}
+
+/// One encoded word, without its "=?" and "?=": charset?encoding?text
+fn decode_word(word: &str) -> Option<String> {
+    let mut fields = word.splitn(3, '?');
+    let (charset, encoding, text) = (fields.next()?, fields.next()?, fields.next()?);
+    let bytes = match encoding {
+        "B" | "b" => decode_base64(text.as_bytes()),
+        "Q" | "q" => decode_quoted_printable(text.replace('_', " ").as_bytes()),
+        _ => return None,
+    };
+    // Language tags like "utf-8*en" aren't part of the charset
+    let charset = charset.split('*').next().unwrap_or(charset);
+    let encoding = Encoding::for_label(charset.as_bytes())?;
+    Some(encoding.decode(&bytes).0.into_owned())
+}
+
+// ============================================================================
+// HTML
+// ============================================================================
+
+/// Tags whose content is never shown
+const HIDDEN_TAGS: &[&str] = &["script", "style", "head", "title"];
+/// Tags that start a new paragraph
+const BLOCK_TAGS: &[&str] = &[
+    "p",
+    "div",
+    "br",
+    "li",
+    "tr",
+    "table",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "blockquote",
+    "section",
+    "article",
+    "hr",
+];
+
+/// Reduce HTML to its visible text, one paragraph per block element
+fn html_to_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+    while let Some(open) = rest.find('<') {
+        text.push_str(&decode_entities(&rest[..open]));
+        let after = &rest[open..];
+        if let Some(comment) = after.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let Some(close) = after.find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = &after[1..close];
+        rest = &after[close + 1..];
+
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        if !tag.starts_with('/') && HIDDEN_TAGS.contains(&name.as_str()) {
+            // Skip to the matching end tag
+            let end = format!("</{}", name);
+            rest = find_ignore_case(rest, &end).map_or("", |at| {
+                rest[at..].find('>').map_or("", |gt| &rest[at + gt + 1..])
+            });
+        } else if BLOCK_TAGS.contains(&name.as_str()) {
+            text.push('\n');
+        } else if name == "td" || name == "th" {
+            // Table cells separate words without breaking the line
+            text.push(' ');
+        }
+    }
+    text.push_str(&decode_entities(rest));
+
+    // Collapse runs of whitespace, keeping blank lines between paragraphs
+    let mut out = String::new();
+    for line in text.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !line.is_empty() {
+            if !out.is_empty() {
+                out.push_str("\n\n");
+            }
+            out.push_str(&line);
+        }
+    }
+    out
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .to_ascii_lowercase()
+        .find(&needle.to_ascii_lowercase())
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let after = &rest[amp + 1..];
+        let decoded = after.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let name = &after[..end];
+            let c = match name {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                "mdash" => Some('—'),
+                "ndash" => Some('–'),
+                "hellip" => Some('…'),
+                "rsquo" => Some('’'),
+                "lsquo" => Some('‘'),
+                "rdquo" => Some('”'),
+                "ldquo" => Some('“'),
+                _ => name
+                    .strip_prefix("#x")
+                    .or_else(|| name.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| name.strip_prefix('#').and_then(|n| n.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
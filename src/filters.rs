@@ -0,0 +1,485 @@
+//! Import-time text filters and the configurable pipeline that runs them
+
+use serde::{Deserialize, Serialize};
+
+/// One step of the import pipeline. The order in `Config::pipeline` is the
+/// order the filters run in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Filter {
+    NormalizeUnicode,
+    StripMarkdown,
+    StripCitations,
+    DropHeaders,
+    /// Leave out the front and back matter around the text proper
+    SkipMatter,
+}
+
+impl Filter {
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            Filter::NormalizeUnicode => normalize_text(text),
+            Filter::StripMarkdown => strip_markdown(text),
+            Filter::StripCitations => strip_citations(text),
+            Filter::DropHeaders => drop_headers(text),
+            Filter::SkipMatter => skip_matter(text),
+        }
+    }
+}
+
+pub fn default_pipeline() -> Vec<Filter> {
+    vec![Filter::NormalizeUnicode]
+}
+
+pub fn run_pipeline(text: &str, filters: &[Filter]) -> String {
+    filters
+        .iter()
+        .fold(text.to_string(), |text, filter| filter.apply(&text))
+}
+
+// ============================================================================
+// Unicode Normalization
+// ============================================================================
+
+/// Normalize typography that trips up tokenization or looks noisy at speed:
+/// smart quotes become straight quotes, em-dashes get a break after them so
+/// joined words split apart, soft hyphens and zero-width characters vanish,
+/// non-breaking spaces become plain spaces, and words hyphenated across a
+/// line break ("inter-\nnational") are rejoined.
+pub fn normalize_text(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' | '\u{00AB}' | '\u{00BB}' => {
+                out.push('"')
+            }
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => out.push('\''),
+            '\u{00A0}' | '\u{2007}' | '\u{202F}' => out.push(' '),
+            '\u{00AD}' | '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{FEFF}' => {}
+            '\r' => {
+                if chars.get(i + 1) != Some(&'\n') {
+                    out.push('\n');
+                }
+            }
+            '\u{2014}' | '\u{2015}' => push_dash(&mut out, &chars, i + 1),
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                // "--" is a typewriter em-dash
+                i += 1;
+                push_dash(&mut out, &chars, i + 1);
+            }
+            '-' => match line_break_continuation(&chars, i) {
+                Some(next) => i = next - 1,
+                None => out.push(c),
+            },
+            _ => out.push(c),
+        }
+        i += 1;
+    }
+    out
+}
+
+fn push_dash(out: &mut String, chars: &[char], next: usize) {
+    out.push('\u{2014}');
+    if chars.get(next).is_some_and(|c| !c.is_whitespace()) {
+        out.push(' ');
+    }
+}
+
+/// If the hyphen at `i` splits a word across a line break, return the index
+/// of the first letter of the word's continuation on the next line
+fn line_break_continuation(chars: &[char], i: usize) -> Option<usize> {
+    if i == 0 || !chars[i - 1].is_alphabetic() {
+        return None;
+    }
+
+    let mut j = i + 1;
+    let mut saw_newline = false;
+    while let Some(&c) = chars.get(j) {
+        if c == '\n' {
+            if saw_newline {
+                // A blank line is a paragraph break, not a wrapped word
+                return None;
+            }
+            saw_newline = true;
+        } else if !c.is_whitespace() {
+            break;
+        }
+        j += 1;
+    }
+
+    let next = chars.get(j)?;
+    (saw_newline && next.is_lowercase()).then_some(j)
+}
+
+// ============================================================================
+// Citations
+// ============================================================================
+
+/// Remove inline citation noise: numeric markers (`[12]`, `[3, 7-9]`,
+/// `[^4]`), author-year references ("(Smith et al., 2020)") and superscript
+/// footnote digits. Whitespace left in front of a removed marker is dropped
+/// so punctuation stays attached to its word.
+pub fn strip_citations(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let marker_end = match c {
+            '[' => closing(&chars, i, ']').filter(|&end| is_numeric_citation(&chars[i + 1..end])),
+            '(' => {
+                closing(&chars, i, ')').filter(|&end| is_author_year_citation(&chars[i + 1..end]))
+            }
+            _ if is_superscript_digit(c) && is_footnote_anchor(&chars[..i]) => {
+                let run = chars[i..].iter().take_while(|&&c| is_superscript_digit(c));
+                Some(i + run.count() - 1)
+            }
+            _ => None,
+        };
+
+        match marker_end {
+            Some(end) => {
+                let trimmed_len = out.trim_end_matches([' ', '\t']).len();
+                let had_space = trimmed_len < out.len();
+                out.truncate(trimmed_len);
+                // Keep words on either side of the marker apart
+                if had_space && chars.get(end + 1).is_some_and(|c| c.is_alphanumeric()) {
+                    out.push(' ');
+                }
+                i = end + 1;
+            }
+            None => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Index of the bracket closing the one at `open`, if it is on the same
+/// line and not too far away to be a citation
+fn closing(chars: &[char], open: usize, close: char) -> Option<usize> {
+    chars
+        .iter()
+        .enumerate()
+        .skip(open + 1)
+        .take(120)
+        .take_while(|(_, &c)| c != '\n' && c != chars[open])
+        .find(|(_, &c)| c == close)
+        .map(|(i, _)| i)
+}
+
+fn is_numeric_citation(inner: &[char]) -> bool {
+    let inner: Vec<char> = inner.iter().copied().skip_while(|&c| c == '^').collect();
+    inner.iter().any(|c| c.is_ascii_digit())
+        && inner
+            .iter()
+            .all(|&c| c.is_ascii_digit() || matches!(c, ',' | '-' | '\u{2013}' | ' '))
+}
+
+/// "(Smith, 2020)", "(Smith et al. 2020a; Jones 2019, p. 12)"
+fn is_author_year_citation(inner: &[char]) -> bool {
+    let inner: String = inner.iter().collect();
+    inner.starts_with(|c: char| c.is_uppercase())
+        && inner.split_whitespace().count() <= 16
+        && inner
+            .split(';')
+            .all(|part| part.split([' ', ',']).any(is_year))
+}
+
+fn is_year(word: &str) -> bool {
+    let digits = word.trim_end_matches(|c: char| c.is_ascii_lowercase());
+    digits.len() == 4
+        && digits.chars().all(|c| c.is_ascii_digit())
+        && (digits.starts_with("1") || digits.starts_with("20"))
+}
+
+/// Whether superscript digits after `before` mark a footnote: they follow
+/// punctuation ending a word, or a word too long to be a unit or symbol.
+/// After a number, "m" or "mc" they are a power, as in "10 m²".
+fn is_footnote_anchor(before: &[char]) -> bool {
+    let Some(&last) = before.last() else {
+        return false;
+    };
+    if matches!(
+        last,
+        '.' | ',' | ';' | ':' | '!' | '?' | ')' | '"' | '\'' | '\u{201D}' | '\u{2019}'
+    ) {
+        return true;
+    }
+    let word = before
+        .iter()
+        .rev()
+        .take_while(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+    word.len() >= 4 && word.iter().all(|c| c.is_alphabetic())
+}
+
+fn is_superscript_digit(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00B9}' | '\u{00B2}' | '\u{00B3}' | '\u{2070}' | '\u{2074}'..='\u{2079}'
+    )
+}
+
+// ============================================================================
+// Markdown
+// ============================================================================
+
+/// Reduce Markdown to its readable text: heading, quote, and list markers,
+/// emphasis and code backticks, code fences, horizontal rules, and HTML tags
+/// are removed; links and images keep only their text.
+pub fn strip_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") || is_horizontal_rule(trimmed) {
+            out.push('\n');
+            continue;
+        }
+
+        let mut content = trimmed;
+        while let Some(rest) = content.strip_prefix('>') {
+            content = rest.trim_start();
+        }
+        content = content.trim_start_matches('#').trim_start();
+        content = strip_list_marker(content);
+
+        out.push_str(&strip_inline_markdown(content));
+        out.push('\n');
+    }
+    out
+}
+
+fn is_horizontal_rule(line: &str) -> bool {
+    let marks: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3
+        && ["-", "*", "_", "="]
+            .iter()
+            .any(|m| marks.chars().all(|c| c.to_string() == *m))
+}
+
+fn strip_list_marker(line: &str) -> &str {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(rest) = line.strip_prefix(bullet) {
+            return rest;
+        }
+    }
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 {
+        let rest = &line[digits..];
+        if let Some(rest) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")) {
+            return rest;
+        }
+    }
+    line
+}
+
+fn strip_inline_markdown(line: &str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut out = String::with_capacity(line.len());
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '*' | '`' => {}
+            '~' if chars.get(i + 1) == Some(&'~') || (i > 0 && chars[i - 1] == '~') => {}
+            // Underscores inside words ("snake_case") are not emphasis
+            '_' if !(i > 0
+                && chars[i - 1].is_alphanumeric()
+                && chars.get(i + 1).is_some_and(|c| c.is_alphanumeric())) => {}
+            '!' if chars.get(i + 1) == Some(&'[') => {}
+            '[' => {
+                if let Some((label_end, link_end)) = markdown_link(&chars, i) {
+                    out.extend(&chars[i + 1..label_end]);
+                    i = link_end + 1;
+                    continue;
+                }
+                out.push(c);
+            }
+            '<' => match chars[i..].iter().position(|&c| c == '>') {
+                Some(len)
+                    if chars
+                        .get(i + 1)
+                        .is_some_and(|c| c.is_alphabetic() || *c == '/') =>
+                {
+                    i += len + 1;
+                    continue;
+                }
+                _ => out.push(c),
+            },
+            _ => out.push(c),
+        }
+        i += 1;
+    }
+    out
+}
+
+/// For a link starting at `open` ("[label](url)"), the indices of the `]`
+/// and of the closing `)`
+fn markdown_link(chars: &[char], open: usize) -> Option<(usize, usize)> {
+    let label_end = open + chars[open..].iter().position(|&c| c == ']')?;
+    if chars.get(label_end + 1) != Some(&'(') {
+        return None;
+    }
+    let link_end = label_end + chars[label_end..].iter().position(|&c| c == ')')?;
+    Some((label_end, link_end))
+}
+
+// ============================================================================
+// Headers
+// ============================================================================
+
+/// Drop heading lines: Markdown `#` headings, setext headings (a line
+/// underlined with `===` or `---`), and short title-like lines standing alone
+/// between blank lines without sentence punctuation.
+pub fn drop_headers(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut out = String::with_capacity(text.len());
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        let underline = lines.get(i + 1).map(|l| l.trim()).unwrap_or("");
+        let is_setext = !line.is_empty()
+            && underline.len() >= 3
+            && (underline.chars().all(|c| c == '=') || underline.chars().all(|c| c == '-'));
+
+        if is_setext {
+            i += 2;
+            continue;
+        }
+        let blank_before = i == 0 || lines[i - 1].trim().is_empty();
+        let blank_after = i + 1 >= lines.len() || lines[i + 1].trim().is_empty();
+        if line.starts_with('#') || (blank_before && blank_after && is_title_like(line)) {
+            i += 1;
+            continue;
+        }
+
+        out.push_str(lines[i]);
+        out.push('\n');
+        i += 1;
+    }
+    out
+}
+
+fn is_title_like(line: &str) -> bool {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    if words.is_empty() || words.len() > 10 {
+        return false;
+    }
+    let ends_like_prose = line.ends_with(['.', '!', '?', ',', ';', ':', '"', '\'']);
+    let capitalized = words
+        .iter()
+        .filter(|w| w.starts_with(|c: char| c.is_uppercase() || c.is_ascii_digit()))
+        .count();
+    !ends_like_prose && capitalized * 2 > words.len()
+}
+
+// ============================================================================
+// Front and Back Matter
+// ============================================================================
+
+/// Headings that open back matter, which runs from there to the end
+const BACK_MATTER: &[&str] = &["index", "bibliography", "references", "works cited"];
+
+/// Leave out what surrounds the text proper, so reading starts at chapter
+/// one and progress counts only the text: Project Gutenberg's header and
+/// license, a table of contents, and an index or bibliography at the end.
+/// Each is only cut when clearly found, and if nothing would be left the
+/// text is kept whole.
+pub fn skip_matter(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let (start, end) = gutenberg_body(&lines);
+    let body = &lines[start..end];
+    let start = contents_end(body).unwrap_or(0);
+    let end = back_matter_start(body).filter(|&end| end > start);
+    let kept = &body[start..end.unwrap_or(body.len())];
+    if kept.iter().all(|line| line.trim().is_empty()) {
+        return text.to_string();
+    }
+    let mut out = kept.join("\n");
+    out.push('\n');
+    out
+}
+
+/// The lines between Project Gutenberg's "*** START OF" and "*** END OF"
+/// markers, or all of them without the markers
+fn gutenberg_body(lines: &[&str]) -> (usize, usize) {
+    let marker = |line: &str, word: &str| {
+        let line = line.trim_start_matches([' ', '*']).to_uppercase();
+        line.starts_with(word) && line.contains("PROJECT GUTENBERG")
+    };
+    let start = lines
+        .iter()
+        .position(|line| marker(line, "START"))
+        .map_or(0, |i| i + 1);
+    let end = lines[start..]
+        .iter()
+        .position(|line| marker(line, "END"))
+        .map_or(lines.len(), |i| start + i);
+    (start, end)
+}
+
+/// Where the text resumes after a table of contents near the start: the
+/// heading of its first entry, found again. Without that match the contents
+/// can't be told from the text, so nothing is cut.
+fn contents_end(lines: &[&str]) -> Option<usize> {
+    let heading = lines
+        .iter()
+        .take((lines.len() / 10).max(200))
+        .position(|line| {
+            let line = line.trim().trim_end_matches([':', '.']).to_lowercase();
+            line == "contents" || line == "table of contents"
+        })?;
+    let blank = |line: &&str| line.trim().is_empty();
+    let first = heading + 1 + lines[heading + 1..].iter().position(|l| !blank(l))?;
+    let entry = heading_words(lines[first]);
+    let found = lines[first + 1..]
+        .iter()
+        .position(|line| same_heading(&entry, &heading_words(line)))?;
+    Some(first + 1 + found)
+}
+
+/// Where an index or bibliography in the second half of the text begins
+fn back_matter_start(lines: &[&str]) -> Option<usize> {
+    let half = lines.len() / 2;
+    (half..lines.len()).find(|&i| {
+        let line = lines[i].trim().trim_end_matches([':', '.']).to_lowercase();
+        let alone = (i == 0 || lines[i - 1].trim().is_empty())
+            && lines.get(i + 1).is_none_or(|next| next.trim().is_empty());
+        alone && BACK_MATTER.contains(&line.as_str())
+    })
+}
+
+/// A heading's words in lowercase without punctuation, leaving out a page
+/// number at the end and dot leaders
+fn heading_words(line: &str) -> Vec<String> {
+    let mut words: Vec<String> = line
+        .split_whitespace()
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect();
+    let page_number = |word: &String| word.chars().all(|c| c.is_ascii_digit());
+    if words.len() > 1 && words.last().is_some_and(page_number) {
+        words.pop();
+    }
+    words
+}
+
+/// Whether two headings name the same chapter, as "Chapter I" and "CHAPTER
+/// I. Loomings" do: one is the other, or starts it with two words or more
+fn same_heading(a: &[String], b: &[String]) -> bool {
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    !short.is_empty() && (short.len() == long.len() || short.len() >= 2) && long.starts_with(short)
+}
@@ -0,0 +1,144 @@
+//! Markdown footnotes: `[^label]` references with their text in
+//! `[^label]: ...` definitions. On import the definitions are taken out of
+//! the text and kept with the book, each tied to the word its reference
+//! followed, so a key can bring one up while reading without the notes
+//! being read in the middle of the text.
+
+use crate::filters::{run_pipeline, Filter};
+use crate::text::word_spans;
+use serde::{Deserialize, Serialize};
+
+/// Words a reference stays within reach of the footnote key after it
+/// passes
+pub const FOOTNOTE_REACH: usize = 30;
+
+/// First of the private-use characters that stand in for references while
+/// the import pipeline runs, so filters can't mangle them
+const MARKER_BASE: u32 = 0xF0000;
+
+/// A footnote kept with a book
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Footnote {
+    /// The word its reference followed
+    pub word: usize,
+    pub label: String,
+    pub text: String,
+}
+
+/// The latest footnote referenced at or before word `index`, if that was
+/// no more than `FOOTNOTE_REACH` words back
+pub fn footnote_near(footnotes: &[Footnote], index: usize) -> Option<&Footnote> {
+    let passed = footnotes.partition_point(|note| note.word <= index);
+    footnotes[..passed]
+        .last()
+        .filter(|note| index - note.word <= FOOTNOTE_REACH)
+}
+
+/// A definition taken out of the text: its label and its text
+pub(crate) type Definition = (String, String);
+
+/// Take the footnote definitions out of `text` and swap each reference to
+/// one for a marker character, attached to the word before it
+pub(crate) fn take_footnotes(text: &str) -> (String, Vec<Definition>) {
+    let mut definitions: Vec<Definition> = Vec::new();
+    let mut body = String::with_capacity(text.len());
+    let mut lines = text.lines().peekable();
+    while let Some(line) = lines.next() {
+        let Some((label, first)) = definition_start(line) else {
+            body.push_str(line);
+            body.push('\n');
+            continue;
+        };
+        // Indented lines after it go on with the same note
+        let mut note = first.trim().to_string();
+        while let Some(more) = lines.next_if(|l| l.starts_with([' ', '\t']) && !l.trim().is_empty())
+        {
+            note.push(' ');
+            note.push_str(more.trim());
+        }
+        if !definitions.iter().any(|(known, _)| known == label) {
+            definitions.push((label.to_string(), note));
+        }
+    }
+    if definitions.is_empty() {
+        return (text.to_string(), definitions);
+    }
+
+    let mut out = String::with_capacity(body.len());
+    let mut rest = body.as_str();
+    while let Some(open) = rest.find("[^") {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 2..];
+        let found = after.find(']').and_then(|close| {
+            let n = definitions
+                .iter()
+                .position(|(label, _)| *label == after[..close])?;
+            Some((close, char::from_u32(MARKER_BASE + n as u32)?))
+        });
+        match found {
+            Some((close, marker)) => {
+                // "word [^1]" attaches to the word all the same
+                out.truncate(out.trim_end_matches([' ', '\t']).len());
+                out.push(marker);
+                rest = &after[close + 1..];
+            }
+            None => {
+                out.push_str("[^");
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    (out, definitions)
+}
+
+/// "[^label]: text" on a line of its own
+fn definition_start(line: &str) -> Option<(&str, &str)> {
+    let rest = line.trim_start().strip_prefix("[^")?;
+    let (label, text) = rest.split_once("]:")?;
+    (!label.is_empty() && !label.contains(char::is_whitespace)).then_some((label, text))
+}
+
+/// Take the markers back out of `text`, once it has been through the
+/// pipeline, and tie each definition to the word its marker was on. Notes
+/// whose reference was filtered away are dropped. The notes' own text goes
+/// through the pipeline's Unicode and Markdown clean-up.
+pub(crate) fn place_footnotes(
+    text: &str,
+    definitions: &[Definition],
+    filters: &[Filter],
+) -> (String, Vec<Footnote>) {
+    if definitions.is_empty() {
+        return (text.to_string(), Vec::new());
+    }
+    let mut clean = String::with_capacity(text.len());
+    let mut markers = Vec::new();
+    for c in text.chars() {
+        match (c as u32).checked_sub(MARKER_BASE) {
+            Some(n) if (n as usize) < definitions.len() => markers.push((clean.len(), n as usize)),
+            _ => clean.push(c),
+        }
+    }
+
+    let cleanup: Vec<Filter> = filters
+        .iter()
+        .copied()
+        .filter(|f| matches!(f, Filter::NormalizeUnicode | Filter::StripMarkdown))
+        .collect();
+    let spans = word_spans(&clean);
+    let footnotes = markers
+        .into_iter()
+        .filter_map(|(at, n)| {
+            let word = spans
+                .partition_point(|&(start, _)| start < at)
+                .checked_sub(1)?;
+            let (label, text) = &definitions[n];
+            Some(Footnote {
+                word,
+                label: label.clone(),
+                text: run_pipeline(text, &cleanup).trim().to_string(),
+            })
+        })
+        .collect();
+    (clean, footnotes)
+}
@@ -0,0 +1,45 @@
+//! Position history: where the reader was before each big jump, so a
+//! mistaken skip can be undone like a browser's back button
+
+/// Most positions kept to go back to
+const MAX_HISTORY: usize = 100;
+
+#[derive(Debug, Default)]
+pub struct JumpHistory {
+    back: Vec<usize>,
+    forward: Vec<usize>,
+}
+
+impl JumpHistory {
+    /// Remember `from` before jumping away from it. A new jump forgets the
+    /// positions that were gone back from.
+    pub fn record(&mut self, from: usize) {
+        if self.back.last() != Some(&from) {
+            if self.back.len() >= MAX_HISTORY {
+                self.back.remove(0);
+            }
+            self.back.push(from);
+        }
+        self.forward.clear();
+    }
+
+    /// The position before the last jump, if any
+    pub fn back(&mut self, current: usize) -> Option<usize> {
+        let to = self.back.pop()?;
+        self.forward.push(current);
+        Some(to)
+    }
+
+    /// Undo a `back`
+    pub fn forward(&mut self, current: usize) -> Option<usize> {
+        let to = self.forward.pop()?;
+        self.back.push(current);
+        Some(to)
+    }
+
+    /// Forget everything, e.g. after switching books
+    pub fn clear(&mut self) {
+        self.back.clear();
+        self.forward.clear();
+    }
+}
@@ -0,0 +1,470 @@
+//! Reading source files and turning them into library books
+
+use crate::analysis::BookAnalysis;
+use crate::email::{is_email_file, read_email_file};
+use crate::filters::{run_pipeline, Filter};
+use crate::footnotes::{place_footnotes, take_footnotes, Footnote};
+use crate::language::detect_language;
+use crate::library::{book_file, ensure_config_dirs, save_library, Book, BookDisplay, Library};
+use crate::links::{find_links, BookLink};
+use crate::text::count_words;
+use encoding_rs::Encoding;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Read a text file, detecting its character encoding. UTF-8 and BOM-marked
+/// UTF-16 are used as-is; anything else (Latin-1, Windows-1252, Shift_JIS...)
+/// is guessed from the content and transcoded to UTF-8. With OCR built in,
+/// images and PDFs are recognized instead.
+pub fn read_text_file(path: &Path) -> io::Result<String> {
+    #[cfg(feature = "ocr")]
+    if crate::ocr::is_ocr_file(path) {
+        return crate::ocr::read_ocr_file(path);
+    }
+
+    let bytes = fs::read(path)?;
+    Ok(decode_text(&bytes))
+}
+
+/// File extensions picked up when importing a folder or shown in file dialogs
+#[cfg(not(feature = "ocr"))]
+pub const SUPPORTED_EXTENSIONS: &[&str] = &["txt", "text", "md", "markdown", "eml", "mbox"];
+#[cfg(feature = "ocr")]
+pub const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "txt", "text", "md", "markdown", "eml", "mbox", "png", "jpg", "jpeg", "tif", "tiff", "bmp",
+    "webp", "pdf",
+];
+
+/// Per-import choices layered on top of the configured pipeline
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImportOptions {
+    pub strip_citations: bool,
+    /// Leave out a Gutenberg license, contents, index and bibliography
+    pub skip_matter: bool,
+    /// When importing a folder, tag every book with the folder's name
+    pub tag_collection: bool,
+}
+
+impl ImportOptions {
+    /// The filters to run for this import: the configured pipeline, plus any
+    /// filter switched on just for this import that it doesn't already have
+    pub fn filters(&self, pipeline: &[Filter]) -> Vec<Filter> {
+        let mut filters = pipeline.to_vec();
+        if self.strip_citations && !filters.contains(&Filter::StripCitations) {
+            filters.push(Filter::StripCitations);
+        }
+        if self.skip_matter && !filters.contains(&Filter::SkipMatter) {
+            filters.push(Filter::SkipMatter);
+        }
+        filters
+    }
+}
+
+pub fn decode_text(bytes: &[u8]) -> String {
+    if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+        return text.into_owned();
+    }
+
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return text.to_string();
+    }
+
+    let mut detector = chardetng::EncodingDetector::new();
+    detector.feed(bytes, true);
+    let encoding = detector.guess(None, true);
+    let (text, _) = encoding.decode_without_bom_handling(bytes);
+    text.into_owned()
+}
+
+/// Import a file into the library: read and filter its text, store a copy in
+/// the books directory, and register the new book. Errors are user-facing
+/// messages.
+pub fn import_book(library: &mut Library, path: &Path, filters: &[Filter]) -> Result<Book, String> {
+    let book = add_file(library, path, filters)?;
+    save(library)?;
+    Ok(book)
+}
+
+/// Add a file's book to the library without saving it
+fn add_file(library: &mut Library, path: &Path, filters: &[Filter]) -> Result<Book, String> {
+    let (content, metadata) = read_source(path)?;
+
+    // Titled as the file says, or else by its name
+    let title = metadata.title.clone().unwrap_or_else(|| {
+        path.file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Unknown")
+            .to_string()
+    });
+
+    let source = path.to_string_lossy();
+    add_book(library, &title, &source, &content, filters, metadata)
+}
+
+fn save(library: &Library) -> Result<(), String> {
+    save_library(library).map_err(|e| format!("Couldn't save the library: {}", e))
+}
+
+/// What a file says about itself besides its text
+#[derive(Debug, Default)]
+struct Metadata {
+    title: Option<String>,
+    author: Option<String>,
+    published: Option<String>,
+    language: Option<String>,
+}
+
+/// The text of a file to import, and what its headers or metadata say
+fn read_source(path: &Path) -> Result<(String, Metadata), String> {
+    // Emails are titled by their subject and written by their sender
+    if is_email_file(path) {
+        match read_email_file(path) {
+            Ok(email) => {
+                let metadata = Metadata {
+                    title: email.subject,
+                    author: email.from,
+                    published: email.date,
+                    language: email.language,
+                };
+                Ok((email.body, metadata))
+            }
+            Err(e) => Err(format!("Error: {}", e)),
+        }
+    } else {
+        match read_text_file(path) {
+            Ok(c) => Ok((c, file_metadata(path))),
+            Err(e) => Err(format!("Error: {}", e)),
+        }
+    }
+}
+
+/// A PDF's title, author and creation date, read with poppler alongside
+/// OCR
+#[cfg(feature = "ocr")]
+fn file_metadata(path: &Path) -> Metadata {
+    let is_pdf = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("pdf"));
+    if !is_pdf {
+        return Metadata::default();
+    }
+    let info = match crate::ocr::pdf_info(path) {
+        Ok(info) => info,
+        Err(e) => {
+            tracing::warn!("No metadata for {}: {}", path.display(), e);
+            return Metadata::default();
+        }
+    };
+    let field = |name: &str| {
+        info.iter()
+            .find(|(field, value)| field == name && !value.is_empty())
+            .map(|(_, value)| value.clone())
+    };
+    Metadata {
+        title: field("Title"),
+        author: field("Author"),
+        // The day is enough of an ISO 8601 date
+        published: field("CreationDate").map(|date| date.chars().take(10).collect()),
+        language: None,
+    }
+}
+
+#[cfg(not(feature = "ocr"))]
+fn file_metadata(_path: &Path) -> Metadata {
+    Metadata::default()
+}
+
+/// A book's text as stored, and what was kept aside from it
+struct Prepared {
+    content: String,
+    word_count: usize,
+    footnotes: Vec<Footnote>,
+    links: Vec<BookLink>,
+}
+
+/// Run `text` through the filters, keeping its links and footnotes
+fn prepare(text: &str, filters: &[Filter]) -> Result<Prepared, String> {
+    // Footnotes are kept aside, away from the filters, and tied to their
+    // words once the text is final
+    let links = find_links(text);
+    let (text, definitions) = take_footnotes(text);
+    let content = run_pipeline(&text, filters);
+    let (content, footnotes) = place_footnotes(&content, &definitions, filters);
+    let word_count = count_words(&content);
+    if word_count == 0 {
+        return Err("File is empty".to_string());
+    }
+    Ok(Prepared {
+        content,
+        word_count,
+        footnotes,
+        links,
+    })
+}
+
+/// Import text that didn't come from a local file, such as an article sent
+/// by the browser extension. `source` is kept as the book's original path.
+pub fn import_text(
+    library: &mut Library,
+    title: &str,
+    source: &str,
+    text: &str,
+    filters: &[Filter],
+) -> Result<Book, String> {
+    let book = add_book(library, title, source, text, filters, Metadata::default())?;
+    save(library)?;
+    Ok(book)
+}
+
+/// Store a book's text and add it to the library, leaving the caller to
+/// save the library
+fn add_book(
+    library: &mut Library,
+    title: &str,
+    source: &str,
+    text: &str,
+    filters: &[Filter],
+    metadata: Metadata,
+) -> Result<Book, String> {
+    let Prepared {
+        content,
+        word_count,
+        footnotes,
+        links,
+    } = prepare(text, filters)?;
+
+    // Generate unique ID
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    std::time::SystemTime::now().hash(&mut hasher);
+    let book_id = format!("{:x}", hasher.finish())[..12].to_string();
+
+    // Save to books directory
+    let path = book_file(&book_id);
+    if let Err(e) = ensure_config_dirs().and_then(|()| fs::write(&path, &content)) {
+        tracing::error!("Couldn't write {}: {}", path.display(), e);
+        return Err(format!("Failed to save book: {}", e));
+    }
+
+    let book = Book {
+        id: book_id,
+        title: title.to_string(),
+        original_path: source.to_string(),
+        total_words: word_count,
+        progress: 0,
+        progress_offset: None,
+        progress_at: 0,
+        last_read_at: 0,
+        tags: Vec::new(),
+        language: metadata
+            .language
+            .or_else(|| detect_language(&content).map(str::to_string)),
+        author: metadata.author,
+        published: metadata.published,
+        vocabulary: Vec::new(),
+        analysis: Some(BookAnalysis::of(&content)),
+        pacing: None,
+        display: BookDisplay::default(),
+        footnotes,
+        links,
+    };
+    library.books.push(book.clone());
+    Ok(book)
+}
+
+/// Read a book again from the file it was imported from, through today's
+/// filters. Its place is kept as a word number, since the text may have
+/// moved around it. The caller saves the library.
+pub fn reload_book(library: &mut Library, book_id: &str, filters: &[Filter]) -> Result<(), String> {
+    let book = library
+        .book_mut(book_id)
+        .ok_or_else(|| "Book not found".to_string())?;
+    let (text, metadata) = read_source(Path::new(&book.original_path))?;
+    let prepared = prepare(&text, filters)?;
+
+    let path = book_file(book_id);
+    if let Err(e) = fs::write(&path, &prepared.content) {
+        tracing::error!("Couldn't write {}: {}", path.display(), e);
+        return Err(format!("Failed to save book: {}", e));
+    }
+    let progress = book.progress.min(prepared.word_count - 1);
+    book.set_progress(progress, None);
+    book.total_words = prepared.word_count;
+    book.language = metadata
+        .language
+        .or_else(|| detect_language(&prepared.content).map(str::to_string));
+    book.author = metadata.author.or(book.author.take());
+    book.published = metadata.published.or(book.published.take());
+    book.analysis = Some(BookAnalysis::of(&prepared.content));
+    book.footnotes = prepared.footnotes;
+    book.links = prepared.links;
+    Ok(())
+}
+
+/// How often the open book's source file is looked at for changes
+const SOURCE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Watches the file the open book was imported from, with `watch_sources`
+/// on, so a document being edited can be read again
+#[derive(Debug, Default)]
+pub struct SourceWatch {
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    checked: Option<Instant>,
+}
+
+impl SourceWatch {
+    /// Watch `book`'s source, if it came from a file that is still there
+    pub fn watch(&mut self, book: &Book) {
+        let path = PathBuf::from(&book.original_path);
+        *self = Self::default();
+        if path.is_file() {
+            self.modified = modified(&path);
+            self.path = Some(path);
+        }
+    }
+
+    pub fn stop(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Whether the file has changed since it was last looked at, if it is
+    /// time to look. Each change is told once.
+    pub fn changed(&mut self) -> bool {
+        let Some(path) = &self.path else {
+            return false;
+        };
+        if self
+            .checked
+            .is_some_and(|at| at.elapsed() < SOURCE_CHECK_INTERVAL)
+        {
+            return false;
+        }
+        self.checked = Some(Instant::now());
+        let modified = modified(path);
+        // A file saved by replacing it can be missing for a moment
+        if modified.is_none() || modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        true
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// Outcome of importing several files in one go
+#[derive(Debug, Default)]
+pub struct BatchImport {
+    pub imported: Vec<Book>,
+    pub failed: Vec<(PathBuf, String)>,
+    /// Why the library couldn't be saved after the batch, if it couldn't
+    pub save_error: Option<String>,
+}
+
+impl BatchImport {
+    /// A one-line summary like "Imported 12 books" or "Imported 10 books, 2 failed"
+    pub fn summary(&self) -> String {
+        let count = self.imported.len();
+        if count == 0 && self.failed.is_empty() {
+            return "No text files found".to_string();
+        }
+        let noun = if count == 1 { "book" } else { "books" };
+        let mut summary = if self.failed.is_empty() {
+            format!("Imported {} {}", count, noun)
+        } else {
+            format!("Imported {} {}, {} failed", count, noun, self.failed.len())
+        };
+        if let Some(e) = &self.save_error {
+            summary += &format!(". {}", e);
+        }
+        summary
+    }
+}
+
+/// Import each file as its own book, carrying on past files that fail,
+/// and save the library once at the end
+pub fn import_books(library: &mut Library, paths: &[PathBuf], filters: &[Filter]) -> BatchImport {
+    let mut batch = add_files(library, paths, filters);
+    if !batch.imported.is_empty() {
+        batch.save_error = save(library).err();
+    }
+    batch
+}
+
+fn add_files(library: &mut Library, paths: &[PathBuf], filters: &[Filter]) -> BatchImport {
+    let mut batch = BatchImport::default();
+    for path in paths {
+        match add_file(library, path, filters) {
+            Ok(book) => batch.imported.push(book),
+            Err(e) => batch.failed.push((path.clone(), e)),
+        }
+    }
+    batch
+}
+
+/// Every supported text file under `dir`, recursing into subfolders, in a
+/// stable order. Hidden files and folders are skipped.
+pub fn collect_text_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            if path.is_dir() {
+                pending.push(path);
+            } else if is_supported(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+fn is_supported(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| SUPPORTED_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// Import every supported file under `dir`. With `options.tag_collection`
+/// each book is tagged with the folder's name so the set can be found again.
+pub fn import_directory(
+    library: &mut Library,
+    dir: &Path,
+    options: &ImportOptions,
+    filters: &[Filter],
+) -> BatchImport {
+    let files = collect_text_files(dir);
+    let mut batch = add_files(library, &files, filters);
+
+    let collection = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string());
+    if let (true, Some(collection)) = (options.tag_collection, collection) {
+        for imported in &mut batch.imported {
+            imported.tags.push(collection.clone());
+            if let Some(book) = library.book_mut(&imported.id) {
+                book.tags.push(collection.clone());
+            }
+        }
+    }
+    if !batch.imported.is_empty() {
+        batch.save_error = save(library).err();
+    }
+    batch
+}
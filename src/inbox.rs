@@ -0,0 +1,146 @@
+//! A local socket the running app listens on, so other programs can hand it
+//! a book to read. The browser extension's native messaging host
+//! (`rsvp-host`) imports the page it is sent and asks the app to open it,
+//! and an app started with files to open passes them to the one already
+//! running instead.
+
+use crate::library::config_dir;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// What the app is asked to do
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "request")]
+pub enum Request {
+    /// Open a book that is already in the library and start reading it
+    Open { book_id: String },
+    /// Import files, or a folder, and open the first of them
+    Import { paths: Vec<PathBuf> },
+}
+
+pub fn socket_path() -> PathBuf {
+    config_dir().join("inbox.sock")
+}
+
+/// Files named on the command line, made absolute so they still resolve
+/// when another instance opens them. `--verbose` is left out.
+pub fn file_args() -> Vec<PathBuf> {
+    std::env::args_os()
+        .skip(1)
+        .filter(|arg| arg != crate::logging::VERBOSE_FLAG)
+        .map(PathBuf::from)
+        .map(|path| std::path::absolute(&path).unwrap_or(path))
+        .collect()
+}
+
+/// Hand files named on the command line to an app that is already running.
+/// True if one took them, and this one can exit.
+pub fn forward_files(paths: &[PathBuf]) -> bool {
+    !paths.is_empty()
+        && send(&Request::Import {
+            paths: paths.to_vec(),
+        })
+        .is_ok()
+}
+
+#[cfg(unix)]
+mod imp {
+    use super::{socket_path, Request};
+    use std::io::{self, BufRead, BufReader, Write};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::time::Duration;
+
+    /// How long a sender gets to finish writing its request
+    const READ_TIMEOUT: Duration = Duration::from_millis(200);
+
+    /// Send a request to the running app. Fails if none is listening.
+    pub fn send(request: &Request) -> io::Result<()> {
+        let mut stream = UnixStream::connect(socket_path())?;
+        let mut line = serde_json::to_string(request)?;
+        line.push('\n');
+        stream.write_all(line.as_bytes())
+    }
+
+    pub struct Inbox {
+        listener: UnixListener,
+    }
+
+    impl Inbox {
+        /// Start listening. Fails if another instance already is, or the
+        /// socket can't be created.
+        pub fn listen() -> io::Result<Self> {
+            let path = socket_path();
+            if UnixStream::connect(&path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    "another instance is listening",
+                ));
+            }
+            // Left behind by an instance that didn't shut down cleanly
+            match std::fs::remove_file(&path) {
+                Ok(()) => tracing::info!("Removed a stale socket at {}", path.display()),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => tracing::warn!("Couldn't remove {}: {}", path.display(), e),
+            }
+            crate::library::ensure_config_dirs()?;
+            let listener = UnixListener::bind(&path)?;
+            listener.set_nonblocking(true)?;
+            Ok(Self { listener })
+        }
+
+        /// Requests sent since the last poll
+        pub fn poll(&self) -> Vec<Request> {
+            let mut requests = Vec::new();
+            while let Ok((stream, _)) = self.listener.accept() {
+                if stream.set_nonblocking(false).is_err()
+                    || stream.set_read_timeout(Some(READ_TIMEOUT)).is_err()
+                {
+                    continue;
+                }
+                for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                    match serde_json::from_str(&line) {
+                        Ok(request) => {
+                            tracing::info!("Received {:?}", request);
+                            requests.push(request);
+                        }
+                        Err(e) => tracing::warn!("Ignored a bad request {:?}: {}", line, e),
+                    }
+                }
+            }
+            requests
+        }
+    }
+
+    impl Drop for Inbox {
+        fn drop(&mut self) {
+            if let Err(e) = std::fs::remove_file(socket_path()) {
+                tracing::warn!("Couldn't remove {}: {}", socket_path().display(), e);
+            }
+        }
+    }
+}
+
+/// Only Unix sockets are supported for now
+#[cfg(not(unix))]
+mod imp {
+    use super::Request;
+    use std::io;
+
+    pub fn send(_request: &Request) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    pub struct Inbox;
+
+    impl Inbox {
+        pub fn listen() -> io::Result<Self> {
+            Err(io::ErrorKind::Unsupported.into())
+        }
+
+        pub fn poll(&self) -> Vec<Request> {
+            Vec::new()
+        }
+    }
+}
+
+pub use imp::{send, Inbox};
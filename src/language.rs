@@ -0,0 +1,256 @@
+//! Each book's language, guessed on import, and the pacing that suits it:
+//! extra time for the long compounds of German or Dutch, and a pace per
+//! character for Chinese and Japanese, which aren't written in words. A
+//! book can also be paced per character or syllable whatever its language.
+
+use crate::text::WordSource;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Characters looked at to guess a language
+const SAMPLE_CHARS: usize = 20_000;
+/// Words read from an already imported book to guess its language
+const SAMPLE_WORDS: usize = 3000;
+/// Fewer stopword hits than this is too little to go on
+const MIN_STOPWORDS: usize = 5;
+
+/// Common short words of the languages written in the Latin alphabet that
+/// are told apart by vocabulary
+const STOPWORDS: &[(&str, &str)] = &[
+    ("en", "the and of to is that with was for it"),
+    ("de", "der die und das ist nicht ich mit sich auf"),
+    ("fr", "le la les et des est une que dans pas"),
+    ("es", "el los las y que del una por con para"),
+    ("it", "il di che non una per della sono con gli"),
+    ("pt", "o os que não uma do da com para em"),
+    ("nl", "de het een en van niet dat ik zijn op"),
+    ("sv", "och att det som en är av för på inte"),
+];
+
+/// Guess the language of some text as an ISO 639-1 code, from its script
+/// or, for Latin-script text, its most common words
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let sample: String = text.chars().take(SAMPLE_CHARS).collect();
+
+    let (mut kana, mut han, mut hangul, mut cyrillic, mut greek, mut latin) = (0, 0, 0, 0, 0, 0);
+    for c in sample.chars().filter(|c| c.is_alphabetic()) {
+        match c as u32 {
+            0x3040..=0x30ff => kana += 1,
+            0x4e00..=0x9fff | 0x3400..=0x4dbf => han += 1,
+            0xac00..=0xd7af | 0x1100..=0x11ff => hangul += 1,
+            0x0400..=0x04ff => cyrillic += 1,
+            0x0370..=0x03ff => greek += 1,
+            _ if c.is_ascii_alphabetic() || ('\u{c0}'..='\u{24f}').contains(&c) => latin += 1,
+            _ => {}
+        }
+    }
+    let letters = kana + han + hangul + cyrillic + greek + latin;
+    if letters == 0 {
+        return None;
+    }
+    // Japanese mixes kana into its kanji; Chinese has none
+    let script = [
+        ("ja", kana + if kana > 0 { han } else { 0 }),
+        ("zh", if kana == 0 { han } else { 0 }),
+        ("ko", hangul),
+        ("ru", cyrillic),
+        ("el", greek),
+    ]
+    .into_iter()
+    .find(|&(_, count)| count * 2 > letters);
+    if let Some((code, _)) = script {
+        return Some(code);
+    }
+    if latin * 2 <= letters {
+        return None;
+    }
+
+    let mut hits = [0usize; STOPWORDS.len()];
+    for word in sample.split_whitespace() {
+        let word = word
+            .trim_matches(|c: char| !c.is_alphabetic())
+            .to_lowercase();
+        for (i, (_, words)) in STOPWORDS.iter().enumerate() {
+            if words.split(' ').any(|stopword| stopword == word) {
+                hits[i] += 1;
+            }
+        }
+    }
+    let (best, &count) = hits.iter().enumerate().max_by_key(|&(_, count)| count)?;
+    (count >= MIN_STOPWORDS).then_some(STOPWORDS[best].0)
+}
+
+/// The ISO 639-1 code in a language tag like "en-US", as a file's own
+/// metadata gives it
+pub fn language_code(tag: &str) -> Option<String> {
+    let code = tag.trim().split(['-', '_', ',']).next()?.to_lowercase();
+    (code.len() == 2 && code.chars().all(|c| c.is_ascii_lowercase())).then_some(code)
+}
+
+/// Guess the language of a book from its opening words, for books
+/// imported before languages were recorded
+pub fn detect_book_language(words: &WordSource) -> Option<&'static str> {
+    let sample: Vec<String> = (0..SAMPLE_WORDS.min(words.len()))
+        .filter_map(|i| words.get(i))
+        .collect();
+    detect_language(&sample.join(" "))
+}
+
+// ============================================================================
+// Pacing
+// ============================================================================
+
+/// What a word period is spent on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Pacing {
+    /// One period per word; WPM is words per minute
+    #[default]
+    Words,
+    /// One period per character, for scripts without spaces between words;
+    /// WPM is read as characters per minute
+    Characters,
+    /// One period per syllable, for text whose word lengths vary widely;
+    /// WPM is read as syllables per minute
+    Syllables,
+}
+
+impl Pacing {
+    /// Short name of the speed unit: WPM, CPM or SPM
+    pub fn rate_label(self) -> &'static str {
+        match self {
+            Pacing::Words => "WPM",
+            Pacing::Characters => "CPM",
+            Pacing::Syllables => "SPM",
+        }
+    }
+
+    /// The pacing picked for a book after `current`: each one in turn, then
+    /// back to the book's language deciding
+    pub fn next_for_book(current: Option<Pacing>) -> Option<Pacing> {
+        match current {
+            None => Some(Pacing::Words),
+            Some(Pacing::Words) => Some(Pacing::Characters),
+            Some(Pacing::Characters) => Some(Pacing::Syllables),
+            Some(Pacing::Syllables) => None,
+        }
+    }
+}
+
+impl fmt::Display for Pacing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Pacing::Words => "words",
+            Pacing::Characters => "characters",
+            Pacing::Syllables => "syllables",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Syllables in a word, counted as runs of vowels less a silent final
+/// "e". Rough, but close enough to pace by and for readability scores.
+/// Words in scripts that don't write vowels count a syllable per
+/// character, Latin ones without vowels like "Mr" one, and ones without
+/// letters none.
+pub fn count_syllables(word: &str) -> usize {
+    let letters: Vec<char> = word
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .collect();
+    let is_vowel = |c: &char| "aeiouyàáâãäåæèéêëìíîïòóôõöøùúûüýœ".contains(*c);
+    let mut count = 0;
+    let mut in_vowels = false;
+    for c in &letters {
+        let vowel = is_vowel(c);
+        if vowel && !in_vowels {
+            count += 1;
+        }
+        in_vowels = vowel;
+    }
+    if count == 0 {
+        return if letters.iter().all(char::is_ascii) {
+            letters.len().min(1)
+        } else {
+            letters.len()
+        };
+    }
+    if count > 1 && letters.ends_with(&['e']) && !letters.ends_with(&['l', 'e']) {
+        count -= 1;
+    }
+    count
+}
+
+/// Pacing for one language. Entries under `[languages]` in `config.toml`,
+/// keyed by language code, replace the built-in ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LanguageProfile {
+    pub pacing: Pacing,
+    /// Words with more letters than this stay on screen longer; 0 for never
+    pub long_word_letters: usize,
+    /// Extra word periods for each letter past `long_word_letters`
+    pub per_extra_letter: f64,
+}
+
+impl LanguageProfile {
+    /// The profile for a language code: from `overrides` if it has one,
+    /// otherwise the built-in one
+    pub fn for_language(code: Option<&str>, overrides: &BTreeMap<String, LanguageProfile>) -> Self {
+        let Some(code) = code else {
+            return Self::default();
+        };
+        if let Some(profile) = overrides.get(code) {
+            return *profile;
+        }
+        match code {
+            "zh" | "ja" => Self {
+                pacing: Pacing::Characters,
+                ..Self::default()
+            },
+            // Languages that write compounds as one word
+            "de" | "nl" | "sv" | "da" | "no" | "fi" | "hu" => Self {
+                long_word_letters: 10,
+                per_extra_letter: 0.05,
+                ..Self::default()
+            },
+            _ => Self::default(),
+        }
+    }
+
+    /// Word periods it takes to show `text`, before punctuation pauses
+    pub fn length_weight(&self, text: &str) -> f64 {
+        match self.pacing {
+            Pacing::Characters => {
+                text.chars().filter(|c| c.is_alphanumeric()).count().max(1) as f64
+            }
+            Pacing::Syllables => count_syllables(text).max(1) as f64,
+            Pacing::Words => {
+                let letters = text.chars().filter(|c| c.is_alphabetic()).count();
+                let extra = if self.long_word_letters > 0 {
+                    letters.saturating_sub(self.long_word_letters)
+                } else {
+                    0
+                };
+                1.0 + extra as f64 * self.per_extra_letter.max(0.0)
+            }
+        }
+    }
+
+    /// Characters or syllables in the book's average word, from its
+    /// opening words, to turn a speed in them into words per minute. 1
+    /// when pacing by words.
+    pub fn units_per_word(&self, words: &WordSource) -> f64 {
+        let sample = SAMPLE_WORDS.min(words.len());
+        if self.pacing == Pacing::Words || sample == 0 {
+            return 1.0;
+        }
+        let units: f64 = (0..sample)
+            .filter_map(|i| words.get(i))
+            .map(|word| self.length_weight(&word))
+            .sum();
+        units / sample as f64
+    }
+}
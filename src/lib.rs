@@ -0,0 +1,35 @@
+//! RSVP Reader - shared core used by both the GUI and TUI frontends
+
+pub mod analysis;
+pub mod autodim;
+pub mod autosave;
+#[cfg(feature = "backup")]
+pub mod backup;
+pub mod blocks;
+pub mod breaks;
+pub mod config;
+pub mod diagnostics;
+pub mod email;
+pub mod filters;
+pub mod footnotes;
+pub mod history;
+pub mod import;
+pub mod inbox;
+pub mod language;
+pub mod library;
+pub mod links;
+pub mod logging;
+pub mod night;
+#[cfg(feature = "ocr")]
+pub mod ocr;
+pub mod ramp;
+pub mod recall;
+pub mod repeat;
+pub mod sessions;
+pub mod speech;
+pub mod speedtest;
+pub mod stats_line;
+pub mod text;
+pub mod timing;
+pub mod tutorial;
+pub mod vocabulary;
@@ -0,0 +1,1214 @@
+//! The book library shared by both frontends: config paths, the persisted
+//! `library.json` model, and reading state.
+//!
+//! Saving merges with whatever is on disk instead of overwriting it, so two
+//! frontends, or two machines syncing the config dir, don't lose each
+//! other's progress. The file is replaced in one rename, with the previous
+//! version kept as `library.json.bak`, so a crash mid-save can't corrupt it.
+//! While a frontend is open it watches the file too, following the position
+//! and speed the other one saves.
+
+use crate::analysis::BookAnalysis;
+use crate::blocks::BlockMode;
+use crate::breaks::BreakOutcome;
+use crate::footnotes::Footnote;
+use crate::language::Pacing;
+use crate::links::BookLink;
+use crate::recall::RecallScore;
+use crate::sessions::SessionRecord;
+use crate::speedtest::TestResult;
+use crate::text::{PacingProfile, MAX_CHUNK};
+use crate::vocabulary::VocabEntry;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// ============================================================================
+// Configuration Paths
+// ============================================================================
+
+pub fn config_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("rsvp-reader")
+}
+
+pub fn library_file() -> PathBuf {
+    config_dir().join("library.json")
+}
+
+/// The library as it was before the last save, read if `library.json` is
+/// missing or can't be read
+pub fn library_backup_file() -> PathBuf {
+    config_dir().join("library.json.bak")
+}
+
+pub fn books_dir() -> PathBuf {
+    config_dir().join("books")
+}
+
+pub fn book_file(book_id: &str) -> PathBuf {
+    books_dir().join(format!("{}.txt", book_id))
+}
+
+pub fn ensure_config_dirs() -> io::Result<()> {
+    fs::create_dir_all(config_dir())?;
+    fs::create_dir_all(books_dir())?;
+    Ok(())
+}
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Book {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub original_path: String,
+    pub total_words: usize,
+    pub progress: usize,
+    /// Byte offset of the current word in the book file; preferred over
+    /// `progress` when restoring since it survives tokenizer changes
+    #[serde(default)]
+    pub progress_offset: Option<u64>,
+    /// Unix time the progress was last saved, so the newer of two copies
+    /// wins when they are merged
+    #[serde(default)]
+    pub progress_at: u64,
+    /// Unix time the book was last opened or read on, 0 if never
+    #[serde(default)]
+    pub last_read_at: u64,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// ISO 639-1 code guessed on import, or given by the file, for
+    /// language-specific pacing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Who wrote it, as the file's own metadata says
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// When it was published or sent, as the file gives it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published: Option<String>,
+    /// Words saved while reading, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub vocabulary: Vec<VocabEntry>,
+    /// Statistics for the analysis screen, worked out at import
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub analysis: Option<BookAnalysis>,
+    /// Pacing picked for this book, in place of its language's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pacing: Option<Pacing>,
+    /// Look and pacing picked for this book
+    #[serde(default, skip_serializing_if = "BookDisplay::is_empty")]
+    pub display: BookDisplay,
+    /// Footnotes taken out of the text on import, in reading order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub footnotes: Vec<Footnote>,
+    /// Links in the text as imported, before filters flattened them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<BookLink>,
+}
+
+impl Book {
+    pub fn set_progress(&mut self, index: usize, offset: Option<u64>) {
+        if index != self.progress {
+            self.last_read_at = unix_now();
+        }
+        self.progress = index;
+        self.progress_offset = offset;
+        self.progress_at = unix_now();
+    }
+
+    /// When the book was last read, like "read 3 days ago"
+    pub fn read_ago(&self) -> Option<String> {
+        self.since_read().map(|ago| format!("read {}", ago))
+    }
+
+    /// How long ago the book was last read, like "3 days ago"
+    pub fn since_read(&self) -> Option<String> {
+        if self.last_read_at == 0 {
+            return None;
+        }
+        let minutes = unix_now().saturating_sub(self.last_read_at) / 60;
+        let (count, unit) = match minutes {
+            0 => return Some("just now".to_string()),
+            1..=59 => (minutes, "minute"),
+            60..=1439 => (minutes / 60, "hour"),
+            1440..=2879 => return Some("yesterday".to_string()),
+            2880..=20159 => (minutes / 1440, "day"),
+            20160..=43199 => (minutes / 10080, "week"),
+            43200..=525599 => (minutes / 43200, "month"),
+            _ => (minutes / 525600, "year"),
+        };
+        let plural = if count == 1 { "" } else { "s" };
+        Some(format!("{} {}{} ago", count, unit, plural))
+    }
+
+    /// What is known about the book besides its text, as labelled lines
+    /// for the details screen
+    pub fn details(&self) -> Vec<(&'static str, String)> {
+        [
+            ("Author", &self.author),
+            ("Published", &self.published),
+            ("Language", &self.language),
+        ]
+        .into_iter()
+        .filter_map(|(label, value)| Some((label, value.clone()?)))
+        .collect()
+    }
+
+    /// Whether reading has reached the last word
+    pub fn is_finished(&self) -> bool {
+        self.total_words > 0 && self.progress + 1 >= self.total_words
+    }
+
+    /// Add a word to the vocabulary list. False if it was already saved
+    /// from the same spot.
+    pub fn save_word(&mut self, entry: VocabEntry) -> bool {
+        if self.vocabulary.iter().any(|saved| saved.same_as(&entry)) {
+            return false;
+        }
+        self.vocabulary.push(entry);
+        true
+    }
+}
+
+/// Display settings for one book, each used in place of the general one
+/// while the book is open. Written as `key=value` pairs for editing, like
+/// `chunk=2 profile=poetry`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BookDisplay {
+    /// Desktop app theme
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appearance: Option<Appearance>,
+    /// Terminal color scheme
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color_scheme: Option<ColorScheme>,
+    /// Size of the word in the desktop app
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_size: Option<u16>,
+    /// Words shown at once
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chunk: Option<usize>,
+    /// A pacing profile from the `[profiles]` table in `config.toml`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// What to do with code blocks and tables
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocks: Option<BlockMode>,
+    /// Keep to the text's own lines, as for poetry and lyrics: a pause and
+    /// a mark at the end of each, and no chunk running past one. Overrides
+    /// the profile's `line_breaks` either way.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verse: Option<bool>,
+}
+
+impl BookDisplay {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Read settings written as `key=value` pairs. The keys are
+    /// `appearance`, `colors`, `font`, `chunk`, `profile`, which must name
+    /// one of `profiles`, `blocks` and `verse`, which is `on` or `off`.
+    pub fn parse(input: &str, profiles: &BTreeMap<String, PacingProfile>) -> Result<Self, String> {
+        let mut display = Self::default();
+        for pair in input.split_whitespace() {
+            let Some((key, value)) = pair.split_once('=') else {
+                return Err(format!("Expected key=value, got \"{}\"", pair));
+            };
+            match key {
+                "appearance" => display.appearance = Some(parse_name("theme", value)?),
+                "colors" => display.color_scheme = Some(parse_name("theme", value)?),
+                "blocks" => display.blocks = Some(parse_name("block mode", value)?),
+                "font" => display.font_size = Some(parse_number(key, value, 8, 300)?),
+                "chunk" => display.chunk = Some(parse_number(key, value, 1, MAX_CHUNK)?),
+                "profile" if profiles.contains_key(value) => {
+                    display.profile = Some(value.to_string())
+                }
+                "profile" => return Err(format!("No profile named \"{}\"", value)),
+                "verse" => {
+                    display.verse = match value {
+                        "on" => Some(true),
+                        "off" => Some(false),
+                        _ => return Err(format!("verse should be on or off, not \"{}\"", value)),
+                    }
+                }
+                _ => return Err(format!("Unknown setting \"{}\"", key)),
+            }
+        }
+        Ok(display)
+    }
+}
+
+impl fmt::Display for BookDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut pairs = Vec::new();
+        if let Some(appearance) = self.appearance {
+            pairs.push(format!("appearance={}", setting_name(appearance)));
+        }
+        if let Some(scheme) = self.color_scheme {
+            pairs.push(format!("colors={}", setting_name(scheme)));
+        }
+        if let Some(size) = self.font_size {
+            pairs.push(format!("font={}", size));
+        }
+        if let Some(chunk) = self.chunk {
+            pairs.push(format!("chunk={}", chunk));
+        }
+        if let Some(profile) = &self.profile {
+            pairs.push(format!("profile={}", profile));
+        }
+        if let Some(blocks) = self.blocks {
+            pairs.push(format!("blocks={}", setting_name(blocks)));
+        }
+        if let Some(verse) = self.verse {
+            pairs.push(format!("verse={}", if verse { "on" } else { "off" }));
+        }
+        f.write_str(&pairs.join(" "))
+    }
+}
+
+/// A setting's value from its name in `library.json`, like `high_contrast`.
+/// `what` names the kind of setting in the error.
+fn parse_name<T: DeserializeOwned>(what: &str, value: &str) -> Result<T, String> {
+    serde_json::from_value(serde_json::Value::String(value.to_string()))
+        .map_err(|_| format!("Unknown {} \"{}\"", what, value))
+}
+
+/// The name a setting's value is stored under, like `high_contrast`
+pub fn setting_name<T: Serialize>(value: T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+fn parse_number<T>(key: &str, value: &str, min: T, max: T) -> Result<T, String>
+where
+    T: FromStr + PartialOrd + fmt::Display,
+{
+    value
+        .parse()
+        .ok()
+        .filter(|n| *n >= min && *n <= max)
+        .ok_or_else(|| format!("{} should be a number from {} to {}", key, min, max))
+}
+
+/// Shorter items, like articles and newsletters, grouped to be read one
+/// after another. Each keeps its own progress as a book.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Playlist {
+    pub name: String,
+    /// Book ids in reading order
+    pub items: Vec<String>,
+    /// Unix time the playlist was made, to tell it from one of the same
+    /// name deleted earlier
+    #[serde(default)]
+    pub created_at: u64,
+}
+
+/// A deleted playlist, kept so merging with an older copy of the library
+/// doesn't bring it back
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemovedPlaylist {
+    pub name: String,
+    /// Unix time it was deleted
+    pub at: u64,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// State that changes while reading. Preferences edited by hand live in
+/// `config.toml` instead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Settings {
+    pub wpm: u32,
+    /// Unix time the speed was last changed, so merging keeps the newer one
+    #[serde(default)]
+    pub wpm_at: u64,
+    #[serde(default)]
+    pub appearance: Appearance,
+    /// Terminal color scheme
+    #[serde(default)]
+    pub color_scheme: ColorScheme,
+    /// Where the desktop window was when the app last closed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<WindowGeometry>,
+    /// How much bigger the desktop app draws everything than the system
+    /// scale alone would
+    #[serde(default = "default_ui_scale")]
+    pub ui_scale: f32,
+    /// How the stats line shows progress through the book
+    #[serde(default)]
+    pub progress_unit: ProgressUnit,
+    /// Focal letter, guide and progress colors that stay apart with color
+    /// blindness
+    #[serde(default)]
+    pub color_vision: ColorVision,
+}
+
+impl Settings {
+    pub fn set_wpm(&mut self, wpm: u32) {
+        self.wpm = wpm;
+        self.wpm_at = unix_now();
+    }
+}
+
+fn default_ui_scale() -> f32 {
+    1.0
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            wpm: 300,
+            wpm_at: 0,
+            appearance: Appearance::default(),
+            color_scheme: ColorScheme::default(),
+            window: None,
+            ui_scale: default_ui_scale(),
+            progress_unit: ProgressUnit::default(),
+            color_vision: ColorVision::default(),
+        }
+    }
+}
+
+/// Size and place of the desktop window, in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub width: f32,
+    pub height: f32,
+    /// Top left corner, unless the platform doesn't say where windows are
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<(i32, i32)>,
+    #[serde(default)]
+    pub fullscreen: bool,
+}
+
+/// GUI color scheme. `System` follows the desktop's light/dark preference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Appearance {
+    #[default]
+    Dark,
+    Light,
+    /// White on black with a yellow focal letter
+    HighContrast,
+    /// Dim amber on near-black, for reading late
+    Night,
+    /// Pure black, which OLED screens leave unlit
+    TrueBlack,
+    System,
+}
+
+impl Appearance {
+    pub const ALL: [Appearance; 6] = [
+        Appearance::Dark,
+        Appearance::Light,
+        Appearance::HighContrast,
+        Appearance::Night,
+        Appearance::TrueBlack,
+        Appearance::System,
+    ];
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&a| a == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+impl fmt::Display for Appearance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Appearance::Dark => "Dark",
+            Appearance::Light => "Light",
+            Appearance::HighContrast => "High contrast",
+            Appearance::Night => "Night",
+            Appearance::TrueBlack => "True black",
+            Appearance::System => "System",
+        })
+    }
+}
+
+/// Recall scores kept in the history
+const RECALL_HISTORY: usize = 200;
+/// Checks averaged for the stats bar
+const RECALL_AVERAGE: usize = 10;
+
+/// One break in the log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BreakRecord {
+    /// Unix time the break ended
+    pub at: u64,
+    pub outcome: BreakOutcome,
+}
+
+/// Logs kept across reading sessions. They are only ever appended to, so
+/// two copies merge by combining their entries.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReadingStats {
+    /// Totals from before breaks were logged one by one
+    #[serde(default)]
+    pub breaks_taken: u32,
+    #[serde(default)]
+    pub breaks_skipped: u32,
+    #[serde(default)]
+    pub breaks: Vec<BreakRecord>,
+    /// Recall check scores, oldest first
+    #[serde(default)]
+    pub recall: Vec<RecallScore>,
+    /// Reading sessions, oldest first
+    #[serde(default)]
+    pub sessions: Vec<SessionRecord>,
+    /// Speed test results, oldest first; the first is the baseline
+    #[serde(default)]
+    pub speed_tests: Vec<TestResult>,
+}
+
+impl ReadingStats {
+    pub fn record_break(&mut self, outcome: BreakOutcome) {
+        self.breaks.push(BreakRecord {
+            at: unix_now(),
+            outcome,
+        });
+    }
+
+    fn breaks_with(&self, outcome: BreakOutcome) -> u32 {
+        self.breaks.iter().filter(|b| b.outcome == outcome).count() as u32
+    }
+
+    /// Fold in another copy's entries, leaving out ones both have
+    fn merge(&mut self, other: ReadingStats) {
+        self.breaks_taken = self.breaks_taken.max(other.breaks_taken);
+        self.breaks_skipped = self.breaks_skipped.max(other.breaks_skipped);
+        for record in other.breaks {
+            if !self.breaks.contains(&record) {
+                self.breaks.push(record);
+            }
+        }
+        self.breaks.sort_by_key(|b| b.at);
+        for score in other.recall {
+            if !self.recall.contains(&score) {
+                self.recall.push(score);
+            }
+        }
+        self.recall.sort_by_key(|score| score.at);
+        let excess = self.recall.len().saturating_sub(RECALL_HISTORY);
+        self.recall.drain(..excess);
+        for session in other.sessions {
+            if !self.sessions.contains(&session) {
+                self.sessions.push(session);
+            }
+        }
+        self.sessions.sort_by_key(|session| session.started);
+        for result in other.speed_tests {
+            if !self.speed_tests.contains(&result) {
+                self.speed_tests.push(result);
+            }
+        }
+        self.speed_tests.sort_by_key(|result| result.at);
+    }
+
+    pub fn record_session(&mut self, session: SessionRecord) {
+        self.sessions.push(session);
+    }
+
+    pub fn record_speed_test(&mut self, result: TestResult) {
+        self.speed_tests.push(result);
+    }
+
+    /// The first speed test taken, which later ones are compared with
+    pub fn speed_baseline(&self) -> Option<&TestResult> {
+        self.speed_tests.first()
+    }
+
+    pub fn record_recall(&mut self, score: RecallScore) {
+        self.recall.push(score);
+        if self.recall.len() > RECALL_HISTORY {
+            self.recall.remove(0);
+        }
+    }
+
+    /// Average recall over the last few checks, as a percentage
+    pub fn recent_recall(&self) -> Option<u32> {
+        let recent = &self.recall[self.recall.len().saturating_sub(RECALL_AVERAGE)..];
+        let total: u32 = recent.iter().map(|score| score.total).sum();
+        let correct: u32 = recent.iter().map(|score| score.correct).sum();
+        (correct * 100).checked_div(total)
+    }
+
+    /// Breaks taken out of all that came up, as "3/4"
+    pub fn break_summary(&self) -> String {
+        let taken = self.breaks_taken + self.breaks_with(BreakOutcome::Taken);
+        let skipped = self.breaks_skipped + self.breaks_with(BreakOutcome::Skipped);
+        format!("{}/{}", taken, taken + skipped)
+    }
+}
+
+/// How many recently opened books the library remembers
+pub const RECENT_LIMIT: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Library {
+    pub books: Vec<Book>,
+    pub last_book: Option<String>,
+    /// Recently opened book ids, most recent first
+    #[serde(default)]
+    pub recent: Vec<String>,
+    #[serde(default)]
+    pub settings: Settings,
+    #[serde(default)]
+    pub stats: ReadingStats,
+    /// Ids of deleted books, so merging with an older copy of the library
+    /// doesn't bring them back
+    #[serde(default)]
+    pub removed: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub playlists: Vec<Playlist>,
+    /// Deleted playlists, which merging drops unless made again since
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed_playlists: Vec<RemovedPlaylist>,
+    /// Older GUI builds stored the speed at the top level
+    #[serde(default, rename = "wpm", skip_serializing)]
+    legacy_wpm: Option<u32>,
+}
+
+impl Library {
+    pub fn book(&self, book_id: &str) -> Option<&Book> {
+        self.books.iter().find(|b| b.id == book_id)
+    }
+
+    pub fn book_mut(&mut self, book_id: &str) -> Option<&mut Book> {
+        self.books.iter_mut().find(|b| b.id == book_id)
+    }
+
+    /// Record a book as opened, making it the last book and the first recent
+    pub fn mark_opened(&mut self, book_id: &str) {
+        self.last_book = Some(book_id.to_string());
+        self.recent.retain(|id| id != book_id);
+        self.recent.insert(0, book_id.to_string());
+        self.recent.truncate(RECENT_LIMIT);
+        if let Some(book) = self.book_mut(book_id) {
+            book.last_read_at = unix_now();
+        }
+    }
+
+    /// Recently opened books that are still in the library, most recently
+    /// read first. Reading on another machine, merged in by a sync tool,
+    /// counts too.
+    pub fn recent_books(&self) -> Vec<&Book> {
+        let mut books: Vec<&Book> = self.recent.iter().filter_map(|id| self.book(id)).collect();
+        books.sort_by_key(|book| std::cmp::Reverse(book.last_read_at));
+        books
+    }
+
+    /// Books started and not yet finished, most recently read first
+    pub fn in_progress(&self) -> Vec<&Book> {
+        let mut books: Vec<&Book> = self
+            .books
+            .iter()
+            .filter(|book| book.progress > 0 && !book.is_finished())
+            .collect();
+        books.sort_by_key(|book| std::cmp::Reverse(book.last_read_at));
+        books
+    }
+
+    /// The book to carry on with: the one read most recently, here or in a
+    /// merged copy of the library, or else the last one opened here
+    pub fn last_read(&self) -> Option<&Book> {
+        self.books
+            .iter()
+            .filter(|book| book.last_read_at > 0)
+            .max_by_key(|book| book.last_read_at)
+            .or_else(|| self.book(self.last_book.as_deref()?))
+    }
+
+    /// The book to read after `book_id`: the most recently opened one not
+    /// yet finished, or else the first unfinished one in the library
+    pub fn next_book(&self, book_id: &str) -> Option<&Book> {
+        let unfinished = |book: &&Book| book.id != book_id && !book.is_finished();
+        self.recent_books()
+            .into_iter()
+            .find(unfinished)
+            .or_else(|| self.books.iter().find(unfinished))
+    }
+
+    pub fn playlist(&self, name: &str) -> Option<&Playlist> {
+        self.playlists.iter().find(|p| p.name == name)
+    }
+
+    /// Add a book to the end of a playlist, which is made if there is none
+    /// by that name yet. Adding a book twice leaves it where it was.
+    pub fn add_to_playlist(&mut self, name: &str, book_id: &str) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Playlist name cannot be empty".to_string());
+        }
+        if self.book(book_id).is_none() {
+            return Err("Book not found".to_string());
+        }
+        let playlist = match self.playlists.iter().position(|p| p.name == name) {
+            Some(i) => &mut self.playlists[i],
+            None => {
+                // Made after any deletion of one by the same name, so merging
+                // keeps it
+                let deleted = self
+                    .removed_playlists
+                    .iter()
+                    .filter(|removed| removed.name == name)
+                    .map(|removed| removed.at + 1)
+                    .max()
+                    .unwrap_or(0);
+                self.playlists.push(Playlist {
+                    name: name.to_string(),
+                    items: Vec::new(),
+                    created_at: unix_now().max(deleted),
+                });
+                self.playlists.last_mut().unwrap()
+            }
+        };
+        if !playlist.items.iter().any(|id| id == book_id) {
+            playlist.items.push(book_id.to_string());
+        }
+        Ok(())
+    }
+
+    /// Delete a playlist. Its books stay in the library.
+    pub fn remove_playlist(&mut self, name: &str) {
+        self.playlists.retain(|p| p.name != name);
+        self.removed_playlists.retain(|p| p.name != name);
+        self.removed_playlists.push(RemovedPlaylist {
+            name: name.to_string(),
+            at: unix_now(),
+        });
+    }
+
+    /// A playlist's books still in the library, in reading order
+    pub fn playlist_books(&self, name: &str) -> Vec<&Book> {
+        self.playlist(name)
+            .map(|p| p.items.iter().filter_map(|id| self.book(id)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Where playing a playlist starts: its first unfinished item, or the
+    /// first item if all are read
+    pub fn playlist_start(&self, name: &str) -> Option<&Book> {
+        let books = self.playlist_books(name);
+        books
+            .iter()
+            .find(|book| !book.is_finished())
+            .or(books.first())
+            .copied()
+    }
+
+    /// The playlist item to play after `book_id`: the next one along that
+    /// isn't finished, if any
+    pub fn playlist_next(&self, name: &str, book_id: &str) -> Option<&Book> {
+        let books = self.playlist_books(name);
+        let current = books.iter().position(|book| book.id == book_id)?;
+        books[current + 1..]
+            .iter()
+            .find(|book| !book.is_finished())
+            .copied()
+    }
+
+    /// Give a book a new title, trimmed of surrounding whitespace
+    pub fn rename_book(&mut self, book_id: &str, title: &str) -> Result<(), String> {
+        let title = title.trim();
+        if title.is_empty() {
+            return Err("Title cannot be empty".to_string());
+        }
+        let book = self.book_mut(book_id).ok_or("Book not found")?;
+        book.title = title.to_string();
+        Ok(())
+    }
+
+    /// Remove a book along with any references to it
+    pub fn remove_book(&mut self, book_id: &str) {
+        self.books.retain(|b| b.id != book_id);
+        self.recent.retain(|id| id != book_id);
+        for playlist in &mut self.playlists {
+            playlist.items.retain(|id| id != book_id);
+        }
+        if self.last_book.as_deref() == Some(book_id) {
+            self.last_book = None;
+        }
+        if !self.removed.iter().any(|id| id == book_id) {
+            self.removed.push(book_id.to_string());
+        }
+    }
+
+    /// Fold in another copy of the library, such as the one on disk or a
+    /// sync tool's conflict copy. Books keep whichever progress was saved
+    /// last and the later time they were read, books only the other copy
+    /// has are added unless deleted here, deletions from either side stick,
+    /// and the stats logs and saved words are combined. Titles, tags,
+    /// settings, the recent list and playlists keep this copy's values,
+    /// though playlists only the other copy has are added, playlists deleted
+    /// on either side since they were made are dropped, and the speed set
+    /// last wins.
+    pub fn merge(&mut self, other: Library) {
+        for id in other.removed {
+            if !self.removed.contains(&id) {
+                self.removed.push(id);
+            }
+        }
+        for book in other.books {
+            match self.book_mut(&book.id) {
+                Some(mine) => {
+                    if book.progress_at > mine.progress_at {
+                        mine.progress = book.progress;
+                        mine.progress_offset = book.progress_offset;
+                        mine.progress_at = book.progress_at;
+                    }
+                    mine.last_read_at = mine.last_read_at.max(book.last_read_at);
+                    if !mine.analysis.as_ref().is_some_and(BookAnalysis::is_current) {
+                        mine.analysis = book.analysis.or(mine.analysis.take());
+                    }
+                    let before = mine.vocabulary.len();
+                    for entry in book.vocabulary {
+                        mine.save_word(entry);
+                    }
+                    if mine.vocabulary.len() > before {
+                        mine.vocabulary.sort_by_key(|entry| entry.saved_at);
+                    }
+                }
+                None => self.books.push(book),
+            }
+        }
+        for id in other.recent {
+            if !self.recent.contains(&id) && self.recent.len() < RECENT_LIMIT {
+                self.recent.push(id);
+            }
+        }
+        for playlist in other.playlists {
+            if self.playlist(&playlist.name).is_none() {
+                self.playlists.push(playlist);
+            }
+        }
+        for removed in other.removed_playlists {
+            match self
+                .removed_playlists
+                .iter_mut()
+                .find(|known| known.name == removed.name)
+            {
+                Some(known) => known.at = known.at.max(removed.at),
+                None => self.removed_playlists.push(removed),
+            }
+        }
+        let removed_playlists = &self.removed_playlists;
+        self.playlists.retain(|playlist| {
+            !removed_playlists
+                .iter()
+                .any(|removed| removed.name == playlist.name && removed.at >= playlist.created_at)
+        });
+        let removed = std::mem::take(&mut self.removed);
+        for id in &removed {
+            self.books.retain(|b| b.id != *id);
+            self.recent.retain(|recent| recent != id);
+            for playlist in &mut self.playlists {
+                playlist.items.retain(|item| item != id);
+            }
+        }
+        if self
+            .last_book
+            .as_ref()
+            .is_some_and(|id| removed.contains(id))
+        {
+            self.last_book = None;
+        }
+        self.removed = removed;
+        self.stats.merge(other.stats);
+        if other.settings.wpm_at > self.settings.wpm_at {
+            self.settings.wpm = other.settings.wpm;
+            self.settings.wpm_at = other.settings.wpm_at;
+        }
+        // The terminal reader doesn't have a window to forget
+        if self.settings.window.is_none() {
+            self.settings.window = other.settings.window;
+        }
+    }
+
+    /// Fold in the library as another program saved it, like `merge`, and
+    /// say what that changed for the book open here
+    pub fn sync(&mut self, on_disk: Library, book_id: Option<&str>) -> SyncChanges {
+        let progress_at = |library: &Library| {
+            book_id
+                .and_then(|id| library.book(id))
+                .map(|book| book.progress_at)
+        };
+        let before = (progress_at(self), self.settings.wpm_at);
+        self.merge(on_disk);
+        let progress = book_id
+            .and_then(|id| self.book(id))
+            .filter(|book| Some(book.progress_at) != before.0)
+            .map(|book| (book.progress, book.progress_offset));
+        let wpm = (self.settings.wpm_at != before.1).then_some(self.settings.wpm);
+        SyncChanges { progress, wpm }
+    }
+}
+
+/// What another program's save changed for the reader
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncChanges {
+    /// The open book's newer position, as a word index and a byte offset
+    pub progress: Option<(usize, Option<u64>)>,
+    pub wpm: Option<u32>,
+}
+
+/// How often `library.json` is looked at for saves by other programs
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Notices when another program, like the other frontend or a sync tool,
+/// saves `library.json`, by its modification time
+#[derive(Debug)]
+pub struct LibraryWatch {
+    modified: Option<SystemTime>,
+    checked: Instant,
+}
+
+impl Default for LibraryWatch {
+    /// Starts from the file as it is now, as just loaded
+    fn default() -> Self {
+        Self {
+            modified: library_modified(),
+            checked: Instant::now(),
+        }
+    }
+}
+
+impl LibraryWatch {
+    /// The library as saved by someone else since last looked at, if it is
+    /// time to look
+    pub fn poll(&mut self) -> Option<Library> {
+        if self.checked.elapsed() < WATCH_INTERVAL {
+            return None;
+        }
+        self.checked = Instant::now();
+        let modified = library_modified();
+        if modified.is_none() || modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        read_library(&library_file())
+    }
+
+    /// Save the library, knowing the save for this program's own so it
+    /// isn't read back. A save by someone else not yet looked at is still
+    /// picked up by the next poll.
+    pub fn save(&mut self, library: &Library) -> io::Result<()> {
+        let seen = library_modified() == self.modified;
+        save_library(library)?;
+        if seen {
+            self.modified = library_modified();
+        }
+        Ok(())
+    }
+}
+
+fn library_modified() -> Option<SystemTime> {
+    fs::metadata(library_file())
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+/// Named color schemes for the terminal frontend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorScheme {
+    #[default]
+    Default,
+    Solarized,
+    Gruvbox,
+    Monochrome,
+    /// White on black with a yellow focal letter
+    HighContrast,
+    /// Dim amber on black, for reading late
+    Night,
+    /// Pure black, which OLED screens leave unlit
+    TrueBlack,
+}
+
+impl ColorScheme {
+    pub const ALL: [ColorScheme; 7] = [
+        ColorScheme::Default,
+        ColorScheme::Solarized,
+        ColorScheme::Gruvbox,
+        ColorScheme::Monochrome,
+        ColorScheme::HighContrast,
+        ColorScheme::Night,
+        ColorScheme::TrueBlack,
+    ];
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&s| s == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+impl fmt::Display for ColorScheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ColorScheme::Default => "Default",
+            ColorScheme::Solarized => "Solarized",
+            ColorScheme::Gruvbox => "Gruvbox",
+            ColorScheme::Monochrome => "Monochrome",
+            ColorScheme::HighContrast => "High contrast",
+            ColorScheme::Night => "Night",
+            ColorScheme::TrueBlack => "True black",
+        })
+    }
+}
+
+/// Palette presets for color blindness, drawn from the Okabe-Ito colors.
+/// Each replaces the focal letter, guide and progress colors of whichever
+/// theme is in use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorVision {
+    /// The theme's own colors
+    #[default]
+    Standard,
+    /// Red-green, with green weak
+    Deuteranopia,
+    /// Red-green, with red weak and dark
+    Protanopia,
+    /// Blue-yellow
+    Tritanopia,
+}
+
+/// Focal letter, guide and progress colors, as RGB
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SafeColors {
+    pub focus: (u8, u8, u8),
+    pub guide: (u8, u8, u8),
+    pub progress: (u8, u8, u8),
+}
+
+impl ColorVision {
+    pub const ALL: [ColorVision; 4] = [
+        ColorVision::Standard,
+        ColorVision::Deuteranopia,
+        ColorVision::Protanopia,
+        ColorVision::Tritanopia,
+    ];
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&v| v == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    /// The preset's colors, or None to keep the theme's. Each stands out
+    /// from white or black text and from the others.
+    pub fn colors(self) -> Option<SafeColors> {
+        match self {
+            ColorVision::Standard => None,
+            // Vermilion stays a dark orange, against blues
+            ColorVision::Deuteranopia => Some(SafeColors {
+                focus: (0xd5, 0x5e, 0x00),
+                guide: (0x56, 0xb4, 0xe9),
+                progress: (0x00, 0x72, 0xb2),
+            }),
+            // Reds go dark, so the focal letter is a lighter orange
+            ColorVision::Protanopia => Some(SafeColors {
+                focus: (0xe6, 0x9f, 0x00),
+                guide: (0x56, 0xb4, 0xe9),
+                progress: (0x00, 0x72, 0xb2),
+            }),
+            // Blues and yellows run together, so reds against a teal
+            ColorVision::Tritanopia => Some(SafeColors {
+                focus: (0xd5, 0x5e, 0x00),
+                guide: (0x00, 0x9e, 0x73),
+                progress: (0xcc, 0x79, 0xa7),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for ColorVision {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ColorVision::Standard => "Standard colors",
+            ColorVision::Deuteranopia => "Deuteranopia",
+            ColorVision::Protanopia => "Protanopia",
+            ColorVision::Tritanopia => "Tritanopia",
+        })
+    }
+}
+
+/// What the progress readout in the stats line counts in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressUnit {
+    #[default]
+    Percent,
+    /// Word number out of the book's words
+    Words,
+    /// Reading time behind and ahead at the current speed
+    Time,
+    /// Chapter number and title
+    Chapter,
+}
+
+impl ProgressUnit {
+    pub const ALL: [ProgressUnit; 4] = [
+        ProgressUnit::Percent,
+        ProgressUnit::Words,
+        ProgressUnit::Time,
+        ProgressUnit::Chapter,
+    ];
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&u| u == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+impl fmt::Display for ProgressUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ProgressUnit::Percent => "Percent",
+            ProgressUnit::Words => "Words",
+            ProgressUnit::Time => "Time",
+            ProgressUnit::Chapter => "Chapter",
+        })
+    }
+}
+
+/// Read a library file, or None if it is missing or damaged
+pub fn read_library(path: &Path) -> Option<Library> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            tracing::warn!("Couldn't read {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    let mut library: Library = match serde_json::from_str(&content) {
+        Ok(library) => library,
+        Err(e) => {
+            tracing::warn!("{} is damaged: {}", path.display(), e);
+            return None;
+        }
+    };
+    if let Some(wpm) = library.legacy_wpm.take().filter(|&wpm| wpm > 0) {
+        library.settings.wpm = wpm;
+    }
+    Some(library)
+}
+
+/// Versions of `library.json` that sync tools set aside when two machines
+/// changed it at once, like "library.sync-conflict-20240101-120000-ABC.json"
+/// (Syncthing) or "library (conflicted copy).json" (Dropbox)
+fn conflict_copies() -> Vec<PathBuf> {
+    let entries = match fs::read_dir(config_dir()) {
+        Ok(entries) => entries,
+        Err(e) => {
+            if e.kind() != io::ErrorKind::NotFound {
+                tracing::warn!("Couldn't list {}: {}", config_dir().display(), e);
+            }
+            return Vec::new();
+        }
+    };
+    entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name != "library.json"
+                        && name.starts_with("library")
+                        && name.ends_with(".json")
+                        && name.contains("conflict")
+                })
+        })
+        .collect()
+}
+
+/// Load the library, folding in and then removing any conflict copies.
+/// Falls back to the backup if the library file is missing or damaged.
+pub fn load_library() -> Library {
+    let on_disk = read_library(&library_file());
+    let intact = on_disk.is_some();
+    let mut library = on_disk
+        .or_else(|| read_library(&library_backup_file()))
+        .unwrap_or_default();
+
+    let copies: Vec<(PathBuf, Library)> = conflict_copies()
+        .into_iter()
+        .filter_map(|path| read_library(&path).map(|copy| (path, copy)))
+        .collect();
+    if !copies.is_empty() {
+        let mut paths = Vec::new();
+        for (path, copy) in copies {
+            library.merge(copy);
+            paths.push(path);
+        }
+        match write_library(&library, intact) {
+            Ok(()) => {
+                for path in paths {
+                    tracing::info!("Merged conflict copy {}", path.display());
+                    if let Err(e) = fs::remove_file(&path) {
+                        tracing::warn!("Couldn't remove {}: {}", path.display(), e);
+                    }
+                }
+            }
+            Err(e) => tracing::error!("Couldn't save the merged library: {}", e),
+        }
+    }
+    library
+}
+
+/// Save the library, merged with the copy on disk in case the other
+/// frontend or a sync tool has written it since it was loaded
+pub fn save_library(library: &Library) -> io::Result<()> {
+    ensure_config_dirs()?;
+    match read_library(&library_file()) {
+        Some(on_disk) => {
+            let mut merged = library.clone();
+            merged.merge(on_disk);
+            write_library(&merged, true)
+        }
+        // A damaged file isn't worth backing up over the last good backup
+        None => write_library(library, false),
+    }
+}
+
+/// Write the library to a temporary file and rename it into place, so the
+/// library file is always either the old version or the new one. With
+/// `backup`, the old version is also kept as `library.json.bak`, linked or
+/// copied rather than moved so the library file never goes missing.
+fn write_library(library: &Library, backup: bool) -> io::Result<()> {
+    let content = serde_json::to_string_pretty(library).map_err(io::Error::other)?;
+    let path = library_file();
+    let temp = path.with_extension("json.tmp");
+    let mut file = File::create(&temp)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+    drop(file);
+
+    if backup {
+        let backup = library_backup_file();
+        let _ = fs::remove_file(&backup);
+        if fs::hard_link(&path, &backup).is_err() {
+            fs::copy(&path, &backup)?;
+        }
+    }
+    fs::rename(&temp, &path)
+}
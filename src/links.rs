@@ -0,0 +1,136 @@
+//! Links found in a book's text on import, kept with the book so they
+//! aren't lost once the text is flattened for reading, and opened in the
+//! browser from the links list
+
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::process::{Command, Stdio};
+
+/// A link kept with a book
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookLink {
+    /// The link's text, or the address itself for a bare one
+    pub label: String,
+    pub url: String,
+}
+
+/// Links in `text`, in the order they first appear: Markdown links
+/// ("[label](https://...)"), autolinks ("<https://...>") and bare web
+/// addresses
+pub fn find_links(text: &str) -> Vec<BookLink> {
+    let mut links: Vec<BookLink> = Vec::new();
+    let mut pos = 0;
+    // Looked for again only once passed, so text full of brackets isn't
+    // searched for an address over and over
+    let mut address = next_address(text);
+    loop {
+        if address.is_some_and(|at| at < pos) {
+            address = next_address(&text[pos..]).map(|at| pos + at);
+        }
+        let bracket = text[pos..].find('[').map(|at| pos + at);
+        let start = match (bracket, address) {
+            (Some(a), Some(b)) => a.min(b),
+            (Some(a), None) | (None, Some(a)) => a,
+            (None, None) => break,
+        };
+        let found = &text[start..];
+        let (link, len) = if found.starts_with('[') {
+            match markdown_link(found) {
+                Some(found) => found,
+                None => {
+                    pos = start + 1;
+                    continue;
+                }
+            }
+        } else {
+            let len = url_len(found);
+            let url = &found[..len];
+            (
+                BookLink {
+                    label: url.to_string(),
+                    url: url.to_string(),
+                },
+                len,
+            )
+        };
+        if !links.iter().any(|known| known.url == link.url) {
+            links.push(link);
+        }
+        pos = start + len;
+    }
+    links
+}
+
+/// Where the next web address starts
+fn next_address(text: &str) -> Option<usize> {
+    ["https://", "http://"]
+        .iter()
+        .filter_map(|scheme| text.find(scheme))
+        .min()
+}
+
+/// "[label](url)" at the start of `text`, with the bytes it takes up, if
+/// the url is a web address
+fn markdown_link(text: &str) -> Option<(BookLink, usize)> {
+    // The label ends at the first "]", and can't span lines or hold
+    // another "["
+    let label_end = 1 + text[1..].find(['\n', '[', ']'])?;
+    if !text[label_end..].starts_with("](") {
+        return None;
+    }
+    let label = &text[1..label_end];
+    let target = &text[label_end + 2..];
+    let url_end = target.find(')')?;
+    // A title can follow the address, as in (https://... "Title")
+    let url = target[..url_end].split_whitespace().next()?;
+    if !is_web_address(url) {
+        return None;
+    }
+    let label = label.trim();
+    let link = BookLink {
+        label: if label.is_empty() { url } else { label }.to_string(),
+        url: url.to_string(),
+    };
+    Some((link, label_end + 2 + url_end + 1))
+}
+
+fn is_web_address(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+/// Bytes of the web address at the start of `text`. Punctuation that ends
+/// a sentence or closes a bracket around the address is left out.
+fn url_len(text: &str) -> usize {
+    let end = text
+        .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"'))
+        .unwrap_or(text.len());
+    let url = &text[..end];
+    let opened = url.matches('(').count();
+    let mut url = url.trim_end_matches(['.', ',', ';', ':', '!', '?', '\'']);
+    // Keep a ")" closing one in the address, as on Wikipedia
+    while url.ends_with(')') && url.matches(')').count() > opened {
+        url = url[..url.len() - 1].trim_end_matches(['.', ',', ';', ':', '!', '?', '\'']);
+    }
+    url.len()
+}
+
+/// Open `url` in the default browser
+pub fn open_in_browser(url: &str) -> io::Result<()> {
+    // Not through cmd, which would run anything after a "&" in the address
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
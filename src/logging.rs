@@ -0,0 +1,59 @@
+//! Diagnostics for the failures the apps get past on their own, like a
+//! conflict copy that couldn't be removed or a socket that couldn't be
+//! opened. They go to `logs/` in the config dir, a file a day per program
+//! with the last week kept. Warnings and errors are always written, and
+//! `--verbose` on the command line adds what the apps are doing.
+
+use crate::library::config_dir;
+use std::path::PathBuf;
+use tracing::level_filters::LevelFilter;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{Builder, Rotation};
+
+pub const VERBOSE_FLAG: &str = "--verbose";
+
+/// Daily log files kept for each program
+const KEEP_FILES: usize = 7;
+
+pub fn log_dir() -> PathBuf {
+    config_dir().join("logs")
+}
+
+/// Whether `--verbose` was passed
+pub fn verbose() -> bool {
+    std::env::args().skip(1).any(|arg| arg == VERBOSE_FLAG)
+}
+
+/// Start writing `logs/<name>.<date>.log`. Lines are written on a background
+/// thread, and the guard flushes them when dropped, so hold it until exit.
+/// None if the log directory can't be created, and nothing is logged.
+pub fn init(name: &str) -> Option<WorkerGuard> {
+    let appender = match Builder::new()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(name)
+        .filename_suffix("log")
+        .max_log_files(KEEP_FILES)
+        .build(log_dir())
+    {
+        Ok(appender) => appender,
+        Err(e) => {
+            eprintln!("Couldn't open the log in {}: {}", log_dir().display(), e);
+            return None;
+        }
+    };
+
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    let level = if verbose() {
+        LevelFilter::DEBUG
+    } else {
+        LevelFilter::WARN
+    };
+    tracing_subscriber::fmt()
+        .with_writer(writer)
+        .with_ansi(false)
+        .with_max_level(level)
+        .try_init()
+        .ok()?;
+    tracing::debug!("{} {} started", name, env!("CARGO_PKG_VERSION"));
+    Some(guard)
+}
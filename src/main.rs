@@ -3,176 +3,569 @@
 //! Keyboard shortcuts:
 //!   Space       - Start/Pause
 //!   Up/Down     - Adjust WPM
+//!   1-9         - Speed presets (200 to 1000 WPM)
 //!   Left/Right  - Navigate words
+//!   S/Alt+Left  - Start of sentence
+//!   P/Alt+Up    - Start of paragraph
+//!   ( / )       - Previous/next sentence
+//!   { / }       - Previous/next paragraph
+//!   Backspace   - Back to the position before the last jump (Tab: forward)
 //!   R           - Reset
-//!   O           - Open file
+//!   O           - Library (arrows to choose, Enter to open, F2 to rename,
+//!                 A to add to a playlist, Delete to remove)
+//!   Shift+P     - Playlists, at the top of the library
+//!   Shift+L     - Loop the sentence, then the paragraph, then stop looping
+//!   X           - Mark loop point A, then B to loop between them
+//!   I           - Import files
+//!   T           - Cycle light/dark/high-contrast/system theme
+//!   Shift+C     - Color blindness palette: deuteranopia, protanopia, tritanopia
+//!   U           - Progress in percent, words, time or chapter
+//!   Shift+W     - Pace this book by words, characters or syllables
+//!   Shift+D     - This book's own theme, font size, chunk size and pacing
+//!   N           - Narration on/off
+//!   Shift+S     - Skim function words on/off
+//!   E           - Keyword emphasis on/off
+//!   C           - Recall check on the last minute of reading
+//!   A           - Book analysis: length, vocabulary and reading time
+//!   G           - Reading statistics: time spent, speed and progress by day
+//!   Shift+B     - Speed test: read a set passage, then answer questions on it
+//!   V           - Save the current word to the vocabulary list
+//!   Shift+V     - Saved words, with buttons to export them for Anki
+//!   Shift+N     - Show the footnote just passed
+//!   Shift+U     - Links in the book, to open in the browser
+//!   F11/Z       - Zen mode (fullscreen, word only; any key exits)
+//!   M           - Compact overlay: a small always-on-top strip with the
+//!                 word and progress, dragged by the word
+//!   Ctrl+= / -  - Zoom in/out: word, controls and text
+//!   F12         - Timing diagnostics on/off
+//!   F5          - Reload config.toml
+//!   ?           - Keyboard shortcuts
 //!   Escape      - Quit
+//!
+//! A `global_hotkey` in config.toml plays and pauses even while the window
+//! is in the background.
+//!
+//! Keys can be rebound in config.toml. The File, View and Help menus offer
+//! the same actions.
+//!
+//! On Linux, screen readers see the current word, playback state, progress
+//! and speed, and can press play/pause and step through words. Media keys
+//! and "now playing" widgets work over MPRIS there too.
+//!
+//! Files named on the command line are imported and opened, by the reader
+//! that is already running if there is one, whose window comes to the front.
+//! With an empty library, a short tutorial on the controls is offered.
+//!
+//! Problems are logged to `logs/` in the config dir, and `--verbose` logs
+//! what the app is doing as well.
 
+use accessibility::{A11yAction, Accessibility, Snapshot};
+use global_hotkey::hotkey::HotKey;
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
 use iced::keyboard::{self, Key};
 use iced::theme::{self, Theme};
 use iced::time;
-use iced::widget::{button, column, container, row, text, Space};
-use iced::{executor, Application, Color, Command, Element, Font, Length, Settings, Subscription};
-use serde::{Deserialize, Serialize};
-use std::collections::hash_map::DefaultHasher;
+use iced::widget::{
+    button, checkbox, column, container, mouse_area, pick_list, progress_bar, row, scrollable,
+    slider, text, text_input, Space,
+};
+use iced::window;
+use iced::{
+    alignment, event, executor, font, Application, Color, Command, Element, Event, Font, Length,
+    Settings, Subscription,
+};
+use mpris::{MediaAction, MediaControls, NowPlaying};
+use rsvp::analysis::fill_analysis;
+use rsvp::autodim::Dimmer;
+use rsvp::autosave::Autosave;
+use rsvp::blocks::{BlockPause, BlockStop};
+use rsvp::breaks::{format_countdown, BreakTimer, ChapterBreak, MicroPauseCounter};
+use rsvp::config::{
+    load_config, parse_color, Action, Config, DialogueStyle, FocalGuide, FocusStyle, KeyChord,
+    KeyName, LetterCase, SentenceEnd,
+};
+use rsvp::diagnostics::Diagnostics;
+use rsvp::footnotes::{footnote_near, Footnote};
+use rsvp::history::JumpHistory;
+use rsvp::import::{
+    import_book, import_books, import_directory, reload_book, BatchImport, ImportOptions,
+    SourceWatch, SUPPORTED_EXTENSIONS,
+};
+use rsvp::inbox::{self, Inbox, Request};
+use rsvp::language::{detect_book_language, LanguageProfile, Pacing};
+use rsvp::library::{
+    book_file, load_library, setting_name, Appearance, Book, BookDisplay, ColorScheme, ColorVision,
+    Library, LibraryWatch, WindowGeometry,
+};
+use rsvp::links::{open_in_browser, BookLink};
+use rsvp::logging;
+use rsvp::night::NightWatch;
+use rsvp::ramp::SpeedRamp;
+use rsvp::recall::{RecallCheck, RecentWords};
+use rsvp::repeat::{Mark, Repeat};
+use rsvp::sessions::{book_stats, completion_summary, SessionTracker};
+use rsvp::speech::Narration;
+use rsvp::speedtest::SpeedTest;
+use rsvp::stats_line::{book_progress, book_summary, library_summary, reading_times, StatsFields};
+use rsvp::text::{
+    display_unit, display_width, emphasis_runs, ends_sentence, frame_weight, split_at_orp,
+    split_long_word, Emphasis, PacingProfile, Pauses, WordSource, MAX_CHUNK,
+};
+use rsvp::timing::{tick_interval, Advance, WordClock};
+use rsvp::tutorial::{Progress, Tutorial};
+use rsvp::vocabulary::{export_all_vocabulary, export_vocabulary, VocabEntry};
+use std::cell::RefCell;
 use std::fs;
-use std::hash::{Hash, Hasher};
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
+use unicode_segmentation::UnicodeSegmentation;
 
 // ============================================================================
-// Configuration
-// ============================================================================
-
-fn config_dir() -> PathBuf {
-    dirs::config_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("rsvp-reader")
-}
-
-fn library_file() -> PathBuf {
-    config_dir().join("library.json")
-}
-
-fn books_dir() -> PathBuf {
-    config_dir().join("books")
-}
-
-fn ensure_config_dirs() -> std::io::Result<()> {
-    fs::create_dir_all(config_dir())?;
-    fs::create_dir_all(books_dir())?;
-    Ok(())
-}
-
-// ============================================================================
-// Data Structures
+// Application
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct Book {
-    id: String,
-    title: String,
-    total_words: usize,
-    progress: usize,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-struct Library {
-    books: Vec<Book>,
-    last_book: Option<String>,
-    wpm: u32,
-}
-
-fn load_library() -> Library {
-    if let Ok(content) = fs::read_to_string(library_file()) {
-        serde_json::from_str(&content).unwrap_or_else(|_| Library {
-            wpm: 300,
-            ..Default::default()
-        })
-    } else {
-        Library {
-            wpm: 300,
-            ..Default::default()
-        }
-    }
-}
+const WORD_FONT_SIZE: u16 = 72;
+// The word in the compact overlay
+const COMPACT_FONT_SIZE: u16 = 36;
+// Approximate character width for monospace-like centering
+const CHAR_WIDTH_RATIO: f32 = 0.6;
+const DEFAULT_WINDOW_WIDTH: f32 = 800.0;
+const MIN_WINDOW: iced::Size = iced::Size::new(600.0, 400.0);
+// The compact overlay is smaller than a normal window may be made, so the
+// window's own minimum has to allow it
+const COMPACT_WINDOW: iced::Size = iced::Size::new(480.0, 110.0);
+const LIBRARY_WIDTH: f32 = 280.0;
+// Steps and bounds of the UI scale, on top of the system's
+const UI_SCALE_STEP: f32 = 0.1;
+const MIN_UI_SCALE: f32 = 0.5;
+const MAX_UI_SCALE: f32 = 3.0;
 
-fn save_library(library: &Library) {
-    let _ = ensure_config_dirs();
-    if let Ok(content) = serde_json::to_string_pretty(library) {
-        let _ = fs::write(library_file(), content);
-    }
+/// The last saved window size, or the default
+fn window_size(geometry: Option<WindowGeometry>) -> iced::Size {
+    geometry.map_or(iced::Size::new(DEFAULT_WINDOW_WIDTH, 500.0), |g| {
+        iced::Size::new(
+            g.width.max(MIN_WINDOW.width),
+            g.height.max(MIN_WINDOW.height),
+        )
+    })
 }
 
-// ============================================================================
-// ORP Calculation
-// ============================================================================
+pub fn main() -> iced::Result {
+    let _log = logging::init("rsvp");
 
-fn calculate_orp(word: &str) -> usize {
-    let len = word.chars().count();
-    match len {
-        0..=1 => 0,
-        2..=5 => 1,
-        6..=9 => 2,
-        10..=13 => 3,
-        _ => 4,
+    // Files to open go to the reader that is already running, if any
+    let files = inbox::file_args();
+    if inbox::forward_files(&files) {
+        return Ok(());
     }
-}
 
-fn tokenize_text(text: &str) -> Vec<String> {
-    text.split_whitespace().map(|s| s.to_string()).collect()
-}
+    // With a tray icon to come back through, closing can just hide the window
+    #[cfg(feature = "tray")]
+    let hide_on_close = tray::spawn() && load_config().is_ok_and(|c| c.close_to_tray);
+    #[cfg(not(feature = "tray"))]
+    let hide_on_close = false;
 
-// ============================================================================
-// Application
-// ============================================================================
+    // The window opens where it was last closed
+    let library = load_library();
+    let geometry = library.settings.window;
+    let size = window_size(geometry);
+    let position = match geometry.and_then(|g| g.position) {
+        Some((x, y)) => window::Position::Specific(iced::Point::new(x as f32, y as f32)),
+        None => window::Position::Default,
+    };
 
-pub fn main() -> iced::Result {
     RSVPApp::run(Settings {
-        window: iced::window::Settings {
-            size: iced::Size::new(800.0, 500.0),
-            min_size: Some(iced::Size::new(600.0, 400.0)),
+        window: window::Settings {
+            size,
+            position,
+            min_size: Some(COMPACT_WINDOW),
+            // Closing saves first, or hides the window
+            exit_on_close_request: false,
             ..Default::default()
         },
         antialiasing: true,
-        ..Default::default()
+        ..Settings::with_flags(Launch {
+            library,
+            files,
+            hide_on_close,
+        })
     })
 }
 
+/// What `main` hands the app at launch
+struct Launch {
+    library: Library,
+    /// Files to open, from the command line
+    files: Vec<PathBuf>,
+    /// Whether closing the window hides it to the tray instead of quitting
+    hide_on_close: bool,
+}
+
 #[derive(Debug, Clone)]
 enum Message {
     Tick,
     TogglePlay,
     SpeedUp,
     SpeedDown,
+    SpeedPreset(u32),
     PrevWord,
     NextWord,
+    SentenceStart,
+    ParagraphStart,
+    PrevSentence,
+    NextSentence,
+    PrevParagraph,
+    NextParagraph,
+    JumpBack,
+    JumpForward,
     Reset,
     OpenFile,
-    FileOpened(Option<PathBuf>),
-    KeyPressed(Key),
+    FilesOpened(Vec<PathBuf>),
+    OpenFolder,
+    FolderOpened(Option<PathBuf>),
+    ToggleTagCollection(bool),
+    ToggleSkipMatter(bool),
+    ToggleStripCitations(bool),
+    ToggleLibrary,
+    OpenBook(String),
+    DeleteBook(String),
+    ConfirmDelete(bool),
+    StartRename(String),
+    RenameInput(String),
+    FinishRename(bool),
+    StartAddToPlaylist(String),
+    PlaylistInput(String),
+    FinishAddToPlaylist(bool),
+    PlayPlaylist(String),
+    DeletePlaylist(String),
+    AppearanceSelected(Appearance),
+    ColorVisionSelected(ColorVision),
+    ToggleZen,
+    ToggleCompact,
+    DragWindow,
+    CloseFinished,
+    EndChapterBreak,
+    EndBlockPause,
+    ShowFootnote,
+    CloseFootnote,
+    ScaleUi(f32),
+    CycleProgress,
+    CyclePacing,
+    StartBookDisplay,
+    BookDisplayInput(String),
+    FinishBookDisplay(bool),
+    ToggleLoop,
+    MarkLoop,
+    ToggleDiagnostics,
+    ToggleHelp,
+    ToggleNarration,
+    ToggleSkim,
+    ToggleKeywords,
+    StartRecall,
+    RecallToggle(usize),
+    RecallSubmit,
+    RecallClose,
+    SaveWord,
+    ToggleVocabulary,
+    ExportVocabulary,
+    ToggleLinks,
+    OpenLink(String),
+    CloseStart,
+    ToggleStart,
+    ShowDetails(String),
+    CloseDetails,
+    ToggleLibraryStats,
+    ReloadBook,
+    DismissReload,
+    ExportAllVocabulary,
+    ToggleAnalysis,
+    ToggleStats,
+    StartSpeedTest,
+    StartTutorial,
+    SpeedTestAnswer(usize),
+    SpeedTestClose,
+    ReloadConfig,
+    Quit,
+    ToggleMenu(Menu),
+    // A menu entry was chosen: close the menu, then handle the message
+    MenuPick(Box<Message>),
+    Scrub(f64),
+    ScrubReleased,
+    KeyPressed(Key, keyboard::Modifiers),
+    WindowResized(iced::Size),
+    WindowMoved(i32, i32),
+    // Only seen when closing is set to hide the window to the tray
+    WindowCloseRequested,
+    WindowFocused(bool),
+    GlobalHotkeyPoll,
+    AccessibilityPoll,
+    MediaPoll,
+    InboxPoll,
+    LibraryPoll,
+    SourcePoll,
+    NightPoll,
+    // Mouse input, which brings back what auto-dim faded
+    Wake,
+    #[cfg(feature = "tray")]
+    TrayPoll,
 }
 
 struct RSVPApp {
     library: Library,
-    words: Vec<String>,
+    config: Config,
+    words: WordSource,
     word_index: usize,
     current_book_id: Option<String>,
     current_book_title: String,
+    /// Pacing for the open book's language
+    language: LanguageProfile,
+    /// Characters or syllables in the open book's average word, with that
+    /// pacing
+    units_per_word: f64,
+    /// Settings picked for the open book, and the pacing profile they name
+    display: BookDisplay,
+    profile: PacingProfile,
+    // The open book's settings being edited, as typed so far
+    editing_display: Option<String>,
     is_playing: bool,
     wpm: u32,
-    last_tick: Instant,
-    status_message: Option<String>,
+    // Times the word on screen
+    clock: WordClock,
+    // Frame within an over-long word, paired with the word index it belongs to
+    frame: (usize, usize),
+    window_width: f32,
+    // Whether closing the window hides it to the tray instead of quitting
+    hide_on_close: bool,
+    import_options: ImportOptions,
+    // Library sidebar: whether it is showing, the highlighted row, a book
+    // waiting for its delete to be confirmed, a book being renamed with
+    // its title so far, and a book going into a playlist with its name so
+    // far
+    library_open: bool,
+    library_selected: usize,
+    pending_delete: Option<String>,
+    renaming: Option<(String, String)>,
+    adding_to_playlist: Option<(String, String)>,
+    // The playlist being played through, if the open book came from one
+    playlist: Option<String>,
+    // Whether the dark palette is in use, with `Appearance::System` resolved
+    dark: bool,
+    // Fullscreen with only the word showing
+    zen: bool,
+    // Shrunk to a frameless always-on-top strip with the word and progress
+    compact: bool,
+    // Menu dropped down from the menu bar, if any
+    open_menu: Option<Menu>,
+    // Keyboard shortcuts shown in place of the word
+    show_help: bool,
+    // The book's saved words shown in place of the word
+    show_vocabulary: bool,
+    // The book's links shown in place of the word
+    show_links: bool,
+    // Books in progress to pick from, shown on launch
+    show_start: bool,
+    // The library book whose details are shown in place of the word
+    details_book: Option<String>,
+    // Totals across the library shown in place of the word
+    show_library_stats: bool,
+    // Statistics about the book shown in place of the word
+    show_analysis: bool,
+    // Time spent, speed and progress on the book shown in place of the word
+    show_stats: bool,
+    // Summary of a book just finished, with what to read next, in place of
+    // the word
+    finished: bool,
+    // Set while the progress slider is dragged: whether playback was running
+    scrub_resume: Option<bool>,
+    // Newest last
+    toasts: Vec<Toast>,
+    // OS-wide shortcuts, if the platform allows them, and the play/pause
+    // key registered with it
+    hotkeys: Option<GlobalHotKeyManager>,
+    global_hotkey: Option<HotKey>,
+    breaks: BreakTimer,
+    // Micro-pauses, and the blank one is showing
+    micro_pauses: MicroPauseCounter,
+    blank_until: Option<Instant>,
+    // The screen between two chapters, while it is up
+    chapter_break: Option<ChapterBreak>,
+    // A code block or table shown whole, while it is up
+    block_pause: Option<BlockPause>,
+    // A footnote brought up with the footnote key, and whether reading
+    // carries on once it is put away
+    footnote: Option<(Footnote, bool)>,
+    // Whether night mode has the night theme on
+    night: NightWatch,
+    // Fades everything but the word during undisturbed playback
+    dimmer: Dimmer,
+    // Speech following the display
+    narration: Narration,
+    // Words from the last minute of playback, and a recall check on them
+    recent_words: RecentWords,
+    /// Positions before big jumps, for jumping back
+    history: JumpHistory,
+    // A sentence, paragraph or marked stretch playing over and over
+    repeat: Repeat,
+    recall: Option<RecallCheck>,
+    // A speed test under way, reading its passage or asking its questions
+    speed_test: Option<SpeedTest>,
+    // The first-launch tutorial, while it is playing
+    tutorial: Option<Tutorial>,
+    // Reading sessions, for the statistics screen
+    sessions: SessionTracker,
+    // The session's speed stepping up, with the speed ramp on
+    ramp: SpeedRamp,
+    // Reading not yet saved to the library
+    autosave: Autosave,
+    // Pacing and drawing times, which `view` adds to, and whether they
+    // are shown
+    diagnostics: RefCell<Diagnostics>,
+    show_diagnostics: bool,
+    accessibility: Accessibility,
+    // Media keys and widgets, when there is a session bus to offer them on
+    media: Option<MediaControls>,
+    // Books sent over by the browser extension
+    inbox: Option<Inbox>,
+    // Saves to the library by the other frontend or a sync tool
+    library_watch: LibraryWatch,
+    // The open book's source file, with watch_sources on
+    source_watch: SourceWatch,
+    // Set when that file has changed, until the reload is taken or turned
+    // down
+    reload_offer: bool,
 }
 
 impl Application for RSVPApp {
     type Executor = executor::Default;
     type Message = Message;
     type Theme = Theme;
-    type Flags = ();
+    type Flags = Launch;
 
-    fn new(_flags: ()) -> (Self, Command<Message>) {
-        let library = load_library();
-        let wpm = if library.wpm > 0 { library.wpm } else { 300 };
+    fn new(launch: Launch) -> (Self, Command<Message>) {
+        let Launch {
+            mut library,
+            files,
+            hide_on_close,
+        } = launch;
+        let (config, toast) = match load_config() {
+            Ok(config) => {
+                let toast = match config.keys.conflicts().into_iter().next() {
+                    Some(conflict) => Toast::new(ToastLevel::Warning, conflict),
+                    None => Toast::new(
+                        ToastLevel::Info,
+                        "Press I to import a file, O for the library",
+                    ),
+                };
+                (config, toast)
+            }
+            Err(e) => (Config::default(), Toast::new(ToastLevel::Error, e)),
+        };
+        let wpm = if library.settings.wpm > 0 { library.settings.wpm } else { 300 };
+        let wpm = config.clamp_wpm(wpm);
 
+        let dark = is_dark(library.settings.appearance);
+        let geometry = library.settings.window;
+        // Guard against a hand-edited scale that would leave nothing visible
+        let ui_scale = library.settings.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+        library.settings.ui_scale = ui_scale;
         let mut app = Self {
             library,
-            words: Vec::new(),
+            config,
+            words: WordSource::default(),
             word_index: 0,
             current_book_id: None,
             current_book_title: String::new(),
+            language: LanguageProfile::default(),
+            units_per_word: 1.0,
+            display: BookDisplay::default(),
+            profile: PacingProfile::default(),
+            editing_display: None,
             is_playing: false,
             wpm,
-            last_tick: Instant::now(),
-            status_message: Some("Press O to open a file, Space to play/pause".to_string()),
+            clock: WordClock::default(),
+            frame: (0, 0),
+            window_width: geometry.map_or(DEFAULT_WINDOW_WIDTH, |g| g.width) / ui_scale,
+            hide_on_close,
+            import_options: ImportOptions::default(),
+            library_open: false,
+            library_selected: 0,
+            pending_delete: None,
+            renaming: None,
+            adding_to_playlist: None,
+            playlist: None,
+            dark,
+            zen: geometry.is_some_and(|g| g.fullscreen),
+            compact: false,
+            open_menu: None,
+            show_help: false,
+            show_vocabulary: false,
+            show_links: false,
+            show_start: false,
+            details_book: None,
+            show_library_stats: false,
+            show_analysis: false,
+            show_stats: false,
+            finished: false,
+            scrub_resume: None,
+            toasts: vec![toast],
+            hotkeys: GlobalHotKeyManager::new()
+                .inspect_err(|e| tracing::warn!("Global hotkeys are unavailable: {}", e))
+                .ok(),
+            global_hotkey: None,
+            breaks: BreakTimer::default(),
+            micro_pauses: MicroPauseCounter::default(),
+            blank_until: None,
+            chapter_break: None,
+            block_pause: None,
+            footnote: None,
+            night: NightWatch::default(),
+            dimmer: Dimmer::default(),
+            narration: Narration::default(),
+            recent_words: RecentWords::default(),
+            history: JumpHistory::default(),
+            repeat: Repeat::default(),
+            recall: None,
+            speed_test: None,
+            tutorial: None,
+            sessions: SessionTracker::default(),
+            ramp: SpeedRamp::default(),
+            autosave: Autosave::default(),
+            diagnostics: RefCell::default(),
+            show_diagnostics: false,
+            accessibility: Accessibility::new(),
+            media: MediaControls::new(),
+            inbox: Inbox::listen()
+                .inspect_err(|e| tracing::warn!("Not listening for books to open: {}", e))
+                .ok(),
+            library_watch: LibraryWatch::default(),
+            source_watch: SourceWatch::default(),
+            reload_offer: false,
         };
+        app.register_global_hotkey();
+        app.update_night();
 
-        // Load last book if available
-        if let Some(book_id) = app.library.last_book.clone() {
+        // Offer the books in progress, or else carry on with the one read
+        // last
+        if files.is_empty() && !app.library.in_progress().is_empty() {
+            app.show_start = true;
+        } else if let Some(book_id) = app.library.last_read().map(|book| book.id.clone()) {
             app.load_book(&book_id);
         }
+        let opened = if files.is_empty() {
+            Command::none()
+        } else {
+            app.open_paths(files)
+        };
+        app.sync_accessibility();
+        app.sync_media();
 
-        (app, Command::none())
+        let fullscreen = if app.zen {
+            window::change_mode(window::Id::MAIN, window::Mode::Fullscreen)
+        } else {
+            Command::none()
+        };
+        (app, Command::batch([opened, fullscreen]))
     }
 
     fn title(&self) -> String {
@@ -186,44 +579,176 @@ impl Application for RSVPApp {
     fn update(&mut self, message: Message) -> Command<Message> {
         match message {
             Message::Tick => {
+                self.diagnostics.get_mut().tick();
+                self.toasts.retain(|t| t.shown.elapsed() < TOAST_DURATION);
+                let pause = self.chapter_break.as_ref();
+                if pause.is_some_and(ChapterBreak::is_over) {
+                    self.chapter_break = None;
+                }
+                if self.breaks.update(self.is_playing, &self.config.breaks) {
+                    self.is_playing = false;
+                    self.save_progress();
+                }
+                let break_over = self.breaks.remaining(&self.config.breaks) == Some(Duration::ZERO);
+                if break_over && self.config.breaks.auto_resume {
+                    self.finish_break();
+                }
+                self.track_session();
+
+                // The tutorial waits at the end of each lesson for its task
+                if let Some(tutorial) = &mut self.tutorial {
+                    let progress =
+                        tutorial.update(self.word_index, self.wpm, self.is_playing, self.show_help);
+                    match progress {
+                        Progress::Reading => {}
+                        Progress::Waiting => self.is_playing = false,
+                        Progress::Next => {
+                            self.is_playing = true;
+                            self.clock.restart();
+                        }
+                        Progress::Finished => {
+                            self.tutorial = None;
+                            self.info("That's the tutorial. Press I to import a book");
+                        }
+                    }
+                }
+
                 if self.is_playing && !self.words.is_empty() {
-                    let delay = Duration::from_secs_f64(60.0 / self.wpm as f64);
-                    if self.last_tick.elapsed() >= delay {
-                        self.last_tick = Instant::now();
-                        if self.word_index < self.words.len() - 1 {
-                            self.word_index += 1;
-                            if self.word_index % 10 == 0 {
-                                self.save_progress();
+                    let delay = self.current_delay();
+                    let since = self.clock.since();
+                    let advance = self.clock.advance(delay);
+                    if let Advance::Stalled(late) = advance {
+                        self.diagnostics.get_mut().stalled(late);
+                        if self.config.pause_on_stall {
+                            self.is_playing = false;
+                            self.toast(ToastLevel::Warning, "Paused: reading stalled");
+                        }
+                    }
+                    if self.is_playing && advance != Advance::Wait {
+                        self.diagnostics.get_mut().word_shown(delay, since);
+                        // Long words are shown as several frames, one word period each
+                        let frame = self.frame_index();
+                        let (shown, covered) = self.current_unit().unwrap_or_default();
+                        if frame + 1 < self.current_frames().len() {
+                            self.frame = (self.word_index, frame + 1);
+                        } else if self.word_index + covered < self.words.len() {
+                            // Every few words or sentences, blank the screen for a moment
+                            let micro = &self.config.micro_pauses;
+                            if self.micro_pauses.word_shown(&shown, micro) {
+                                let until = Instant::now() + micro.length();
+                                self.blank_until = Some(until);
+                                self.clock.hold_until(until);
                             }
-                        } else {
+                            for word in shown.split(' ') {
+                                self.recent_words.seen(word);
+                            }
+                            let from = self.word_index;
+                            self.word_index += covered;
+                            self.sessions.word_shown(covered);
+                            self.autosave.read(covered);
+                            // A loop starts over once playback runs past its end
+                            if let Some(start) = self.repeat.wrap(from, self.word_index) {
+                                self.next_pass(start);
+                            }
+                            // Code blocks and tables are skipped or shown
+                            // whole, as the book is set to
+                            let mode = self.display.blocks.unwrap_or_default();
+                            match BlockStop::entering(&self.words, from, self.word_index, mode) {
+                                Some(BlockStop::Skip(to)) => self.word_index = to,
+                                Some(BlockStop::Show(pause)) => {
+                                    self.is_playing = false;
+                                    self.block_pause = Some(pause);
+                                }
+                                None => {}
+                            }
+                            // Stop between chapters, for a while or until a key
+                            let settings = &self.config.chapter_breaks;
+                            let crossed = ChapterBreak::crossing(
+                                &self.words,
+                                from,
+                                self.word_index,
+                                settings,
+                            );
+                            if let Some(pause) = crossed {
+                                match pause.until {
+                                    Some(until) => self.clock.hold_until(until),
+                                    None => self.is_playing = false,
+                                }
+                                self.chapter_break = Some(pause);
+                            }
+                        } else if let Some(start) =
+                            self.repeat.wrap(self.word_index, self.words.len())
+                        {
+                            self.next_pass(start);
+                        } else if let Some(test) = &mut self.speed_test {
+                            // The passage is read; on to the questions
+                            self.is_playing = false;
+                            test.reading = false;
+                        } else if self.next_in_playlist() {
+                            // Carried on into the playlist's next item
+                        } else if self.current_book_id.is_some() {
                             self.is_playing = false;
-                            self.status_message = Some("Finished!".to_string());
+                            self.end_session();
                             self.save_progress();
+                            self.finished = true;
+                        } else {
+                            self.is_playing = false;
+                            self.info("Finished!");
                         }
                     }
                 }
+
+                // Save what has been read every so often, and once it stops
+                if self.autosave.due(self.is_playing, &self.config.autosave) {
+                    self.save_progress();
+                }
+
+                let speech = &self.config.speech;
+                let narrated = self.narration.update(
+                    self.is_playing,
+                    &self.words,
+                    self.word_index,
+                    self.wpm,
+                    speech,
+                );
+                if let Err(e) = narrated {
+                    self.toast(ToastLevel::Error, e);
+                }
             }
             Message::TogglePlay => {
-                if !self.words.is_empty() {
+                if self.breaks.on_break() {
+                    self.finish_break();
+                } else if !self.words.is_empty() {
                     if self.word_index >= self.words.len() - 1 {
                         self.word_index = 0;
                     }
                     self.is_playing = !self.is_playing;
-                    self.last_tick = Instant::now();
-                    self.status_message = None;
+                    self.clock.restart();
+                    self.blank_until = None;
+                    if !self.is_playing && self.config.recall.after_pause {
+                        self.start_recall_check();
+                    }
                 }
             }
             Message::SpeedUp => {
-                self.wpm = (self.wpm + 50).min(2000);
-                self.library.wpm = self.wpm;
-                save_library(&self.library);
-                self.status_message = Some(format!("{} WPM", self.wpm));
+                self.wpm = self.config.clamp_wpm(self.wpm + self.config.wpm_step);
+                self.library.settings.set_wpm(self.wpm);
+                self.save_library();
+                self.info(format!("{} WPM", self.wpm));
+            }
+            Message::SpeedPreset(wpm) => {
+                self.wpm = wpm;
+                self.library.settings.set_wpm(wpm);
+                self.save_library();
+                self.info(format!("{} WPM", wpm));
             }
             Message::SpeedDown => {
-                self.wpm = self.wpm.saturating_sub(50).max(50);
-                self.library.wpm = self.wpm;
-                save_library(&self.library);
-                self.status_message = Some(format!("{} WPM", self.wpm));
+                self.wpm = self
+                    .config
+                    .clamp_wpm(self.wpm.saturating_sub(self.config.wpm_step));
+                self.library.settings.set_wpm(self.wpm);
+                self.save_library();
+                self.info(format!("{} WPM", self.wpm));
             }
             Message::PrevWord => {
                 self.is_playing = false;
@@ -235,141 +760,987 @@ impl Application for RSVPApp {
                     self.word_index = (self.word_index + 1).min(self.words.len() - 1);
                 }
             }
+            Message::SentenceStart => {
+                self.is_playing = false;
+                self.jump(self.words.sentence_start(self.word_index));
+            }
+            Message::ParagraphStart => {
+                self.is_playing = false;
+                self.jump(self.words.paragraph_start(self.word_index));
+            }
+            Message::PrevSentence => {
+                self.is_playing = false;
+                self.jump(self.words.prev_sentence(self.word_index));
+            }
+            Message::NextSentence => {
+                self.is_playing = false;
+                self.jump(self.words.next_sentence(self.word_index));
+            }
+            Message::PrevParagraph => {
+                self.is_playing = false;
+                self.jump(self.words.prev_paragraph(self.word_index));
+            }
+            Message::NextParagraph => {
+                self.is_playing = false;
+                self.jump(self.words.next_paragraph(self.word_index));
+            }
+            Message::JumpBack => {
+                self.is_playing = false;
+                match self.history.back(self.word_index) {
+                    Some(index) => self.word_index = index,
+                    None => self.info("No earlier position"),
+                }
+            }
+            Message::JumpForward => {
+                self.is_playing = false;
+                match self.history.forward(self.word_index) {
+                    Some(index) => self.word_index = index,
+                    None => self.info("No later position"),
+                }
+            }
             Message::Reset => {
+                self.finished = false;
                 self.is_playing = false;
-                self.word_index = 0;
+                self.jump(0);
                 self.save_progress();
-                self.status_message = Some("Reset to beginning".to_string());
+                self.info("Reset to beginning");
             }
             Message::OpenFile => {
                 return Command::perform(
                     async {
                         rfd::AsyncFileDialog::new()
-                            .add_filter("Text files", &["txt", "md", "text"])
-                            .pick_file()
+                            .add_filter("Text files", SUPPORTED_EXTENSIONS)
+                            .pick_files()
+                            .await
+                            .unwrap_or_default()
+                            .iter()
+                            .map(|f| f.path().to_path_buf())
+                            .collect()
+                    },
+                    Message::FilesOpened,
+                );
+            }
+            Message::OpenFolder => {
+                return Command::perform(
+                    async {
+                        rfd::AsyncFileDialog::new()
+                            .pick_folder()
                             .await
                             .map(|f| f.path().to_path_buf())
                     },
-                    Message::FileOpened,
+                    Message::FolderOpened,
                 );
             }
-            Message::FileOpened(path) => {
-                if let Some(path) = path {
-                    self.import_file(&path);
+            Message::FolderOpened(dir) => {
+                if let Some(dir) = dir {
+                    let filters = self.import_options.filters(&self.config.pipeline);
+                    let batch =
+                        import_directory(&mut self.library, &dir, &self.import_options, &filters);
+                    self.finish_batch(batch);
                 }
             }
-            Message::KeyPressed(key) => match key.as_ref() {
-                Key::Named(keyboard::key::Named::Space) => {
-                    return self.update(Message::TogglePlay);
+            Message::ToggleTagCollection(enabled) => {
+                self.import_options.tag_collection = enabled;
+            }
+            Message::FilesOpened(paths) => match paths.as_slice() {
+                [] => {}
+                [path] => {
+                    self.import_file(path);
+                }
+                _ => self.import_files(&paths),
+            },
+            Message::ToggleStripCitations(enabled) => {
+                self.import_options.strip_citations = enabled;
+            }
+            Message::ToggleSkipMatter(enabled) => {
+                self.import_options.skip_matter = enabled;
+            }
+            Message::ToggleLibrary => {
+                self.finished = false;
+                self.library_open = !self.library_open;
+                self.pending_delete = None;
+                self.renaming = None;
+                self.adding_to_playlist = None;
+                // Start on the open book
+                self.library_selected = self
+                    .current_book_id
+                    .as_ref()
+                    .and_then(|id| self.library.books.iter().position(|b| b.id == *id))
+                    .unwrap_or(0);
+                return self.scroll_to_selected();
+            }
+            Message::OpenBook(book_id) => {
+                self.finished = false;
+                self.details_book = None;
+                self.show_library_stats = false;
+                // Keep the book being left at the spot it was left at
+                self.save_progress();
+                self.is_playing = false;
+                if self.load_book(&book_id) {
+                    self.info(format!("Opened: {}", self.current_book_title));
+                } else {
+                    self.toast(ToastLevel::Error, "Could not open book");
                 }
-                Key::Named(keyboard::key::Named::ArrowUp) => {
-                    return self.update(Message::SpeedUp);
+                if let Some(i) = self.library.books.iter().position(|b| b.id == book_id) {
+                    self.library_selected = i;
                 }
-                Key::Named(keyboard::key::Named::ArrowDown) => {
-                    return self.update(Message::SpeedDown);
+            }
+            Message::DeleteBook(book_id) => {
+                self.is_playing = false;
+                self.renaming = None;
+                self.pending_delete = Some(book_id);
+            }
+            Message::ConfirmDelete(confirmed) => {
+                if let Some(book_id) = self.pending_delete.take().filter(|_| confirmed) {
+                    self.delete_book(&book_id);
                 }
-                Key::Named(keyboard::key::Named::ArrowLeft) => {
-                    return self.update(Message::PrevWord);
+            }
+            Message::StartRename(book_id) => {
+                self.is_playing = false;
+                self.pending_delete = None;
+                let title = self.library.book(&book_id).map(|b| b.title.clone());
+                if let Some(title) = title {
+                    self.renaming = Some((book_id, title));
+                    let id = text_input::Id::new("rename");
+                    return Command::batch([
+                        text_input::focus(id.clone()),
+                        text_input::select_all(id),
+                    ]);
                 }
-                Key::Named(keyboard::key::Named::ArrowRight) => {
-                    return self.update(Message::NextWord);
+            }
+            Message::RenameInput(title) => {
+                if let Some((_, draft)) = &mut self.renaming {
+                    *draft = title;
                 }
-                Key::Named(keyboard::key::Named::Escape) => {
-                    std::process::exit(0);
+            }
+            Message::FinishRename(save) => {
+                if let Some((book_id, title)) = self.renaming.take().filter(|_| save) {
+                    match self.library.rename_book(&book_id, &title) {
+                        Ok(()) => {
+                            self.save_library();
+                            if self.current_book_id.as_ref() == Some(&book_id) {
+                                self.current_book_title = title.trim().to_string();
+                            }
+                            self.info(format!("Renamed to: {}", title.trim()));
+                        }
+                        Err(e) => self.toast(ToastLevel::Error, e),
+                    }
                 }
-                Key::Character(c) => {
-                    let s: &str = c.as_ref();
-                    match s {
-                        "r" | "R" => return self.update(Message::Reset),
-                        "o" | "O" => return self.update(Message::OpenFile),
-                        " " => return self.update(Message::TogglePlay),
-                        _ => {}
+            }
+            Message::StartAddToPlaylist(book_id) => {
+                self.pending_delete = None;
+                self.renaming = None;
+                // Suggest the playlist last added to
+                let name = self.library.playlists.last().map(|p| p.name.clone());
+                self.adding_to_playlist = Some((book_id, name.unwrap_or_default()));
+                let id = text_input::Id::new("playlist");
+                return Command::batch([text_input::focus(id.clone()), text_input::select_all(id)]);
+            }
+            Message::PlaylistInput(name) => {
+                if let Some((_, draft)) = &mut self.adding_to_playlist {
+                    *draft = name;
+                }
+            }
+            Message::FinishAddToPlaylist(save) => {
+                if let Some((book_id, name)) = self.adding_to_playlist.take().filter(|_| save) {
+                    match self.library.add_to_playlist(&name, &book_id) {
+                        Ok(()) => {
+                            self.save_library();
+                            self.info(format!("Added to {}", name.trim()));
+                        }
+                        Err(e) => self.toast(ToastLevel::Error, e),
+                    }
+                }
+            }
+            Message::PlayPlaylist(name) => {
+                let Some(book_id) = self.library.playlist_start(&name).map(|b| b.id.clone()) else {
+                    return Command::none();
+                };
+                let opened = self.update(Message::OpenBook(book_id.clone()));
+                if self.current_book_id == Some(book_id) {
+                    self.playlist = Some(name);
+                }
+                return opened;
+            }
+            Message::DeletePlaylist(name) => {
+                self.library.remove_playlist(&name);
+                self.save_library();
+                if self.playlist.as_ref() == Some(&name) {
+                    self.playlist = None;
+                }
+                self.info(format!("Deleted playlist: {}", name));
+            }
+            Message::AppearanceSelected(appearance) => {
+                self.library.settings.appearance = appearance;
+                self.dark = is_dark(self.appearance());
+                self.save_library();
+                if self.night.is_night() {
+                    self.info("Night mode keeps the night theme on for now");
+                } else if self.display.appearance.is_some() {
+                    self.info("This book keeps its own theme");
+                }
+            }
+            Message::ToggleZen => {
+                // The overlay is meant to sit beside other windows
+                if self.compact {
+                    return Command::none();
+                }
+                self.zen = !self.zen;
+                self.window_geometry().fullscreen = self.zen;
+                let mode = if self.zen {
+                    window::Mode::Fullscreen
+                } else {
+                    window::Mode::Windowed
+                };
+                return window::change_mode(window::Id::MAIN, mode);
+            }
+            Message::ToggleCompact => {
+                self.compact = !self.compact;
+                let mut commands = Vec::new();
+                let (size, level) = if self.compact {
+                    if self.zen {
+                        self.zen = false;
+                        self.window_geometry().fullscreen = false;
+                        let windowed = window::Mode::Windowed;
+                        commands.push(window::change_mode(window::Id::MAIN, windowed));
+                    }
+                    (COMPACT_WINDOW, window::Level::AlwaysOnTop)
+                } else {
+                    // Back to the size the window had before it shrank
+                    (
+                        window_size(self.library.settings.window),
+                        window::Level::Normal,
+                    )
+                };
+                commands.push(window::resize(window::Id::MAIN, size));
+                commands.push(window::toggle_decorations(window::Id::MAIN));
+                commands.push(window::change_level(window::Id::MAIN, level));
+                return Command::batch(commands);
+            }
+            Message::ScaleUi(step) => {
+                let old = self.library.settings.ui_scale;
+                let scale = ((old + step) / UI_SCALE_STEP).round() * UI_SCALE_STEP;
+                let scale = scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+                // The window holds fewer or more scaled pixels now
+                self.window_width *= old / scale;
+                self.library.settings.ui_scale = scale;
+                self.save_library();
+                self.toast(ToastLevel::Info, format!("UI scale {:.0}%", scale * 100.0));
+            }
+            Message::ColorVisionSelected(vision) => {
+                self.library.settings.color_vision = vision;
+                self.save_library();
+            }
+            Message::CycleProgress => {
+                let unit = self.library.settings.progress_unit.next();
+                self.library.settings.progress_unit = unit;
+                self.save_library();
+            }
+            Message::CloseFinished => self.finished = false,
+            Message::EndChapterBreak => {
+                if self.chapter_break.take().is_some() {
+                    self.is_playing = true;
+                    self.clock.restart();
+                }
+            }
+            Message::EndBlockPause => {
+                if let Some(pause) = self.block_pause.take() {
+                    self.word_index = pause.resume_at;
+                    self.is_playing = true;
+                    self.clock.restart();
+                }
+            }
+            // Without decorations the overlay is moved by dragging the word
+            Message::DragWindow => return window::drag(window::Id::MAIN),
+            Message::ToggleDiagnostics => self.show_diagnostics = !self.show_diagnostics,
+            Message::ToggleHelp => {
+                self.show_help = !self.show_help;
+                if self.show_help {
+                    self.is_playing = false;
+                }
+            }
+            Message::ToggleNarration => {
+                let enabled = !self.config.speech.enabled;
+                self.config.speech.enabled = enabled;
+                self.narration.reset();
+                let state = if enabled { "on" } else { "off" };
+                self.info(format!("Narration {}", state));
+            }
+            Message::ToggleKeywords => {
+                let enabled = !self.config.theme.keywords;
+                self.config.theme.keywords = enabled;
+                let state = if enabled { "on" } else { "off" };
+                self.info(format!("Keyword emphasis {}", state));
+            }
+            Message::ToggleSkim => {
+                let enabled = !self.config.skim.enabled;
+                self.config.skim.enabled = enabled;
+                let state = if enabled { "on" } else { "off" };
+                self.info(format!("Skimming {}", state));
+            }
+            Message::StartRecall => {
+                if !self.start_recall_check() {
+                    self.info("Read for a little longer before a recall check");
+                }
+            }
+            Message::RecallToggle(choice) => {
+                if let Some(check) = &mut self.recall {
+                    check.toggle(choice);
+                }
+            }
+            Message::RecallSubmit => {
+                if let Some(check) = &mut self.recall {
+                    let score = check.submit(self.wpm);
+                    self.library.stats.record_recall(score);
+                    self.save_library();
+                }
+            }
+            Message::RecallClose => self.recall = None,
+            Message::SaveWord => self.save_word(),
+            Message::ShowFootnote => self.show_footnote(),
+            Message::CloseFootnote => {
+                if let Some((_, true)) = self.footnote.take() {
+                    self.is_playing = true;
+                    self.clock.restart();
+                }
+            }
+            Message::CyclePacing => self.cycle_pacing(),
+            Message::StartBookDisplay => {
+                if self.current_book_id.is_none() {
+                    self.info("No book loaded");
+                    return Command::none();
+                }
+                self.is_playing = false;
+                self.editing_display = Some(self.display.to_string());
+                let id = text_input::Id::new("book_display");
+                return Command::batch([text_input::focus(id.clone()), text_input::select_all(id)]);
+            }
+            Message::BookDisplayInput(input) => {
+                if let Some(draft) = &mut self.editing_display {
+                    *draft = input;
+                }
+            }
+            Message::FinishBookDisplay(save) => {
+                if let Some(draft) = self.editing_display.take().filter(|_| save) {
+                    self.save_book_display(draft);
+                }
+            }
+            Message::ToggleLoop => self.toggle_loop(),
+            Message::MarkLoop => self.mark_loop(),
+            Message::ToggleVocabulary => {
+                self.show_vocabulary = !self.show_vocabulary;
+                if self.show_vocabulary {
+                    self.is_playing = false;
+                }
+            }
+            Message::ToggleLinks => {
+                self.show_links = !self.show_links;
+                if self.show_links {
+                    self.is_playing = false;
+                }
+            }
+            Message::CloseStart => self.show_start = false,
+            Message::ToggleStart => {
+                if self.show_start {
+                    self.show_start = false;
+                } else if self.library.in_progress().is_empty() {
+                    self.info("No books in progress");
+                } else {
+                    self.is_playing = false;
+                    self.show_start = true;
+                }
+            }
+            Message::ShowDetails(book_id) => {
+                self.is_playing = false;
+                self.details_book = Some(book_id);
+            }
+            Message::CloseDetails => self.details_book = None,
+            Message::ToggleLibraryStats => {
+                self.show_library_stats = !self.show_library_stats;
+                if self.show_library_stats {
+                    // Count the session under way
+                    self.is_playing = false;
+                    self.end_session();
+                    self.save_library();
+                }
+            }
+            Message::OpenLink(url) => match open_in_browser(&url) {
+                Ok(()) => self.info(format!("Opened {}", url)),
+                Err(e) => self.toast(ToastLevel::Error, format!("Couldn't open {}: {}", url, e)),
+            },
+            Message::ExportVocabulary => {
+                let book = self
+                    .current_book_id
+                    .as_ref()
+                    .and_then(|id| self.library.book(id));
+                match book.map(export_vocabulary) {
+                    Some(Ok(path)) => self.info(format!("Exported to {}", path.display())),
+                    Some(Err(e)) => self.toast(ToastLevel::Error, format!("Export failed: {}", e)),
+                    None => {}
+                }
+            }
+            Message::ToggleAnalysis => {
+                if self.show_analysis {
+                    self.show_analysis = false;
+                } else if let Some(book) = self
+                    .current_book_id
+                    .clone()
+                    .and_then(|id| self.library.book_mut(&id))
+                {
+                    if fill_analysis(book) {
+                        self.save_library();
                     }
+                    self.show_analysis = true;
+                    self.is_playing = false;
+                } else {
+                    self.info("No book loaded");
                 }
-                _ => {}
+            }
+            Message::ToggleStats => {
+                if self.show_stats {
+                    self.show_stats = false;
+                } else if self.current_book_id.is_some() {
+                    self.is_playing = false;
+                    self.end_session();
+                    self.save_library();
+                    self.show_stats = true;
+                } else {
+                    self.info("No book loaded");
+                }
+            }
+            Message::StartSpeedTest => self.start_speed_test(),
+            Message::StartTutorial => self.start_tutorial(),
+            Message::SpeedTestAnswer(choice) => {
+                let result = self
+                    .speed_test
+                    .as_mut()
+                    .and_then(|test| test.answer(choice));
+                if let Some(result) = result {
+                    self.library.stats.record_speed_test(result);
+                    self.save_library();
+                }
+            }
+            Message::SpeedTestClose => self.end_speed_test(),
+            Message::ExportAllVocabulary => match export_all_vocabulary(&self.library) {
+                Ok(path) => self.info(format!("Exported every book's words to {}", path.display())),
+                Err(e) => self.toast(ToastLevel::Error, format!("Export failed: {}", e)),
             },
-        }
-        Command::none()
-    }
+            Message::ReloadConfig => self.reload_config(),
+            Message::Quit => {
+                self.end_session();
+                self.save_progress();
+                // Nothing else saved the window's place without a book open
+                if self.current_book_id.is_none() {
+                    self.save_library();
+                }
+                self.narration.stop();
+                std::process::exit(0);
+            }
+            Message::ToggleMenu(menu) => {
+                self.open_menu = (self.open_menu != Some(menu)).then_some(menu);
+            }
+            Message::MenuPick(message) => {
+                self.open_menu = None;
+                return self.update(*message);
+            }
+            Message::Scrub(position) => {
+                if self.scrub_resume.is_none() {
+                    self.scrub_resume = Some(self.is_playing);
+                    self.is_playing = false;
+                    self.history.record(self.word_index);
+                }
+                if !self.words.is_empty() {
+                    self.word_index = (position as usize).min(self.words.len() - 1);
+                }
+            }
+            Message::ScrubReleased => {
+                if let Some(resume) = self.scrub_resume.take() {
+                    self.is_playing = resume;
+                    self.clock.restart();
+                }
+                self.save_progress();
+            }
+            Message::WindowResized(size) => {
+                self.window_width = size.width;
+                // Fullscreen and overlay sizes aren't worth going back to
+                // Sizes come in scaled pixels, and the window is made in
+                // unscaled ones
+                if !self.zen && !self.compact {
+                    let scale = self.library.settings.ui_scale;
+                    let geometry = self.window_geometry();
+                    geometry.width = size.width * scale;
+                    geometry.height = size.height * scale;
+                }
+            }
+            Message::WindowMoved(x, y) => {
+                if !self.zen && !self.compact {
+                    let scale = self.library.settings.ui_scale;
+                    let (x, y) = ((x as f32 * scale) as i32, (y as f32 * scale) as i32);
+                    self.window_geometry().position = Some((x, y));
+                }
+            }
+            Message::WindowCloseRequested => {
+                if !self.hide_on_close {
+                    return self.update(Message::Quit);
+                }
+                self.is_playing = false;
+                self.save_progress();
+                return window::change_mode(window::Id::MAIN, window::Mode::Hidden);
+            }
+            Message::GlobalHotkeyPoll => {
+                let pressed = GlobalHotKeyEvent::receiver().try_iter().any(|event| {
+                    event.state == HotKeyState::Pressed
+                        && self.global_hotkey.is_some_and(|key| key.id() == event.id)
+                });
+                if pressed {
+                    return self.update(Message::TogglePlay);
+                }
+            }
+            Message::WindowFocused(focused) => self.accessibility.focus_changed(focused),
+            Message::AccessibilityPoll => {
+                let commands: Vec<_> = self
+                    .accessibility
+                    .poll()
+                    .into_iter()
+                    .map(|action| {
+                        self.update(match action {
+                            A11yAction::PlayPause => Message::TogglePlay,
+                            A11yAction::SpeedUp => Message::SpeedUp,
+                            A11yAction::SpeedDown => Message::SpeedDown,
+                            A11yAction::PrevWord => Message::PrevWord,
+                            A11yAction::NextWord => Message::NextWord,
+                        })
+                    })
+                    .collect();
+                self.sync_accessibility();
+                return Command::batch(commands);
+            }
+            Message::MediaPoll => {
+                let actions = self
+                    .media
+                    .as_ref()
+                    .map(MediaControls::poll)
+                    .unwrap_or_default();
+                let commands: Vec<_> = actions
+                    .into_iter()
+                    .map(|action| self.media_action(action))
+                    .collect();
+                self.sync_media();
+                return Command::batch(commands);
+            }
+            Message::NightPoll => self.update_night(),
+            Message::LibraryPoll => self.sync_library(),
+            Message::SourcePoll => {
+                if self.source_watch.changed() && self.current_book_id.is_some() {
+                    self.reload_offer = true;
+                    self.is_playing = false;
+                    self.save_progress();
+                }
+            }
+            Message::ReloadBook => self.reload_book(),
+            Message::DismissReload => self.reload_offer = false,
+            Message::Wake => self.dimmer.wake(),
+            Message::InboxPoll => {
+                let requests = self.inbox.as_ref().map(Inbox::poll).unwrap_or_default();
+                let commands: Vec<_> = requests
+                    .into_iter()
+                    .map(|request| self.inbox_request(request))
+                    .collect();
+                return Command::batch(commands);
+            }
+            #[cfg(feature = "tray")]
+            Message::TrayPoll => {
+                let commands: Vec<_> = tray::poll()
+                    .into_iter()
+                    .map(|action| self.tray_action(action))
+                    .collect();
+                return Command::batch(commands);
+            }
+            Message::KeyPressed(key, modifiers) => {
+                let action =
+                    key_chord(&key, modifiers).and_then(|key| self.config.keys.action(&key));
+                self.dimmer.wake();
 
-    fn view(&self) -> Element<Message> {
-        let progress = if self.words.is_empty() {
-            0.0
-        } else {
-            (self.word_index as f32 / self.words.len() as f32) * 100.0
-        };
+                // Any key ends a break
+                if self.breaks.on_break() {
+                    self.finish_break();
+                    return Command::none();
+                }
 
-        // Word display with ORP highlighting
-        // The ORP character must stay at a fixed center position so eyes don't move
-        let word_display: Element<Message> = if let Some(word) = self.words.get(self.word_index) {
-            let orp = calculate_orp(word);
-            let chars: Vec<char> = word.chars().collect();
-            let font_size = 72;
-            // Approximate character width for monospace-like centering
-            let char_width = font_size as f32 * 0.6;
+                // Any key puts a footnote away, carrying on if reading was
+                // playing
+                if self.footnote.is_some() {
+                    return self.update(Message::CloseFootnote);
+                }
 
-            // Build left part (before ORP)
-            let mut left_row = row![];
-            for (i, ch) in chars.iter().enumerate() {
-                if i < orp {
-                    left_row = left_row.push(
-                        text(ch.to_string())
-                            .size(font_size)
-                            .style(Color::from_rgb(0.9, 0.9, 0.9))
-                            .font(Font::MONOSPACE),
-                    );
+                // Any key puts a shown block away and reads on past it
+                if self.block_pause.is_some() {
+                    return self.update(Message::EndBlockPause);
                 }
-            }
 
-            // Build ORP character (centered)
-            let orp_char = chars.get(orp).map(|ch| {
-                text(ch.to_string())
-                    .size(font_size)
-                    .style(Color::from_rgb(0.9, 0.2, 0.2))
-                    .font(Font::MONOSPACE)
-            });
+                // Any key ends a chapter break and carries on reading
+                if self.chapter_break.is_some() {
+                    return self.update(Message::EndChapterBreak);
+                }
 
-            // Build right part (after ORP)
-            let mut right_row = row![];
-            for (i, ch) in chars.iter().enumerate() {
-                if i > orp {
-                    right_row = right_row.push(
-                        text(ch.to_string())
-                            .size(font_size)
-                            .style(Color::from_rgb(0.9, 0.9, 0.9))
-                            .font(Font::MONOSPACE),
-                    );
+                // Any key brings the chrome back; leaving zen mode is all
+                // that the zen key and Escape do there
+                if self.zen {
+                    let restore = self.update(Message::ToggleZen);
+                    if matches!(action, Some(Action::ToggleZen | Action::Quit)) {
+                        return restore;
+                    }
+                    let handled = self.update(Message::KeyPressed(key, modifiers));
+                    return Command::batch([restore, handled]);
                 }
-            }
 
-            // Calculate padding to keep ORP centered
-            // Left side needs enough space + its characters, right side balances it
-            let left_chars = orp;
-            let right_chars = chars.len().saturating_sub(orp + 1);
-            let max_chars = left_chars.max(right_chars);
+                // A recall check takes the number keys, Enter and Escape,
+                // and any key once it has been answered
+                if let Some(check) = &mut self.recall {
+                    let answered = check.result.is_some();
+                    match key.as_ref() {
+                        Key::Character(c) if !answered => {
+                            if let Ok(n @ 1..=9) = c.parse::<usize>() {
+                                check.toggle(n - 1);
+                            }
+                        }
+                        Key::Named(keyboard::key::Named::Enter) if !answered => {
+                            return self.update(Message::RecallSubmit);
+                        }
+                        Key::Named(keyboard::key::Named::Escape) => self.recall = None,
+                        _ if answered => self.recall = None,
+                        _ => {}
+                    }
+                    return Command::none();
+                }
 
-            let left_padding = ((max_chars - left_chars) as f32 * char_width) as u16;
-            let right_padding = ((max_chars - right_chars) as f32 * char_width) as u16;
+                // A speed test plays its passage through, then takes the
+                // number keys for answers and any key once it is over.
+                // Escape cancels it.
+                if let Some(test) = &self.speed_test {
+                    let answering = !test.reading && test.result.is_none();
+                    match key.as_ref() {
+                        Key::Named(keyboard::key::Named::Escape) => {
+                            self.end_speed_test();
+                            self.info("Speed test cancelled");
+                        }
+                        Key::Character(c) if answering => {
+                            if let Ok(n @ 1..=4) = c.parse::<usize>() {
+                                return self.update(Message::SpeedTestAnswer(n - 1));
+                            }
+                        }
+                        _ if test.result.is_some() => self.end_speed_test(),
+                        _ => {}
+                    }
+                    return Command::none();
+                }
 
-            let word_row = row![
-                Space::with_width(Length::Fixed(left_padding as f32)),
-                container(left_row).width(Length::Fixed((left_chars as f32 * char_width) as f32)),
-            ]
-            .push(orp_char.unwrap_or_else(|| text("".to_string()).size(font_size).font(Font::MONOSPACE)))
-            .push(container(right_row).width(Length::Fixed((right_chars as f32 * char_width) as f32)))
-            .push(Space::with_width(Length::Fixed(right_padding as f32)));
+                // Typing goes to the book settings field; Escape gives up
+                // on the changes
+                if self.editing_display.is_some() {
+                    if matches!(key.as_ref(), Key::Named(keyboard::key::Named::Escape)) {
+                        return self.update(Message::FinishBookDisplay(false));
+                    }
+                    return Command::none();
+                }
 
-            container(word_row)
-                .width(Length::Fill)
+                if self.details_book.is_some()
+                    && matches!(key.as_ref(), Key::Named(keyboard::key::Named::Escape))
+                {
+                    return self.update(Message::CloseDetails);
+                }
+                if self.show_library_stats
+                    && matches!(key.as_ref(), Key::Named(keyboard::key::Named::Escape))
+                {
+                    return self.update(Message::ToggleLibraryStats);
+                }
+
+                // The start screen opens a book by its number and carries on
+                // with the first on Enter. Escape closes it, and other keys
+                // work as usual.
+                if self.show_start {
+                    let pick = match key.as_ref() {
+                        Key::Named(keyboard::key::Named::Enter) => Some(0),
+                        Key::Named(keyboard::key::Named::Escape) => {
+                            self.show_start = false;
+                            return Command::none();
+                        }
+                        Key::Character(c) => match c.parse::<usize>() {
+                            Ok(n @ 1..=9) => Some(n - 1),
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+                    let in_progress = self.library.in_progress();
+                    let book_id = pick.and_then(|i| in_progress.get(i).map(|b| b.id.clone()));
+                    if let Some(book_id) = book_id {
+                        return self.update(Message::OpenBook(book_id));
+                    }
+                }
+
+                if self.open_menu.is_some() && matches!(action, Some(Action::Quit)) {
+                    self.open_menu = None;
+                    return Command::none();
+                }
+                if self.show_help && matches!(action, Some(Action::Help | Action::Quit)) {
+                    self.show_help = false;
+                    return Command::none();
+                }
+                if self.show_vocabulary && matches!(action, Some(Action::Vocabulary | Action::Quit))
+                {
+                    self.show_vocabulary = false;
+                    return Command::none();
+                }
+                if self.show_links && matches!(action, Some(Action::Links | Action::Quit)) {
+                    self.show_links = false;
+                    return Command::none();
+                }
+                if self.show_analysis && matches!(action, Some(Action::Analysis | Action::Quit)) {
+                    self.show_analysis = false;
+                    return Command::none();
+                }
+                if self.show_stats && matches!(action, Some(Action::Stats | Action::Quit)) {
+                    self.show_stats = false;
+                    return Command::none();
+                }
+                if self.finished && action == Some(Action::Quit) {
+                    self.finished = false;
+                    return Command::none();
+                }
+
+                if self.library_open {
+                    if let Some(command) = self.library_key(&key) {
+                        return command;
+                    }
+                }
+
+                match action {
+                    Some(Action::PlayPause) => return self.update(Message::TogglePlay),
+                    Some(Action::SpeedUp) => return self.update(Message::SpeedUp),
+                    Some(Action::SpeedDown) => return self.update(Message::SpeedDown),
+                    Some(Action::PrevWord) => return self.update(Message::PrevWord),
+                    Some(Action::NextWord) => return self.update(Message::NextWord),
+                    Some(Action::SentenceStart) => return self.update(Message::SentenceStart),
+                    Some(Action::ParagraphStart) => return self.update(Message::ParagraphStart),
+                    Some(Action::PrevSentence) => return self.update(Message::PrevSentence),
+                    Some(Action::NextSentence) => return self.update(Message::NextSentence),
+                    Some(Action::PrevParagraph) => return self.update(Message::PrevParagraph),
+                    Some(Action::NextParagraph) => return self.update(Message::NextParagraph),
+                    Some(Action::JumpBack) => return self.update(Message::JumpBack),
+                    Some(Action::JumpForward) => return self.update(Message::JumpForward),
+                    Some(Action::Reset) => return self.update(Message::Reset),
+                    Some(Action::Library) => return self.update(Message::ToggleLibrary),
+                    Some(Action::Playlists) if !self.library_open => {
+                        return self.update(Message::ToggleLibrary)
+                    }
+                    Some(Action::Import) => return self.update(Message::OpenFile),
+                    Some(Action::CycleTheme) => {
+                        let appearance = self.library.settings.appearance.next();
+                        return self.update(Message::AppearanceSelected(appearance));
+                    }
+                    Some(Action::CycleColorVision) => {
+                        let vision = self.library.settings.color_vision.next();
+                        self.info(format!("Palette: {}", vision));
+                        return self.update(Message::ColorVisionSelected(vision));
+                    }
+                    Some(Action::CycleProgress) => return self.update(Message::CycleProgress),
+                    Some(Action::CyclePacing) => return self.update(Message::CyclePacing),
+                    Some(Action::BookDisplay) => return self.update(Message::StartBookDisplay),
+                    Some(Action::ToggleZen) => return self.update(Message::ToggleZen),
+                    Some(Action::ToggleCompact) => return self.update(Message::ToggleCompact),
+                    Some(Action::ScaleUp) => return self.update(Message::ScaleUi(UI_SCALE_STEP)),
+                    Some(Action::ScaleDown) => {
+                        return self.update(Message::ScaleUi(-UI_SCALE_STEP))
+                    }
+                    Some(Action::ToggleDebug) => return self.update(Message::ToggleDiagnostics),
+                    Some(Action::Narrate) => return self.update(Message::ToggleNarration),
+                    Some(Action::ToggleSkim) => return self.update(Message::ToggleSkim),
+                    Some(Action::ToggleKeywords) => return self.update(Message::ToggleKeywords),
+                    Some(Action::RecallCheck) => return self.update(Message::StartRecall),
+                    Some(Action::SaveWord) => return self.update(Message::SaveWord),
+                    Some(Action::ShowFootnote) => return self.update(Message::ShowFootnote),
+                    Some(Action::ToggleLoop) => return self.update(Message::ToggleLoop),
+                    Some(Action::MarkLoop) => return self.update(Message::MarkLoop),
+                    Some(Action::Vocabulary) => return self.update(Message::ToggleVocabulary),
+                    Some(Action::Links) => return self.update(Message::ToggleLinks),
+                    Some(Action::Analysis) => return self.update(Message::ToggleAnalysis),
+                    Some(Action::Stats) => return self.update(Message::ToggleStats),
+                    Some(Action::SpeedTest) => return self.update(Message::StartSpeedTest),
+                    Some(Action::Help) => return self.update(Message::ToggleHelp),
+                    Some(Action::Recents) => return self.update(Message::ToggleStart),
+                    Some(Action::ReloadConfig) => return self.update(Message::ReloadConfig),
+                    Some(Action::Quit) => return self.update(Message::Quit),
+                    // Number keys not bound to anything else pick a speed preset
+                    None => {
+                        let preset = match key.as_ref() {
+                            Key::Character(c) => {
+                                c.chars().next().and_then(|c| self.config.speed_preset(c))
+                            }
+                            _ => None,
+                        };
+                        if let Some(wpm) = preset {
+                            return self.update(Message::SpeedPreset(wpm));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Command::none()
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let building = Instant::now();
+        let palette = self.palette();
+        let progress = if self.words.is_empty() {
+            0.0
+        } else {
+            (self.word_index as f32 / self.words.len() as f32) * 100.0
+        };
+
+        // Word display with ORP highlighting
+        // The ORP character must stay at a fixed center position so eyes don't move
+        let word_display: Element<Message> = if self.blanking() {
+            Space::new(Length::Fill, Length::Fill).into()
+        } else if let Some(word) = self.current_frame() {
+            let (before, orp_grapheme, after) = split_at_orp(&word);
+            let font_size = self.word_font_size();
+            let char_width = font_size as f32 * CHAR_WIDTH_RATIO;
+
+            let keywords = self.config.theme.keywords;
+            let case = self.config.theme.letter_case;
+            let dialogue = self.dialogue();
+            let orp_grapheme = case.apply(orp_grapheme);
+            let orp_start = before.len();
+            let orp_end = word.len() - after.len();
+
+            // Build left part (before ORP)
+            let left_row = word_part(
+                &word,
+                0..orp_start,
+                &palette,
+                keywords,
+                case,
+                dialogue,
+                font_size,
+            );
+
+            // Build ORP character (centered), with the configured emphasis
+            let emphasis = &self.config.theme;
+            let orp_font = if emphasis.focus_has(FocusStyle::Bold) {
+                Font {
+                    weight: font::Weight::Bold,
+                    ..Font::MONOSPACE
+                }
+            } else {
+                Font::MONOSPACE
+            };
+            let orp_text = text(&orp_grapheme)
+                .size(font_size)
+                .style(palette.focus)
+                .font(orp_font);
+            let orp_width = display_width(&orp_grapheme).max(1) as f32 * char_width;
+            let orp_char: Element<Message> = if emphasis.focus_has(FocusStyle::Underline) {
+                let underline = container(Space::new(Length::Fixed(orp_width), Length::Fixed(4.0)))
+                    .style(theme::Container::Custom(Box::new(AppContainer {
+                        background: palette.focus,
+                        text: palette.focus,
+                    })));
+                column![orp_text, underline].into()
+            } else {
+                orp_text.into()
+            };
+
+            // Build right part (after ORP)
+            let right_row = word_part(
+                &word,
+                orp_end..word.len(),
+                &palette,
+                keywords,
+                case,
+                dialogue,
+                font_size,
+            );
+            let sentence_end = self.config.theme.sentence_end;
+            let at_sentence_end = sentence_end != SentenceEnd::None && self.at_sentence_end();
+            // A dim mark a space after a footnote reference, the last word of
+            // a line of verse, or of a sentence
+            let marked = at_sentence_end && sentence_end == SentenceEnd::Mark;
+            let mark = if self.at_footnote() {
+                Some("†")
+            } else if self.at_line_end() {
+                Some("↵")
+            } else {
+                marked.then_some("¶")
+            };
+            let right_row: Element<Message> = match mark {
+                Some(mark) => {
+                    let mark = text(mark).size(font_size).style(palette.hint);
+                    row![right_row, Space::with_width(char_width), mark].into()
+                }
+                None => right_row,
+            };
+
+            // Pin the ORP character to a fixed cell in the middle of the window.
+            // Both sides split the remaining width evenly, the text before it
+            // right-aligned and the text after left-aligned, so the focal
+            // letter stays put no matter how long either side is
+            let word_row = row![
+                container(left_row)
+                    .width(Length::Fill)
+                    .align_x(alignment::Horizontal::Right),
+                container(orp_char)
+                    .width(Length::Fixed(orp_width))
+                    .center_x(),
+                container(right_row).width(Length::Fill),
+            ];
+
+            let guide_width = self.window_width * 0.6;
+            let guide_color = if at_sentence_end && sentence_end == SentenceEnd::Guides {
+                palette.focus
+            } else {
+                palette.guide
+            };
+            let guided = focal_guide(
+                self.config.theme.guide,
+                word_row.into(),
+                guide_color,
+                guide_width,
+            );
+
+            container(guided)
+                .width(Length::Fill)
                 .height(Length::Fill)
                 .center_x()
                 .center_y()
                 .into()
+        } else if self.library.books.is_empty() {
+            // First launch: offer the tutorial
+            container(
+                column![
+                    text("Press I to import a file")
+                        .size(32)
+                        .style(palette.hint),
+                    text("New to RSVP? Take a one-minute tour of the controls")
+                        .size(16)
+                        .style(palette.stats),
+                    button(text("Start the tutorial"))
+                        .on_press(Message::StartTutorial)
+                        .padding(10),
+                ]
+                .spacing(16)
+                .align_items(iced::Alignment::Center),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
         } else {
             container(
-                text("Press O to open a file")
+                text("Press I to import a file")
                     .size(32)
-                    .style(Color::from_rgb(0.5, 0.5, 0.5)),
+                    .style(palette.hint),
             )
             .width(Length::Fill)
             .height(Length::Fill)
@@ -378,25 +1749,129 @@ impl Application for RSVPApp {
             .into()
         };
 
+        let word_display = if self.breaks.on_break() {
+            self.break_panel(palette)
+        } else if let Some(check) = &self.recall {
+            self.recall_panel(check, palette)
+        } else if let Some(test) = self.speed_test.as_ref().filter(|test| !test.reading) {
+            self.speed_test_panel(test, palette)
+        } else if let Some(draft) = &self.editing_display {
+            self.book_display_panel(draft, palette)
+        } else if self.show_help {
+            self.help_panel(palette)
+        } else if let Some(book) = self
+            .details_book
+            .as_ref()
+            .and_then(|id| self.library.book(id))
+        {
+            self.book_details_panel(book, palette)
+        } else if self.show_library_stats {
+            self.library_stats_panel(palette)
+        } else if self.show_start {
+            self.start_panel(palette)
+        } else if self.show_vocabulary {
+            self.vocabulary_panel(palette)
+        } else if self.show_links {
+            self.links_panel(palette)
+        } else if self.show_analysis {
+            self.analysis_panel(palette)
+        } else if self.show_stats {
+            self.stats_panel(palette)
+        } else if self.finished {
+            self.finished_panel(palette)
+        } else if let Some((note, _)) = &self.footnote {
+            self.footnote_panel(note, palette)
+        } else if let Some(pause) = &self.block_pause {
+            self.block_pause_panel(pause, palette)
+        } else if let Some(pause) = &self.chapter_break {
+            self.chapter_break_panel(pause, palette)
+        } else {
+            word_display
+        };
+
+        // The tutorial's instructions, while it waits for them to be followed
+        let hint = self
+            .tutorial
+            .as_ref()
+            .and_then(|tutorial| tutorial.hint(&self.config.keys));
+        let word_display = match hint {
+            Some(hint) => column![
+                word_display,
+                container(text(hint).size(22).style(palette.focus))
+                    .width(Length::Fill)
+                    .padding(10)
+                    .center_x(),
+            ]
+            .into(),
+            None => word_display,
+        };
+
+        // The open book's file changed while it was being read
+        let word_display = if self.reload_offer {
+            let offer = row![
+                text(format!("'{}' changed on disk", self.current_book_title))
+                    .size(16)
+                    .style(palette.focus),
+                button(text("Reload")).on_press(Message::ReloadBook),
+                button(text("Dismiss")).on_press(Message::DismissReload),
+            ]
+            .spacing(10)
+            .align_items(iced::Alignment::Center);
+            column![
+                container(offer).width(Length::Fill).padding(10).center_x(),
+                word_display,
+            ]
+            .into()
+        } else {
+            word_display
+        };
+
         // Stats bar
         let status = if self.is_playing { "▶ Playing" } else { "⏸ Paused" };
-        let stats_text = format!(
-            "WPM: {}  │  Word: {}/{}  │  Progress: {:.1}%  │  {}",
-            self.wpm,
-            self.word_index + 1,
-            self.words.len().max(1),
-            progress,
-            status
-        );
+        let fields = self.stats_fields(status);
+        let stats_text = if let Some(format) = &self.config.stats_format {
+            fields.fill(format)
+        } else {
+            let speed = fields.speed(self.config.adaptive.enabled);
+            let mut stats_text = format!("{}  │  {}  │  {}", speed, fields.progress(), status);
+            if self.config.breaks.enabled {
+                stats_text += &format!("  │  Breaks: {}", self.library.stats.break_summary());
+            }
+            if let Some(percent) = self.library.stats.recent_recall() {
+                stats_text += &format!("  │  Recall: {}%", percent);
+            }
+            if let Some(repeat) = self.repeat.summary() {
+                stats_text += &format!("  │  Loop: {}", repeat);
+            }
+            stats_text
+        };
 
         let stats_bar = container(
             text(stats_text)
                 .size(16)
-                .style(Color::from_rgb(0.6, 0.6, 0.6)),
+                .style(palette.stats),
         )
         .width(Length::Fill)
         .padding(10)
         .center_x();
+        let stats_bar: Element<Message> = if self.show_diagnostics {
+            column![stats_bar, self.diagnostics_panel(palette)].into()
+        } else {
+            stats_bar.into()
+        };
+
+        // Progress scrubber
+        let scrubber = container(
+            slider(
+                0.0..=self.words.len().saturating_sub(1) as f64,
+                self.word_index as f64,
+                Message::Scrub,
+            )
+            .on_release(Message::ScrubReleased)
+            .step(1.0),
+        )
+        .width(Length::Fill)
+        .padding([0, 40]);
 
         // Control buttons
         let controls = row![
@@ -411,7 +1886,37 @@ impl Application for RSVPApp {
             text(format!("{} WPM", self.wpm)).size(16),
             button(text("+").size(20)).on_press(Message::SpeedUp).padding(10),
             Space::with_width(20),
+            button(text("Library").size(16))
+                .on_press(Message::ToggleLibrary)
+                .padding(10),
             button(text("Open").size(16)).on_press(Message::OpenFile).padding(10),
+            button(text("Folder").size(16))
+                .on_press(Message::OpenFolder)
+                .padding(10),
+            column![
+                checkbox("Strip citations", self.import_options.strip_citations)
+                    .on_toggle(Message::ToggleStripCitations)
+                    .text_size(14),
+                checkbox("Tag as collection", self.import_options.tag_collection)
+                    .on_toggle(Message::ToggleTagCollection)
+                    .text_size(14),
+                checkbox("Skip front/back matter", self.import_options.skip_matter)
+                    .on_toggle(Message::ToggleSkipMatter)
+                    .text_size(14),
+            ]
+            .spacing(4),
+            pick_list(
+                &Appearance::ALL[..],
+                Some(self.library.settings.appearance),
+                Message::AppearanceSelected,
+            )
+            .text_size(14),
+            pick_list(
+                &ColorVision::ALL[..],
+                Some(self.library.settings.color_vision),
+                Message::ColorVisionSelected,
+            )
+            .text_size(14),
         ]
         .spacing(10)
         .align_items(iced::Alignment::Center);
@@ -421,149 +1926,3547 @@ impl Application for RSVPApp {
             .padding(15)
             .center_x();
 
-        // Status message
-        let status_bar = if let Some(msg) = &self.status_message {
-            container(text(msg).size(14).style(Color::from_rgb(0.7, 0.7, 0.3)))
-                .width(Length::Fill)
-                .padding(5)
-                .center_x()
-        } else {
-            container(text("").size(14))
-                .width(Length::Fill)
-                .padding(5)
-        };
+        // Toasts, stacked oldest first
+        let mut toasts = column![].spacing(2).align_items(iced::Alignment::Center);
+        for toast in &self.toasts {
+            let color = match toast.level {
+                ToastLevel::Info => palette.info,
+                ToastLevel::Warning => palette.warning,
+                ToastLevel::Error => palette.error,
+            };
+            toasts = toasts.push(text(&toast.text).size(14).style(color));
+        }
+        if self.toasts.is_empty() {
+            toasts = toasts.push(text("").size(14));
+        }
+        let status_bar = container(toasts).width(Length::Fill).padding(5).center_x();
 
         // Main layout
-        let content = column![
-            stats_bar,
-            word_display,
-            controls_bar,
-            status_bar,
-        ]
-        .spacing(0);
+        let content: Element<Message> = if self.zen {
+            column![word_display].into()
+        } else if self.compact {
+            let strip = column![
+                word_display,
+                progress_bar(0.0..=100.0, progress).height(Length::Fixed(4.0)),
+            ];
+            mouse_area(strip).on_press(Message::DragWindow).into()
+        } else {
+            let reader = column![
+                stats_bar,
+                word_display,
+                scrubber,
+                controls_bar,
+                status_bar,
+            ]
+            .spacing(0);
+            let body: Element<Message> = if self.library_open {
+                row![self.library_panel(palette), reader].into()
+            } else {
+                reader.into()
+            };
+            column![self.menu_bar(palette), body].into()
+        };
 
-        container(content)
+        let view = container(content)
             .width(Length::Fill)
             .height(Length::Fill)
-            .style(theme::Container::Custom(Box::new(DarkContainer)))
-            .into()
+            .style(theme::Container::Custom(Box::new(AppContainer {
+                background: palette.background,
+                text: palette.text,
+            })))
+            .into();
+        self.diagnostics.borrow_mut().rendered(building.elapsed());
+        view
     }
 
     fn subscription(&self) -> Subscription<Message> {
-        let tick = if self.is_playing {
-            time::every(Duration::from_millis(10)).map(|_| Message::Tick)
+        // Ticks come as often as the speed needs while playing. Toasts and
+        // break countdowns still need them while paused, and narration
+        // needs one soon after pausing to stop the voice, as does saving
+        // the reading done.
+        let tick = if self.is_playing && !self.words.is_empty() {
+            time::every(tick_interval(self.fastest_wpm())).map(|_| Message::Tick)
+        } else if self.narration.speaking() {
+            time::every(Duration::from_millis(100)).map(|_| Message::Tick)
+        } else if self.autosave.pending() {
+            // One more to save what was read before the pause
+            time::every(Duration::from_millis(250)).map(|_| Message::Tick)
+        } else if !self.toasts.is_empty() || self.breaks.on_break() || self.chapter_break.is_some()
+        {
+            time::every(Duration::from_millis(250)).map(|_| Message::Tick)
+        } else {
+            Subscription::none()
+        };
+
+        let keys =
+            keyboard::on_key_press(|key, modifiers| Some(Message::KeyPressed(key, modifiers)));
+
+        let window_events = event::listen_with(|event, _status| match event {
+            Event::Window(_, window::Event::Resized { width, height }) => Some(
+                Message::WindowResized(iced::Size::new(width as f32, height as f32)),
+            ),
+            Event::Window(_, window::Event::Moved { x, y }) => Some(Message::WindowMoved(x, y)),
+            Event::Window(_, window::Event::CloseRequested) => Some(Message::WindowCloseRequested),
+            Event::Window(_, window::Event::Focused) => Some(Message::WindowFocused(true)),
+            Event::Window(_, window::Event::Unfocused) => Some(Message::WindowFocused(false)),
+            _ => None,
+        });
+
+        let global_hotkey = if self.global_hotkey.is_some() {
+            time::every(Duration::from_millis(100)).map(|_| Message::GlobalHotkeyPoll)
+        } else {
+            Subscription::none()
+        };
+
+        // Picks up screen reader actions and keeps what it reads current
+        let accessibility = if self.accessibility.active() {
+            time::every(Duration::from_millis(100)).map(|_| Message::AccessibilityPoll)
+        } else {
+            Subscription::none()
+        };
+
+        // Picks up media keys and keeps "now playing" widgets current
+        let media = if self.media.is_some() {
+            time::every(Duration::from_millis(250)).map(|_| Message::MediaPoll)
+        } else {
+            Subscription::none()
+        };
+
+        let inbox = if self.inbox.is_some() {
+            time::every(Duration::from_millis(250)).map(|_| Message::InboxPoll)
+        } else {
+            Subscription::none()
+        };
+
+        let mouse = if self.config.auto_dim.enabled && self.is_playing {
+            event::listen_with(|event, _status| match event {
+                Event::Mouse(_) => Some(Message::Wake),
+                _ => None,
+            })
+        } else {
+            Subscription::none()
+        };
+
+        let library = time::every(Duration::from_secs(1)).map(|_| Message::LibraryPoll);
+
+        let source = if self.config.watch_sources && self.current_book_id.is_some() {
+            time::every(Duration::from_secs(1)).map(|_| Message::SourcePoll)
+        } else {
+            Subscription::none()
+        };
+
+        let night = if self.config.night_mode.enabled {
+            time::every(Duration::from_secs(10)).map(|_| Message::NightPoll)
         } else {
             Subscription::none()
         };
 
-        let keys = keyboard::on_key_press(|key, _modifiers| Some(Message::KeyPressed(key)));
+        #[cfg(feature = "tray")]
+        let tray = time::every(Duration::from_millis(200)).map(|_| Message::TrayPoll);
+        #[cfg(not(feature = "tray"))]
+        let tray = Subscription::none();
+
+        Subscription::batch([
+            tick,
+            keys,
+            window_events,
+            global_hotkey,
+            accessibility,
+            media,
+            inbox,
+            library,
+            source,
+            night,
+            mouse,
+            tray,
+        ])
+    }
 
-        Subscription::batch([tick, keys])
+    fn scale_factor(&self) -> f64 {
+        f64::from(self.library.settings.ui_scale)
     }
 
     fn theme(&self) -> Theme {
-        Theme::Dark
+        let base = if self.dark { Theme::Dark } else { Theme::Light };
+        let brightness = self.brightness();
+        let true_black = self.appearance() == Appearance::TrueBlack;
+        let safe = self.library.settings.color_vision.colors();
+        if brightness >= 1.0 && !true_black && safe.is_none() {
+            return base;
+        }
+        // Buttons and sliders fade with the rest
+        let mut colors = base.palette();
+        if true_black {
+            colors.background = Color::BLACK;
+        }
+        // The progress bar and scrubber are drawn in the primary color
+        if let Some(safe) = safe {
+            colors.primary = rgb(safe.progress);
+        }
+        let fade = |color| fade(color, colors.background, brightness);
+        colors.text = fade(colors.text);
+        colors.primary = fade(colors.primary);
+        colors.success = fade(colors.success);
+        colors.danger = fade(colors.danger);
+        Theme::custom("Reading".to_string(), colors)
     }
 }
 
 impl RSVPApp {
-    fn load_book(&mut self, book_id: &str) -> bool {
-        let book_file = books_dir().join(format!("{}.txt", book_id));
+    fn frame_index(&self) -> usize {
+        if self.frame.0 == self.word_index {
+            self.frame.1
+        } else {
+            0
+        }
+    }
+
+    fn word_font_size(&self) -> u16 {
+        if self.compact {
+            COMPACT_FONT_SIZE
+        } else {
+            self.display.font_size.unwrap_or(WORD_FONT_SIZE)
+        }
+    }
+
+    /// Columns available on each side of the focal letter at the current window width
+    fn word_half_width(&self) -> usize {
+        let char_width = self.word_font_size() as f32 * CHAR_WIDTH_RATIO;
+        ((self.window_width / 2.0 / char_width) as usize).saturating_sub(1)
+    }
+
+    /// Move to another position, remembering this one for jumping back
+    fn jump(&mut self, index: usize) {
+        if index != self.word_index {
+            self.history.record(self.word_index);
+            self.word_index = index;
+            self.chapter_break = None;
+            self.block_pause = None;
+            self.footnote = None;
+            self.save_progress();
+        }
+    }
+
+    /// What the stats line can show
+    fn stats_fields<'a>(&'a self, status: &'a str) -> StatsFields<'a> {
+        let analysis = self
+            .current_book_id
+            .as_ref()
+            .and_then(|id| self.library.book(id))
+            .and_then(|book| book.analysis.as_ref());
+        let current_wpm = self.current_wpm();
+        let (elapsed_time, remaining_time) = reading_times(
+            analysis,
+            self.word_index,
+            self.words.len(),
+            current_wpm,
+            &self.pauses(),
+        );
+        StatsFields {
+            wpm: self.wpm,
+            current_wpm,
+            pacing: self.language.pacing,
+            units_per_word: self.units_per_word,
+            word: self.word_index + 1,
+            words: self.words.len().max(1),
+            percent: self.word_index as f64 * 100.0 / self.words.len().max(1) as f64,
+            elapsed_time,
+            remaining_time,
+            chapter: self.words.chapter_at(self.word_index),
+            chapters: self.words.chapters(),
+            unit: self.library.settings.progress_unit,
+            status,
+            breaks: self
+                .config
+                .breaks
+                .enabled
+                .then(|| self.library.stats.break_summary()),
+            recall: self.library.stats.recent_recall(),
+            repeat: self.repeat.summary(),
+            title: &self.current_book_title,
+        }
+    }
+
+    /// The speed for the current word, after any adaptive adjustment
+    fn current_wpm(&self) -> u32 {
+        self.config
+            .adaptive
+            .wpm(self.wpm, &self.words, self.word_index)
+    }
+
+    /// The fastest playback can go at the chosen speed, for a tick rate
+    /// that doesn't change with every word
+    fn fastest_wpm(&self) -> u32 {
+        let adaptive = &self.config.adaptive;
+        if adaptive.enabled {
+            self.wpm.max(adaptive.max_wpm)
+        } else {
+            self.wpm
+        }
+    }
+
+    /// The current word, joined by the rest of its chunk and any words
+    /// skimming shows with it, and how many words that is
+    fn current_unit(&self) -> Option<(String, usize)> {
+        let chunk = self.display.chunk.unwrap_or(1);
+        let lines = self.profile.line_breaks;
+        display_unit(
+            &self.words,
+            self.word_index,
+            &self.config.skim,
+            chunk,
+            lines,
+        )
+    }
+
+    /// The current word split into frames that fit the window
+    fn current_frames(&self) -> Vec<String> {
+        self.current_unit()
+            .map(|(text, _)| split_long_word(&text, self.word_half_width()))
+            .unwrap_or_default()
+    }
+
+    /// How long the current frame stays on screen. Punctuation pauses apply
+    /// only once the last frame of a word is shown.
+    fn current_delay(&self) -> Duration {
+        let period = 60.0 / self.current_wpm() as f64 * self.profile.slowdown.max(0.1);
+        let frames = self.current_frames();
+        let multiplier = if self.frame_index() + 1 >= frames.len() {
+            let pauses = self.pauses();
+            let covered = self.current_unit().map_or(1, |(_, covered)| covered);
+            let last = self.word_index + covered - 1;
+            frames.last().map_or(1.0, |w| {
+                frame_weight(w, &pauses, &self.config.skim, &self.language)
+                    + self.profile.line_pause(&self.words, last, &pauses)
+            })
+        } else {
+            frames
+                .get(self.frame_index())
+                .map_or(1.0, |frame| self.language.length_weight(frame))
+        };
+        Duration::from_secs_f64(period * multiplier)
+    }
+
+    /// Whether a micro-pause is blanking the screen
+    fn blanking(&self) -> bool {
+        self.blank_until.is_some_and(|until| Instant::now() < until)
+    }
+
+    /// The part of the current word being shown right now
+    fn current_frame(&self) -> Option<String> {
+        let frames = self.current_frames();
+        let i = self.frame_index().min(frames.len().saturating_sub(1));
+        frames.into_iter().nth(i)
+    }
+
+    /// How to draw the frame on screen, if its first word is inside quotes
+    fn dialogue(&self) -> Option<DialogueStyle> {
+        let style = self.config.theme.dialogue;
+        (style != DialogueStyle::Plain && self.words.in_dialogue(self.word_index)).then_some(style)
+    }
+
+    /// Whether the frame on screen ends a sentence, for the `sentence_end`
+    /// marker
+    fn at_sentence_end(&self) -> bool {
+        self.current_frame()
+            .is_some_and(|frame| frame.rsplit(' ').next().is_some_and(ends_sentence))
+    }
+
+    /// Whether the frame on screen ends a line of verse, for its mark
+    fn at_line_end(&self) -> bool {
+        if !self.profile.line_breaks || self.frame_index() + 1 < self.current_frames().len() {
+            return false;
+        }
+        let covered = self.current_unit().map_or(1, |(_, covered)| covered);
+        self.words.ends_line(self.word_index + covered - 1)
+    }
+
+    /// Loop the sentence around the current word, then its paragraph, then
+    /// stop looping
+    fn toggle_loop(&mut self) {
+        if self.words.is_empty() {
+            return;
+        }
+        match self.repeat.cycle(&self.words, self.word_index) {
+            Some(scope) => {
+                self.start_loop();
+                self.info(format!("Looping the {}", scope));
+            }
+            None => self.info("Loop off"),
+        }
+    }
+
+    /// Mark loop point A, then point B, then stop looping
+    fn mark_loop(&mut self) {
+        if self.words.is_empty() {
+            return;
+        }
+        match self.repeat.mark(self.word_index) {
+            Mark::Started => self.info("Loop from here: mark where it ends"),
+            Mark::Looping => {
+                self.start_loop();
+                self.info("Looping A-B");
+            }
+            Mark::Cleared => self.info("Loop off"),
+        }
+    }
+
+    /// Go back to the start of the loop just set
+    fn start_loop(&mut self) {
+        if let Some(active) = self.repeat.active {
+            self.jump(active.start);
+            self.clock.restart();
+        }
+    }
+
+    /// Start the loop over, faster by the repeat settings' step
+    fn next_pass(&mut self, start: usize) {
+        self.word_index = start;
+        let step = self.config.repeat.wpm_step;
+        if step > 0 {
+            self.wpm = self.config.clamp_wpm(self.wpm + step);
+            self.library.settings.set_wpm(self.wpm);
+        }
+        let passes = self.repeat.active.map_or(0, |active| active.passes);
+        self.info(format!("Pass {} at {} WPM", passes + 1, self.wpm));
+    }
+
+    /// Move on to the playing playlist's next item, still playing. False
+    /// at the end of the playlist.
+    fn next_in_playlist(&mut self) -> bool {
+        let (Some(name), Some(book_id)) = (self.playlist.clone(), &self.current_book_id) else {
+            return false;
+        };
+        let Some(next) = self.library.playlist_next(&name, book_id) else {
+            return false;
+        };
+        let (next_id, title) = (next.id.clone(), next.title.clone());
+        self.end_session();
+        self.save_progress();
+        if !self.load_book(&next_id) {
+            return false;
+        }
+        self.playlist = Some(name.clone());
+        self.clock.restart();
+        self.info(format!("Next in {}: {}", name, title));
+        true
+    }
 
-        let content = match fs::read_to_string(&book_file) {
-            Ok(c) => c,
+    fn load_book(&mut self, book_id: &str) -> bool {
+        self.words = match WordSource::open(book_file(book_id)) {
+            Ok(words) => words,
             Err(_) => return false,
         };
 
-        self.words = tokenize_text(&content);
         if self.words.is_empty() {
             return false;
         }
 
         if let Some(book) = self.library.books.iter().find(|b| b.id == book_id) {
             self.current_book_title = book.title.clone();
-            self.word_index = book.progress.min(self.words.len().saturating_sub(1));
+            let saved = match book.progress_offset {
+                Some(offset) => self.words.index_at_offset(offset),
+                None => book.progress.min(self.words.len().saturating_sub(1)),
+            };
+            self.word_index = self.config.resume_at.snap(&self.words, saved);
         } else {
             self.current_book_title = "Unknown".to_string();
             self.word_index = 0;
         }
 
+        // Books imported before languages and analyses were kept get them now
+        let mut suggested_wpm = None;
+        if let Some(book) = self.library.book_mut(book_id) {
+            if book.language.is_none() {
+                book.language = detect_book_language(&self.words).map(str::to_string);
+            }
+            fill_analysis(book);
+            if self.config.suggest_wpm && book.progress_at == 0 {
+                suggested_wpm = book.analysis.as_ref().map(|a| a.suggested_wpm(self.wpm));
+            }
+        }
+        if let Some(wpm) = suggested_wpm {
+            self.wpm = self.config.clamp_wpm(wpm);
+            self.library.settings.set_wpm(self.wpm);
+        }
+
+        // Opening a book, from anywhere, ends a speed test or the tutorial
+        self.speed_test = None;
+        self.tutorial = None;
+        self.chapter_break = None;
+        self.block_pause = None;
+        self.footnote = None;
+        self.playlist = None;
+        self.repeat.clear();
         self.current_book_id = Some(book_id.to_string());
-        self.library.last_book = Some(book_id.to_string());
-        save_library(&self.library);
+        self.reload_offer = false;
+        self.show_start = false;
+        match self.library.books.iter().find(|b| b.id == book_id) {
+            Some(book) if self.config.watch_sources => self.source_watch.watch(book),
+            _ => self.source_watch.stop(),
+        }
+        self.update_language();
+        self.recent_words.clear();
+        self.history.clear();
+        self.library.mark_opened(book_id);
+        self.save_library();
+        if suggested_wpm.is_some() {
+            self.info(format!("{} WPM suits this book", self.wpm));
+        }
 
         true
     }
 
-    fn import_file(&mut self, path: &PathBuf) -> bool {
-        let content = match fs::read_to_string(path) {
-            Ok(c) => c,
-            Err(e) => {
-                self.status_message = Some(format!("Error: {}", e));
-                return false;
-            }
+    /// Add the current word and its sentence to the book's vocabulary list
+    fn save_word(&mut self) {
+        let Some(book_id) = self.current_book_id.clone() else {
+            self.info("No book loaded");
+            return;
         };
-
-        let words = tokenize_text(&content);
-        if words.is_empty() {
-            self.status_message = Some("File is empty".to_string());
-            return false;
+        let Some(entry) = VocabEntry::capture(&self.words, self.word_index) else {
+            return;
+        };
+        let word = entry.word.clone();
+        let Some(book) = self.library.book_mut(&book_id) else {
+            return;
+        };
+        if book.save_word(entry) {
+            self.save_library();
+            self.info(format!("Saved \"{}\"", word));
+        } else {
+            self.info(format!("\"{}\" is already saved", word));
         }
+    }
 
-        let mut hasher = DefaultHasher::new();
-        path.hash(&mut hasher);
-        std::time::SystemTime::now().hash(&mut hasher);
-        let book_id = format!("{:x}", hasher.finish())[..12].to_string();
-
-        let _ = ensure_config_dirs();
-        let book_file = books_dir().join(format!("{}.txt", book_id));
-        if fs::write(&book_file, &content).is_err() {
-            self.status_message = Some("Failed to save book".to_string());
-            return false;
-        }
+    /// Pause on the latest footnote at or just before the current words and
+    /// show it
+    fn show_footnote(&mut self) {
+        let covered = self.current_unit().map_or(1, |(_, covered)| covered);
+        let last = self.word_index + covered - 1;
+        let Some(note) = footnote_near(self.footnotes(), last).cloned() else {
+            self.info("No footnote nearby");
+            return;
+        };
+        self.footnote = Some((note, self.is_playing));
+        self.is_playing = false;
+    }
 
-        let title = path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("Unknown")
-            .to_string();
+    /// Whether a footnote reference is among the words on screen
+    fn at_footnote(&self) -> bool {
+        let covered = self.current_unit().map_or(1, |(_, covered)| covered);
+        let words = self.word_index..self.word_index + covered;
+        self.footnotes()
+            .iter()
+            .any(|note| words.contains(&note.word))
+    }
 
-        let book = Book {
-            id: book_id.clone(),
-            title: title.clone(),
-            total_words: words.len(),
-            progress: 0,
-        };
-        self.library.books.push(book);
-        save_library(&self.library);
+    /// Footnotes of the open book
+    fn footnotes(&self) -> &[Footnote] {
+        self.current_book_id
+            .as_ref()
+            .and_then(|id| self.library.book(id))
+            .map_or(&[], |book| &book.footnotes)
+    }
 
-        self.status_message = Some(format!("Loaded: {} ({} words)", title, words.len()));
-        self.load_book(&book_id);
+    /// Links kept with the open book
+    fn links(&self) -> &[BookLink] {
+        self.current_book_id
+            .as_ref()
+            .and_then(|id| self.library.book(id))
+            .map_or(&[], |book| &book.links)
+    }
 
-        true
+    /// Saved words of the open book
+    fn vocabulary(&self) -> &[VocabEntry] {
+        self.current_book_id
+            .as_ref()
+            .and_then(|id| self.library.book(id))
+            .map_or(&[], |book| &book.vocabulary)
     }
 
-    fn save_progress(&mut self) {
-        if let Some(ref book_id) = self.current_book_id {
-            if let Some(book) = self.library.books.iter_mut().find(|b| b.id == *book_id) {
-                book.progress = self.word_index;
+    /// Follow playback in the session log, logging sessions as they end
+    fn track_session(&mut self) {
+        let before = self.sessions.in_session();
+        let ended = self.sessions.update(
+            self.current_book_id.as_deref(),
+            self.is_playing,
+            self.word_index,
+        );
+        let started = self.sessions.in_session() && (!before || ended.is_some());
+        if let Some(session) = ended {
+            self.library.stats.record_session(session);
+            self.save_library();
+            self.ramp.stop();
+        }
+
+        // With the speed ramp on, each session starts slow and speeds up
+        // minute by minute
+        let ramp = self.config.ramp;
+        if started && ramp.enabled {
+            let start = match ramp.start_wpm {
+                0 => self.library.settings.wpm,
+                wpm => wpm,
+            };
+            let target = ramp.target_wpm;
+            self.wpm = self.config.clamp_wpm(start);
+            self.ramp.start(self.wpm);
+            self.info(format!("Speed ramp: {} WPM, up to {}", self.wpm, target));
+        }
+        if let Some(step) = self.ramp.update(self.is_playing, self.wpm, &ramp) {
+            self.sessions.sustained(step.sustained);
+            if let Some(next) = step.next {
+                self.wpm = self.config.clamp_wpm(next);
+                self.info(format!("Speed ramp: {} WPM", self.wpm));
             }
-            save_library(&self.library);
         }
     }
-}
 
-// Custom dark container style
-struct DarkContainer;
+    /// Log the session in progress now instead of when it would end, as on
+    /// quitting or before showing statistics. The library still needs
+    /// saving.
+    fn end_session(&mut self) {
+        if let Some(session) = self.sessions.finish() {
+            self.library.stats.record_session(session);
+        }
+    }
 
-impl container::StyleSheet for DarkContainer {
-    type Style = Theme;
+    /// Pick the pacing profile for the open book's language, with the
+    /// pacing and display settings picked for the book if there are any
+    fn update_language(&mut self) {
+        let book = self
+            .current_book_id
+            .as_ref()
+            .and_then(|id| self.library.books.iter().find(|b| &b.id == id));
+        let code = book.and_then(|book| book.language.as_deref());
+        let pacing = book.and_then(|book| book.pacing);
+        self.display = book.map(|book| book.display.clone()).unwrap_or_default();
+        self.language = LanguageProfile::for_language(code, &self.config.languages);
+        if let Some(pacing) = pacing {
+            self.language.pacing = pacing;
+        }
+        self.profile = self
+            .display
+            .profile
+            .as_ref()
+            .and_then(|name| self.config.profiles.get(name))
+            .copied()
+            .unwrap_or_default();
+        if let Some(verse) = self.display.verse {
+            self.profile.line_breaks = verse;
+        }
+        self.units_per_word = self.language.units_per_word(&self.words);
+        self.dark = is_dark(self.appearance());
+    }
 
-    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
-        container::Appearance {
-            background: Some(iced::Background::Color(Color::from_rgb(0.1, 0.1, 0.12))),
-            text_color: Some(Color::WHITE),
-            ..Default::default()
+    /// Save settings typed for the open book, or keep them open to fix if
+    /// they don't read
+    fn save_book_display(&mut self, draft: String) {
+        let display = match BookDisplay::parse(&draft, &self.config.profiles) {
+            Ok(display) => display,
+            Err(e) => {
+                self.editing_display = Some(draft);
+                self.toast(ToastLevel::Error, e);
+                return;
+            }
+        };
+        let Some(book) = self
+            .current_book_id
+            .clone()
+            .and_then(|id| self.library.book_mut(&id))
+        else {
+            return;
+        };
+        book.display = display;
+        self.save_library();
+        self.update_language();
+        self.info("Saved for this book");
+    }
+
+    /// Sentence and clause pauses, from the book's pacing profile if it
+    /// sets them
+    fn pauses(&self) -> Pauses {
+        self.profile.pauses.unwrap_or(self.config.pauses)
+    }
+
+    /// The night theme during night mode, otherwise the theme picked for
+    /// the open book, or the general one
+    fn appearance(&self) -> Appearance {
+        if self.night.is_night() {
+            return Appearance::Night;
+        }
+        self.display
+            .appearance
+            .unwrap_or(self.library.settings.appearance)
+    }
+
+    /// Switch to or from the night theme if night mode's hours, or the
+    /// desktop's dark mode, call for it
+    fn update_night(&mut self) {
+        let system_dark = || dark_light::detect() == dark_light::Mode::Dark;
+        if self.night.update(&self.config.night_mode, system_dark) {
+            self.dark = is_dark(self.appearance());
+        }
+    }
+
+    /// Pace the open book by words, characters or syllables, or as its
+    /// language says
+    fn cycle_pacing(&mut self) {
+        let Some(id) = self.current_book_id.clone() else {
+            return;
+        };
+        let Some(book) = self.library.book_mut(&id) else {
+            return;
+        };
+        let pacing = Pacing::next_for_book(book.pacing);
+        book.pacing = pacing;
+        self.save_library();
+        self.update_language();
+        let message = match pacing {
+            Some(pacing) => format!("Pacing by {} ({})", pacing, pacing.rate_label()),
+            None => format!("Pacing by {}, as the book's language", self.language.pacing),
+        };
+        self.info(message);
+    }
+
+    fn import_file(&mut self, path: &Path) -> bool {
+        let filters = self.import_options.filters(&self.config.pipeline);
+
+        match import_book(&mut self.library, path, &filters) {
+            Ok(book) => {
+                self.info(format!(
+                    "Loaded: {} ({} words)",
+                    book.title, book.total_words
+                ));
+                self.load_book(&book.id);
+                true
+            }
+            Err(e) => {
+                self.toast(ToastLevel::Error, e);
+                false
+            }
+        }
+    }
+
+    /// Import several files as separate books and open the first of them
+    fn import_files(&mut self, paths: &[PathBuf]) {
+        let filters = self.import_options.filters(&self.config.pipeline);
+        let batch = import_books(&mut self.library, paths, &filters);
+        self.finish_batch(batch);
+    }
+
+    /// Open the first book of a multi-file import and report how it went
+    fn finish_batch(&mut self, batch: BatchImport) {
+        if let Some(first) = batch.imported.first() {
+            let first_id = first.id.clone();
+            self.load_book(&first_id);
+        }
+        let level = if batch.failed.is_empty() && batch.save_error.is_none() {
+            ToastLevel::Info
+        } else if batch.imported.is_empty() {
+            ToastLevel::Error
+        } else {
+            ToastLevel::Warning
+        };
+        self.toast(level, batch.summary());
+    }
+
+    /// Remove a book from the library and disk, closing it if it is open
+    fn delete_book(&mut self, book_id: &str) {
+        let title = self
+            .library
+            .book(book_id)
+            .map(|b| b.title.clone())
+            .unwrap_or_default();
+
+        self.library.remove_book(book_id);
+        self.save_library();
+        let path = book_file(book_id);
+        if let Err(e) = fs::remove_file(&path) {
+            tracing::warn!("Couldn't remove {}: {}", path.display(), e);
+        }
+
+        if self.current_book_id.as_deref() == Some(book_id) {
+            self.words = WordSource::default();
+            self.current_book_id = None;
+            self.source_watch.stop();
+            self.reload_offer = false;
+            self.current_book_title.clear();
+            self.word_index = 0;
+            self.history.clear();
+            self.is_playing = false;
+        }
+        self.library_selected = self
+            .library_selected
+            .min(self.library.books.len().saturating_sub(1));
+        self.info(format!("Deleted: {}", title));
+    }
+
+    /// Keys with a meaning of their own while the library is open. Returns
+    /// `None` for keys that should act as they do while reading.
+    fn library_key(&mut self, key: &Key) -> Option<Command<Message>> {
+        use keyboard::key::Named;
+
+        // A pending delete takes every key until it is answered
+        if self.pending_delete.is_some() {
+            let confirmed = matches!(key.as_ref(), Key::Named(Named::Enter) | Key::Character("y"));
+            return Some(self.update(Message::ConfirmDelete(confirmed)));
+        }
+
+        // Typing goes to the title field; Escape gives up on the new title
+        if self.renaming.is_some() {
+            let cancelled = matches!(key.as_ref(), Key::Named(Named::Escape));
+            return Some(if cancelled {
+                self.update(Message::FinishRename(false))
+            } else {
+                Command::none()
+            });
+        }
+
+        // The same for a playlist name
+        if self.adding_to_playlist.is_some() {
+            let cancelled = matches!(key.as_ref(), Key::Named(Named::Escape));
+            return Some(if cancelled {
+                self.update(Message::FinishAddToPlaylist(false))
+            } else {
+                Command::none()
+            });
+        }
+
+        let count = self.library.books.len();
+        let selected = self.library.books.get(self.library_selected);
+        match key.as_ref() {
+            Key::Named(Named::ArrowUp) | Key::Character("k") => {
+                if count > 0 {
+                    self.library_selected = (self.library_selected + count - 1) % count;
+                }
+                Some(self.scroll_to_selected())
+            }
+            Key::Named(Named::ArrowDown) | Key::Character("j") => {
+                if count > 0 {
+                    self.library_selected = (self.library_selected + 1) % count;
+                }
+                Some(self.scroll_to_selected())
+            }
+            Key::Named(Named::Enter) => {
+                let book_id = selected.map(|b| b.id.clone())?;
+                Some(self.update(Message::OpenBook(book_id)))
+            }
+            Key::Named(Named::F2) => {
+                let book_id = selected.map(|b| b.id.clone())?;
+                Some(self.update(Message::StartRename(book_id)))
+            }
+            Key::Character("I") => {
+                let book_id = selected.map(|b| b.id.clone())?;
+                Some(self.update(Message::ShowDetails(book_id)))
+            }
+            Key::Character("S") => Some(self.update(Message::ToggleLibraryStats)),
+            Key::Character("a") => {
+                let book_id = selected.map(|b| b.id.clone())?;
+                Some(self.update(Message::StartAddToPlaylist(book_id)))
+            }
+            Key::Named(Named::Delete) | Key::Character("d") => {
+                let book_id = selected.map(|b| b.id.clone())?;
+                Some(self.update(Message::DeleteBook(book_id)))
+            }
+            Key::Named(Named::Escape) => Some(self.update(Message::ToggleLibrary)),
+            _ => None,
+        }
+    }
+
+    /// Keep the highlighted library row in view
+    fn scroll_to_selected(&self) -> Command<Message> {
+        let last = self.library.books.len().saturating_sub(1).max(1);
+        let y = self.library_selected as f32 / last as f32;
+        scrollable::snap_to(
+            scrollable::Id::new("library"),
+            scrollable::RelativeOffset { x: 0.0, y },
+        )
+    }
+
+    /// The library sidebar: every book with its progress, open, rename and
+    /// delete buttons, and the highlighted row for keyboard navigation
+    fn library_panel(&self, palette: Palette) -> Element<'_, Message> {
+        let mut books = column![].spacing(2);
+        for (i, book) in self.library.books.iter().enumerate() {
+            let pct = if book.total_words > 0 {
+                (book.progress as f32 / book.total_words as f32) * 100.0
+            } else {
+                0.0
+            };
+            let title_color = if Some(&book.id) == self.current_book_id.as_ref() {
+                palette.focus
+            } else {
+                palette.text
+            };
+
+            let entry: Element<Message> = if self.pending_delete.as_ref() == Some(&book.id) {
+                column![
+                    text(format!("Delete '{}'?", book.title)).size(15),
+                    row![
+                        button(text("Delete").size(14))
+                            .style(theme::Button::Destructive)
+                            .on_press(Message::ConfirmDelete(true)),
+                        button(text("Cancel").size(14))
+                            .style(theme::Button::Secondary)
+                            .on_press(Message::ConfirmDelete(false)),
+                    ]
+                    .spacing(8),
+                ]
+                .spacing(6)
+                .into()
+            } else if let Some((_, draft)) = self.renaming.as_ref().filter(|(id, _)| *id == book.id)
+            {
+                column![
+                    text_input("Title", draft)
+                        .id(text_input::Id::new("rename"))
+                        .on_input(Message::RenameInput)
+                        .on_submit(Message::FinishRename(true))
+                        .size(15)
+                        .padding(4),
+                    row![
+                        button(text("Rename").size(14)).on_press(Message::FinishRename(true)),
+                        button(text("Cancel").size(14))
+                            .style(theme::Button::Secondary)
+                            .on_press(Message::FinishRename(false)),
+                    ]
+                    .spacing(8),
+                ]
+                .spacing(6)
+                .into()
+            } else if let Some((_, draft)) = self
+                .adding_to_playlist
+                .as_ref()
+                .filter(|(id, _)| *id == book.id)
+            {
+                column![
+                    text(format!("Add '{}' to the playlist", book.title)).size(15),
+                    text_input("Playlist", draft)
+                        .id(text_input::Id::new("playlist"))
+                        .on_input(Message::PlaylistInput)
+                        .on_submit(Message::FinishAddToPlaylist(true))
+                        .size(15)
+                        .padding(4),
+                    row![
+                        button(text("Add").size(14)).on_press(Message::FinishAddToPlaylist(true)),
+                        button(text("Cancel").size(14))
+                            .style(theme::Button::Secondary)
+                            .on_press(Message::FinishAddToPlaylist(false)),
+                    ]
+                    .spacing(8),
+                ]
+                .spacing(6)
+                .into()
+            } else {
+                let readability = book
+                    .analysis
+                    .as_ref()
+                    .map_or(String::new(), |a| a.readability(book.language.as_deref()));
+                let title = button(text(&book.title).size(15).style(title_color))
+                    .style(theme::Button::Text)
+                    .on_press(Message::OpenBook(book.id.clone()))
+                    .padding(0);
+                let byline = book
+                    .author
+                    .as_ref()
+                    .map(|author| text(format!("by {}", author)).size(12).style(palette.stats));
+                column![title]
+                    .push_maybe(byline)
+                    .push(
+                        row![
+                            progress_bar(0.0..=100.0, pct).height(Length::Fixed(6.0)),
+                            text(format!("{:.0}%", pct)).size(12).style(palette.stats),
+                            text(readability).size(12).style(palette.stats),
+                            text(book.read_ago().unwrap_or_default())
+                                .size(12)
+                                .style(palette.stats),
+                            button(text("ⓘ").size(12))
+                                .style(theme::Button::Text)
+                                .on_press(Message::ShowDetails(book.id.clone()))
+                                .padding([0, 4]),
+                            button(text("✎").size(12))
+                                .style(theme::Button::Text)
+                                .on_press(Message::StartRename(book.id.clone()))
+                                .padding([0, 4]),
+                            button(text("+").size(12))
+                                .style(theme::Button::Text)
+                                .on_press(Message::StartAddToPlaylist(book.id.clone()))
+                                .padding([0, 4]),
+                            button(text("✕").size(12))
+                                .style(theme::Button::Text)
+                                .on_press(Message::DeleteBook(book.id.clone()))
+                                .padding([0, 4]),
+                        ]
+                        .spacing(8)
+                        .align_items(iced::Alignment::Center),
+                    )
+                    .spacing(4)
+                    .into()
+            };
+
+            let background = if i == self.library_selected {
+                palette.selection
+            } else {
+                palette.panel
+            };
+            let row_style = AppContainer {
+                background,
+                text: palette.text,
+            };
+            books = books.push(
+                container(entry)
+                    .width(Length::Fill)
+                    .padding(8)
+                    .style(theme::Container::Custom(Box::new(row_style))),
+            );
+        }
+
+        let list: Element<Message> = if self.library.books.is_empty() {
+            text("No books yet").size(14).style(palette.hint).into()
+        } else {
+            scrollable(books)
+                .id(scrollable::Id::new("library"))
+                .height(Length::Fill)
+                .into()
+        };
+
+        let mut panel = column![].spacing(10);
+        if !self.library.playlists.is_empty() {
+            panel = panel.push(text("Playlists").size(18));
+            panel = panel.push(self.playlists_list(palette));
+        }
+        let heading = row![
+            text("Library").size(18).width(Length::Fill),
+            button(text("Statistics").size(12))
+                .style(theme::Button::Text)
+                .on_press(Message::ToggleLibraryStats)
+                .padding([0, 4]),
+        ]
+        .align_items(iced::Alignment::Center);
+        panel = panel.push(heading).push(list);
+
+        container(panel)
+            .width(Length::Fixed(LIBRARY_WIDTH))
+            .height(Length::Fill)
+            .padding(10)
+            .style(theme::Container::Custom(Box::new(AppContainer {
+                background: palette.panel,
+                text: palette.text,
+            })))
+            .into()
+    }
+
+    /// Each playlist with play and delete buttons, and its items with their
+    /// progress
+    fn playlists_list(&self, palette: Palette) -> Element<'_, Message> {
+        let mut playlists = column![].spacing(2);
+        for playlist in &self.library.playlists {
+            let books = self.library.playlist_books(&playlist.name);
+            let read = books.iter().filter(|book| book.is_finished()).count();
+            let name_color = if self.playlist.as_ref() == Some(&playlist.name) {
+                palette.focus
+            } else {
+                palette.text
+            };
+            let mut entry = column![row![
+                text(&playlist.name)
+                    .size(15)
+                    .style(name_color)
+                    .width(Length::Fill),
+                text(format!("{}/{} read", read, books.len()))
+                    .size(12)
+                    .style(palette.stats),
+                button(text("▶").size(12))
+                    .style(theme::Button::Text)
+                    .on_press(Message::PlayPlaylist(playlist.name.clone()))
+                    .padding([0, 4]),
+                button(text("✕").size(12))
+                    .style(theme::Button::Text)
+                    .on_press(Message::DeletePlaylist(playlist.name.clone()))
+                    .padding([0, 4]),
+            ]
+            .spacing(8)
+            .align_items(iced::Alignment::Center)]
+            .spacing(2);
+            for book in books {
+                let pct = if book.total_words > 0 {
+                    (book.progress as f32 / book.total_words as f32) * 100.0
+                } else {
+                    0.0
+                };
+                let title_color = if Some(&book.id) == self.current_book_id.as_ref() {
+                    palette.focus
+                } else {
+                    palette.text
+                };
+                entry = entry.push(row![
+                    button(text(&book.title).size(13).style(title_color))
+                        .style(theme::Button::Text)
+                        .on_press(Message::OpenBook(book.id.clone()))
+                        .padding([0, 0, 0, 12])
+                        .width(Length::Fill),
+                    text(format!("{:.0}%", pct)).size(12).style(palette.stats),
+                ]);
+            }
+            let row_style = AppContainer {
+                background: palette.panel,
+                text: palette.text,
+            };
+            playlists = playlists.push(
+                container(entry)
+                    .width(Length::Fill)
+                    .padding(8)
+                    .style(theme::Container::Custom(Box::new(row_style))),
+            );
+        }
+        playlists.into()
+    }
+
+    /// The menu bar, with the open menu dropped down below its title
+    fn menu_bar(&self, palette: Palette) -> Element<'_, Message> {
+        let mut titles = row![];
+        for menu in Menu::ALL {
+            titles = titles.push(
+                button(text(menu.to_string()).size(14))
+                    .style(theme::Button::Text)
+                    .width(Length::Fixed(MENU_WIDTH))
+                    .padding([4, 10])
+                    .on_press(Message::ToggleMenu(menu)),
+            );
+        }
+
+        let Some(menu) = self.open_menu else {
+            return titles.into();
+        };
+
+        let mut entries = column![].spacing(2);
+        for (label, action, message) in self.menu_entries(menu) {
+            let shortcut = action
+                .and_then(|action| self.config.keys.keys(action).first())
+                .map(|key| key.to_string())
+                .unwrap_or_default();
+            entries = entries.push(
+                button(row![
+                    text(label).size(14),
+                    Space::with_width(Length::Fill),
+                    text(shortcut).size(13).style(palette.hint),
+                ])
+                .style(theme::Button::Text)
+                .width(Length::Fill)
+                .padding([4, 10])
+                .on_press(Message::MenuPick(Box::new(message))),
+            );
+        }
+
+        let offset = Menu::ALL.iter().position(|&m| m == menu).unwrap_or(0);
+        let dropdown = container(entries)
+            .width(Length::Fixed(MENU_DROPDOWN_WIDTH))
+            .padding(4)
+            .style(theme::Container::Custom(Box::new(AppContainer {
+                background: palette.panel,
+                text: palette.text,
+            })));
+        column![
+            titles,
+            row![Space::with_width(offset as f32 * MENU_WIDTH), dropdown],
+        ]
+        .into()
+    }
+
+    /// What each menu offers: a label, the action whose key it shows, and
+    /// the message it sends
+    fn menu_entries(&self, menu: Menu) -> Vec<(&'static str, Option<Action>, Message)> {
+        match menu {
+            Menu::File => vec![
+                ("Open files…", Some(Action::Import), Message::OpenFile),
+                ("Open folder…", None, Message::OpenFolder),
+                ("Quit", Some(Action::Quit), Message::Quit),
+            ],
+            Menu::View => vec![
+                ("Library", Some(Action::Library), Message::ToggleLibrary),
+                ("Zen mode", Some(Action::ToggleZen), Message::ToggleZen),
+                (
+                    "Progress units",
+                    Some(Action::CycleProgress),
+                    Message::CycleProgress,
+                ),
+                (
+                    "Pacing unit",
+                    Some(Action::CyclePacing),
+                    Message::CyclePacing,
+                ),
+                (
+                    "Book display…",
+                    Some(Action::BookDisplay),
+                    Message::StartBookDisplay,
+                ),
+                (
+                    "Compact overlay",
+                    Some(Action::ToggleCompact),
+                    Message::ToggleCompact,
+                ),
+                (
+                    "Zoom in",
+                    Some(Action::ScaleUp),
+                    Message::ScaleUi(UI_SCALE_STEP),
+                ),
+                (
+                    "Zoom out",
+                    Some(Action::ScaleDown),
+                    Message::ScaleUi(-UI_SCALE_STEP),
+                ),
+                (
+                    "Timing diagnostics",
+                    Some(Action::ToggleDebug),
+                    Message::ToggleDiagnostics,
+                ),
+                ("Narration", Some(Action::Narrate), Message::ToggleNarration),
+                ("Skimming", Some(Action::ToggleSkim), Message::ToggleSkim),
+                (
+                    "Keyword emphasis",
+                    Some(Action::ToggleKeywords),
+                    Message::ToggleKeywords,
+                ),
+                (
+                    "Recall check",
+                    Some(Action::RecallCheck),
+                    Message::StartRecall,
+                ),
+                ("Save word", Some(Action::SaveWord), Message::SaveWord),
+                (
+                    "Footnote",
+                    Some(Action::ShowFootnote),
+                    Message::ShowFootnote,
+                ),
+                ("Loop", Some(Action::ToggleLoop), Message::ToggleLoop),
+                ("A-B loop", Some(Action::MarkLoop), Message::MarkLoop),
+                (
+                    "Saved words",
+                    Some(Action::Vocabulary),
+                    Message::ToggleVocabulary,
+                ),
+                ("Links", Some(Action::Links), Message::ToggleLinks),
+                (
+                    "Book analysis",
+                    Some(Action::Analysis),
+                    Message::ToggleAnalysis,
+                ),
+                (
+                    "Reading statistics",
+                    Some(Action::Stats),
+                    Message::ToggleStats,
+                ),
+                (
+                    "Speed test",
+                    Some(Action::SpeedTest),
+                    Message::StartSpeedTest,
+                ),
+                (
+                    "Next theme",
+                    Some(Action::CycleTheme),
+                    Message::AppearanceSelected(self.library.settings.appearance.next()),
+                ),
+                (
+                    "Color blindness palette",
+                    Some(Action::CycleColorVision),
+                    Message::ColorVisionSelected(self.library.settings.color_vision.next()),
+                ),
+                (
+                    "Reload config",
+                    Some(Action::ReloadConfig),
+                    Message::ReloadConfig,
+                ),
+            ],
+            Menu::Help => vec![
+                (
+                    "Keyboard shortcuts",
+                    Some(Action::Help),
+                    Message::ToggleHelp,
+                ),
+                ("Tutorial", None, Message::StartTutorial),
+            ],
+        }
+    }
+
+    /// The break screen, counting down to the end of the break
+    fn break_panel(&self, palette: Palette) -> Element<'_, Message> {
+        let remaining = self
+            .breaks
+            .remaining(&self.config.breaks)
+            .unwrap_or_default();
+        let (countdown, hint) = if remaining.is_zero() {
+            ("Break over".to_string(), "Press any key to keep reading")
+        } else {
+            (
+                format_countdown(remaining),
+                "Press any key to end the break early",
+            )
+        };
+
+        let content = column![
+            text("Time for a break").size(28).style(palette.focus),
+            text("Look away from the screen and let your eyes rest")
+                .size(16)
+                .style(palette.stats),
+            text(countdown).size(WORD_FONT_SIZE).style(palette.word),
+            text(hint).size(14).style(palette.hint),
+        ]
+        .spacing(16)
+        .align_items(iced::Alignment::Center);
+
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// End the current break, early or not, and go back to reading
+    fn finish_break(&mut self) {
+        if let Some(outcome) = self.breaks.end_break(&self.config.breaks) {
+            self.library.stats.record_break(outcome);
+            self.save_library();
+        }
+        self.is_playing = !self.words.is_empty();
+        self.clock.restart();
+    }
+
+    /// Pause and quiz the reader on the last minute of reading. Returns
+    /// false if too little has been read for a check.
+    fn start_recall_check(&mut self) -> bool {
+        let Some(check) = RecallCheck::new(&self.recent_words, &self.words, self.word_index) else {
+            return false;
+        };
+        self.is_playing = false;
+        self.save_progress();
+        self.recall = Some(check);
+        true
+    }
+
+    /// The recall check: words to pick from, then how the answers went
+    fn recall_panel<'a>(
+        &'a self,
+        check: &'a RecallCheck,
+        palette: Palette,
+    ) -> Element<'a, Message> {
+        let mut choices = column![].spacing(10);
+        for (i, choice) in check.choices.iter().enumerate() {
+            let label = format!("{}  {}", i + 1, choice.word);
+            let line: Element<Message> = if check.result.is_none() {
+                checkbox(label, choice.picked)
+                    .on_toggle(move |_| Message::RecallToggle(i))
+                    .size(20)
+                    .text_size(20)
+                    .into()
+            } else {
+                let right = choice.picked == choice.appeared;
+                let mark = if right { "✓" } else { "✗" };
+                let shown = if choice.appeared { "  (shown)" } else { "" };
+                text(format!("{} {}{}", mark, label, shown))
+                    .size(20)
+                    .style(if right { palette.word } else { palette.error })
+                    .into()
+            };
+            choices = choices.push(line);
+        }
+
+        let footer: Element<Message> = match check.result {
+            None => column![
+                row![
+                    button(text("Check answers"))
+                        .on_press(Message::RecallSubmit)
+                        .padding(10),
+                    button(text("Skip"))
+                        .style(theme::Button::Secondary)
+                        .on_press(Message::RecallClose)
+                        .padding(10),
+                ]
+                .spacing(10),
+                text("1-5 to pick, Enter to check, Escape to skip")
+                    .size(14)
+                    .style(palette.hint),
+            ]
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .into(),
+            Some(score) => {
+                let average = self
+                    .library
+                    .stats
+                    .recent_recall()
+                    .unwrap_or(score.percent());
+                column![
+                    text(format!(
+                        "{}/{} right, {}% over recent checks",
+                        score.correct, score.total, average
+                    ))
+                    .size(18)
+                    .style(palette.focus),
+                    button(text("Back to reading"))
+                        .on_press(Message::RecallClose)
+                        .padding(10),
+                ]
+                .spacing(10)
+                .align_items(iced::Alignment::Center)
+                .into()
+            }
+        };
+
+        let content = column![
+            text("Recall check").size(28).style(palette.focus),
+            text("Which of these came up in the last minute?")
+                .size(16)
+                .style(palette.stats),
+            choices,
+            footer,
+        ]
+        .spacing(16)
+        .align_items(iced::Alignment::Center);
+
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// Put the open book aside and play the speed test passage at the
+    /// current speed
+    fn start_speed_test(&mut self) {
+        let passage = match SpeedTest::passage() {
+            Ok(passage) => passage,
+            Err(e) => {
+                self.toast(
+                    ToastLevel::Error,
+                    format!("Couldn't start the speed test: {}", e),
+                );
+                return;
+            }
+        };
+        self.save_progress();
+        self.end_session();
+        self.save_library();
+        self.recall = None;
+        self.tutorial = None;
+        self.source_watch.stop();
+        self.reload_offer = false;
+        self.speed_test = Some(SpeedTest::new(self.wpm, self.current_book_id.take()));
+        self.words = passage;
+        self.word_index = 0;
+        self.frame = (0, 0);
+        self.current_book_title = "Speed test".to_string();
+        self.update_language();
+        self.recent_words.clear();
+        self.history.clear();
+        self.is_playing = true;
+        self.clock.restart();
+    }
+
+    fn start_tutorial(&mut self) {
+        let book = match Tutorial::book() {
+            Ok(book) => book,
+            Err(e) => {
+                self.toast(
+                    ToastLevel::Error,
+                    format!("Couldn't start the tutorial: {}", e),
+                );
+                return;
+            }
+        };
+        self.save_progress();
+        self.recall = None;
+        self.speed_test = None;
+        self.tutorial = Some(Tutorial::new(&book));
+        self.current_book_id = None;
+        self.source_watch.stop();
+        self.reload_offer = false;
+        self.words = book;
+        self.word_index = 0;
+        self.frame = (0, 0);
+        self.current_book_title = "Tutorial".to_string();
+        self.update_language();
+        self.recent_words.clear();
+        self.history.clear();
+        self.is_playing = true;
+        self.clock.restart();
+    }
+
+    /// Finish or cancel the speed test and go back to the book it
+    /// interrupted
+    fn end_speed_test(&mut self) {
+        let Some(test) = self.speed_test.take() else {
+            return;
+        };
+        self.is_playing = false;
+        let resumed = test
+            .resume_book
+            .is_some_and(|book_id| self.load_book(&book_id));
+        if !resumed {
+            self.words = WordSource::default();
+            self.word_index = 0;
+            self.current_book_title.clear();
+        }
+    }
+
+    /// The speed test's questions, one at a time, then the result against
+    /// the baseline
+    fn speed_test_panel<'a>(
+        &'a self,
+        test: &'a SpeedTest,
+        palette: Palette,
+    ) -> Element<'a, Message> {
+        let body: Element<Message> = match (test.result, test.question()) {
+            (Some(result), _) => column![
+                text(result.summary(self.library.stats.speed_baseline()))
+                    .size(18)
+                    .style(palette.focus),
+                button(text("Back to reading"))
+                    .on_press(Message::SpeedTestClose)
+                    .padding(10),
+            ]
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .into(),
+            (None, Some((i, question))) => {
+                let mut choices = column![].spacing(10);
+                for (n, choice) in question.choices.iter().enumerate() {
+                    choices = choices.push(
+                        button(text(format!("{}  {}", n + 1, choice)).size(18))
+                            .style(theme::Button::Secondary)
+                            .on_press(Message::SpeedTestAnswer(n))
+                            .padding(10),
+                    );
+                }
+                column![
+                    text(format!("{}. {}", i + 1, question.prompt))
+                        .size(20)
+                        .style(palette.word),
+                    choices,
+                    text("1-4 to answer, Escape to cancel")
+                        .size(14)
+                        .style(palette.hint),
+                ]
+                .spacing(16)
+                .align_items(iced::Alignment::Center)
+                .into()
+            }
+            (None, None) => column![].into(),
+        };
+
+        let content = column![text("Speed test").size(28).style(palette.focus), body]
+            .spacing(16)
+            .align_items(iced::Alignment::Center);
+
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// The open book's saved words, each with its sentence
+    fn vocabulary_panel(&self, palette: Palette) -> Element<'_, Message> {
+        let vocabulary = self.vocabulary();
+        let mut entries = column![].spacing(10).padding([0, 20]);
+        for entry in vocabulary {
+            entries = entries.push(column![
+                text(&entry.word).size(18).style(palette.focus),
+                text(&entry.context).size(14).style(palette.stats),
+            ]);
+        }
+        let list: Element<Message> = if vocabulary.is_empty() {
+            text("No saved words yet")
+                .size(16)
+                .style(palette.hint)
+                .into()
+        } else {
+            scrollable(entries).height(Length::Fill).into()
+        };
+
+        let mut export = button(text("Export for Anki")).padding(10);
+        if !vocabulary.is_empty() {
+            export = export.on_press(Message::ExportVocabulary);
+        }
+        let buttons = row![
+            export,
+            button(text("Export all books"))
+                .style(theme::Button::Secondary)
+                .on_press(Message::ExportAllVocabulary)
+                .padding(10),
+            button(text("Close"))
+                .style(theme::Button::Secondary)
+                .on_press(Message::ToggleVocabulary)
+                .padding(10),
+        ]
+        .spacing(10);
+
+        let content = column![
+            text("Saved words").size(28).style(palette.focus),
+            list,
+            buttons,
+        ]
+        .spacing(16)
+        .align_items(iced::Alignment::Center);
+
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(20)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// The open book's links, each with a button to open it in the browser
+    fn links_panel(&self, palette: Palette) -> Element<'_, Message> {
+        let links = self.links();
+        let mut entries = column![].spacing(10).padding([0, 20]);
+        for link in links {
+            let mut label = column![text(&link.label).size(18).style(palette.focus)];
+            if link.label != link.url {
+                label = label.push(text(&link.url).size(14).style(palette.stats));
+            }
+            entries = entries.push(
+                row![
+                    label.width(Length::Fill),
+                    button(text("Open"))
+                        .style(theme::Button::Secondary)
+                        .on_press(Message::OpenLink(link.url.clone()))
+                        .padding(8),
+                ]
+                .spacing(10)
+                .align_items(iced::Alignment::Center),
+            );
+        }
+        let list: Element<Message> = if links.is_empty() {
+            text("No links were found in this book")
+                .size(16)
+                .style(palette.hint)
+                .into()
+        } else {
+            scrollable(entries).height(Length::Fill).into()
+        };
+
+        let content = column![
+            text("Links").size(28).style(palette.focus),
+            list,
+            button(text("Close"))
+                .style(theme::Button::Secondary)
+                .on_press(Message::ToggleLinks)
+                .padding(10),
+        ]
+        .spacing(16)
+        .align_items(iced::Alignment::Center);
+
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(20)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// Books in progress to carry on with, shown on launch
+    fn start_panel(&self, palette: Palette) -> Element<'_, Message> {
+        let mut entries = column![].spacing(14).padding([0, 20]);
+        for (i, book) in self.library.in_progress().into_iter().enumerate() {
+            let (pct, left) = book_progress(book, self.wpm, &self.config.pauses);
+            let mut details = format!("{:.0}%  ·  {} left", pct, left);
+            if let Some(ago) = book.read_ago() {
+                details += &format!("  ·  {}", ago);
+            }
+            // Only the first nine get a number key
+            let title = if i < 9 {
+                format!("{}  {}", i + 1, book.title)
+            } else {
+                book.title.clone()
+            };
+            entries = entries.push(
+                row![
+                    column![
+                        text(title).size(18).style(palette.focus),
+                        progress_bar(0.0..=100.0, pct as f32).height(Length::Fixed(6.0)),
+                        text(details).size(14).style(palette.stats),
+                    ]
+                    .spacing(4)
+                    .width(Length::Fill),
+                    button(text("Continue"))
+                        .on_press(Message::OpenBook(book.id.clone()))
+                        .padding(8),
+                ]
+                .spacing(10)
+                .align_items(iced::Alignment::Center),
+            );
+        }
+
+        let content = column![
+            text("Continue reading").size(28).style(palette.focus),
+            scrollable(entries).height(Length::Fill),
+            row![
+                button(text("Library"))
+                    .style(theme::Button::Secondary)
+                    .on_press(Message::ToggleLibrary)
+                    .padding(10),
+                button(text("Close"))
+                    .style(theme::Button::Secondary)
+                    .on_press(Message::CloseStart)
+                    .padding(10),
+            ]
+            .spacing(10),
+        ]
+        .spacing(16)
+        .align_items(iced::Alignment::Center);
+
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(20)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// Author and date, and the length, vocabulary and reading time of the
+    /// open book
+    fn analysis_panel(&self, palette: Palette) -> Element<'_, Message> {
+        let book = self
+            .current_book_id
+            .as_ref()
+            .and_then(|id| self.library.book(id));
+        let mut rows = book.map(Book::details).unwrap_or_default();
+        if let Some(analysis) = book.and_then(|book| book.analysis.as_ref()) {
+            let read = self.word_index as f64 / self.words.len().max(1) as f64;
+            rows.extend(analysis.summary(self.wpm, &self.pauses(), read));
+        }
+        let mut lines = column![text(&self.current_book_title).size(28).style(palette.focus)]
+            .spacing(8)
+            .padding([0, 40]);
+        for (label, value) in rows {
+            lines = lines.push(row![
+                text(label)
+                    .size(16)
+                    .style(palette.stats)
+                    .width(Length::Fixed(140.0)),
+                text(value).size(16),
+            ]);
+        }
+        lines = lines.push(
+            button(text("Back to reading"))
+                .on_press(Message::ToggleAnalysis)
+                .padding(10),
+        );
+
+        container(lines)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// Time spent, speed and progress on the open book, from the session log
+    fn stats_panel(&self, palette: Palette) -> Element<'_, Message> {
+        let mut lines = column![text(&self.current_book_title).size(28).style(palette.focus)]
+            .spacing(8)
+            .padding([0, 40]);
+        if let Some(id) = &self.current_book_id {
+            for (label, value) in book_stats(&self.library.stats, id, self.words.len()) {
+                lines = lines.push(row![
+                    text(label)
+                        .size(16)
+                        .style(palette.stats)
+                        .width(Length::Fixed(140.0)),
+                    text(value).size(16),
+                ]);
+            }
+        }
+        lines = lines.push(
+            button(text("Back to reading"))
+                .on_press(Message::ToggleStats)
+                .padding(10),
+        );
+
+        container(lines)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// Everything about a library book: its details, length, progress,
+    /// saved words and reading history
+    fn book_details_panel<'a>(&'a self, book: &'a Book, palette: Palette) -> Element<'a, Message> {
+        let mut lines = column![text(&book.title).size(28).style(palette.focus)]
+            .spacing(8)
+            .padding([0, 40]);
+        let rows = book_summary(book, &self.library.stats, self.wpm, &self.config.pauses);
+        for (label, value) in rows {
+            lines = lines.push(row![
+                text(label)
+                    .size(16)
+                    .style(palette.stats)
+                    .width(Length::Fixed(140.0)),
+                text(value).size(16),
+            ]);
+        }
+        lines = lines.push(
+            row![
+                button(text("Open"))
+                    .on_press(Message::OpenBook(book.id.clone()))
+                    .padding(10),
+                button(text("Back"))
+                    .style(theme::Button::Secondary)
+                    .on_press(Message::CloseDetails)
+                    .padding(10),
+            ]
+            .spacing(10),
+        );
+
+        container(scrollable(lines))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// Totals across the library: books, words, how much has been read, and
+    /// time and speed from the session log
+    fn library_stats_panel(&self, palette: Palette) -> Element<'_, Message> {
+        let mut lines = column![text("Library").size(28).style(palette.focus)]
+            .spacing(8)
+            .padding([0, 40]);
+        for (label, value) in library_summary(&self.library) {
+            lines = lines.push(row![
+                text(label)
+                    .size(16)
+                    .style(palette.stats)
+                    .width(Length::Fixed(180.0)),
+                text(value).size(16),
+            ]);
+        }
+        lines = lines.push(
+            button(text("Back"))
+                .on_press(Message::ToggleLibraryStats)
+                .padding(10),
+        );
+
+        container(lines)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// The book just finished: time spent, speed, sessions and streak, and
+    /// what to read next
+    fn finished_panel(&self, palette: Palette) -> Element<'_, Message> {
+        let mut lines = column![text(format!("Finished: {}", self.current_book_title))
+            .size(28)
+            .style(palette.focus)]
+        .spacing(8)
+        .padding([0, 40]);
+        if let Some(id) = &self.current_book_id {
+            for (label, value) in completion_summary(&self.library.stats, id) {
+                lines = lines.push(row![
+                    text(label)
+                        .size(16)
+                        .style(palette.stats)
+                        .width(Length::Fixed(140.0)),
+                    text(value).size(16),
+                ]);
+            }
+        }
+        let mut actions = row![
+            button(text("Read again")).on_press(Message::Reset).padding(10),
+            button(text("Library"))
+                .on_press(Message::ToggleLibrary)
+                .padding(10),
+        ]
+        .spacing(10);
+        if let Some(next) = self
+            .current_book_id
+            .as_deref()
+            .and_then(|id| self.library.next_book(id))
+        {
+            actions = actions.push(
+                button(text(format!("Next: {}", next.title)))
+                    .on_press(Message::OpenBook(next.id.clone()))
+                    .padding(10),
+            );
+        }
+        actions = actions.push(
+            button(text("Close"))
+                .on_press(Message::CloseFinished)
+                .padding(10),
+        );
+        lines = lines.push(actions);
+
+        container(lines)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// The open book's own settings, typed as `key=value` pairs, with what
+    /// each key takes
+    fn book_display_panel(&self, draft: &str, palette: Palette) -> Element<'_, Message> {
+        let names = |names: Vec<String>| names.join(", ");
+        let mut keys = vec![
+            format!(
+                "appearance: {}",
+                names(Appearance::ALL.into_iter().map(setting_name).collect())
+            ),
+            format!(
+                "colors: {} (terminal)",
+                names(ColorScheme::ALL.into_iter().map(setting_name).collect())
+            ),
+            format!("font: word size, {} by default", WORD_FONT_SIZE),
+            format!("chunk: words shown at once, up to {}", MAX_CHUNK),
+            "blocks: read, skip or show code blocks and tables".to_string(),
+            "verse: on keeps to the text's own lines".to_string(),
+        ];
+        if !self.config.profiles.is_empty() {
+            let profiles = names(self.config.profiles.keys().cloned().collect());
+            keys.push(format!("profile: {}", profiles));
+        }
+
+        let mut lines = column![
+            text(format!("Settings for {}", self.current_book_title))
+                .size(22)
+                .style(palette.focus),
+            text_input("appearance=light font=56 chunk=2", draft)
+                .id(text_input::Id::new("book_display"))
+                .on_input(Message::BookDisplayInput)
+                .on_submit(Message::FinishBookDisplay(true))
+                .padding(8),
+        ]
+        .spacing(8)
+        .padding([0, 40]);
+        for key in keys {
+            lines = lines.push(text(key).size(14).style(palette.stats));
+        }
+        lines = lines.push(
+            row![
+                button(text("Save")).on_press(Message::FinishBookDisplay(true)),
+                button(text("Cancel")).on_press(Message::FinishBookDisplay(false)),
+            ]
+            .spacing(8),
+        );
+
+        container(lines)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// Between chapters: the one just read and the one coming up
+    fn chapter_break_panel(&self, pause: &ChapterBreak, palette: Palette) -> Element<'_, Message> {
+        let mut lines = column![]
+            .spacing(8)
+            .padding([0, 40])
+            .align_items(iced::Alignment::Center);
+        if let Some(finished) = &pause.finished {
+            lines = lines
+                .push(text("Finished").size(16).style(palette.stats))
+                .push(text(finished).size(22));
+        }
+        lines = lines
+            .push(text("Up next").size(16).style(palette.stats))
+            .push(text(&pause.next).size(28).style(palette.focus))
+            .push(
+                button(text("Carry on"))
+                    .on_press(Message::EndChapterBreak)
+                    .padding(10),
+            );
+
+        container(lines)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// A footnote over the word display
+    fn footnote_panel(&self, note: &Footnote, palette: Palette) -> Element<'_, Message> {
+        let lines = column![
+            text(format!("Footnote {}", note.label))
+                .size(16)
+                .style(palette.stats),
+            scrollable(text(&note.text).size(20)),
+            button(text("Back to reading"))
+                .on_press(Message::CloseFootnote)
+                .padding(10),
+        ]
+        .spacing(12)
+        .padding([0, 40])
+        .align_items(iced::Alignment::Center);
+
+        container(lines)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// A code block or table, drawn whole as written
+    fn block_pause_panel(&self, pause: &BlockPause, palette: Palette) -> Element<'_, Message> {
+        let lines = column![
+            text(pause.kind.to_string()).size(16).style(palette.stats),
+            scrollable(text(&pause.text).size(16).font(Font::MONOSPACE)),
+            button(text("Read on"))
+                .on_press(Message::EndBlockPause)
+                .padding(10),
+        ]
+        .spacing(12)
+        .padding([0, 40])
+        .align_items(iced::Alignment::Center);
+
+        container(lines)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// Timing figures in a line under the stats bar
+    fn diagnostics_panel(&self, palette: Palette) -> Element<'_, Message> {
+        let figures: Vec<String> = self
+            .diagnostics
+            .borrow()
+            .summary()
+            .into_iter()
+            .map(|(label, value)| format!("{}: {}", label, value))
+            .collect();
+        container(text(figures.join("  │  ")).size(13).style(palette.stats))
+            .width(Length::Fill)
+            .padding([0, 10, 10, 10])
+            .center_x()
+            .into()
+    }
+
+    /// Every shortcut the desktop app understands, with its current keys
+    fn help_panel(&self, palette: Palette) -> Element<'_, Message> {
+        let shortcuts = [
+            (Action::PlayPause, "Start/Pause reading"),
+            (Action::SpeedUp, "Increase speed"),
+            (Action::SpeedDown, "Decrease speed"),
+            (Action::PrevWord, "Previous word"),
+            (Action::NextWord, "Next word"),
+            (Action::SentenceStart, "Start of sentence"),
+            (Action::ParagraphStart, "Start of paragraph"),
+            (Action::PrevSentence, "Previous sentence"),
+            (Action::NextSentence, "Next sentence"),
+            (Action::PrevParagraph, "Previous paragraph"),
+            (Action::NextParagraph, "Next paragraph"),
+            (Action::JumpBack, "Back to the position before a jump"),
+            (Action::JumpForward, "Forward again"),
+            (Action::ToggleLoop, "Loop sentence, then paragraph"),
+            (Action::MarkLoop, "Mark loop point A, then B"),
+            (Action::Reset, "Reset to beginning"),
+            (Action::Library, "Library"),
+            (Action::Playlists, "Playlists"),
+            (Action::Import, "Import files"),
+            (Action::CycleTheme, "Next theme"),
+            (Action::CycleColorVision, "Color blindness palette"),
+            (Action::CycleProgress, "Progress units"),
+            (Action::CyclePacing, "Pace by words, characters or syllables"),
+            (Action::BookDisplay, "This book's theme and pacing"),
+            (Action::ToggleZen, "Zen mode"),
+            (Action::ToggleCompact, "Compact always-on-top overlay"),
+            (Action::ScaleUp, "Zoom in"),
+            (Action::ScaleDown, "Zoom out"),
+            (Action::ToggleDebug, "Timing diagnostics"),
+            (Action::Narrate, "Narration on/off"),
+            (Action::ToggleSkim, "Skim function words"),
+            (Action::ToggleKeywords, "Keyword emphasis"),
+            (Action::RecallCheck, "Recall check"),
+            (Action::SaveWord, "Save word to vocabulary"),
+            (Action::Vocabulary, "Saved words"),
+            (Action::ShowFootnote, "Show the footnote just passed"),
+            (Action::Links, "Links in the book"),
+            (Action::Analysis, "Book analysis"),
+            (Action::Stats, "Reading statistics"),
+            (Action::SpeedTest, "Speed test"),
+            (Action::Recents, "Books in progress"),
+            (Action::ReloadConfig, "Reload config"),
+            (Action::Help, "Keyboard shortcuts"),
+            (Action::Quit, "Quit"),
+        ];
+
+        let mut lines = column![text("Keyboard Shortcuts").size(20)].spacing(6);
+        for (action, description) in shortcuts {
+            let keys: Vec<String> = self
+                .config
+                .keys
+                .keys(action)
+                .iter()
+                // Media keys are labelled on the keyboard already
+                .filter(|key| !key.key.is_media())
+                .map(|key| key.to_string())
+                .collect();
+            lines = lines.push(row![
+                text(keys.join(" / "))
+                    .size(15)
+                    .style(palette.focus)
+                    .width(Length::Fixed(140.0)),
+                text(description).size(15),
+            ]);
+        }
+        let presets: Vec<String> = self
+            .config
+            .speed_presets
+            .iter()
+            .take(9)
+            .map(|wpm| wpm.to_string())
+            .collect();
+        lines = lines.push(row![
+            text("1-9")
+                .size(15)
+                .style(palette.focus)
+                .width(Length::Fixed(140.0)),
+            text(format!("Speed presets: {}", presets.join(", "))).size(15),
+        ]);
+
+        container(lines)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// Carry out a choice from the tray icon's menu
+    #[cfg(feature = "tray")]
+    fn tray_action(&mut self, action: tray::TrayAction) -> Command<Message> {
+        let show = Command::batch([
+            window::change_mode(window::Id::MAIN, window::Mode::Windowed),
+            window::gain_focus(window::Id::MAIN),
+        ]);
+        match action {
+            tray::TrayAction::PlayPause => self.update(Message::TogglePlay),
+            tray::TrayAction::ContinueLast => {
+                if self.current_book_id.is_none() {
+                    if let Some(book_id) = self.library.last_read().map(|book| book.id.clone()) {
+                        self.load_book(&book_id);
+                    }
+                }
+                let play = if self.is_playing {
+                    Command::none()
+                } else {
+                    self.update(Message::TogglePlay)
+                };
+                Command::batch([play, show])
+            }
+            tray::TrayAction::Show => show,
+            tray::TrayAction::Quit => self.update(Message::Quit),
+        }
+    }
+
+    /// Open a book the browser extension has imported, or files another
+    /// launch was asked to open, and bring the window up to read it
+    fn inbox_request(&mut self, request: Request) -> Command<Message> {
+        match request {
+            Request::Open { book_id } => {
+                self.save_progress();
+                // The book was added to the library file by another process
+                self.library.merge(load_library());
+                if !self.load_book(&book_id) {
+                    self.toast(ToastLevel::Error, "Book file not found");
+                    return Command::none();
+                }
+                self.info(format!("Opened: {}", self.current_book_title));
+                let play = if self.is_playing || self.breaks.on_break() {
+                    Command::none()
+                } else {
+                    self.update(Message::TogglePlay)
+                };
+                Command::batch([play, self.raise_window()])
+            }
+            Request::Import { paths } => {
+                self.save_progress();
+                let opened = self.open_paths(paths);
+                Command::batch([opened, self.raise_window()])
+            }
+        }
+    }
+
+    /// Import files or a folder named on the command line
+    fn open_paths(&mut self, paths: Vec<PathBuf>) -> Command<Message> {
+        match paths.as_slice() {
+            [dir] if dir.is_dir() => self.update(Message::FolderOpened(Some(dir.clone()))),
+            _ => self.update(Message::FilesOpened(paths)),
+        }
+    }
+
+    /// Bring the window to the front
+    fn raise_window(&self) -> Command<Message> {
+        // Zen mode is already fullscreen; otherwise the window may be
+        // hidden in the tray
+        let show = if self.zen {
+            Command::none()
+        } else {
+            window::change_mode(window::Id::MAIN, window::Mode::Windowed)
+        };
+        Command::batch([show, window::gain_focus(window::Id::MAIN)])
+    }
+
+    /// Tell screen readers what is on screen now
+    fn sync_accessibility(&mut self) {
+        // No countdown here: the status is announced each time it changes
+        let status = if self.breaks.on_break() {
+            "On a break".to_string()
+        } else if self.words.is_empty() {
+            "No book open".to_string()
+        } else if self.is_playing {
+            "Playing".to_string()
+        } else {
+            "Paused".to_string()
+        };
+        let progress = if self.words.is_empty() {
+            0.0
+        } else {
+            self.word_index as f64 / self.words.len() as f64 * 100.0
+        };
+        self.accessibility.update(Snapshot {
+            title: self.title(),
+            word: self.words.get(self.word_index).unwrap_or_default(),
+            status,
+            playing: self.is_playing,
+            wpm: self.wpm,
+            wpm_range: (self.config.min_wpm, self.config.max_wpm),
+            progress,
+        });
+    }
+
+    /// Handle a media key or a request from a "now playing" widget
+    fn media_action(&mut self, action: MediaAction) -> Command<Message> {
+        match action {
+            MediaAction::PlayPause => self.update(Message::TogglePlay),
+            MediaAction::Play if !self.is_playing => self.update(Message::TogglePlay),
+            MediaAction::Pause | MediaAction::Stop if self.is_playing => {
+                self.update(Message::TogglePlay)
+            }
+            MediaAction::Play | MediaAction::Pause | MediaAction::Stop => Command::none(),
+            MediaAction::Next => self.update(Message::NextSentence),
+            MediaAction::Previous => self.update(Message::PrevSentence),
+            MediaAction::Seek(offset) => {
+                let position = self.reading_time(self.word_index).as_micros() as i64;
+                self.seek_to(position.saturating_add(offset));
+                Command::none()
+            }
+            MediaAction::SetPosition(position) => {
+                self.seek_to(position);
+                Command::none()
+            }
+            MediaAction::Raise => self.raise_window(),
+            MediaAction::Quit => self.update(Message::Quit),
+        }
+    }
+
+    /// How long reading up to word `index` takes at the set speed
+    fn reading_time(&self, index: usize) -> Duration {
+        Duration::from_secs_f64(index as f64 * 60.0 / f64::from(self.wpm.max(1)))
+    }
+
+    /// Jump to the word `position` microseconds of reading into the book
+    fn seek_to(&mut self, position: i64) {
+        if self.words.is_empty() {
+            return;
+        }
+        let words = position.max(0) as f64 / 60_000_000.0 * f64::from(self.wpm);
+        self.jump((words as usize).min(self.words.len() - 1));
+        let position = self.reading_time(self.word_index).as_micros() as i64;
+        if let Some(media) = &self.media {
+            media.seeked(position);
+        }
+    }
+
+    /// Tell media widgets what is open and whether it is playing
+    fn sync_media(&mut self) {
+        let now = NowPlaying {
+            book_id: self.current_book_id.clone(),
+            title: self.current_book_title.clone(),
+            playing: self.is_playing,
+            position: self.reading_time(self.word_index),
+            length: self.reading_time(self.words.len()),
+        };
+        if let Some(media) = &mut self.media {
+            media.update(now);
+        }
+    }
+
+    /// Show a toast, making room by dropping the oldest
+    fn toast(&mut self, level: ToastLevel, text: impl Into<String>) {
+        if self.toasts.len() >= MAX_TOASTS {
+            self.toasts.remove(0);
+        }
+        self.toasts.push(Toast::new(level, text));
+    }
+
+    fn info(&mut self, text: impl Into<String>) {
+        self.toast(ToastLevel::Info, text);
+    }
+
+    /// Where the window is, kept with the settings to reopen it there
+    fn window_geometry(&mut self) -> &mut WindowGeometry {
+        let width = self.window_width;
+        self.library
+            .settings
+            .window
+            .get_or_insert(WindowGeometry {
+                width,
+                height: 500.0,
+                position: None,
+                fullscreen: false,
+            })
+    }
+
+    /// Save the library, saying so if it fails
+    fn save_library(&mut self) {
+        if let Err(e) = self.library_watch.save(&self.library) {
+            tracing::error!("Couldn't save the library: {}", e);
+            self.toast(
+                ToastLevel::Error,
+                format!("Couldn't save the library: {}", e),
+            );
+        }
+    }
+
+    /// Read the open book again from the file it was imported from
+    fn reload_book(&mut self) {
+        self.reload_offer = false;
+        let Some(book_id) = self.current_book_id.clone() else {
+            return;
+        };
+        self.save_progress();
+        if let Err(e) = reload_book(&mut self.library, &book_id, &self.config.pipeline) {
+            self.toast(ToastLevel::Error, format!("Couldn't reload: {}", e));
+            return;
+        }
+        self.save_library();
+        if self.load_book(&book_id) {
+            self.info(format!("Reloaded: {}", self.current_book_title));
+        }
+    }
+
+    /// Follow what the other frontend, or a sync tool, saved to the library:
+    /// its speed, and its place in this book unless reading is going on
+    /// here, whose own place is saved over it
+    fn sync_library(&mut self) {
+        let Some(on_disk) = self.library_watch.poll() else {
+            return;
+        };
+        let changes = self.library.sync(on_disk, self.current_book_id.as_deref());
+        if let Some(wpm) = changes.wpm {
+            self.wpm = self.config.clamp_wpm(wpm);
+        }
+        let Some((progress, offset)) = changes.progress.filter(|_| !self.is_playing) else {
+            return;
+        };
+        let index = match offset {
+            Some(offset) => self.words.index_at_offset(offset),
+            None => progress.min(self.words.len().saturating_sub(1)),
+        };
+        if index != self.word_index {
+            self.jump(index);
+            self.info("Caught up with reading saved elsewhere");
+        }
+    }
+
+    /// The active light, dark or high-contrast palette, with any color
+    /// blindness preset and colors set in config.toml
+    fn palette(&self) -> Palette {
+        let mut palette = match self.appearance() {
+            Appearance::HighContrast => Palette::HIGH_CONTRAST,
+            Appearance::Night => Palette::NIGHT,
+            Appearance::TrueBlack => Palette::TRUE_BLACK,
+            _ if self.dark => Palette::DARK,
+            _ => Palette::LIGHT,
+        };
+        if let Some(safe) = self.library.settings.color_vision.colors() {
+            palette.focus = rgb(safe.focus);
+            palette.guide = rgb(safe.guide);
+        }
+        let colors = &self.config.theme;
+        palette.word = theme_color(&colors.word, palette.word);
+        palette.dialogue = theme_color(&colors.dialogue_color, palette.dialogue);
+        palette.focus = if colors.focus_has(FocusStyle::Color) {
+            theme_color(&colors.focus, palette.focus)
+        } else {
+            palette.word
+        };
+        palette.background = theme_color(&colors.background, palette.background);
+        palette.dimmed(self.brightness())
+    }
+
+    /// How bright everything but the word is drawn, with auto-dim
+    fn brightness(&self) -> f32 {
+        self.dimmer
+            .brightness(self.is_playing, &self.config.auto_dim)
+    }
+
+    /// Re-read `config.toml`, keeping the current settings if it has errors
+    fn reload_config(&mut self) {
+        match load_config() {
+            Ok(config) => {
+                self.config = config;
+                self.wpm = self.config.clamp_wpm(self.wpm);
+                self.update_language();
+                self.night.recheck();
+                self.update_night();
+                self.narration.reset();
+                self.register_global_hotkey();
+                self.info("Config reloaded");
+                if let Some(conflict) = self.config.keys.conflicts().into_iter().next() {
+                    self.toast(ToastLevel::Warning, conflict);
+                }
+            }
+            Err(e) => self.toast(ToastLevel::Error, e),
+        }
+    }
+
+    /// Grab the configured play/pause key system-wide, releasing any
+    /// earlier one
+    fn register_global_hotkey(&mut self) {
+        if let (Some(manager), Some(old)) = (&self.hotkeys, self.global_hotkey.take()) {
+            if let Err(e) = manager.unregister(old) {
+                tracing::warn!("Couldn't release the global hotkey: {}", e);
+            }
+        }
+        let Some(chord) = self.config.global_hotkey else {
+            return;
+        };
+        let Some(manager) = &self.hotkeys else {
+            self.toast(ToastLevel::Warning, "Global hotkeys aren't available here");
+            return;
+        };
+
+        let registered = chord
+            .to_string()
+            .parse::<HotKey>()
+            .map_err(|e| e.to_string())
+            .and_then(|key| manager.register(key).map(|()| key).map_err(|e| e.to_string()));
+        match registered {
+            Ok(key) => self.global_hotkey = Some(key),
+            Err(e) => self.toast(ToastLevel::Error, format!("Global hotkey {}: {}", chord, e)),
+        }
+    }
+
+    fn save_progress(&mut self) {
+        self.autosave.saved();
+        if let Some(ref book_id) = self.current_book_id {
+            if let Some(book) = self.library.books.iter_mut().find(|b| b.id == *book_id) {
+                book.set_progress(self.word_index, self.words.offset_of(self.word_index));
+            }
+            self.save_library();
+        }
+    }
+}
+
+/// Translate an iced key press into the form keybindings are written in
+fn key_chord(key: &Key, modifiers: keyboard::Modifiers) -> Option<KeyChord> {
+    use keyboard::key::Named;
+
+    let key = match key.as_ref() {
+        Key::Named(Named::Space) => KeyName::Space,
+        Key::Named(Named::Enter) => KeyName::Enter,
+        Key::Named(Named::Tab) => KeyName::Tab,
+        Key::Named(Named::Backspace) => KeyName::Backspace,
+        Key::Named(Named::Escape) => KeyName::Esc,
+        Key::Named(Named::ArrowUp) => KeyName::Up,
+        Key::Named(Named::ArrowDown) => KeyName::Down,
+        Key::Named(Named::ArrowLeft) => KeyName::Left,
+        Key::Named(Named::ArrowRight) => KeyName::Right,
+        Key::Named(Named::Home) => KeyName::Home,
+        Key::Named(Named::End) => KeyName::End,
+        Key::Named(Named::PageUp) => KeyName::PageUp,
+        Key::Named(Named::PageDown) => KeyName::PageDown,
+        Key::Named(Named::F1) => KeyName::F(1),
+        Key::Named(Named::F2) => KeyName::F(2),
+        Key::Named(Named::F3) => KeyName::F(3),
+        Key::Named(Named::F4) => KeyName::F(4),
+        Key::Named(Named::F5) => KeyName::F(5),
+        Key::Named(Named::F6) => KeyName::F(6),
+        Key::Named(Named::F7) => KeyName::F(7),
+        Key::Named(Named::F8) => KeyName::F(8),
+        Key::Named(Named::F9) => KeyName::F(9),
+        Key::Named(Named::F10) => KeyName::F(10),
+        Key::Named(Named::F11) => KeyName::F(11),
+        Key::Named(Named::F12) => KeyName::F(12),
+        Key::Named(Named::MediaPlayPause) => KeyName::MediaPlayPause,
+        Key::Named(Named::MediaTrackNext) => KeyName::MediaNext,
+        Key::Named(Named::MediaTrackPrevious) => KeyName::MediaPrev,
+        Key::Character(" ") => KeyName::Space,
+        Key::Character(c) => {
+            let mut chars = c.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyName::Char(c),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    Some(KeyChord {
+        key,
+        ctrl: modifiers.control(),
+        alt: modifiers.alt(),
+        shift: modifiers.shift(),
+    })
+}
+
+// ============================================================================
+// Appearance
+// ============================================================================
+
+/// Colors for one variant of the window: light, dark or high contrast
+#[derive(Debug, Clone, Copy)]
+struct Palette {
+    background: Color,
+    text: Color,
+    word: Color,
+    // Quoted dialogue, with `dialogue = "color"`
+    dialogue: Color,
+    focus: Color,
+    stats: Color,
+    hint: Color,
+    guide: Color,
+    // Toast text by level
+    info: Color,
+    warning: Color,
+    error: Color,
+    // Library sidebar and its highlighted row
+    panel: Color,
+    selection: Color,
+}
+
+impl Palette {
+    const DARK: Palette = Palette {
+        background: Color::from_rgb(0.1, 0.1, 0.12),
+        text: Color::WHITE,
+        word: Color::from_rgb(0.9, 0.9, 0.9),
+        dialogue: Color::from_rgb(0.45, 0.75, 0.95),
+        focus: Color::from_rgb(0.9, 0.2, 0.2),
+        stats: Color::from_rgb(0.6, 0.6, 0.6),
+        hint: Color::from_rgb(0.5, 0.5, 0.5),
+        guide: Color::from_rgb(0.3, 0.3, 0.32),
+        info: Color::from_rgb(0.7, 0.7, 0.3),
+        warning: Color::from_rgb(0.95, 0.6, 0.2),
+        error: Color::from_rgb(0.95, 0.35, 0.35),
+        panel: Color::from_rgb(0.13, 0.13, 0.16),
+        selection: Color::from_rgb(0.2, 0.2, 0.26),
+    };
+
+    const LIGHT: Palette = Palette {
+        background: Color::from_rgb(0.97, 0.96, 0.93),
+        text: Color::BLACK,
+        word: Color::from_rgb(0.12, 0.12, 0.12),
+        dialogue: Color::from_rgb(0.1, 0.4, 0.7),
+        focus: Color::from_rgb(0.8, 0.1, 0.1),
+        stats: Color::from_rgb(0.4, 0.4, 0.4),
+        hint: Color::from_rgb(0.55, 0.55, 0.55),
+        guide: Color::from_rgb(0.78, 0.77, 0.74),
+        info: Color::from_rgb(0.5, 0.4, 0.0),
+        warning: Color::from_rgb(0.75, 0.4, 0.0),
+        error: Color::from_rgb(0.75, 0.1, 0.1),
+        panel: Color::from_rgb(0.93, 0.92, 0.88),
+        selection: Color::from_rgb(0.85, 0.84, 0.79),
+    };
+
+    // Everything well past WCAG AAA contrast against pure black
+    const HIGH_CONTRAST: Palette = Palette {
+        background: Color::BLACK,
+        text: Color::WHITE,
+        word: Color::WHITE,
+        dialogue: Color::from_rgb(0.0, 1.0, 1.0),
+        focus: Color::from_rgb(1.0, 1.0, 0.0),
+        stats: Color::from_rgb(0.85, 0.85, 0.85),
+        hint: Color::from_rgb(0.8, 0.8, 0.8),
+        guide: Color::from_rgb(0.75, 0.75, 0.75),
+        info: Color::from_rgb(1.0, 1.0, 0.0),
+        warning: Color::from_rgb(1.0, 0.75, 0.3),
+        error: Color::from_rgb(1.0, 0.6, 0.6),
+        panel: Color::BLACK,
+        selection: Color::from_rgb(0.2, 0.2, 0.2),
+    };
+
+    // Unlit on OLED screens, panels included
+    const TRUE_BLACK: Palette = Palette {
+        background: Color::BLACK,
+        text: Color::from_rgb(0.85, 0.85, 0.85),
+        word: Color::from_rgb(0.88, 0.88, 0.88),
+        dialogue: Color::from_rgb(0.54, 0.71, 0.97),
+        focus: Color::from_rgb(0.9, 0.23, 0.23),
+        stats: Color::from_rgb(0.55, 0.55, 0.55),
+        hint: Color::from_rgb(0.45, 0.45, 0.45),
+        guide: Color::from_rgb(0.25, 0.25, 0.25),
+        info: Color::from_rgb(0.7, 0.7, 0.3),
+        warning: Color::from_rgb(0.95, 0.6, 0.2),
+        error: Color::from_rgb(0.95, 0.35, 0.35),
+        panel: Color::BLACK,
+        selection: Color::from_rgb(0.15, 0.15, 0.15),
+    };
+
+    // Dim amber on near-black: little blue light and little glare
+    const NIGHT: Palette = Palette {
+        background: Color::from_rgb(0.05, 0.035, 0.025),
+        text: Color::from_rgb(0.72, 0.58, 0.42),
+        word: Color::from_rgb(0.78, 0.6, 0.4),
+        dialogue: Color::from_rgb(0.75, 0.48, 0.23),
+        focus: Color::from_rgb(0.85, 0.35, 0.15),
+        stats: Color::from_rgb(0.5, 0.4, 0.3),
+        hint: Color::from_rgb(0.42, 0.33, 0.25),
+        guide: Color::from_rgb(0.2, 0.15, 0.11),
+        info: Color::from_rgb(0.7, 0.55, 0.25),
+        warning: Color::from_rgb(0.85, 0.5, 0.2),
+        error: Color::from_rgb(0.85, 0.35, 0.25),
+        panel: Color::from_rgb(0.08, 0.06, 0.045),
+        selection: Color::from_rgb(0.16, 0.12, 0.09),
+    };
+}
+
+impl Palette {
+    /// Everything but the word, its focal letter and guide faded toward the
+    /// background, to `brightness` of full
+    fn dimmed(self, brightness: f32) -> Self {
+        if brightness >= 1.0 {
+            return self;
+        }
+        let fade = |color| fade(color, self.background, brightness);
+        Self {
+            text: fade(self.text),
+            stats: fade(self.stats),
+            hint: fade(self.hint),
+            info: fade(self.info),
+            warning: fade(self.warning),
+            error: fade(self.error),
+            panel: fade(self.panel),
+            selection: fade(self.selection),
+            ..self
+        }
+    }
+}
+
+/// An iced color from a preset's RGB
+fn rgb((r, g, b): (u8, u8, u8)) -> Color {
+    Color::from_rgb8(r, g, b)
+}
+
+/// `color` mixed with `background`, `brightness` of the way from it
+fn fade(color: Color, background: Color, brightness: f32) -> Color {
+    let mix = |c: f32, bg: f32| bg + (c - bg) * brightness;
+    Color {
+        r: mix(color.r, background.r),
+        g: mix(color.g, background.g),
+        b: mix(color.b, background.b),
+        a: color.a,
+    }
+}
+
+/// Resolve an appearance setting, asking the desktop when it is `System`
+fn is_dark(appearance: Appearance) -> bool {
+    match appearance {
+        Appearance::Dark => true,
+        Appearance::Light => false,
+        Appearance::HighContrast => true,
+        Appearance::Night => true,
+        Appearance::TrueBlack => true,
+        Appearance::System => dark_light::detect() != dark_light::Mode::Light,
+    }
+}
+
+/// A solid block of color, used to draw guide marks
+fn rule<'a>(width: f32, height: f32, color: Color) -> Element<'a, Message> {
+    container(Space::new(Length::Fixed(width), Length::Fixed(height)))
+        .style(theme::Container::Custom(Box::new(AppContainer {
+            background: color,
+            text: color,
+        })))
+        .into()
+}
+
+/// Part of the word around the focal letter, a grapheme per cell, in the
+/// configured letter case, styled as dialogue inside quotes and with
+/// keyword emphasis if it is on
+fn word_part<'a>(
+    word: &str,
+    range: Range<usize>,
+    palette: &Palette,
+    keywords: bool,
+    case: LetterCase,
+    dialogue: Option<DialogueStyle>,
+    size: u16,
+) -> Element<'a, Message> {
+    let (word_color, style) = match dialogue {
+        Some(DialogueStyle::Color) => (palette.dialogue, font::Style::Normal),
+        Some(DialogueStyle::Italic) => (palette.word, font::Style::Italic),
+        _ => (palette.word, font::Style::Normal),
+    };
+    let runs = if keywords {
+        emphasis_runs(word, range)
+    } else {
+        vec![(&word[range], Emphasis::Normal)]
+    };
+    let mut part = row![];
+    for (run, emphasis) in runs {
+        let (color, weight) = match emphasis {
+            Emphasis::Dim => (palette.hint, font::Weight::Normal),
+            Emphasis::Normal => (word_color, font::Weight::Normal),
+            Emphasis::Strong => (word_color, font::Weight::Bold),
+        };
+        let font = Font {
+            weight,
+            style,
+            ..Font::MONOSPACE
+        };
+        for grapheme in run.graphemes(true) {
+            part = part.push(
+                text(case.apply(grapheme))
+                    .size(size)
+                    .style(color)
+                    .font(font),
+            );
+        }
+    }
+    part.into()
+}
+
+/// Surround the word with the configured focal guide. The word row keeps
+/// its focal letter at its horizontal center, so centered marks line up with it.
+fn focal_guide<'a>(
+    guide: FocalGuide,
+    word: Element<'a, Message>,
+    color: Color,
+    width: f32,
+) -> Element<'a, Message> {
+    let gap = || Space::with_height(12);
+    let bar = || rule(2.0, 24.0, color);
+    match guide {
+        FocalGuide::None => word,
+        FocalGuide::Bars => column![bar(), gap(), word, gap(), bar()]
+            .align_items(iced::Alignment::Center)
+            .into(),
+        FocalGuide::Crosshair => {
+            let middle = row![
+                rule(32.0, 2.0, color),
+                Space::with_width(24),
+                word,
+                Space::with_width(24),
+                rule(32.0, 2.0, color),
+            ]
+            .align_items(iced::Alignment::Center);
+            column![bar(), gap(), middle, gap(), bar()]
+                .align_items(iced::Alignment::Center)
+                .into()
+        }
+        FocalGuide::Reticle => column![
+            rule(width, 2.0, color),
+            rule(2.0, 12.0, color),
+            gap(),
+            word,
+            gap(),
+            rule(2.0, 12.0, color),
+            rule(width, 2.0, color),
+        ]
+        .align_items(iced::Alignment::Center)
+        .into(),
+    }
+}
+
+/// A color from the theme config, or `default` if it is unset or invalid
+fn theme_color(value: &Option<String>, default: Color) -> Color {
+    value
+        .as_deref()
+        .and_then(parse_color)
+        .map_or(default, |(r, g, b)| Color::from_rgb8(r, g, b))
+}
+
+// Background container style for the active palette
+struct AppContainer {
+    background: Color,
+    text: Color,
+}
+
+impl container::StyleSheet for AppContainer {
+    type Style = Theme;
+
+    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            background: Some(iced::Background::Color(self.background)),
+            text_color: Some(self.text),
+            ..Default::default()
+        }
+    }
+}
+
+// ============================================================================
+// Toasts
+// ============================================================================
+
+/// How long a toast stays on screen
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+/// Toasts shown at once; a new one pushes out the oldest
+const MAX_TOASTS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ToastLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+/// A short message that dismisses itself after `TOAST_DURATION`
+struct Toast {
+    text: String,
+    level: ToastLevel,
+    shown: Instant,
+}
+
+impl Toast {
+    fn new(level: ToastLevel, text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            level,
+            shown: Instant::now(),
+        }
+    }
+}
+
+// ============================================================================
+// Menus
+// ============================================================================
+
+const MENU_WIDTH: f32 = 70.0;
+const MENU_DROPDOWN_WIDTH: f32 = 220.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Menu {
+    File,
+    View,
+    Help,
+}
+
+impl Menu {
+    const ALL: [Menu; 3] = [Menu::File, Menu::View, Menu::Help];
+}
+
+impl std::fmt::Display for Menu {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            Menu::File => "File",
+            Menu::View => "View",
+            Menu::Help => "Help",
+        })
+    }
+}
+
+// ============================================================================
+// Tray
+// ============================================================================
+
+#[cfg(feature = "tray")]
+mod tray {
+    use tray_icon::menu::MenuEvent;
+
+    /// Entries in the tray icon's menu
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum TrayAction {
+        PlayPause,
+        ContinueLast,
+        Show,
+        Quit,
+    }
+
+    const ENTRIES: [(TrayAction, &str, &str); 4] = [
+        (TrayAction::PlayPause, "play_pause", "Play/Pause"),
+        (TrayAction::ContinueLast, "continue", "Continue last book"),
+        (TrayAction::Show, "show", "Show window"),
+        (TrayAction::Quit, "quit", "Quit"),
+    ];
+
+    /// Put the icon in the tray, returning whether there will be one. It
+    /// lives on its own thread running the GTK main loop it needs.
+    #[cfg(target_os = "linux")]
+    pub fn spawn() -> bool {
+        use tray_icon::menu::{Menu, MenuItem};
+        use tray_icon::TrayIconBuilder;
+
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            if gtk::init().is_err() {
+                let _ = ready_tx.send(false);
+                return;
+            }
+            let menu = Menu::new();
+            for (_, id, label) in ENTRIES {
+                let _ = menu.append(&MenuItem::with_id(id, label, true, None));
+            }
+            let mut builder = TrayIconBuilder::new()
+                .with_menu(Box::new(menu))
+                .with_tooltip("RSVP Reader");
+            if let Some(icon) = icon() {
+                builder = builder.with_icon(icon);
+            }
+            let Ok(_tray) = builder.build() else {
+                let _ = ready_tx.send(false);
+                return;
+            };
+            let _ = ready_tx.send(true);
+            gtk::main();
+        });
+        ready_rx.recv().unwrap_or(false)
+    }
+
+    /// Elsewhere the icon has to live on the main thread's event loop,
+    /// which iced owns
+    #[cfg(not(target_os = "linux"))]
+    pub fn spawn() -> bool {
+        false
+    }
+
+    /// Menu entries chosen since the last poll
+    pub fn poll() -> Vec<TrayAction> {
+        MenuEvent::receiver()
+            .try_iter()
+            .filter_map(|event| {
+                ENTRIES
+                    .iter()
+                    .find(|(_, id, _)| event.id == *id)
+                    .map(|&(action, _, _)| action)
+            })
+            .collect()
+    }
+
+    /// A red focal dot on a dark disc, drawn rather than shipped as a file
+    #[cfg(target_os = "linux")]
+    fn icon() -> Option<tray_icon::Icon> {
+        const SIZE: u32 = 32;
+        let center = (SIZE as f32 - 1.0) / 2.0;
+        let mut rgba = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let distance = (x as f32 - center).hypot(y as f32 - center);
+                let pixel = if distance < 6.0 {
+                    [230, 51, 51, 255]
+                } else if distance < center {
+                    [26, 26, 31, 255]
+                } else {
+                    [0, 0, 0, 0]
+                };
+                rgba.extend_from_slice(&pixel);
+            }
+        }
+        tray_icon::Icon::from_rgba(rgba, SIZE, SIZE).ok()
+    }
+}
+
+// ============================================================================
+// Accessibility
+// ============================================================================
+
+/// Exposes the reader to screen readers: the current word, whether it is
+/// playing, progress and speed, and buttons for the main controls. iced has
+/// no accessibility tree of its own, so this is a small parallel one.
+mod accessibility {
+    /// Controls a screen reader can operate
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum A11yAction {
+        PlayPause,
+        SpeedUp,
+        SpeedDown,
+        PrevWord,
+        NextWord,
+    }
+
+    /// What assistive technology is told about the app
+    #[derive(Debug, Clone, Default, PartialEq)]
+    pub struct Snapshot {
+        pub title: String,
+        pub word: String,
+        /// "Playing", "Paused", a break countdown, and so on
+        pub status: String,
+        pub playing: bool,
+        pub wpm: u32,
+        /// Slowest and fastest speeds allowed
+        pub wpm_range: (u32, u32),
+        /// Percent through the book
+        pub progress: f64,
+    }
+
+    #[cfg(target_os = "linux")]
+    pub use self::atspi::Accessibility;
+
+    #[cfg(target_os = "linux")]
+    mod atspi {
+        use super::{A11yAction, Snapshot};
+        use accesskit::{
+            Action, ActionHandler, ActionRequest, ActivationHandler, DeactivationHandler, Live,
+            Node, NodeId, Role, Tree, TreeUpdate,
+        };
+        use accesskit_unix::Adapter;
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::mpsc::{channel, Receiver, Sender};
+        use std::sync::{Arc, Mutex};
+
+        const WINDOW: NodeId = NodeId(0);
+        const WORD: NodeId = NodeId(1);
+        const STATUS: NodeId = NodeId(2);
+        const PROGRESS: NodeId = NodeId(3);
+        const SPEED: NodeId = NodeId(4);
+        const PLAY: NodeId = NodeId(5);
+        const PREV: NodeId = NodeId(6);
+        const NEXT: NodeId = NodeId(7);
+
+        /// The app's tree on the AT-SPI bus. Screen readers activate it when
+        /// they start; until then updates are skipped.
+        pub struct Accessibility {
+            adapter: Adapter,
+            shown: Arc<Mutex<Snapshot>>,
+            active: Arc<AtomicBool>,
+            actions: Receiver<A11yAction>,
+        }
+
+        impl Accessibility {
+            pub fn new() -> Self {
+                let shown = Arc::new(Mutex::new(Snapshot::default()));
+                let active = Arc::new(AtomicBool::new(false));
+                let (sender, actions) = channel();
+                let adapter = Adapter::new(
+                    Activation {
+                        shown: Arc::clone(&shown),
+                        active: Arc::clone(&active),
+                    },
+                    Actions(sender),
+                    Deactivation(Arc::clone(&active)),
+                );
+                Self {
+                    adapter,
+                    shown,
+                    active,
+                    actions,
+                }
+            }
+
+            /// Whether a screen reader is listening
+            pub fn active(&self) -> bool {
+                self.active.load(Ordering::Relaxed)
+            }
+
+            /// Publish the app's state if it changed since the last call
+            pub fn update(&mut self, snapshot: Snapshot) {
+                let mut shown = self.shown.lock().unwrap();
+                if *shown == snapshot {
+                    return;
+                }
+                *shown = snapshot;
+                self.adapter.update_if_active(|| tree(&shown));
+            }
+
+            pub fn focus_changed(&mut self, focused: bool) {
+                self.adapter.update_window_focus_state(focused);
+            }
+
+            /// Controls operated since the last poll
+            pub fn poll(&self) -> Vec<A11yAction> {
+                self.actions.try_iter().collect()
+            }
+        }
+
+        struct Activation {
+            shown: Arc<Mutex<Snapshot>>,
+            active: Arc<AtomicBool>,
+        }
+
+        impl ActivationHandler for Activation {
+            fn request_initial_tree(&mut self) -> Option<TreeUpdate> {
+                self.active.store(true, Ordering::Relaxed);
+                Some(tree(&self.shown.lock().unwrap()))
+            }
+        }
+
+        struct Deactivation(Arc<AtomicBool>);
+
+        impl DeactivationHandler for Deactivation {
+            fn deactivate_accessibility(&mut self) {
+                self.0.store(false, Ordering::Relaxed);
+            }
+        }
+
+        struct Actions(Sender<A11yAction>);
+
+        impl ActionHandler for Actions {
+            fn do_action(&mut self, request: ActionRequest) {
+                let action = match (request.target, request.action) {
+                    (PLAY, Action::Click) => A11yAction::PlayPause,
+                    (PREV, Action::Click) => A11yAction::PrevWord,
+                    (NEXT, Action::Click) => A11yAction::NextWord,
+                    (SPEED, Action::Increment) => A11yAction::SpeedUp,
+                    (SPEED, Action::Decrement) => A11yAction::SpeedDown,
+                    _ => return,
+                };
+                let _ = self.0.send(action);
+            }
+        }
+
+        fn tree(snapshot: &Snapshot) -> TreeUpdate {
+            let mut window = Node::new(Role::Window);
+            window.set_label(snapshot.title.as_str());
+            window.set_children(vec![WORD, STATUS, PROGRESS, SPEED, PLAY, PREV, NEXT]);
+
+            // Announcing every word during playback would drown the screen
+            // reader, so the word is only read out when stepping through
+            let mut word = Node::new(Role::Label);
+            word.set_label(snapshot.word.as_str());
+            word.set_live(if snapshot.playing {
+                Live::Off
+            } else {
+                Live::Polite
+            });
+
+            let mut status = Node::new(Role::Status);
+            status.set_label(snapshot.status.as_str());
+            status.set_live(Live::Polite);
+
+            let mut progress = Node::new(Role::ProgressIndicator);
+            progress.set_label("Progress");
+            progress.set_numeric_value(snapshot.progress);
+            progress.set_min_numeric_value(0.0);
+            progress.set_max_numeric_value(100.0);
+
+            let mut speed = Node::new(Role::Slider);
+            speed.set_label("Words per minute");
+            speed.set_numeric_value(f64::from(snapshot.wpm));
+            let (min_wpm, max_wpm) = snapshot.wpm_range;
+            speed.set_min_numeric_value(f64::from(min_wpm));
+            speed.set_max_numeric_value(f64::from(max_wpm));
+            speed.add_action(Action::Increment);
+            speed.add_action(Action::Decrement);
+
+            let button = |label: &str| {
+                let mut node = Node::new(Role::Button);
+                node.set_label(label);
+                node.add_action(Action::Click);
+                node
+            };
+            let play = button(if snapshot.playing { "Pause" } else { "Play" });
+
+            TreeUpdate {
+                nodes: vec![
+                    (WINDOW, window),
+                    (WORD, word),
+                    (STATUS, status),
+                    (PROGRESS, progress),
+                    (SPEED, speed),
+                    (PLAY, play),
+                    (PREV, button("Previous word")),
+                    (NEXT, button("Next word")),
+                ],
+                tree: Some(Tree::new(WINDOW)),
+                focus: WINDOW,
+            }
+        }
+    }
+
+    /// Windows and macOS adapters need the native window, which iced keeps
+    /// to itself, so there the tree stays unpublished
+    #[cfg(not(target_os = "linux"))]
+    pub struct Accessibility;
+
+    #[cfg(not(target_os = "linux"))]
+    impl Accessibility {
+        pub fn new() -> Self {
+            Self
+        }
+
+        pub fn active(&self) -> bool {
+            false
+        }
+
+        pub fn update(&mut self, _snapshot: Snapshot) {}
+
+        pub fn focus_changed(&mut self, _focused: bool) {}
+
+        pub fn poll(&self) -> Vec<A11yAction> {
+            Vec::new()
+        }
+    }
+}
+
+// ============================================================================
+// Media Controls
+// ============================================================================
+
+/// MPRIS on Linux: desktop media keys and "now playing" widgets can play,
+/// pause and seek the reader, and show the open book. Positions are reading
+/// time at the current speed.
+mod mpris {
+    use std::time::Duration;
+
+    /// Requests from a media key or widget. Offsets and positions are in
+    /// microseconds, as MPRIS has them.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum MediaAction {
+        Play,
+        Pause,
+        PlayPause,
+        Stop,
+        Next,
+        Previous,
+        Seek(i64),
+        SetPosition(i64),
+        Raise,
+        Quit,
+    }
+
+    /// What media widgets are told about the app
+    #[derive(Debug, Clone, Default, PartialEq)]
+    pub struct NowPlaying {
+        pub book_id: Option<String>,
+        pub title: String,
+        pub playing: bool,
+        /// Reading time up to the current word, and for the whole book
+        pub position: Duration,
+        pub length: Duration,
+    }
+
+    #[cfg(target_os = "linux")]
+    pub use self::dbus::MediaControls;
+
+    #[cfg(target_os = "linux")]
+    mod dbus {
+        use super::{MediaAction, NowPlaying};
+        use std::collections::HashMap;
+        use std::sync::mpsc::{channel, Receiver, Sender};
+        use std::sync::{Arc, Mutex};
+        use std::time::Duration;
+        use zbus::blocking::connection::Builder;
+        use zbus::blocking::Connection;
+        use zbus::fdo::RequestNameFlags;
+        use zbus::interface;
+        use zbus::zvariant::{ObjectPath, OwnedValue, Value};
+
+        const BUS_NAME: &str = "org.mpris.MediaPlayer2.rsvp";
+        const PATH: &str = "/org/mpris/MediaPlayer2";
+        const PLAYER: &str = "org.mpris.MediaPlayer2.Player";
+
+        /// The app's player on the session bus
+        pub struct MediaControls {
+            connection: Connection,
+            shown: Arc<Mutex<NowPlaying>>,
+            actions: Receiver<MediaAction>,
+        }
+
+        impl MediaControls {
+            /// `None` without a session bus
+            pub fn new() -> Option<Self> {
+                let shown = Arc::new(Mutex::new(NowPlaying::default()));
+                let (sender, actions) = channel();
+                let connection = Builder::session()
+                    .ok()?
+                    .serve_at(
+                        PATH,
+                        Root {
+                            actions: sender.clone(),
+                        },
+                    )
+                    .ok()?
+                    .serve_at(
+                        PATH,
+                        Player {
+                            shown: Arc::clone(&shown),
+                            actions: sender,
+                        },
+                    )
+                    .ok()?
+                    .build()
+                    .ok()?;
+                // A second window takes a name of its own, as MPRIS asks
+                let instance = format!("{}.instance{}", BUS_NAME, std::process::id());
+                let flags = RequestNameFlags::DoNotQueue;
+                connection
+                    .request_name_with_flags(BUS_NAME, flags.into())
+                    .or_else(|_| connection.request_name_with_flags(instance, flags.into()))
+                    .ok()?;
+                Some(Self {
+                    connection,
+                    shown,
+                    actions,
+                })
+            }
+
+            /// Publish the app's state, signalling whatever changed besides
+            /// the position, which widgets read when they need it
+            pub fn update(&mut self, now: NowPlaying) {
+                let mut shown = self.shown.lock().unwrap();
+                let mut changed: HashMap<&str, Value> = HashMap::new();
+                if shown.playing != now.playing {
+                    changed.insert("PlaybackStatus", playback_status(&now).into());
+                }
+                if shown.book_id != now.book_id
+                    || shown.title != now.title
+                    || shown.length != now.length
+                {
+                    changed.insert("Metadata", metadata(&now).into());
+                }
+                *shown = now;
+                drop(shown);
+                if !changed.is_empty() {
+                    let invalidated: Vec<&str> = Vec::new();
+                    let _ = self.connection.emit_signal(
+                        None::<()>,
+                        PATH,
+                        "org.freedesktop.DBus.Properties",
+                        "PropertiesChanged",
+                        &(PLAYER, changed, invalidated),
+                    );
+                }
+            }
+
+            /// Tell widgets the position jumped, after a seek
+            pub fn seeked(&self, position: i64) {
+                let _ =
+                    self.connection
+                        .emit_signal(None::<()>, PATH, PLAYER, "Seeked", &(position,));
+            }
+
+            /// Requests made since the last poll
+            pub fn poll(&self) -> Vec<MediaAction> {
+                self.actions.try_iter().collect()
+            }
+        }
+
+        fn playback_status(now: &NowPlaying) -> &'static str {
+            if now.book_id.is_none() {
+                "Stopped"
+            } else if now.playing {
+                "Playing"
+            } else {
+                "Paused"
+            }
+        }
+
+        fn track_id(now: &NowPlaying) -> ObjectPath<'static> {
+            let path = match &now.book_id {
+                Some(id) => format!("/org/rsvp/book/b{}", id),
+                None => "/org/mpris/MediaPlayer2/TrackList/NoTrack".to_string(),
+            };
+            ObjectPath::try_from(path)
+                .unwrap_or_else(|_| ObjectPath::from_static_str_unchecked("/org/rsvp/book"))
+        }
+
+        fn metadata(now: &NowPlaying) -> HashMap<String, OwnedValue> {
+            let mut metadata = HashMap::new();
+            let mut insert = |key: &str, value: Value| {
+                if let Ok(value) = value.try_to_owned() {
+                    metadata.insert(key.to_string(), value);
+                }
+            };
+            insert("mpris:trackid", track_id(now).into());
+            if now.book_id.is_some() {
+                insert("xesam:title", now.title.as_str().into());
+                insert("mpris:length", micros(now.length).into());
+            }
+            metadata
+        }
+
+        fn micros(position: Duration) -> i64 {
+            position.as_micros() as i64
+        }
+
+        struct Root {
+            actions: Sender<MediaAction>,
+        }
+
+        #[interface(name = "org.mpris.MediaPlayer2")]
+        impl Root {
+            fn raise(&self) {
+                let _ = self.actions.send(MediaAction::Raise);
+            }
+
+            fn quit(&self) {
+                let _ = self.actions.send(MediaAction::Quit);
+            }
+
+            #[zbus(property)]
+            fn can_raise(&self) -> bool {
+                true
+            }
+
+            #[zbus(property)]
+            fn can_quit(&self) -> bool {
+                true
+            }
+
+            #[zbus(property)]
+            fn has_track_list(&self) -> bool {
+                false
+            }
+
+            #[zbus(property)]
+            fn identity(&self) -> &str {
+                "RSVP Reader"
+            }
+
+            #[zbus(property)]
+            fn supported_uri_schemes(&self) -> Vec<String> {
+                Vec::new()
+            }
+
+            #[zbus(property)]
+            fn supported_mime_types(&self) -> Vec<String> {
+                Vec::new()
+            }
+        }
+
+        struct Player {
+            shown: Arc<Mutex<NowPlaying>>,
+            actions: Sender<MediaAction>,
+        }
+
+        impl Player {
+            fn send(&self, action: MediaAction) {
+                let _ = self.actions.send(action);
+            }
+
+            fn has_book(&self) -> bool {
+                self.shown.lock().unwrap().book_id.is_some()
+            }
+        }
+
+        #[interface(name = "org.mpris.MediaPlayer2.Player")]
+        impl Player {
+            fn next(&self) {
+                self.send(MediaAction::Next);
+            }
+
+            fn previous(&self) {
+                self.send(MediaAction::Previous);
+            }
+
+            fn pause(&self) {
+                self.send(MediaAction::Pause);
+            }
+
+            fn play_pause(&self) {
+                self.send(MediaAction::PlayPause);
+            }
+
+            fn stop(&self) {
+                self.send(MediaAction::Stop);
+            }
+
+            fn play(&self) {
+                self.send(MediaAction::Play);
+            }
+
+            fn seek(&self, offset: i64) {
+                self.send(MediaAction::Seek(offset));
+            }
+
+            fn set_position(&self, track: ObjectPath<'_>, position: i64) {
+                // Requests for a book that is no longer open are ignored
+                if track == track_id(&self.shown.lock().unwrap()) {
+                    self.send(MediaAction::SetPosition(position));
+                }
+            }
+
+            fn open_uri(&self, _uri: &str) {}
+
+            #[zbus(property)]
+            fn playback_status(&self) -> &'static str {
+                playback_status(&self.shown.lock().unwrap())
+            }
+
+            #[zbus(property)]
+            fn rate(&self) -> f64 {
+                1.0
+            }
+
+            #[zbus(property)]
+            fn minimum_rate(&self) -> f64 {
+                1.0
+            }
+
+            #[zbus(property)]
+            fn maximum_rate(&self) -> f64 {
+                1.0
+            }
+
+            #[zbus(property)]
+            fn metadata(&self) -> HashMap<String, OwnedValue> {
+                metadata(&self.shown.lock().unwrap())
+            }
+
+            #[zbus(property)]
+            fn volume(&self) -> f64 {
+                1.0
+            }
+
+            #[zbus(property)]
+            fn position(&self) -> i64 {
+                micros(self.shown.lock().unwrap().position)
+            }
+
+            #[zbus(property)]
+            fn can_go_next(&self) -> bool {
+                self.has_book()
+            }
+
+            #[zbus(property)]
+            fn can_go_previous(&self) -> bool {
+                self.has_book()
+            }
+
+            #[zbus(property)]
+            fn can_play(&self) -> bool {
+                self.has_book()
+            }
+
+            #[zbus(property)]
+            fn can_pause(&self) -> bool {
+                self.has_book()
+            }
+
+            #[zbus(property)]
+            fn can_seek(&self) -> bool {
+                self.has_book()
+            }
+
+            #[zbus(property)]
+            fn can_control(&self) -> bool {
+                true
+            }
+        }
+    }
+
+    /// Only Linux has MPRIS
+    #[cfg(not(target_os = "linux"))]
+    pub struct MediaControls;
+
+    #[cfg(not(target_os = "linux"))]
+    impl MediaControls {
+        pub fn new() -> Option<Self> {
+            None
+        }
+
+        pub fn update(&mut self, _now: NowPlaying) {}
+
+        pub fn seeked(&self, _position: i64) {}
+
+        pub fn poll(&self) -> Vec<MediaAction> {
+            Vec::new()
         }
     }
 }
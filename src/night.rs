@@ -0,0 +1,139 @@
+//! Night mode: the dim, warm night theme switched on between set hours, or
+//! whenever the desktop is in dark mode, to cut glare when reading late
+
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// How often the clock, or the desktop's dark mode, is looked at again
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A time of day, written like "21:30"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TimeOfDay {
+    /// Minutes since midnight
+    minutes: u16,
+}
+
+impl TimeOfDay {
+    pub fn new(hour: u16, minute: u16) -> Self {
+        Self {
+            minutes: hour * 60 + minute,
+        }
+    }
+
+    /// The local time now
+    pub fn now() -> Self {
+        let now = chrono::Local::now();
+        Self::new(now.hour() as u16, now.minute() as u16)
+    }
+}
+
+impl FromStr for TimeOfDay {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let parsed = s
+            .split_once(':')
+            .and_then(|(hour, minute)| Some((hour.parse().ok()?, minute.parse().ok()?)));
+        match parsed {
+            Some((hour @ 0..=23, minute @ 0..=59)) => Ok(Self::new(hour, minute)),
+            _ => Err(format!("\"{}\" isn't a time like \"21:30\"", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for TimeOfDay {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, String> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.minutes / 60, self.minutes % 60)
+    }
+}
+
+impl From<TimeOfDay> for String {
+    fn from(time: TimeOfDay) -> String {
+        time.to_string()
+    }
+}
+
+/// The `[night_mode]` table in `config.toml`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NightMode {
+    pub enabled: bool,
+    /// When the night theme comes on and goes off, local time. The span can
+    /// run past midnight.
+    pub start: TimeOfDay,
+    pub end: TimeOfDay,
+    /// Follow the desktop's dark mode instead of the clock
+    pub follow_system: bool,
+}
+
+impl Default for NightMode {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: TimeOfDay::new(21, 0),
+            end: TimeOfDay::new(7, 0),
+            follow_system: false,
+        }
+    }
+}
+
+impl NightMode {
+    /// Whether `time` falls between `start` and `end`
+    pub fn covers(&self, time: TimeOfDay) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// Keeps track of whether it is night, looking again every half minute
+#[derive(Debug, Default)]
+pub struct NightWatch {
+    night: bool,
+    checked: Option<Instant>,
+}
+
+impl NightWatch {
+    /// Look at the clock, or ask `system_dark` whether the desktop is in
+    /// dark mode, if it is time to. Returns whether that turned night mode
+    /// on or off.
+    pub fn update(&mut self, settings: &NightMode, system_dark: impl FnOnce() -> bool) -> bool {
+        if self.checked.is_some_and(|at| at.elapsed() < CHECK_INTERVAL) {
+            return false;
+        }
+        self.checked = Some(Instant::now());
+        let night = settings.enabled
+            && if settings.follow_system {
+                system_dark()
+            } else {
+                settings.covers(TimeOfDay::now())
+            };
+        let changed = night != self.night;
+        self.night = night;
+        changed
+    }
+
+    pub fn is_night(&self) -> bool {
+        self.night
+    }
+
+    /// Look again on the next update, as after the settings change
+    pub fn recheck(&mut self) {
+        self.checked = None;
+    }
+}
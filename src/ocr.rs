@@ -0,0 +1,104 @@
+//! Text recognition for screenshots and scanned PDFs, through the
+//! `tesseract` program. PDFs are rendered to page images first with
+//! `pdftoppm` from poppler, and their title and author are read with its
+//! `pdfinfo`.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Image formats tesseract reads
+pub const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "tif", "tiff", "bmp", "webp"];
+/// Resolution scanned pages are rendered at; tesseract does best at 300 DPI
+const PDF_DPI: &str = "300";
+
+/// Whether a file goes through OCR rather than being read as text
+pub fn is_ocr_file(path: &Path) -> bool {
+    extension(path).is_some_and(|ext| ext == "pdf" || IMAGE_EXTENSIONS.contains(&ext.as_str()))
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_lowercase)
+}
+
+/// Recognize the text in an image, or in every page of a PDF
+pub fn read_ocr_file(path: &Path) -> io::Result<String> {
+    if extension(path).as_deref() == Some("pdf") {
+        read_pdf(path)
+    } else {
+        read_image(path)
+    }
+}
+
+fn read_image(path: &Path) -> io::Result<String> {
+    let mut command = Command::new("tesseract");
+    command.arg(path).arg("stdout");
+    run(command, "tesseract")
+}
+
+fn read_pdf(path: &Path) -> io::Result<String> {
+    let pages_dir = std::env::temp_dir().join(format!("rsvp-ocr-{}", std::process::id()));
+    fs::create_dir_all(&pages_dir)?;
+    let text = render_pages(path, &pages_dir).and_then(|pages| {
+        let pages: Vec<String> = pages
+            .iter()
+            .map(|page| read_image(page))
+            .collect::<io::Result<_>>()?;
+        Ok(pages.join("\n\n"))
+    });
+    if let Err(e) = fs::remove_dir_all(&pages_dir) {
+        tracing::warn!("Couldn't remove {}: {}", pages_dir.display(), e);
+    }
+    text
+}
+
+/// The fields `pdfinfo` lists for a PDF, like ("Title", "..."), with dates
+/// in ISO 8601
+pub fn pdf_info(path: &Path) -> io::Result<Vec<(String, String)>> {
+    let mut command = Command::new("pdfinfo");
+    command.arg("-isodates").arg(path);
+    let info = run(command, "pdfinfo")?;
+    Ok(info
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect())
+}
+
+/// Render each page of a PDF to a PNG in `dir`, in page order
+fn render_pages(path: &Path, dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut command = Command::new("pdftoppm");
+    command
+        .args(["-r", PDF_DPI, "-png"])
+        .arg(path)
+        .arg(dir.join("page"));
+    run(command, "pdftoppm")?;
+
+    // Page numbers are zero-padded to the same width, so names sort in order
+    let mut pages: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|page| extension(page).as_deref() == Some("png"))
+        .collect();
+    pages.sort();
+    Ok(pages)
+}
+
+/// Run a poppler or OCR tool and return what it printed, or its complaint
+fn run(mut command: Command, program: &str) -> io::Result<String> {
+    let output = command.output().map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} is not installed", program),
+        ),
+        _ => e,
+    })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().last().unwrap_or("failed").trim().to_string();
+        return Err(io::Error::other(format!("{}: {}", program, reason)));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,104 @@
+//! Speed ramp: each session starts at a comfortable speed and steps up
+//! every minute of reading until it reaches a target, to stretch the pace a
+//! reader can hold. Every minute finished at a speed counts as sustaining
+//! it.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Reading time at each speed before the next step up
+const RAMP_STEP_TIME: Duration = Duration::from_secs(60);
+
+/// The `[ramp]` table in `config.toml`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RampSettings {
+    pub enabled: bool,
+    /// Speed each session starts at; 0 starts at the saved speed
+    pub start_wpm: u32,
+    /// WPM added after every minute of reading
+    pub step: u32,
+    pub target_wpm: u32,
+}
+
+impl Default for RampSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start_wpm: 0,
+            step: 25,
+            target_wpm: 600,
+        }
+    }
+}
+
+/// A minute finished at one speed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RampStep {
+    /// The speed just held for a minute
+    pub sustained: u32,
+    /// The speed to go on at, None once the target is reached
+    pub next: Option<u32>,
+}
+
+#[derive(Debug)]
+struct Ramping {
+    wpm: u32,
+    held: Duration,
+    last_update: Instant,
+}
+
+/// Follows playback while a session's speed ramps up
+#[derive(Debug, Default)]
+pub struct SpeedRamp {
+    active: Option<Ramping>,
+}
+
+impl SpeedRamp {
+    /// Start ramping from `wpm`, the speed the frontend set
+    pub fn start(&mut self, wpm: u32) {
+        self.active = Some(Ramping {
+            wpm,
+            held: Duration::ZERO,
+            last_update: Instant::now(),
+        });
+    }
+
+    pub fn stop(&mut self) {
+        self.active = None;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.is_some()
+    }
+
+    /// Count playing time at `wpm`, the speed in use. Returns the step
+    /// when a minute at it is up. A speed other than the one the ramp
+    /// last asked for, as from a change by hand or the configured range
+    /// cutting a step short, ends the ramp.
+    pub fn update(&mut self, playing: bool, wpm: u32, settings: &RampSettings) -> Option<RampStep> {
+        let ramping = self.active.as_mut()?;
+        if ramping.wpm != wpm {
+            self.active = None;
+            return None;
+        }
+        let now = Instant::now();
+        if playing {
+            ramping.held += now - ramping.last_update;
+        }
+        ramping.last_update = now;
+        if ramping.held < RAMP_STEP_TIME {
+            return None;
+        }
+        ramping.held = Duration::ZERO;
+        let next = (wpm < settings.target_wpm).then(|| {
+            let next = (wpm + settings.step.max(1)).min(settings.target_wpm);
+            ramping.wpm = next;
+            next
+        });
+        Some(RampStep {
+            sustained: wpm,
+            next,
+        })
+    }
+}
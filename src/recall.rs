@@ -0,0 +1,185 @@
+//! Recall checks: a quick self-test that shows five words and asks which
+//! of them came up in the last minute of reading, as a rough measure of
+//! comprehension at the current speed
+
+use crate::text::WordSource;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::collections::{HashSet, VecDeque};
+use std::hash::BuildHasher;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// How far back a recall check reaches
+pub const RECALL_WINDOW: Duration = Duration::from_secs(60);
+/// Words offered in each check
+pub const RECALL_CHOICES: usize = 5;
+/// Shorter words are mostly function words that say nothing about recall
+const MIN_WORD_CHARS: usize = 4;
+/// How much unread text is searched for words that didn't appear, and how
+/// many of those words the distractors are drawn from
+const DISTRACTOR_SCAN: usize = 2000;
+const DISTRACTOR_POOL: usize = 50;
+
+/// The `[recall]` table in `config.toml`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecallSettings {
+    /// Offer a check every time playback is paused
+    pub after_pause: bool,
+}
+
+/// Words shown during playback in the last minute
+#[derive(Debug, Default)]
+pub struct RecentWords {
+    words: VecDeque<(Instant, String)>,
+}
+
+impl RecentWords {
+    /// Note a word that has just been shown
+    pub fn seen(&mut self, word: &str) {
+        let now = Instant::now();
+        while self
+            .words
+            .front()
+            .is_some_and(|(shown, _)| now - *shown > RECALL_WINDOW)
+        {
+            self.words.pop_front();
+        }
+        if let Some(word) = normalize(word) {
+            self.words.push_back((now, word));
+        }
+    }
+
+    /// Forget everything, e.g. after switching books
+    pub fn clear(&mut self) {
+        self.words.clear();
+    }
+
+    fn distinct(&self) -> HashSet<&str> {
+        self.words
+            .iter()
+            .filter(|(shown, _)| shown.elapsed() <= RECALL_WINDOW)
+            .map(|(_, word)| word.as_str())
+            .collect()
+    }
+}
+
+/// Lowercase a word and strip its punctuation, skipping short ones
+fn normalize(word: &str) -> Option<String> {
+    let word: String = word
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase();
+    (word.chars().count() >= MIN_WORD_CHARS).then_some(word)
+}
+
+#[derive(Debug, Clone)]
+pub struct RecallChoice {
+    pub word: String,
+    /// Whether it was shown in the last minute
+    pub appeared: bool,
+    /// Whether the reader says it was
+    pub picked: bool,
+}
+
+/// One check in progress, or answered once `result` is set
+#[derive(Debug, Clone)]
+pub struct RecallCheck {
+    pub choices: Vec<RecallChoice>,
+    pub result: Option<RecallScore>,
+}
+
+impl RecallCheck {
+    /// Mix two or three recently shown words with ones from the unread
+    /// text. `None` when too little has been read for a fair check.
+    pub fn new(recent: &RecentWords, words: &WordSource, index: usize) -> Option<Self> {
+        let seen = recent.distinct();
+        let random = RandomState::new();
+        let mut shown: Vec<&str> = seen.iter().copied().collect();
+        shown.sort_by_key(|word| random.hash_one(word));
+
+        let mut unseen: Vec<String> = Vec::new();
+        // Unread text first, wrapping round to the start of the book
+        let candidates = (index + 1..words.len()).chain(0..index);
+        for word in candidates
+            .take(DISTRACTOR_SCAN)
+            .filter_map(|i| words.get(i))
+        {
+            if let Some(word) = normalize(&word) {
+                if !seen.contains(word.as_str()) && !unseen.contains(&word) {
+                    unseen.push(word);
+                }
+            }
+            if unseen.len() >= DISTRACTOR_POOL {
+                break;
+            }
+        }
+        unseen.sort_by_key(|word| random.hash_one(word));
+
+        let appeared = 2 + (random.hash_one(index) % 2) as usize;
+        let distractors = RECALL_CHOICES - appeared;
+        if shown.len() < appeared || unseen.len() < distractors {
+            return None;
+        }
+
+        let mut choices: Vec<RecallChoice> = shown[..appeared]
+            .iter()
+            .map(|word| (word.to_string(), true))
+            .chain(unseen.drain(..distractors).map(|word| (word, false)))
+            .map(|(word, appeared)| RecallChoice {
+                word,
+                appeared,
+                picked: false,
+            })
+            .collect();
+        choices.sort_by_key(|choice| random.hash_one(&choice.word));
+        Some(Self {
+            choices,
+            result: None,
+        })
+    }
+
+    pub fn toggle(&mut self, choice: usize) {
+        if self.result.is_none() {
+            if let Some(choice) = self.choices.get_mut(choice) {
+                choice.picked = !choice.picked;
+            }
+        }
+    }
+
+    /// Mark the answers, counting both words rightly picked and words
+    /// rightly left out
+    pub fn submit(&mut self, wpm: u32) -> RecallScore {
+        let correct = self
+            .choices
+            .iter()
+            .filter(|choice| choice.picked == choice.appeared)
+            .count() as u32;
+        let score = RecallScore {
+            at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            correct,
+            total: self.choices.len() as u32,
+            wpm,
+        };
+        self.result = Some(score);
+        score
+    }
+}
+
+/// The outcome of one recall check, kept in the reading history
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecallScore {
+    /// Unix time of the check
+    pub at: u64,
+    pub correct: u32,
+    pub total: u32,
+    /// Reading speed at the time
+    pub wpm: u32,
+}
+
+impl RecallScore {
+    pub fn percent(&self) -> u32 {
+        (self.correct * 100).checked_div(self.total).unwrap_or(0)
+    }
+}
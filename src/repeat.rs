@@ -0,0 +1,166 @@
+//! Loop and A-B repeat for speed drills: the current sentence, paragraph or
+//! a marked stretch plays over and over until the loop is turned off,
+//! optionally a little faster on each pass
+
+use crate::text::WordSource;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The `[repeat]` table in `config.toml`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RepeatSettings {
+    /// WPM added each time the loop starts over, 0 to keep the speed
+    pub wpm_step: u32,
+}
+
+/// What a loop repeats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopScope {
+    Sentence,
+    Paragraph,
+    /// From point A to point B, marked while reading
+    Marked,
+}
+
+impl fmt::Display for LoopScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LoopScope::Sentence => "sentence",
+            LoopScope::Paragraph => "paragraph",
+            LoopScope::Marked => "A-B",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A stretch of words played over and over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepeatLoop {
+    pub scope: LoopScope,
+    pub start: usize,
+    /// One past the last word
+    pub end: usize,
+    /// Times the loop has started over
+    pub passes: u32,
+}
+
+impl RepeatLoop {
+    fn new(scope: LoopScope, start: usize, end: usize) -> Self {
+        Self {
+            scope,
+            start,
+            end,
+            passes: 0,
+        }
+    }
+
+    /// The sentence or paragraph around word `index`
+    fn around(scope: LoopScope, words: &WordSource, index: usize) -> Self {
+        let (start, next, next_start) = match scope {
+            LoopScope::Paragraph => {
+                let next = words.next_paragraph(index);
+                (
+                    words.paragraph_start(index),
+                    next,
+                    words.paragraph_start(next),
+                )
+            }
+            _ => {
+                let next = words.next_sentence(index);
+                (
+                    words.sentence_start(index),
+                    next,
+                    words.sentence_start(next),
+                )
+            }
+        };
+        // With none after it, the step forward stops on the book's last
+        // word, still inside; the loop then runs to the end
+        let end = if next_start == start {
+            words.len()
+        } else {
+            next
+        };
+        Self::new(scope, start, end)
+    }
+}
+
+/// What the mark key did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    /// Point A set, waiting for B
+    Started,
+    /// Point B set; the loop is playing
+    Looping,
+    /// The loop, or a lone point A, is gone
+    Cleared,
+}
+
+/// The loop playing, if any, and point A while waiting for point B
+#[derive(Debug, Default)]
+pub struct Repeat {
+    pub active: Option<RepeatLoop>,
+    mark: Option<usize>,
+}
+
+impl Repeat {
+    /// The loop key: off, then the sentence around `index`, then its
+    /// paragraph, then off again. Returns the scope now looping.
+    pub fn cycle(&mut self, words: &WordSource, index: usize) -> Option<LoopScope> {
+        self.mark = None;
+        let scope = match self.active.map(|active| active.scope) {
+            None => Some(LoopScope::Sentence),
+            Some(LoopScope::Sentence) => Some(LoopScope::Paragraph),
+            Some(_) => None,
+        };
+        self.active = scope.map(|scope| RepeatLoop::around(scope, words, index));
+        scope
+    }
+
+    /// The mark key: point A at `index`, then point B, which starts the
+    /// loop, then off. A and B can be marked in either order.
+    pub fn mark(&mut self, index: usize) -> Mark {
+        if self.active.is_some() {
+            self.clear();
+            return Mark::Cleared;
+        }
+        match self.mark.take() {
+            None => {
+                self.mark = Some(index);
+                Mark::Started
+            }
+            Some(a) => {
+                let (start, last) = (a.min(index), a.max(index));
+                self.active = Some(RepeatLoop::new(LoopScope::Marked, start, last + 1));
+                Mark::Looping
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.active = None;
+        self.mark = None;
+    }
+
+    /// Where to go back to after playback moved from word `from` to `to`,
+    /// if that ran past the end of the loop. Counts the pass.
+    pub fn wrap(&mut self, from: usize, to: usize) -> Option<usize> {
+        let active = self.active.as_mut()?;
+        if from < active.end && to >= active.end {
+            active.passes += 1;
+            Some(active.start)
+        } else {
+            None
+        }
+    }
+
+    /// A line for the stats bar, like "sentence, pass 3", while looping
+    pub fn summary(&self) -> Option<String> {
+        if let Some(active) = &self.active {
+            Some(format!("{}, pass {}", active.scope, active.passes + 1))
+        } else {
+            self.mark.map(|_| "A marked".to_string())
+        }
+    }
+}
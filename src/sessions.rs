@@ -0,0 +1,304 @@
+//! Reading sessions: stretches of reading one book, logged so each book's
+//! statistics screen can show time spent, speed over time and progress by
+//! day
+
+use crate::analysis::format_reading_time;
+use crate::library::ReadingStats;
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// A pause longer than this ends a session
+const SESSION_GAP: Duration = Duration::from_secs(5 * 60);
+/// Shorter sessions, like a quick peek at a book, aren't logged
+const MIN_SESSION: Duration = Duration::from_secs(10);
+/// Days of progress and sessions of speed the sparklines show
+const SPARKLINE_DAYS: u64 = 30;
+const SPARKLINE_SESSIONS: usize = 30;
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One session in the log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub book_id: String,
+    /// Unix time reading started
+    pub started: u64,
+    /// Time spent playing, leaving out pauses
+    pub seconds: u64,
+    /// Words shown
+    pub words: usize,
+    /// Word index reached by the end
+    pub position: usize,
+    /// Fastest speed held for a full minute of a speed ramp
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sustained_wpm: Option<u32>,
+}
+
+impl SessionRecord {
+    pub fn wpm(&self) -> u32 {
+        (self.words as u64 * 60 / self.seconds.max(1)) as u32
+    }
+}
+
+struct ActiveSession {
+    book_id: String,
+    started: u64,
+    played: Duration,
+    words: usize,
+    position: usize,
+    sustained_wpm: Option<u32>,
+    playing: bool,
+    last_update: Instant,
+    last_played: Instant,
+}
+
+/// Follows playback to find where sessions start and end. Frontends call
+/// `update` on every tick and `word_shown` as words go by, and log the
+/// sessions that come back.
+#[derive(Default)]
+pub struct SessionTracker {
+    active: Option<ActiveSession>,
+}
+
+impl SessionTracker {
+    /// Returns a session that just ended: reading moved to another book, or
+    /// stayed paused for more than a few minutes
+    pub fn update(
+        &mut self,
+        book_id: Option<&str>,
+        playing: bool,
+        position: usize,
+    ) -> Option<SessionRecord> {
+        let now = Instant::now();
+        let mut ended = false;
+        if let Some(active) = &mut self.active {
+            if active.playing {
+                active.played += now - active.last_update;
+                active.last_played = now;
+            }
+            active.last_update = now;
+            active.playing = playing;
+            if book_id == Some(active.book_id.as_str()) {
+                active.position = position;
+            } else {
+                ended = true;
+            }
+            ended |= !playing && now - active.last_played > SESSION_GAP;
+        }
+        let session = if ended { self.finish() } else { None };
+
+        if let (None, true, Some(book_id)) = (&self.active, playing, book_id) {
+            self.active = Some(ActiveSession {
+                book_id: book_id.to_string(),
+                started: unix_now(),
+                played: Duration::ZERO,
+                words: 0,
+                position,
+                sustained_wpm: None,
+                playing,
+                last_update: now,
+                last_played: now,
+            });
+        }
+        session
+    }
+
+    /// Whether a session is under way
+    pub fn in_session(&self) -> bool {
+        self.active.is_some()
+    }
+
+    /// Note a speed held for a full minute, keeping the fastest
+    pub fn sustained(&mut self, wpm: u32) {
+        if let Some(active) = &mut self.active {
+            active.sustained_wpm = active.sustained_wpm.max(Some(wpm));
+        }
+    }
+
+    /// Count words just shown
+    pub fn word_shown(&mut self, words: usize) {
+        if let Some(active) = &mut self.active {
+            active.words += words;
+        }
+    }
+
+    /// End the current session, as on quitting. None if it was too short
+    /// to log.
+    pub fn finish(&mut self) -> Option<SessionRecord> {
+        let mut active = self.active.take()?;
+        if active.playing {
+            active.played += active.last_update.elapsed();
+        }
+        (active.played >= MIN_SESSION).then_some(SessionRecord {
+            book_id: active.book_id,
+            started: active.started,
+            seconds: active.played.as_secs(),
+            words: active.words,
+            position: active.position,
+            sustained_wpm: active.sustained_wpm,
+        })
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// The day a Unix time falls on in the local timezone, as a day number, so
+/// days roll over at the reader's midnight
+fn local_day(unix: u64) -> u64 {
+    chrono::DateTime::from_timestamp(unix as i64, 0).map_or(0, |time| {
+        let date = time.with_timezone(&chrono::Local).date_naive();
+        date.num_days_from_ce().max(0) as u64
+    })
+}
+
+/// A row of block characters, one per value, as tall as the value is
+/// against the largest
+pub fn sparkline(values: &[u64]) -> String {
+    let max = values.iter().copied().max().unwrap_or(0).max(1);
+    values
+        .iter()
+        .map(|&value| SPARK_LEVELS[(value * 7).div_ceil(max) as usize])
+        .collect()
+}
+
+/// Days in a row with some reading, up to today, and whether today is one
+/// of them. A streak not yet carried on today still counts until the day
+/// is out.
+pub fn reading_streak(stats: &ReadingStats) -> (u64, bool) {
+    let days: BTreeSet<u64> = stats
+        .sessions
+        .iter()
+        .map(|session| local_day(session.started))
+        .collect();
+    let today = local_day(unix_now());
+    let read_today = days.contains(&today);
+    let mut day = if read_today {
+        Some(today)
+    } else {
+        today.checked_sub(1)
+    };
+    let mut streak = 0;
+    while let Some(d) = day.filter(|d| days.contains(d)) {
+        streak += 1;
+        day = d.checked_sub(1);
+    }
+    (streak, read_today)
+}
+
+/// Labelled lines for the screen shown on finishing a book, from the
+/// session log
+pub fn completion_summary(stats: &ReadingStats, book_id: &str) -> Vec<(&'static str, String)> {
+    let sessions: Vec<&SessionRecord> = stats
+        .sessions
+        .iter()
+        .filter(|session| session.book_id == book_id)
+        .collect();
+    let seconds: u64 = sessions.iter().map(|session| session.seconds).sum();
+    let words: usize = sessions.iter().map(|session| session.words).sum();
+    let average = words as u64 * 60 / seconds.max(1);
+
+    vec![
+        (
+            "Time spent",
+            format_reading_time(Duration::from_secs(seconds)),
+        ),
+        ("Average speed", format!("{} WPM", average)),
+        ("Sessions", sessions.len().to_string()),
+        ("Streak", streak_text(stats)),
+    ]
+}
+
+/// The reading streak in words, for summaries
+pub fn streak_text(stats: &ReadingStats) -> String {
+    match reading_streak(stats) {
+        (0, _) => "none".to_string(),
+        (1, true) => "1 day, started today".to_string(),
+        (days, true) => format!("{} days in a row, today included", days),
+        (days, false) => format!("{} days in a row, read today to keep it going", days),
+    }
+}
+
+/// Labelled lines for a book's statistics screen, from the session log
+pub fn book_stats(
+    stats: &ReadingStats,
+    book_id: &str,
+    total_words: usize,
+) -> Vec<(&'static str, String)> {
+    let sessions: Vec<&SessionRecord> = stats
+        .sessions
+        .iter()
+        .filter(|session| session.book_id == book_id)
+        .collect();
+    let Some(first) = sessions.first() else {
+        return vec![("Sessions", "none yet".to_string())];
+    };
+
+    let seconds: u64 = sessions.iter().map(|session| session.seconds).sum();
+    let words: usize = sessions.iter().map(|session| session.words).sum();
+    let average = words as u64 * 60 / seconds.max(1);
+
+    let sustained = sessions
+        .iter()
+        .filter_map(|session| session.sustained_wpm)
+        .max();
+
+    let recent = &sessions[sessions.len().saturating_sub(SPARKLINE_SESSIONS)..];
+    let speeds: Vec<u64> = recent.iter().map(|session| session.wpm() as u64).collect();
+
+    // Progress at the end of each day, carried over days without reading
+    let today = local_day(unix_now());
+    let first_day = local_day(first.started).max((today + 1).saturating_sub(SPARKLINE_DAYS));
+    let mut progress = Vec::new();
+    let mut reached = 0;
+    let mut next = 0;
+    for day in first_day..=today {
+        while let Some(session) = sessions.get(next) {
+            if local_day(session.started) > day {
+                break;
+            }
+            reached = session.position;
+            next += 1;
+        }
+        progress.push((reached * 100 / total_words.max(1)) as u64);
+    }
+
+    let mut lines = vec![
+        (
+            "Time spent",
+            format_reading_time(Duration::from_secs(seconds)),
+        ),
+        ("Sessions", sessions.len().to_string()),
+        ("Average speed", format!("{} WPM", average)),
+        (
+            "Speed",
+            format!(
+                "{}  last {} sessions, {} to {} WPM",
+                sparkline(&speeds),
+                recent.len(),
+                speeds.iter().min().unwrap_or(&0),
+                speeds.iter().max().unwrap_or(&0)
+            ),
+        ),
+        (
+            "Progress",
+            format!(
+                "{}  last {} days, now {}%",
+                sparkline(&progress),
+                progress.len(),
+                progress.last().unwrap_or(&0)
+            ),
+        ),
+    ];
+    if let Some(wpm) = sustained {
+        lines.insert(
+            3,
+            ("Sustained speed", format!("{} WPM on a speed ramp", wpm)),
+        );
+    }
+    lines
+}
@@ -0,0 +1,151 @@
+//! Narration: speaks the text through a system speech command, a sentence
+//! at a time, so it keeps pace with the words on screen
+
+use crate::text::{ends_sentence, WordSource};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::process::{Child, Command, Stdio};
+
+/// Longest stretch handed to the speech engine at once, for text without
+/// sentence endings
+const MAX_CHUNK_WORDS: usize = 40;
+
+/// The `[speech]` table in `config.toml`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpeechSettings {
+    pub enabled: bool,
+    /// Speech program and its arguments, with `{wpm}` replaced by the
+    /// reading speed. The text to speak is added as the last argument.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Words the voice trails the display by; negative to speak ahead of it
+    pub offset: i32,
+}
+
+impl SpeechSettings {
+    /// The configured command, or the platform's own speech program
+    fn command_line(&self, wpm: u32) -> Result<Vec<String>, String> {
+        let command = match &self.command {
+            Some(command) => command.as_str(),
+            None if cfg!(target_os = "macos") => "say -r {wpm}",
+            None if cfg!(unix) => "espeak-ng -s {wpm}",
+            None => return Err("Narration needs speech.command in config.toml".to_string()),
+        };
+        let args: Vec<String> = command
+            .split_whitespace()
+            .map(|arg| arg.replace("{wpm}", &wpm.to_string()))
+            .collect();
+        if args.is_empty() {
+            return Err("speech.command is empty".to_string());
+        }
+        Ok(args)
+    }
+}
+
+/// Keeps a speech process running over the words around the display.
+/// Frontends call `update` on every tick; stopping playback or jumping
+/// elsewhere in the book cuts the voice off.
+#[derive(Debug, Default)]
+pub struct Narration {
+    child: Option<Child>,
+    /// Words handed to the current speech process
+    spoken: Range<usize>,
+    /// Set after the speech program failed to start, until the settings change
+    failed: bool,
+}
+
+impl Narration {
+    /// Speak the sentence the display has reached, if it isn't already being
+    /// spoken. Returns an error the first time the speech program can't run.
+    pub fn update(
+        &mut self,
+        playing: bool,
+        words: &WordSource,
+        index: usize,
+        wpm: u32,
+        settings: &SpeechSettings,
+    ) -> Result<(), String> {
+        self.reap();
+        if !settings.enabled || !playing || self.failed || words.is_empty() {
+            self.stop();
+            return Ok(());
+        }
+
+        let Some(at) = index.checked_add_signed(-(settings.offset as isize)) else {
+            return Ok(());
+        };
+        if at >= words.len() || self.spoken.contains(&at) {
+            return Ok(());
+        }
+
+        // The display moved past what is being said, or jumped elsewhere
+        self.stop();
+        let mut text = String::new();
+        let mut end = at;
+        while let Some(word) = words.get(end) {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(&word);
+            end += 1;
+            if ends_sentence(&word) || end - at >= MAX_CHUNK_WORDS {
+                break;
+            }
+        }
+        self.spoken = at..end;
+
+        let args = settings
+            .command_line(wpm)
+            .inspect_err(|_| self.failed = true)?;
+        let child = Command::new(&args[0])
+            .args(&args[1..])
+            .arg(&text)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| {
+                self.failed = true;
+                format!("Narration failed: {}: {}", args[0], e)
+            })?;
+        self.child = Some(child);
+        Ok(())
+    }
+
+    /// Whether the voice is still going
+    pub fn speaking(&self) -> bool {
+        self.child.is_some()
+    }
+
+    /// Cut the voice off
+    pub fn stop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        self.spoken = 0..0;
+    }
+
+    /// Try the speech program again, e.g. after the config changed
+    pub fn reset(&mut self) {
+        self.stop();
+        self.failed = false;
+    }
+
+    /// Drop the speech process once it has finished on its own, keeping the
+    /// spoken range so the same sentence isn't said twice
+    fn reap(&mut self) {
+        if let Some(child) = &mut self.child {
+            if !matches!(child.try_wait(), Ok(None)) {
+                self.child = None;
+            }
+        }
+    }
+}
+
+impl Drop for Narration {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -0,0 +1,175 @@
+//! The speed test: a bundled passage read at the current speed, then a few
+//! questions on it. Speed times the share answered right gives an effective
+//! WPM; the first test is the baseline later ones are measured against.
+
+use crate::library::config_dir;
+use crate::text::WordSource;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const PASSAGE: &str = "\
+Lighthouses
+
+For most of history, sailors feared the coast more than the open sea. Storms could be ridden out far from land, but rocks and shoals near shore sank ships in sight of harbor. The first lights to warn them were simple fires kept burning on hilltops. The most famous early lighthouse stood at Alexandria in Egypt, where a tower more than one hundred meters tall guided ships into port for over a thousand years before earthquakes brought it down.
+
+Fires and candles gave a weak light, and most of it was wasted shining up into the sky or back toward land. The great improvement came in 1822, when the French engineer Augustin Fresnel designed a lens built from rings of glass prisms. His lens gathered the light of a single lamp into a narrow beam that could be seen more than thirty kilometers away. Within a few decades Fresnel lenses had been fitted to lighthouses around the world.
+
+A light that can be seen is only useful if sailors know which light it is. Each lighthouse was therefore given its own pattern. Some flash once every few seconds, some show groups of two or three flashes, and others shine steadily or are hidden behind colored panes. Charts list the pattern of every light, so a navigator who times the flashes can tell exactly where the ship is.
+
+Keeping a light burning was hard and lonely work. Keepers trimmed wicks, polished the lens, and wound the clockwork that turned it, often on islands cut off for weeks by bad weather. Electricity and automatic controls ended the job in most countries during the twentieth century, and satellite navigation has since made many lights unnecessary. Yet hundreds remain in service as a backup, and many more are kept as museums.";
+
+pub struct Question {
+    pub prompt: &'static str,
+    pub choices: [&'static str; 4],
+    /// Index of the right choice
+    pub answer: usize,
+}
+
+pub const QUESTIONS: &[Question] = &[
+    Question {
+        prompt: "Where did the famous early lighthouse stand?",
+        choices: ["Rome", "Alexandria", "Athens", "Carthage"],
+        answer: 1,
+    },
+    Question {
+        prompt: "What did Fresnel's lens do?",
+        choices: [
+            "Made the lamp burn longer",
+            "Colored the light red",
+            "Gathered the light into a narrow beam",
+            "Turned the light on at dusk",
+        ],
+        answer: 2,
+    },
+    Question {
+        prompt: "How can a navigator tell one lighthouse from another?",
+        choices: [
+            "By the height of its tower",
+            "By the sound of its horn",
+            "By the color of its walls",
+            "By its pattern of flashes",
+        ],
+        answer: 3,
+    },
+    Question {
+        prompt: "What ended the keeper's job in most countries?",
+        choices: [
+            "Electricity and automatic controls",
+            "Earthquakes",
+            "A lack of volunteers",
+            "New shipping laws",
+        ],
+        answer: 0,
+    },
+];
+
+/// One test in the log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestResult {
+    /// Unix time the test was taken
+    pub at: u64,
+    pub wpm: u32,
+    pub correct: u32,
+    pub total: u32,
+}
+
+impl TestResult {
+    /// Speed discounted by the questions missed
+    pub fn effective_wpm(&self) -> u32 {
+        self.wpm * self.correct / self.total.max(1)
+    }
+
+    /// How the test went, against the baseline if there is one
+    pub fn summary(&self, baseline: Option<&TestResult>) -> String {
+        let result = format!(
+            "{}/{} right at {} WPM: {} effective WPM",
+            self.correct,
+            self.total,
+            self.wpm,
+            self.effective_wpm()
+        );
+        match baseline {
+            Some(baseline) if baseline != self => {
+                let before = baseline.effective_wpm();
+                let change =
+                    (self.effective_wpm() as f64 - before as f64) * 100.0 / before.max(1) as f64;
+                format!("{}. Baseline {} ({:+.0}%)", result, before, change)
+            }
+            _ => format!("{}. This is your baseline.", result),
+        }
+    }
+}
+
+/// A test in progress
+pub struct SpeedTest {
+    pub wpm: u32,
+    /// Still reading the passage, before the questions
+    pub reading: bool,
+    answers: Vec<usize>,
+    pub result: Option<TestResult>,
+    /// The book that was open, to go back to afterwards
+    pub resume_book: Option<String>,
+}
+
+impl SpeedTest {
+    pub fn new(wpm: u32, resume_book: Option<String>) -> Self {
+        Self {
+            wpm,
+            reading: true,
+            answers: Vec::new(),
+            result: None,
+            resume_book,
+        }
+    }
+
+    /// The passage, written out to the config directory to be read from
+    /// like a book
+    pub fn passage() -> io::Result<WordSource> {
+        crate::library::ensure_config_dirs()?;
+        let path = passage_file();
+        fs::write(&path, PASSAGE)?;
+        WordSource::open(path)
+    }
+
+    /// The question being asked, once the passage has been read
+    pub fn question(&self) -> Option<(usize, &'static Question)> {
+        if self.reading {
+            return None;
+        }
+        let i = self.answers.len();
+        QUESTIONS.get(i).map(|question| (i, question))
+    }
+
+    /// Answer the current question. The result comes back after the last.
+    pub fn answer(&mut self, choice: usize) -> Option<TestResult> {
+        if self.question().is_none() || choice >= 4 {
+            return None;
+        }
+        self.answers.push(choice);
+        if self.answers.len() < QUESTIONS.len() {
+            return None;
+        }
+        let correct = QUESTIONS
+            .iter()
+            .zip(&self.answers)
+            .filter(|(question, &answer)| question.answer == answer)
+            .count();
+        let result = TestResult {
+            at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            wpm: self.wpm,
+            correct: correct as u32,
+            total: QUESTIONS.len() as u32,
+        };
+        self.result = Some(result);
+        Some(result)
+    }
+}
+
+fn passage_file() -> PathBuf {
+    config_dir().join("speed-test.txt")
+}
@@ -0,0 +1,274 @@
+//! The stats line, filled in from a `stats_format` template in config.toml
+//! like `"{wpm} wpm · {remaining_time} left · {percent}%"`. Without one,
+//! each frontend keeps its own layout.
+//!
+//! The progress readout counts in the unit picked with the progress key,
+//! so the line needn't carry every measure at once.
+
+use crate::analysis::{format_reading_time, BookAnalysis};
+use crate::language::Pacing;
+use crate::library::{Book, Library, ProgressUnit, ReadingStats};
+use crate::sessions::{book_stats, streak_text};
+use crate::text::{Chapter, Pauses};
+use std::time::Duration;
+
+/// What the stats line can show
+pub struct StatsFields<'a> {
+    /// The speed set, and the one in use with adaptive speed
+    pub wpm: u32,
+    pub current_wpm: u32,
+    /// What the speed counts, and how many of them make an average word
+    pub pacing: Pacing,
+    pub units_per_word: f64,
+    /// Position as a word number from 1, and the book's length
+    pub word: usize,
+    pub words: usize,
+    pub percent: f64,
+    /// Reading time behind and ahead at the current speed
+    pub elapsed_time: Duration,
+    pub remaining_time: Duration,
+    /// Position in the book's chapters of the one being read, if past the
+    /// first heading, and the headings found
+    pub chapter: Option<usize>,
+    pub chapters: &'a [Chapter],
+    /// What the progress readout counts in
+    pub unit: ProgressUnit,
+    /// Playing or paused, as the frontend words it
+    pub status: &'a str,
+    /// Breaks taken out of all that came up, if breaks are on
+    pub breaks: Option<String>,
+    /// Recent recall check score
+    pub recall: Option<u32>,
+    /// What is looping and on which pass, while looping
+    pub repeat: Option<String>,
+    pub title: &'a str,
+}
+
+impl StatsFields<'_> {
+    fn value(&self, name: &str) -> Option<String> {
+        let value = match name {
+            "wpm" => self.wpm.to_string(),
+            "current_wpm" => self.current_wpm.to_string(),
+            "unit" => self.pacing.rate_label().to_string(),
+            "equivalent_wpm" => self.equivalent_wpm().to_string(),
+            "word" => self.word.to_string(),
+            "words" => self.words.to_string(),
+            "words_left" => self.words.saturating_sub(self.word).to_string(),
+            "percent" => format!("{:.1}", self.percent),
+            "elapsed_time" => format_reading_time(self.elapsed_time),
+            "remaining_time" => format_reading_time(self.remaining_time),
+            "chapter" => match self.chapter {
+                Some(i) => format!("{}/{}", i + 1, self.chapters.len()),
+                None => "-".to_string(),
+            },
+            "chapter_title" => self
+                .chapter
+                .map_or("-".to_string(), |i| self.chapters[i].title.clone()),
+            "progress" => self.progress(),
+            "status" => self.status.to_string(),
+            "breaks" => self.breaks.clone().unwrap_or_else(|| "-".to_string()),
+            "recall" => self.recall.map_or("-".to_string(), |r| r.to_string()),
+            "loop" => self.repeat.clone().unwrap_or_else(|| "-".to_string()),
+            "title" => self.title.to_string(),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// The speed in use as words per minute, when pacing by characters or
+    /// syllables
+    pub fn equivalent_wpm(&self) -> u32 {
+        (self.current_wpm as f64 / self.units_per_word.max(0.01)).round() as u32
+    }
+
+    /// The speed, labelled with its unit. With adaptive speed it shows the
+    /// speed in use as well, and when pacing by characters or syllables the
+    /// words per minute that comes to.
+    pub fn speed(&self, adaptive: bool) -> String {
+        let mut speed = format!("{}: {}", self.pacing.rate_label(), self.wpm);
+        if adaptive {
+            speed += &format!(" (now {})", self.current_wpm);
+        }
+        if self.pacing != Pacing::Words {
+            speed += &format!(" ≈ {} WPM", self.equivalent_wpm());
+        }
+        speed
+    }
+
+    /// Progress in the chosen unit, labelled
+    pub fn progress(&self) -> String {
+        match self.unit {
+            ProgressUnit::Percent => format!("Progress: {:.1}%", self.percent),
+            ProgressUnit::Words => format!("Word: {}/{}", self.word, self.words),
+            ProgressUnit::Time => format!(
+                "Read {}, {} left",
+                format_reading_time(self.elapsed_time),
+                format_reading_time(self.remaining_time)
+            ),
+            ProgressUnit::Chapter => match self.chapter {
+                Some(i) => format!(
+                    "Chapter {}/{}: {}",
+                    i + 1,
+                    self.chapters.len(),
+                    self.chapters[i].title
+                ),
+                None if self.chapters.is_empty() => "No chapters found".to_string(),
+                None => format!("Before chapter 1/{}", self.chapters.len()),
+            },
+        }
+    }
+
+    /// The template with each `{placeholder}` replaced. Unknown names are
+    /// left as written, so a typo shows up on screen.
+    pub fn fill(&self, template: &str) -> String {
+        let mut line = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(open) = rest.find('{') {
+            line.push_str(&rest[..open]);
+            let after = &rest[open + 1..];
+            match after
+                .find('}')
+                .and_then(|close| Some((close, self.value(&after[..close])?)))
+            {
+                Some((close, value)) => {
+                    line.push_str(&value);
+                    rest = &after[close + 1..];
+                }
+                None => {
+                    line.push('{');
+                    rest = after;
+                }
+            }
+        }
+        line.push_str(rest);
+        line
+    }
+}
+
+/// How far into `book` reading has got, as a percentage, and the time left
+/// in it at `wpm`, for lists of books
+pub fn book_progress(book: &Book, wpm: u32, pauses: &Pauses) -> (f64, String) {
+    let words = book.total_words;
+    let percent = (book.progress as f64 * 100.0 / words.max(1) as f64).min(100.0);
+    let (_, left) = reading_times(book.analysis.as_ref(), book.progress, words, wpm, pauses);
+    (percent, format_reading_time(left))
+}
+
+/// Everything the details screen shows about `book`: what its file said,
+/// its length and difficulty, how far it has been read with the time left
+/// at `wpm`, what has been kept from it, and its reading sessions
+pub fn book_summary(
+    book: &Book,
+    stats: &ReadingStats,
+    wpm: u32,
+    pauses: &Pauses,
+) -> Vec<(&'static str, String)> {
+    let mut rows = book.details();
+    rows.push(("Words", book.total_words.to_string()));
+    if let Some(analysis) = &book.analysis {
+        rows.push(("Difficulty", analysis.readability(book.language.as_deref())));
+    }
+    let (percent, left) = book_progress(book, wpm, pauses);
+    rows.push(("Read", format!("{:.0}%, {} left", percent, left)));
+    if let Some(ago) = book.since_read() {
+        rows.push(("Last read", ago));
+    }
+    if !book.tags.is_empty() {
+        rows.push(("Tags", book.tags.join(", ")));
+    }
+    let kept = [
+        ("Saved words", book.vocabulary.len()),
+        ("Footnotes", book.footnotes.len()),
+        ("Links", book.links.len()),
+    ];
+    for (label, count) in kept {
+        if count > 0 {
+            rows.push((label, count.to_string()));
+        }
+    }
+    rows.extend(book_stats(stats, &book.id, book.total_words));
+    rows
+}
+
+/// Totals across the whole library: its books and their length, how much
+/// of them has been read, and the time and speed from the session log
+pub fn library_summary(library: &Library) -> Vec<(&'static str, String)> {
+    let books = &library.books;
+    let finished = books.iter().filter(|book| book.is_finished()).count();
+    let started = library.in_progress().len();
+    let words: usize = books.iter().map(|book| book.total_words).sum();
+    let read: usize = books
+        .iter()
+        .map(|book| {
+            if book.is_finished() {
+                book.total_words
+            } else {
+                book.progress.min(book.total_words)
+            }
+        })
+        .sum();
+    let sized: Vec<&Book> = books.iter().filter(|book| book.total_words > 0).collect();
+    let completion = sized
+        .iter()
+        .map(|book| {
+            if book.is_finished() {
+                1.0
+            } else {
+                (book.progress as f64 / book.total_words as f64).min(1.0)
+            }
+        })
+        .sum::<f64>()
+        * 100.0
+        / sized.len().max(1) as f64;
+
+    let mut rows = vec![
+        (
+            "Books",
+            format!(
+                "{}, {} finished, {} in progress",
+                books.len(),
+                finished,
+                started
+            ),
+        ),
+        ("Words", words.to_string()),
+        ("Words read", read.to_string()),
+        ("Average completion", format!("{:.0}%", completion)),
+    ];
+
+    let sessions = &library.stats.sessions;
+    if !sessions.is_empty() {
+        let seconds: u64 = sessions.iter().map(|session| session.seconds).sum();
+        let shown: usize = sessions.iter().map(|session| session.words).sum();
+        let average = shown as u64 * 60 / seconds.max(1);
+        rows.extend([
+            (
+                "Time spent",
+                format_reading_time(Duration::from_secs(seconds)),
+            ),
+            ("Words shown", shown.to_string()),
+            ("Average speed", format!("{} WPM", average)),
+            ("Sessions", sessions.len().to_string()),
+            ("Streak", streak_text(&library.stats)),
+        ]);
+    }
+    rows
+}
+
+/// Time to read up to word `index`, and from there to the end, at `wpm`.
+/// With the book's analysis, sentence and clause pauses are counted too.
+pub fn reading_times(
+    analysis: Option<&BookAnalysis>,
+    index: usize,
+    words: usize,
+    wpm: u32,
+    pauses: &Pauses,
+) -> (Duration, Duration) {
+    let total = match analysis {
+        Some(analysis) => analysis.reading_time(wpm, pauses),
+        None => Duration::from_secs_f64(words as f64 * 60.0 / wpm.max(1) as f64),
+    };
+    let read = (index as f64 / words.max(1) as f64).clamp(0.0, 1.0);
+    let elapsed = total.mul_f64(read);
+    (elapsed, total.saturating_sub(elapsed))
+}
@@ -0,0 +1,1027 @@
+//! Text processing: ORP calculation and the on-demand word source
+
+use crate::blocks::{BlockScan, TextBlock};
+use crate::language::LanguageProfile;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Target size of a tokenized chunk. Chunks are extended to the next ASCII
+/// whitespace byte so no word ever straddles two chunks.
+const CHUNK_BYTES: usize = 256 * 1024;
+
+/// Number of tokenized chunks kept in memory at once
+const CACHED_CHUNKS: usize = 3;
+
+/// Calculate the Optimal Recognition Point (ORP) for a word, as an index into
+/// its grapheme clusters so accents and emoji count as a single letter
+pub fn calculate_orp(word: &str) -> usize {
+    // In a phrase of skimmed words the focus falls on the last word
+    if let Some((lead, last)) = word.rsplit_once(' ') {
+        return lead.graphemes(true).count() + 1 + calculate_orp(last);
+    }
+    let len = word.graphemes(true).count();
+    match len {
+        0..=1 => 0,
+        2..=5 => 1,
+        6..=9 => 2,
+        10..=13 => 3,
+        _ => 4,
+    }
+}
+
+/// Split a word around its ORP grapheme into (before, focal, after)
+pub fn split_at_orp(word: &str) -> (&str, &str, &str) {
+    let orp = calculate_orp(word);
+    match word.grapheme_indices(true).nth(orp) {
+        Some((start, focal)) => (&word[..start], focal, &word[start + focal.len()..]),
+        None => (word, "", ""),
+    }
+}
+
+/// Number of terminal/monospace columns a string occupies (wide CJK and
+/// emoji take two, combining marks take none)
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// Split a word that is too wide to show around its ORP into several frames,
+/// each fitting within `half_width` columns on either side of the focal
+/// letter. Breaks go after an existing hyphen, dash, or slash where possible;
+/// otherwise the word is hard-split with a trailing "-".
+pub fn split_long_word(word: &str, half_width: usize) -> Vec<String> {
+    let half_width = half_width.max(1);
+    if fits_around_orp(word, half_width) {
+        return vec![word.to_string()];
+    }
+
+    let graphemes: Vec<&str> = word.graphemes(true).collect();
+    let mut frames = Vec::new();
+    let mut start = 0;
+    while start < graphemes.len() {
+        let rest = graphemes[start..].concat();
+        if fits_around_orp(&rest, half_width) {
+            frames.push(rest);
+            break;
+        }
+
+        // Longest piece that still fits with a trailing hyphen
+        let mut end = start + 1;
+        while end < graphemes.len()
+            && fits_around_orp(&format!("{}-", graphemes[start..=end].concat()), half_width)
+        {
+            end += 1;
+        }
+
+        // A break character can take the place of the hyphen, so the
+        // grapheme just past the piece is a candidate too
+        let limit = (end + 1).min(graphemes.len());
+        let natural_break = (start + 1..=limit)
+            .rev()
+            .find(|&i| is_break_point(graphemes[i - 1]));
+        match natural_break {
+            Some(i) => {
+                frames.push(graphemes[start..i].concat());
+                start = i;
+            }
+            None => {
+                frames.push(format!("{}-", graphemes[start..end].concat()));
+                start = end;
+            }
+        }
+    }
+    frames
+}
+
+fn fits_around_orp(piece: &str, half_width: usize) -> bool {
+    let (before, _, after) = split_at_orp(piece);
+    display_width(before) <= half_width && display_width(after) <= half_width
+}
+
+fn is_break_point(grapheme: &str) -> bool {
+    matches!(grapheme, "-" | "\u{2010}" | "\u{2013}" | "\u{2014}" | "/")
+}
+
+/// Count words without allocating a token per word
+pub fn count_words(text: &str) -> usize {
+    word_spans(text).len()
+}
+
+// ============================================================================
+// Tokenizer
+// ============================================================================
+
+/// How long punctuated words stay on screen, relative to one word period
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Pauses {
+    /// Words ending a sentence
+    pub sentence: f64,
+    /// Words ending a clause (comma, semicolon, ...)
+    pub clause: f64,
+}
+
+impl Default for Pauses {
+    fn default() -> Self {
+        Self {
+            sentence: 2.0,
+            clause: 1.5,
+        }
+    }
+}
+
+/// A pacing profile under `[profiles]` in `config.toml`, named by the books
+/// that use it: slower for code-heavy documents, or a pause at every line of
+/// verse
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PacingProfile {
+    /// How many times longer each word stays on screen
+    pub slowdown: f64,
+    /// Sentence and clause pauses in place of the `[pauses]` table
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pauses: Option<Pauses>,
+    /// Pause at the end of every line as at the end of a sentence
+    pub line_breaks: bool,
+}
+
+impl Default for PacingProfile {
+    fn default() -> Self {
+        Self {
+            slowdown: 1.0,
+            pauses: None,
+            line_breaks: false,
+        }
+    }
+}
+
+impl PacingProfile {
+    /// Extra word periods after the word at `index` for ending a line, so
+    /// that with `line_breaks` on it gets a sentence pause
+    pub fn line_pause(&self, words: &WordSource, index: usize, pauses: &Pauses) -> f64 {
+        if !self.line_breaks || !words.ends_line(index) {
+            return 0.0;
+        }
+        let punctuated = words
+            .get(index)
+            .map_or(1.0, |word| pause_multiplier(&word, pauses));
+        (pauses.sentence - punctuated).max(0.0)
+    }
+}
+
+/// Abbreviations whose trailing period does not end a sentence. Initialisms
+/// like "e.g." and "U.S.A." are recognized by shape instead.
+const ABBREVIATIONS: &[&str] = &[
+    "mr.", "mrs.", "ms.", "dr.", "prof.", "sr.", "jr.", "st.", "mt.", "vs.", "etc.", "al.", "cf.",
+    "approx.", "no.", "nos.", "vol.", "vols.", "fig.", "figs.", "ch.", "pp.", "p.", "ed.", "eds.",
+    "inc.", "ltd.", "co.", "corp.", "dept.", "univ.", "gen.", "gov.", "sen.", "rep.", "rev.",
+    "capt.", "col.", "lt.", "sgt.", "jan.", "feb.", "mar.", "apr.", "jun.", "jul.", "aug.", "sep.",
+    "sept.", "oct.", "nov.", "dec.",
+];
+
+/// Byte ranges of the words in `text`. Starts from whitespace splitting, then
+/// re-joins pieces that read as a single unit: spaced-out abbreviations
+/// ("e. g.", "U. S. A.") and currency symbols detached from their amount
+/// ("$ 1,000,000"). Numbers like "3.14" are never split in the first place.
+pub(crate) fn word_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                push_span(text, &mut spans, (s, i));
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        push_span(text, &mut spans, (s, text.len()));
+    }
+    spans
+}
+
+fn push_span(text: &str, spans: &mut Vec<(usize, usize)>, span: (usize, usize)) {
+    if let Some(last) = spans.last_mut() {
+        if should_merge(&text[last.0..last.1], &text[span.0..span.1]) {
+            last.1 = span.1;
+            return;
+        }
+    }
+    spans.push(span);
+}
+
+fn should_merge(prev: &str, next: &str) -> bool {
+    let detached_currency = !prev.is_empty()
+        && prev.chars().all(is_currency_symbol)
+        && next.starts_with(|c: char| c.is_ascii_digit());
+    detached_currency || (initials(prev) > 0 && initials(next) > 0)
+}
+
+fn is_currency_symbol(c: char) -> bool {
+    matches!(c, '$' | '€' | '£' | '¥' | '₹' | '₩' | '₽' | '¢')
+}
+
+/// How many single letters each followed by a period make up `word`
+/// ("J." is 1, "U.S.A." 3), ignoring any whitespace between them; 0 if it
+/// is anything else
+fn initials(word: &str) -> usize {
+    let chars: Vec<char> = word.chars().filter(|c| !c.is_whitespace()).collect();
+    let all_initials = chars.len().is_multiple_of(2)
+        && chars
+            .chunks(2)
+            .all(|pair| pair[0].is_alphabetic() && pair[1] == '.');
+    if all_initials {
+        chars.len() / 2
+    } else {
+        0
+    }
+}
+
+/// A run of at least two initials ("e.g.", "U.S.A."). A lone "I." or "A."
+/// is as likely to end a sentence.
+fn is_initialism(word: &str) -> bool {
+    initials(word) >= 2
+}
+
+/// Strip closing quotes and brackets so "end.)" and "said." look alike
+fn trim_closers(word: &str) -> &str {
+    word.trim_end_matches(['"', '\'', ')', ']', '}', '\u{201D}', '\u{2019}', '\u{00BB}'])
+}
+
+fn is_abbreviation(word: &str) -> bool {
+    let word = word.trim_start_matches(['"', '\'', '(', '[', '\u{201C}', '\u{2018}', '\u{00AB}']);
+    is_initialism(word) || ABBREVIATIONS.contains(&word.to_lowercase().as_str())
+}
+
+/// Whether a word ends a sentence. Periods after abbreviations and initials
+/// ("Dr.", "e.g.", "U.S.A.") do not count.
+pub fn ends_sentence(word: &str) -> bool {
+    let word = trim_closers(word);
+    match word.chars().last() {
+        Some('!' | '?' | '\u{2026}') => true,
+        Some('.') => !is_abbreviation(word),
+        _ => false,
+    }
+}
+
+/// Whether a word ends a clause, with a comma, semicolon, colon or dash
+pub fn ends_clause(word: &str) -> bool {
+    matches!(
+        trim_closers(word).chars().last(),
+        Some(',' | ';' | ':' | '\u{2013}' | '\u{2014}')
+    )
+}
+
+/// Display time of a word relative to one word period at the current WPM
+pub fn pause_multiplier(word: &str, pauses: &Pauses) -> f64 {
+    if ends_sentence(word) {
+        pauses.sentence
+    } else if ends_clause(word) {
+        pauses.clause
+    } else {
+        1.0
+    }
+}
+
+// ============================================================================
+// Skimming
+// ============================================================================
+
+/// Most words a skimmed frame holds: function words plus the word they lead to
+const MAX_MERGED_WORDS: usize = 3;
+
+/// Articles, prepositions, conjunctions, pronouns and auxiliaries. Negations
+/// are left out on purpose: skimming past "not" changes the meaning.
+const FUNCTION_WORDS: &[&str] = &[
+    "a", "an", "the", "of", "to", "in", "on", "at", "by", "for", "with", "from", "into", "onto",
+    "upon", "about", "over", "under", "and", "or", "but", "nor", "so", "as", "if", "than", "that",
+    "this", "these", "those", "is", "are", "was", "were", "be", "been", "am", "do", "does", "did",
+    "has", "have", "had", "will", "would", "can", "could", "shall", "should", "may", "might",
+    "must", "it", "its", "he", "she", "they", "we", "you", "i", "his", "her", "their", "our", "my",
+    "your", "me", "him", "them", "us", "which", "who", "whom",
+];
+
+/// The `[skim]` table in `config.toml`: a faster mode that spends less time
+/// on high-frequency function words
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Skim {
+    pub enabled: bool,
+    /// How long function words stay on screen, in word periods
+    pub function_word: f64,
+    /// Show function words together with the word after them
+    pub merge: bool,
+}
+
+impl Default for Skim {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            function_word: 0.5,
+            merge: false,
+        }
+    }
+}
+
+/// Whether a word is a function word like "the", "of" or "and", ignoring
+/// case and surrounding quotes or brackets
+pub fn is_function_word(word: &str) -> bool {
+    let word = word
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase();
+    FUNCTION_WORDS.contains(&word.as_str())
+}
+
+/// A function word without a punctuation pause, which keeps its full time
+fn is_skimmable(word: &str) -> bool {
+    is_function_word(word) && !ends_sentence(word) && !ends_clause(word)
+}
+
+/// Display time of a frame relative to one word period: each word's
+/// punctuation pause plus any extra time its language gives long words or
+/// characters, with unpunctuated function words shortened while skimming
+pub fn frame_weight(frame: &str, pauses: &Pauses, skim: &Skim, language: &LanguageProfile) -> f64 {
+    frame
+        .split(' ')
+        .map(|word| {
+            if skim.enabled && is_skimmable(word) {
+                skim.function_word.max(0.0)
+            } else {
+                pause_multiplier(word, pauses) + language.length_weight(word) - 1.0
+            }
+        })
+        .sum()
+}
+
+/// Most words a book can be set to show at once
+pub const MAX_CHUNK: usize = 5;
+
+/// The text shown at `index` and how many words it covers: `chunk` words at
+/// a time, though never past the end of a sentence, nor of a line when
+/// keeping to `lines`. Skimming with `merge` on joins function words to the
+/// word after them, as in "of the river".
+pub fn display_unit(
+    words: &WordSource,
+    index: usize,
+    skim: &Skim,
+    chunk: usize,
+    lines: bool,
+) -> Option<(String, usize)> {
+    let mut last = words.get(index)?;
+    let mut text = last.clone();
+    let mut covered = 1;
+    loop {
+        if lines && words.ends_line(index + covered - 1) {
+            break;
+        }
+        let merging =
+            skim.enabled && skim.merge && covered < MAX_MERGED_WORDS && is_skimmable(&last);
+        let chunking = covered < chunk.min(MAX_CHUNK) && !ends_sentence(&last);
+        if !merging && !chunking {
+            break;
+        }
+        let Some(next) = words.get(index + covered) else {
+            break;
+        };
+        text.push(' ');
+        text.push_str(&next);
+        last = next;
+        covered += 1;
+    }
+    Some((text, covered))
+}
+
+// ============================================================================
+// Keyword Emphasis
+// ============================================================================
+
+/// Letters a word needs before it counts as a keyword
+const KEYWORD_MIN_LETTERS: usize = 7;
+
+/// How a word is drawn with keyword emphasis on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Emphasis {
+    /// Function words, drawn dimmer so they recede
+    Dim,
+    Normal,
+    /// Long words and numbers, the rarer tokens that carry most of the meaning
+    Strong,
+}
+
+pub fn word_emphasis(word: &str) -> Emphasis {
+    if is_function_word(word) {
+        Emphasis::Dim
+    } else if word.chars().any(|c| c.is_numeric())
+        || word.chars().filter(|c| c.is_alphabetic()).count() >= KEYWORD_MIN_LETTERS
+    {
+        Emphasis::Strong
+    } else {
+        Emphasis::Normal
+    }
+}
+
+/// Split the `range` of a frame into pieces that each lie in one word, with
+/// that word's emphasis. Both frontends draw the text either side of the
+/// focal letter this way, so merged frames like "of the river" keep a
+/// separate emphasis per word.
+pub fn emphasis_runs(frame: &str, range: Range<usize>) -> Vec<(&str, Emphasis)> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for word in frame.split(' ') {
+        // Each word takes the space after it along
+        let end = (start + word.len() + 1).min(frame.len());
+        let (from, to) = (start.max(range.start), end.min(range.end));
+        if from < to {
+            runs.push((&frame[from..to], word_emphasis(word)));
+        }
+        start = end;
+    }
+    runs
+}
+
+// ============================================================================
+// Adaptive Speed
+// ============================================================================
+
+/// Words ahead of the display that set the adaptive speed
+const ADAPTIVE_LOOKAHEAD: usize = 8;
+
+/// The `[adaptive]` table in `config.toml`: slow down for hard stretches of
+/// text and speed back up for easy ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdaptiveSpeed {
+    pub enabled: bool,
+    /// Bounds the adjusted speed stays within
+    pub min_wpm: u32,
+    pub max_wpm: u32,
+}
+
+impl Default for AdaptiveSpeed {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_wpm: 150,
+            max_wpm: 900,
+        }
+    }
+}
+
+impl AdaptiveSpeed {
+    /// The speed for the word at `index`, given the speed the reader chose
+    pub fn wpm(&self, base: u32, words: &WordSource, index: usize) -> u32 {
+        if !self.enabled {
+            return base;
+        }
+        let wpm = (base as f64 / difficulty(words, index)).round() as u32;
+        wpm.clamp(self.min_wpm.min(self.max_wpm), self.max_wpm)
+    }
+}
+
+/// How demanding the next few words are, around 1.0 for ordinary prose.
+/// Long words, which tend to be the rare ones, numbers, and stretches full
+/// of clause breaks with no sentence end in sight score higher; runs of
+/// short function words score lower.
+pub fn difficulty(words: &WordSource, index: usize) -> f64 {
+    let upcoming: Vec<String> = (index..index + ADAPTIVE_LOOKAHEAD)
+        .map_while(|i| words.get(i))
+        .collect();
+    if upcoming.is_empty() {
+        return 1.0;
+    }
+    let lexical = upcoming
+        .iter()
+        .map(|word| word_difficulty(word))
+        .sum::<f64>()
+        / upcoming.len() as f64;
+
+    let clauses = upcoming.iter().filter(|word| ends_clause(word)).count();
+    let open_sentence = !upcoming.iter().any(|word| ends_sentence(word));
+    let syntax = 1.0 + 0.05 * clauses as f64 + if open_sentence { 0.1 } else { 0.0 };
+    (lexical * syntax).clamp(0.5, 2.0)
+}
+
+fn word_difficulty(word: &str) -> f64 {
+    if is_function_word(word) {
+        0.75
+    } else if word.chars().any(|c| c.is_numeric()) {
+        1.3
+    } else {
+        let letters = word.chars().filter(|c| c.is_alphabetic()).count();
+        (0.75 + 0.05 * letters as f64).min(1.8)
+    }
+}
+
+// ============================================================================
+// Chapters
+// ============================================================================
+
+/// First words of a chapter heading that need a number after them
+const NUMBERED_HEADINGS: &[&str] = &["chapter", "part", "book", "section"];
+
+/// Headings that stand on their own
+const NAMED_HEADINGS: &[&str] = &[
+    "prologue",
+    "epilogue",
+    "preface",
+    "foreword",
+    "introduction",
+    "afterword",
+    "interlude",
+    "appendix",
+];
+
+/// Spelled-out numbers in headings like "Chapter Twenty-One", besides
+/// the teens
+const NUMBER_WORDS: &[&str] = &[
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven",
+    "twelve", "twenty", "thirty", "forty", "fifty", "first", "second", "third", "fourth", "fifth",
+    "sixth", "seventh", "eighth", "ninth", "tenth", "last", "final",
+];
+
+/// Longest line, in words, taken for a heading
+const HEADING_WORDS: usize = 8;
+
+/// A chapter heading found in the text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chapter {
+    /// Index of the heading's first word
+    pub first_word: usize,
+    pub title: String,
+}
+
+/// Whether a line opening a paragraph reads as a chapter heading, like
+/// "Chapter 12", "CHAPTER XII. The Storm", "Part Two" or "Epilogue"
+fn is_chapter_heading(line: &str) -> bool {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    if words.is_empty() || words.len() > HEADING_WORDS {
+        return false;
+    }
+    let bare = |word: &str| {
+        word.trim_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase()
+    };
+    let first = bare(words[0]);
+    if NAMED_HEADINGS.contains(&first.as_str()) {
+        return true;
+    }
+    NUMBERED_HEADINGS.contains(&first.as_str())
+        && words.get(1).is_some_and(|word| {
+            let number = bare(word);
+            let first_part = number.split('-').next().unwrap_or_default();
+            !number.is_empty()
+                && (number.chars().all(|c| c.is_ascii_digit())
+                    || number.chars().all(|c| "ivxlcdm".contains(c))
+                    || NUMBER_WORDS.contains(&first_part)
+                    || first_part.ends_with("teen"))
+        })
+}
+
+/// Chapter headings in a chunk whose first word is `first_word`.
+/// `blank_before` says whether the line about to start follows a blank
+/// one, and carries over from chunk to chunk.
+fn find_chapters(
+    text: &str,
+    spans: &[(usize, usize)],
+    first_word: usize,
+    blank_before: &mut bool,
+) -> Vec<Chapter> {
+    let mut chapters = Vec::new();
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            *blank_before = true;
+        } else {
+            if *blank_before && is_chapter_heading(trimmed) {
+                let local = spans.partition_point(|&(start, _)| start < offset);
+                chapters.push(Chapter {
+                    first_word: first_word + local,
+                    title: trimmed.to_string(),
+                });
+            }
+            *blank_before = false;
+        }
+        offset += line.len();
+    }
+    chapters
+}
+
+/// Whether each word lies inside quotation marks, starting `quoted` or not,
+/// and whether the last one leaves a quote open. Double quotes, curly or
+/// straight, and guillemets count; single quotes are left alone, being
+/// mostly apostrophes. A paragraph break closes any quote left open, as
+/// fiction reopens a quote that runs on into the next paragraph.
+fn dialogue_flags(text: &str, spans: &[(usize, usize)], mut quoted: bool) -> (Vec<bool>, bool) {
+    let mut flags = Vec::with_capacity(spans.len());
+    let mut prev_end = None;
+    for &(start, end) in spans {
+        if prev_end.is_some_and(|prev| text[prev..start].matches('\n').count() >= 2) {
+            quoted = false;
+        }
+        let word = &text[start..end];
+        let opening = word.trim_start_matches(['(', '[']);
+        // Straight quotes open and close alike, so they go by position
+        let opens_straight = !quoted && opening.starts_with('"');
+        if opens_straight || opening.starts_with(['\u{201C}', '\u{00AB}']) {
+            quoted = true;
+        }
+        flags.push(quoted);
+        let closing = word.trim_end_matches(|c: char| c.is_ascii_punctuation() && c != '"');
+        let closes_straight = closing.ends_with('"') && !(opens_straight && opening == "\"");
+        if closes_straight || closing.ends_with(['\u{201D}', '\u{00BB}']) {
+            quoted = false;
+        }
+        prev_end = Some(end);
+    }
+    (flags, quoted)
+}
+
+// ============================================================================
+// Word Source
+// ============================================================================
+
+#[derive(Debug, Clone, Copy)]
+struct ChunkInfo {
+    offset: u64,
+    len: usize,
+    first_word: usize,
+    /// Whether the chunk's first word is inside a quote opened before it
+    quoted: bool,
+}
+
+/// A tokenized chunk: the raw text in one buffer plus the byte span of every
+/// word, rather than one allocation per word.
+#[derive(Debug)]
+struct LoadedChunk {
+    index: usize,
+    text: String,
+    spans: Vec<(usize, usize)>,
+    /// Whether each word is inside a quote
+    dialogue: Vec<bool>,
+}
+
+impl LoadedChunk {
+    fn word(&self, local: usize) -> Option<String> {
+        let &(start, end) = self.spans.get(local)?;
+        // Merged tokens ("e. g.") are shown without their inner whitespace
+        Some(self.text[start..end].split_whitespace().collect())
+    }
+
+    /// Line breaks between the word at `local` and the one before it in
+    /// this chunk, or the start of the chunk for its first word
+    fn newlines_before(&self, local: usize) -> usize {
+        let start = local.checked_sub(1).map_or(0, |prev| self.spans[prev].1);
+        let end = self.spans.get(local).map_or(self.text.len(), |s| s.0);
+        self.text[start..end].matches('\n').count()
+    }
+}
+
+/// Words of a book file, tokenized lazily in chunks around the words that are
+/// actually requested. Opening only scans the file once to record where each
+/// chunk starts, so memory stays bounded regardless of file size.
+#[derive(Debug, Default)]
+pub struct WordSource {
+    path: PathBuf,
+    chunks: Vec<ChunkInfo>,
+    total: usize,
+    chapters: Vec<Chapter>,
+    /// Code blocks and tables, in order
+    blocks: Vec<TextBlock>,
+    cache: RefCell<VecDeque<LoadedChunk>>,
+}
+
+impl WordSource {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut reader = BufReader::new(File::open(&path)?);
+
+        let mut chunks = Vec::new();
+        let mut chapters = Vec::new();
+        let mut offset = 0u64;
+        let mut total = 0usize;
+        let mut blank_before = true;
+        let mut blocks = BlockScan::default();
+        // Whether a quote is open, and line breaks since the last word
+        let mut quoted = false;
+        let mut newlines = 0;
+        loop {
+            let buf = read_chunk(&mut reader)?;
+            if buf.is_empty() {
+                break;
+            }
+            let text = String::from_utf8_lossy(&buf);
+            let spans = word_spans(&text);
+            let count = spans.len();
+            chapters.extend(find_chapters(&text, &spans, total, &mut blank_before));
+            blocks.chunk(&text, &spans, total, offset);
+            if let (Some(first), Some(last)) = (spans.first(), spans.last()) {
+                newlines += text[..first.0].matches('\n').count();
+                quoted &= newlines < 2;
+                chunks.push(ChunkInfo {
+                    offset,
+                    len: buf.len(),
+                    first_word: total,
+                    quoted,
+                });
+                quoted = dialogue_flags(&text, &spans, quoted).1;
+                newlines = text[last.1..].matches('\n').count();
+            } else {
+                newlines += text.matches('\n').count();
+            }
+            offset += buf.len() as u64;
+            total += count;
+        }
+        blocks.finish(offset, total);
+
+        Ok(Self {
+            path,
+            chunks,
+            total,
+            chapters,
+            blocks: blocks.blocks,
+            cache: RefCell::new(VecDeque::new()),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.total
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    /// Get the word at `index`, loading its chunk from disk if needed
+    pub fn get(&self, index: usize) -> Option<String> {
+        let (chunk_index, local) = self.locate(index)?;
+        self.with_chunk(chunk_index, |chunk| chunk.word(local))?
+    }
+
+    /// Byte offset of the word at `index` within the book file. Unlike a word
+    /// index, this stays valid if tokenization rules change.
+    pub fn offset_of(&self, index: usize) -> Option<u64> {
+        let (chunk_index, local) = self.locate(index)?;
+        let start = self.with_chunk(chunk_index, |chunk| chunk.spans.get(local).map(|s| s.0))??;
+        Some(self.chunks[chunk_index].offset + start as u64)
+    }
+
+    /// Index of the word containing `offset`, or the first word after it if
+    /// the offset falls on whitespace
+    pub fn index_at_offset(&self, offset: u64) -> usize {
+        if self.chunks.is_empty() {
+            return 0;
+        }
+        let chunk_index = self
+            .chunks
+            .partition_point(|c| c.offset <= offset)
+            .saturating_sub(1);
+        let info = self.chunks[chunk_index];
+        let local_offset = offset.saturating_sub(info.offset) as usize;
+
+        let local = self
+            .with_chunk(chunk_index, |chunk| {
+                chunk.spans.partition_point(|&(_, end)| end <= local_offset)
+            })
+            .unwrap_or(0);
+        (info.first_word + local).min(self.total.saturating_sub(1))
+    }
+
+    /// Chapter headings in the order they come
+    pub fn chapters(&self) -> &[Chapter] {
+        &self.chapters
+    }
+
+    /// Position in `chapters()` of the chapter the word at `index` is in,
+    /// or None before the first heading
+    pub fn chapter_at(&self, index: usize) -> Option<usize> {
+        self.chapters
+            .partition_point(|chapter| chapter.first_word <= index)
+            .checked_sub(1)
+    }
+
+    /// Whether the word at `index` opens a paragraph: the first word of the
+    /// book, or one after a blank line
+    pub fn starts_paragraph(&self, index: usize) -> bool {
+        index < self.len() && (index == 0 || self.newlines_before(index) >= 2)
+    }
+
+    /// The code block or table the word at `index` is in, if any
+    pub fn block_at(&self, index: usize) -> Option<&TextBlock> {
+        let i = self
+            .blocks
+            .partition_point(|block| block.first_word <= index)
+            .checked_sub(1)?;
+        Some(&self.blocks[i]).filter(|block| block.contains(index))
+    }
+
+    /// A block's text as written, lines and all, without its fences
+    pub fn block_text(&self, block: &TextBlock) -> Option<String> {
+        let read = || -> io::Result<String> {
+            let mut file = File::open(&self.path)?;
+            file.seek(SeekFrom::Start(block.offset))?;
+            let mut buf = vec![0; block.len];
+            file.read_exact(&mut buf)?;
+            Ok(String::from_utf8_lossy(&buf).trim_end().to_string())
+        };
+        read()
+            .map_err(|e| tracing::error!("Couldn't read {}: {}", self.path.display(), e))
+            .ok()
+    }
+
+    /// Whether the word at `index` is inside quotation marks
+    pub fn in_dialogue(&self, index: usize) -> bool {
+        self.locate(index)
+            .and_then(|(chunk_index, local)| {
+                self.with_chunk(chunk_index, |chunk| chunk.dialogue.get(local).copied())
+            })
+            .flatten()
+            .unwrap_or(false)
+    }
+
+    /// Whether the word at `index` is the last on its line
+    pub fn ends_line(&self, index: usize) -> bool {
+        index + 1 < self.len() && self.newlines_before(index + 1) >= 1
+    }
+
+    /// Line breaks between the word at `index` and the one before it
+    fn newlines_before(&self, index: usize) -> usize {
+        let Some((chunk_index, local)) = self.locate(index) else {
+            return 0;
+        };
+        if index == 0 {
+            return 0;
+        }
+        let mut newlines = self
+            .with_chunk(chunk_index, |chunk| chunk.newlines_before(local))
+            .unwrap_or(0);
+        // The whitespace before a chunk's first word can start in the chunk
+        // before it
+        if local == 0 {
+            newlines += self
+                .with_chunk(chunk_index - 1, |chunk| {
+                    chunk.newlines_before(chunk.spans.len())
+                })
+                .unwrap_or(0);
+        }
+        newlines
+    }
+
+    /// Whether the word at `index` opens a sentence. Paragraph breaks count
+    /// as sentence breaks, so headings stand on their own.
+    fn starts_sentence(&self, index: usize) -> bool {
+        self.starts_paragraph(index)
+            || index
+                .checked_sub(1)
+                .and_then(|prev| self.get(prev))
+                .is_some_and(|word| ends_sentence(&word))
+    }
+
+    /// Index of the first word of the sentence containing `index`
+    pub fn sentence_start(&self, index: usize) -> usize {
+        self.rewind(index, |i| self.starts_sentence(i))
+    }
+
+    /// Index of the first word of the paragraph containing `index`
+    pub fn paragraph_start(&self, index: usize) -> usize {
+        self.rewind(index, |i| self.starts_paragraph(i))
+    }
+
+    /// The start of the current sentence, or of the one before if `index`
+    /// is already at a start
+    pub fn prev_sentence(&self, index: usize) -> usize {
+        self.step_back(index, |i| self.starts_sentence(i))
+    }
+
+    pub fn next_sentence(&self, index: usize) -> usize {
+        self.step_forward(index, |i| self.starts_sentence(i))
+    }
+
+    /// The start of the current paragraph, or of the one before if `index`
+    /// is already at a start
+    pub fn prev_paragraph(&self, index: usize) -> usize {
+        self.step_back(index, |i| self.starts_paragraph(i))
+    }
+
+    pub fn next_paragraph(&self, index: usize) -> usize {
+        self.step_forward(index, |i| self.starts_paragraph(i))
+    }
+
+    /// The nearest word at or before `index` where `is_start` holds
+    fn rewind(&self, index: usize, is_start: impl Fn(usize) -> bool) -> usize {
+        let mut start = index.min(self.total.saturating_sub(1));
+        while start > 0 && !is_start(start) {
+            start -= 1;
+        }
+        start
+    }
+
+    fn step_back(&self, index: usize, is_start: impl Fn(usize) -> bool) -> usize {
+        let start = self.rewind(index, &is_start);
+        if start < index {
+            start
+        } else {
+            self.rewind(start.saturating_sub(1), is_start)
+        }
+    }
+
+    /// The first word after `index` where `is_start` holds, or the last word
+    /// of the book if there is none
+    fn step_forward(&self, index: usize, is_start: impl Fn(usize) -> bool) -> usize {
+        let last = self.total.saturating_sub(1);
+        (index + 1..self.total)
+            .find(|&i| is_start(i))
+            .unwrap_or(last)
+    }
+
+    /// Map a word index to its chunk and the word's position within it
+    fn locate(&self, index: usize) -> Option<(usize, usize)> {
+        if index >= self.total {
+            return None;
+        }
+        let chunk_index = self.chunks.partition_point(|c| c.first_word <= index) - 1;
+        Some((chunk_index, index - self.chunks[chunk_index].first_word))
+    }
+
+    fn with_chunk<T>(&self, chunk_index: usize, f: impl FnOnce(&LoadedChunk) -> T) -> Option<T> {
+        let mut cache = self.cache.borrow_mut();
+        if let Some(pos) = cache.iter().position(|c| c.index == chunk_index) {
+            // Move to the back so the least recently used chunk is evicted first
+            let chunk = cache.remove(pos)?;
+            cache.push_back(chunk);
+        } else {
+            let chunk = match self.load_chunk(chunk_index) {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    tracing::error!("Couldn't read {}: {}", self.path.display(), e);
+                    return None;
+                }
+            };
+            if cache.len() >= CACHED_CHUNKS {
+                cache.pop_front();
+            }
+            cache.push_back(chunk);
+        }
+        cache.back().map(f)
+    }
+
+    fn load_chunk(&self, chunk_index: usize) -> io::Result<LoadedChunk> {
+        let info = self.chunks[chunk_index];
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(info.offset))?;
+        let mut buf = vec![0; info.len];
+        file.read_exact(&mut buf)?;
+
+        // Book files are written as UTF-8; lossy decoding would shift offsets
+        let text = String::from_utf8(buf)
+            .unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned());
+        let spans = word_spans(&text);
+        let (dialogue, _) = dialogue_flags(&text, &spans, info.quoted);
+        Ok(LoadedChunk {
+            index: chunk_index,
+            text,
+            spans,
+            dialogue,
+        })
+    }
+}
+
+/// Read roughly `CHUNK_BYTES`, then continue up to the next ASCII whitespace
+/// byte. ASCII bytes never occur inside multi-byte UTF-8 sequences, so the
+/// cut is always on a character boundary.
+fn read_chunk(reader: &mut impl BufRead) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(CHUNK_BYTES);
+    reader
+        .by_ref()
+        .take(CHUNK_BYTES as u64)
+        .read_to_end(&mut buf)?;
+
+    loop {
+        let available = reader.fill_buf()?;
+        if available.is_empty() {
+            break;
+        }
+        match available.iter().position(|b| b.is_ascii_whitespace()) {
+            Some(pos) => {
+                buf.extend_from_slice(&available[..pos]);
+                reader.consume(pos);
+                break;
+            }
+            None => {
+                let n = available.len();
+                buf.extend_from_slice(available);
+                reader.consume(n);
+            }
+        }
+    }
+
+    Ok(buf)
+}
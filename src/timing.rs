@@ -0,0 +1,120 @@
+//! Playback timing against deadlines. Each word's time on screen is
+//! counted from the instant the one before it was due to go, not from when
+//! the frontend got round to swapping it, so a late tick shortens the next
+//! word instead of pushing every word after it back.
+//!
+//! A stall, like the system suspending the app or a load spike, would leave
+//! many words overdue at once. Rather than flash through them to catch up,
+//! the clock forgets the lost time and eases back in with the next few
+//! words held a little longer.
+
+use std::time::{Duration, Instant};
+
+/// Overdue by at least this much, and by more than a word's time, counts
+/// as a stall
+const STALL: Duration = Duration::from_millis(250);
+
+/// Words after a stall that are held longer, the first the longest
+const RAMP_WORDS: u32 = 5;
+
+/// How much longer the first word after a stall is held
+const RAMP_EXTRA: f64 = 0.5;
+
+/// Bounds on how often a frontend that ticks at a fixed rate wakes up
+const MIN_TICK: Duration = Duration::from_millis(4);
+const MAX_TICK: Duration = Duration::from_millis(50);
+
+/// How often a frontend that can only tick at a fixed rate should while
+/// playing at `wpm`: an eighth of a word's time, so words change within a
+/// few milliseconds of their deadlines at high speeds without waking up
+/// needlessly at low ones. Whole milliseconds, so the rate only changes
+/// when the speed does by a fair amount.
+pub fn tick_interval(wpm: u32) -> Duration {
+    let period_ms = 60_000 / u64::from(wpm.max(1));
+    Duration::from_millis(period_ms / 8).clamp(MIN_TICK, MAX_TICK)
+}
+
+/// What `WordClock::advance` found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Advance {
+    /// The word on screen still has time left
+    Wait,
+    /// The word on screen has had its time
+    Next,
+    /// The word on screen should have gone this long ago, and the clock
+    /// has started over from now
+    Stalled(Duration),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct WordClock {
+    /// When the word on screen started its time
+    shown_at: Instant,
+    /// Words left in the ramp after a stall
+    ramp: u32,
+}
+
+impl Default for WordClock {
+    fn default() -> Self {
+        Self {
+            shown_at: Instant::now(),
+            ramp: 0,
+        }
+    }
+}
+
+impl WordClock {
+    /// Time the word on screen from now, e.g. after playback starts or the
+    /// reader moves
+    pub fn restart(&mut self) {
+        self.shown_at = Instant::now();
+        self.ramp = 0;
+    }
+
+    /// Hold the word on screen back until `until`, e.g. while a
+    /// micro-pause blanks the display
+    pub fn hold_until(&mut self, until: Instant) {
+        self.shown_at = until;
+    }
+
+    /// When the word on screen started its time
+    pub fn since(&self) -> Instant {
+        self.shown_at
+    }
+
+    /// `delay` stretched while easing back in after a stall
+    fn ramped(&self, delay: Duration) -> Duration {
+        let extra = RAMP_EXTRA * f64::from(self.ramp) / f64::from(RAMP_WORDS);
+        delay.mul_f64(1.0 + extra)
+    }
+
+    /// When the word on screen, meant to stay up for `delay`, is due to go
+    pub fn due(&self, delay: Duration) -> Instant {
+        self.shown_at + self.ramped(delay)
+    }
+
+    /// Time left until the word on screen is due to go, zero if it is
+    /// already
+    pub fn until_due(&self, delay: Duration) -> Duration {
+        self.due(delay).saturating_duration_since(Instant::now())
+    }
+
+    /// Whether the word on screen has had its `delay`. If so, the next
+    /// word's time starts from the deadline, or from now after a stall.
+    pub fn advance(&mut self, delay: Duration) -> Advance {
+        let now = Instant::now();
+        let due = self.due(delay);
+        if now < due {
+            return Advance::Wait;
+        }
+        let late = now - due;
+        if late >= STALL && late > delay {
+            self.shown_at = now;
+            self.ramp = RAMP_WORDS;
+            return Advance::Stalled(late);
+        }
+        self.shown_at = due;
+        self.ramp = self.ramp.saturating_sub(1);
+        Advance::Next
+    }
+}
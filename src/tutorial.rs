@@ -0,0 +1,137 @@
+//! The tutorial offered on first launch: a short text that teaches the
+//! controls as it plays. Each paragraph is a lesson ending in a task, like
+//! speeding up or jumping back; playback waits at the end of the paragraph
+//! until the reader has done it.
+
+use crate::config::{Action, KeyBindings};
+use crate::library::{config_dir, ensure_config_dirs};
+use crate::text::WordSource;
+use std::fs;
+use std::io;
+
+pub struct Lesson {
+    pub text: &'static str,
+    /// What the reader is asked to do at the end of the lesson
+    pub task: Action,
+    /// The task in a few words, after "Press <key> to"
+    pub prompt: &'static str,
+}
+
+pub const LESSONS: &[Lesson] = &[
+    Lesson {
+        text: "Welcome to RSVP. Words appear one at a time in the same spot, so your eyes can stay still. The highlighted letter marks where your eye should rest. Don't try to read ahead; just let the words come to you. The tutorial stops whenever it wants you to try something. Let's start by going a little faster.",
+        task: Action::SpeedUp,
+        prompt: "speed up",
+    },
+    Lesson {
+        text: "That's faster. The speed is shown at the bottom, in words per minute. Most people are comfortable somewhere between three and five hundred. If it ever feels too fast, slow down the same way.",
+        task: Action::SpeedDown,
+        prompt: "slow down",
+    },
+    Lesson {
+        text: "Sometimes a sentence slips past before you have taken it in. Rather than starting over, jump back to the start of the sentence and read it again. Try it now and this sentence plays again.",
+        task: Action::PrevSentence,
+        prompt: "go back a sentence",
+    },
+    Lesson {
+        text: "Good. Pausing works as in any player, and reading picks up exactly where it stopped. The tutorial is paused now, so carry on when you're ready.",
+        task: Action::PlayPause,
+        prompt: "carry on",
+    },
+    Lesson {
+        text: "Those are the basics. There are keys for stepping word by word, opening your library, saving words and checking what you remember, and the list of them is always one press away. After that, import a book of your own and start reading.",
+        task: Action::Help,
+        prompt: "see every key",
+    },
+];
+
+/// What the frontend should do after a tutorial update
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    /// Keep going
+    Reading,
+    /// The lesson is over and its task not done yet: stay paused
+    Waiting,
+    /// The task is done: play the next lesson
+    Next,
+    /// The last task is done
+    Finished,
+}
+
+pub struct Tutorial {
+    lesson: usize,
+    /// Index of the last word of each lesson
+    ends: Vec<usize>,
+    /// The speed when the current lesson ended, for the speed tasks
+    waiting_at: Option<u32>,
+}
+
+impl Tutorial {
+    /// The lessons, written out to the config directory to be read from
+    /// like a book
+    pub fn book() -> io::Result<WordSource> {
+        ensure_config_dirs()?;
+        let path = config_dir().join("tutorial.txt");
+        let text: Vec<&str> = LESSONS.iter().map(|lesson| lesson.text).collect();
+        fs::write(&path, text.join("\n\n"))?;
+        WordSource::open(path)
+    }
+
+    pub fn new(words: &WordSource) -> Self {
+        let ends = (0..words.len())
+            .filter(|&i| i + 1 == words.len() || words.starts_paragraph(i + 1))
+            .collect();
+        Self {
+            lesson: 0,
+            ends,
+            waiting_at: None,
+        }
+    }
+
+    /// Follow the reader: at `index`, reading at `wpm`, with playback and
+    /// the help screen on or off
+    pub fn update(&mut self, index: usize, wpm: u32, playing: bool, help_open: bool) -> Progress {
+        let (Some(lesson), Some(&end)) = (LESSONS.get(self.lesson), self.ends.get(self.lesson))
+        else {
+            return Progress::Finished;
+        };
+        let Some(wpm_at) = self.waiting_at else {
+            if index < end {
+                return Progress::Reading;
+            }
+            self.waiting_at = Some(wpm);
+            return Progress::Waiting;
+        };
+
+        let done = match lesson.task {
+            Action::SpeedUp => wpm > wpm_at,
+            Action::SpeedDown => wpm < wpm_at,
+            Action::PrevSentence => index < end,
+            Action::PlayPause => playing,
+            Action::Help => help_open,
+            _ => false,
+        };
+        if !done {
+            return Progress::Waiting;
+        }
+        self.lesson += 1;
+        self.waiting_at = None;
+        if self.lesson < LESSONS.len().min(self.ends.len()) {
+            Progress::Next
+        } else {
+            Progress::Finished
+        }
+    }
+
+    /// What to do to carry on, naming the first key bound to it, while the
+    /// tutorial is waiting
+    pub fn hint(&self, keys: &KeyBindings) -> Option<String> {
+        self.waiting_at?;
+        let lesson = LESSONS.get(self.lesson)?;
+        let key = keys
+            .keys(lesson.task)
+            .iter()
+            .find(|key| !key.key.is_media())?;
+        Some(format!("Press {} to {}", key, lesson.prompt))
+    }
+}
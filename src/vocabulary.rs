@@ -0,0 +1,170 @@
+//! Words saved while reading, each with the sentence it was read in. They
+//! are kept with their book in the library and exported as tab-separated
+//! files with the header lines Anki reads, so File > Import turns each word
+//! into a note with its sentence and book, tagged by book.
+
+use crate::library::{config_dir, Book, Library};
+use crate::text::{ends_sentence, WordSource};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Most words of context kept around a saved word, for run-on sentences
+const MAX_CONTEXT_WORDS: usize = 60;
+
+/// Header lines Anki reads to set up the import: tab-separated HTML fields
+/// and a column of tags
+const ANKI_HEADER: &str =
+    "#separator:tab\n#html:true\n#columns:Word\tContext\tBook\tTags\n#tags column:4\n";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VocabEntry {
+    pub word: String,
+    /// The sentence the word was read in
+    pub context: String,
+    /// Byte offset of the word in the book file, so saving the same spot
+    /// twice is caught
+    #[serde(default)]
+    pub offset: Option<u64>,
+    /// Unix time it was saved
+    #[serde(default)]
+    pub saved_at: u64,
+}
+
+impl VocabEntry {
+    /// The word at `index`, stripped of punctuation, with its sentence
+    pub fn capture(words: &WordSource, index: usize) -> Option<Self> {
+        let word = words
+            .get(index)?
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_string();
+        if word.is_empty() {
+            return None;
+        }
+
+        let start = words
+            .sentence_start(index)
+            .max(index.saturating_sub(MAX_CONTEXT_WORDS / 2));
+        let mut context = Vec::new();
+        for i in start..words.len().min(start + MAX_CONTEXT_WORDS) {
+            if i > index && words.starts_paragraph(i) {
+                break;
+            }
+            let Some(word) = words.get(i) else { break };
+            let last = i >= index && ends_sentence(&word);
+            context.push(word);
+            if last {
+                break;
+            }
+        }
+
+        Some(Self {
+            word,
+            context: context.join(" "),
+            offset: words.offset_of(index),
+            saved_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+        })
+    }
+
+    /// Whether two entries are the same word saved from the same spot
+    pub fn same_as(&self, other: &VocabEntry) -> bool {
+        self.word == other.word && self.offset == other.offset
+    }
+}
+
+pub fn vocabulary_dir() -> PathBuf {
+    config_dir().join("vocabulary")
+}
+
+/// One note per saved word: the word, its sentence with the word in bold,
+/// the book's title, and the book's title as a tag, so cards can be
+/// studied a book at a time
+fn anki_notes(book: &Book) -> String {
+    let tag: String = html_field(&book.title).replace(' ', "_");
+    book.vocabulary
+        .iter()
+        .map(|entry| {
+            format!(
+                "{}\t{}\t{}\t{}\n",
+                html_field(&entry.word),
+                bold_word(&html_field(&entry.context), &html_field(&entry.word)),
+                html_field(&book.title),
+                tag,
+            )
+        })
+        .collect()
+}
+
+/// Text as one line of escaped HTML, safe in a tab-separated field
+fn html_field(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The context with the first appearance of the word in bold, as a whole
+/// word so "he" isn't found inside "The"
+fn bold_word(context: &str, word: &str) -> String {
+    let whole_word = |&(at, _): &(usize, &str)| {
+        let before = context[..at].chars().next_back();
+        let after = context[at + word.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    };
+    match context.match_indices(word).find(whole_word) {
+        Some((at, _)) if !word.is_empty() => format!(
+            "{}<b>{}</b>{}",
+            &context[..at],
+            word,
+            &context[at + word.len()..]
+        ),
+        _ => context.to_string(),
+    }
+}
+
+/// A title made safe to use as a file name
+fn file_name(title: &str, fallback: &str) -> String {
+    let name: String = title
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || " -_".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    match name.trim() {
+        "" => fallback.to_string(),
+        name => name.to_string(),
+    }
+}
+
+fn write_export(name: &str, notes: &str) -> io::Result<PathBuf> {
+    fs::create_dir_all(vocabulary_dir())?;
+    let path = vocabulary_dir().join(format!("{}.tsv", name));
+    fs::write(&path, format!("{}{}", ANKI_HEADER, notes))?;
+    Ok(path)
+}
+
+/// Write a book's saved words to `vocabulary/<title> <id>.tsv` in the
+/// config directory, ready for Anki's File > Import, and return the path.
+/// The id keeps books of the same title, or one called "all", apart.
+pub fn export_vocabulary(book: &Book) -> io::Result<PathBuf> {
+    let name = format!("{} {}", file_name(&book.title, "book"), book.id);
+    write_export(&name, &anki_notes(book))
+}
+
+/// Write the saved words of every book to `vocabulary/all.tsv`, tagged by
+/// book, and return the path
+pub fn export_all_vocabulary(library: &Library) -> io::Result<PathBuf> {
+    let notes: String = library.books.iter().map(anki_notes).collect();
+    write_export("all", &notes)
+}